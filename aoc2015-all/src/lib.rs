@@ -0,0 +1,322 @@
+//! Every day of Advent of Code 2015, behind a single function.
+//!
+//! This is the integration point for embedding the whole year in another tool:
+//!
+//! ```
+//! use aoc2015_all::{solve, Part};
+//!
+//! let floor = solve(1, Part::One, "(()(()(").unwrap();
+//! assert_eq!(floor.to_string(), "3");
+//! ```
+
+use std::{fmt, str::FromStr};
+use util::progress::{Progress, Silent};
+pub use util::{example::Example, Answer};
+
+/// Days which have been implemented.
+pub const DAYS: std::ops::RangeInclusive<u8> = 1..=25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Part::One => "1",
+            Part::Two => "2",
+        })
+    }
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(Error::InvalidPart(s.to_string())),
+        }
+    }
+}
+
+/// The parts which exist for a given day.
+pub fn parts_of(day: u8) -> &'static [Part] {
+    if day == 25 {
+        // christmas day has only a single part
+        &[Part::One]
+    } else {
+        &[Part::One, Part::Two]
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("part must be 1 or 2, not \"{0}\"")]
+    InvalidPart(String),
+    #[error("day {day} part {part} is not implemented")]
+    Unimplemented { day: u8, part: Part },
+    #[error(transparent)]
+    Solve(#[from] util::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+macro_rules! dispatch {
+    ($day:expr, $part:expr, $input:expr; $($n:literal => $krate:ident),* $(,)?) => {
+        match ($day, $part) {
+            $(
+                ($n, Part::One) => $krate::part1_str($input),
+                ($n, Part::Two) => $krate::part2_str($input),
+            )*
+            (day, part) => return Err(Error::Unimplemented { day, part }),
+        }
+    };
+}
+
+/// Solve the given part of the given day's puzzle for `input`.
+pub fn solve(day: u8, part: Part, input: &str) -> Result<Answer> {
+    solve_with_progress(day, part, input, &Silent)
+}
+
+/// As [`solve`], seeding the days which search randomly with `seed`.
+///
+/// Other days ignore the seed. [`solve`] always uses [`util::rng::DEFAULT_SEED`].
+pub fn solve_with_seed(day: u8, part: Part, input: &str, seed: u64) -> Result<Answer> {
+    match (day, part) {
+        (15, Part::One) => Ok(day15::part1_with_seed(input.as_bytes(), seed)?),
+        _ => solve(day, part, input),
+    }
+}
+
+/// As [`solve_with_seed`], solving with the algorithm named `algo`.
+///
+/// Only days with several algorithms can be made to use one; see [`util::algo`]. Other days ignore
+/// `algo`. The rest choose the algorithm which best suits the input unless made to use another.
+pub fn solve_with_algo(day: u8, part: Part, input: &str, seed: u64, algo: &str) -> Result<Answer> {
+    let algo = Some(algo);
+    let answer = match (day, part) {
+        (9, Part::One) => day09::part1_with_algo(input.as_bytes(), algo),
+        (9, Part::Two) => day09::part2_with_algo(input.as_bytes(), algo),
+        (13, Part::One) => day13::part1_with_algo(input.as_bytes(), algo),
+        (13, Part::Two) => day13::part2_with_algo(input.as_bytes(), algo),
+        (15, Part::One) => day15::part1_with_algo(input.as_bytes(), seed, algo),
+        (15, Part::Two) => day15::part2_with_algo(input.as_bytes(), algo),
+        (20, Part::One) => day20::part1_with_algo(input.as_bytes(), &Silent, algo),
+        (20, Part::Two) => day20::part2_with_algo(input.as_bytes(), &Silent, algo),
+        _ => return solve_with_seed(day, part, input, seed),
+    };
+    Ok(answer?)
+}
+
+/// As [`solve`], reporting the progress of the slow days to `progress`.
+///
+/// Days which don't report progress solve exactly as [`solve`] does, and never call `progress`.
+pub fn solve_with_progress(
+    day: u8,
+    part: Part,
+    input: &str,
+    progress: &dyn Progress,
+) -> Result<Answer> {
+    let answer = match (day, part) {
+        (4, Part::One) => day04::part1_with_progress(input.as_bytes(), progress),
+        (4, Part::Two) => day04::part2_with_progress(input.as_bytes(), progress),
+        (20, Part::One) => day20::part1_with_progress(input.as_bytes(), progress),
+        (20, Part::Two) => day20::part2_with_progress(input.as_bytes(), progress),
+        (22, Part::One) => day22::part1_with_progress(input.as_bytes(), progress),
+        (22, Part::Two) => day22::part2_with_progress(input.as_bytes(), progress),
+        (24, Part::One) => day24::part1_with_progress(input.as_bytes(), progress),
+        (24, Part::Two) => day24::part2_with_progress(input.as_bytes(), progress),
+        (25, Part::One) => day25::part1_str(input),
+        // christmas day has only a single part
+        (25, Part::Two) => return Err(Error::Unimplemented { day, part }),
+        _ => dispatch!(
+        day, part, input;
+        1 => day01,
+        2 => day02,
+        3 => day03,
+        5 => day05,
+        6 => day06,
+        7 => day07,
+        8 => day08,
+        9 => day09,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        13 => day13,
+        14 => day14,
+        15 => day15,
+        16 => day16,
+        17 => day17,
+        18 => day18,
+        19 => day19,
+        21 => day21,
+        23 => day23,
+        ),
+    };
+    Ok(answer?)
+}
+
+/// The worked examples from the puzzle text for the given day.
+///
+/// Days whose puzzle text works through no example have none.
+pub fn examples(day: u8) -> &'static [Example] {
+    match day {
+        1 => day01::EXAMPLES,
+        2 => day02::EXAMPLES,
+        3 => day03::EXAMPLES,
+        4 => day04::EXAMPLES,
+        5 => day05::EXAMPLES,
+        6 => day06::EXAMPLES,
+        7 => day07::EXAMPLES,
+        8 => day08::EXAMPLES,
+        9 => day09::EXAMPLES,
+        10 => day10::EXAMPLES,
+        11 => day11::EXAMPLES,
+        12 => day12::EXAMPLES,
+        13 => day13::EXAMPLES,
+        14 => day14::EXAMPLES,
+        15 => day15::EXAMPLES,
+        16 => day16::EXAMPLES,
+        17 => day17::EXAMPLES,
+        18 => day18::EXAMPLES,
+        19 => day19::EXAMPLES,
+        20 => day20::EXAMPLES,
+        21 => day21::EXAMPLES,
+        22 => day22::EXAMPLES,
+        23 => day23::EXAMPLES,
+        24 => day24::EXAMPLES,
+        25 => day25::EXAMPLES,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(1, Part::Two, "()())").unwrap().to_string(), "5");
+        assert_eq!(solve(2, Part::One, "2x3x4\n").unwrap().to_string(), "58");
+        let manual = "To continue, please consult the code grid in the manual.  \
+                      Enter the code at row 1, column 1.";
+        assert_eq!(
+            solve(25, Part::One, manual).unwrap().to_string(),
+            "20151125"
+        );
+    }
+
+    #[test]
+    fn test_progress_reported() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        #[derive(Default)]
+        struct Finished(AtomicBool);
+
+        impl Progress for Finished {
+            fn advance(&self, _done: u64) {}
+
+            fn finish(&self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let progress = Finished::default();
+        let answer = solve_with_progress(20, Part::One, "150", &progress).unwrap();
+        assert_eq!(answer.to_string(), "8");
+        assert!(progress.0.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_solve_with_seed() {
+        let input = "\
+Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
+Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3
+";
+        for seed in 0..4 {
+            let answer = solve_with_seed(15, Part::One, input, seed).unwrap();
+            assert_eq!(answer, solve(15, Part::One, input).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_solve_with_algo() {
+        let input = "\
+London to Dublin = 464
+London to Belfast = 518
+Dublin to Belfast = 141
+";
+        for algo in &["permutations", "held-karp"] {
+            let answer = solve_with_algo(9, Part::Two, input, 0, algo).unwrap();
+            assert_eq!(answer.to_string(), "982");
+        }
+        assert!(solve_with_algo(9, Part::One, input, 0, "guesswork").is_err());
+        // days with a single algorithm ignore it
+        assert_eq!(
+            solve_with_algo(1, Part::One, "(((", 0, "guesswork").unwrap(),
+            solve(1, Part::One, "(((").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unimplemented() {
+        for &(day, part) in &[(0, Part::One), (25, Part::Two), (26, Part::Two)] {
+            match solve(day, part, "") {
+                Err(Error::Unimplemented { day: d, part: p }) => assert_eq!((d, p), (day, part)),
+                other => panic!("expected unimplemented, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_error() {
+        assert!(matches!(
+            solve(20, Part::One, ""),
+            Err(Error::Solve(util::Error::NoInput))
+        ));
+    }
+
+    #[test]
+    fn test_examples() {
+        for day in DAYS {
+            for example in examples(day) {
+                assert!(parts_of(day)
+                    .iter()
+                    .any(|part| part.number() == example.part));
+                if day == 4 && cfg!(debug_assertions) {
+                    // mining is too slow unoptimized
+                    continue;
+                }
+                let answer = example.solve().unwrap();
+                assert!(
+                    example.is_expected(&answer),
+                    "day {} part {}: expected {}, got {}",
+                    day,
+                    example.part,
+                    example.expected,
+                    answer
+                );
+            }
+        }
+        assert!(examples(26).is_empty());
+    }
+
+    #[test]
+    fn test_part_from_str() {
+        assert_eq!("2".parse::<Part>().unwrap(), Part::Two);
+        assert!("3".parse::<Part>().is_err());
+    }
+}
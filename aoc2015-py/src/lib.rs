@@ -0,0 +1,169 @@
+//! Python bindings for every day's solutions.
+//!
+//! Each day is a submodule taking the puzzle input as a string, with descriptively-named
+//! functions as well as `part1` and `part2`:
+//!
+//! ```python
+//! import aoc2015
+//! aoc2015.day09.shortest_route(open("input-2015-09.txt").read())
+//! ```
+//!
+//! Build with `maturin develop` from this directory.
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use util::Answer;
+
+/// An answer, converted to a Python `int` or `str`.
+struct PyAnswer(Answer);
+
+impl IntoPy<PyObject> for PyAnswer {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        match self.0 {
+            Answer::Int(n) => n.into_py(py),
+            Answer::Str(s) => s.into_py(py),
+        }
+    }
+}
+
+/// Convert a solver's result, raising `ValueError` on failure.
+fn convert<E: std::fmt::Display>(result: Result<Answer, E>) -> PyResult<PyAnswer> {
+    result
+        .map(PyAnswer)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+macro_rules! day_module {
+    ($krate:ident: $part1:ident $(, $part2:ident)?) => {
+        mod $krate {
+            use super::*;
+
+            #[pyfunction]
+            pub fn $part1(input: &str) -> PyResult<PyAnswer> {
+                convert(::$krate::part1_str(input))
+            }
+
+            $(
+                #[pyfunction]
+                pub fn $part2(input: &str) -> PyResult<PyAnswer> {
+                    convert(::$krate::part2_str(input))
+                }
+            )?
+
+            pub fn module(py: Python<'_>) -> PyResult<&PyModule> {
+                let module = PyModule::new(py, stringify!($krate))?;
+                module.add_function(wrap_pyfunction!($part1, module)?)?;
+                module.add("part1", module.getattr(stringify!($part1))?)?;
+                $(
+                    module.add_function(wrap_pyfunction!($part2, module)?)?;
+                    module.add("part2", module.getattr(stringify!($part2))?)?;
+                )?
+                Ok(module)
+            }
+        }
+    };
+}
+
+day_module!(day01: final_floor, basement_entry);
+day_module!(day02: wrapping_paper, ribbon);
+day_module!(day03: houses_visited, houses_visited_with_robot);
+day_module!(day04: coin_five_zeros, coin_six_zeros);
+day_module!(day05: nice_strings, nice_strings_revised);
+day_module!(day06: lights_lit, total_brightness);
+day_module!(day07: signal_a, signal_a_overridden);
+day_module!(day08: unescape_overhead, escape_overhead);
+day_module!(day09: shortest_route, longest_route);
+day_module!(day10: look_and_say_40, look_and_say_50);
+day_module!(day11: next_password, next_password_after);
+day_module!(day12: sum_numbers, sum_numbers_without_red);
+day_module!(day13: optimal_happiness, optimal_happiness_with_self);
+day_module!(day14: winning_distance, winning_points);
+day_module!(day15: best_cookie, best_cookie_500_calories);
+day_module!(day16: aunt_sue, real_aunt_sue);
+day_module!(day17: container_combinations, min_container_combinations);
+day_module!(day18: lights_on, lights_on_stuck_corners);
+day_module!(day19: distinct_molecules, fabrication_steps);
+day_module!(day20: first_house, first_house_lazy_elves);
+day_module!(day21: cheapest_win, priciest_loss);
+day_module!(day22: least_mana, least_mana_hard);
+day_module!(day23: register_b, register_b_from_a1);
+day_module!(day24: ideal_entanglement, ideal_entanglement_with_trunk);
+// christmas day has only a single part
+day_module!(day25: code);
+
+#[pymodule]
+fn aoc2015(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    for module in [
+        day01::module(py)?,
+        day02::module(py)?,
+        day03::module(py)?,
+        day04::module(py)?,
+        day05::module(py)?,
+        day06::module(py)?,
+        day07::module(py)?,
+        day08::module(py)?,
+        day09::module(py)?,
+        day10::module(py)?,
+        day11::module(py)?,
+        day12::module(py)?,
+        day13::module(py)?,
+        day14::module(py)?,
+        day15::module(py)?,
+        day16::module(py)?,
+        day17::module(py)?,
+        day18::module(py)?,
+        day19::module(py)?,
+        day20::module(py)?,
+        day21::module(py)?,
+        day22::module(py)?,
+        day23::module(py)?,
+        day24::module(py)?,
+        day25::module(py)?,
+    ]
+    .iter()
+    {
+        m.add_submodule(module)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    fn eval(code: &str) -> PyResult<String> {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "aoc2015")?;
+            aoc2015(py, module)?;
+            let locals = PyDict::new(py);
+            locals.set_item("aoc2015", module)?;
+            Ok(py.eval(code, None, Some(locals))?.repr()?.to_string())
+        })
+    }
+
+    #[test]
+    fn test_typed_answers() {
+        let routes = "London to Dublin = 464\\nLondon to Belfast = 518\\nDublin to Belfast = 141";
+        assert_eq!(
+            eval(&format!("aoc2015.day09.shortest_route('{}')", routes)).unwrap(),
+            "605"
+        );
+        assert_eq!(
+            eval(&format!("aoc2015.day09.part2('{}')", routes)).unwrap(),
+            "982"
+        );
+        assert_eq!(
+            eval("aoc2015.day11.next_password('abcdefgh')").unwrap(),
+            "'abcdffaa'"
+        );
+    }
+
+    #[test]
+    fn test_errors_raise() {
+        let err = eval("aoc2015.day03.houses_visited('x')").unwrap_err();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| assert!(err.is_instance_of::<PyValueError>(py)));
+        assert!(eval("aoc2015.day25.part2").is_err());
+    }
+}
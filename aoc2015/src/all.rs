@@ -0,0 +1,157 @@
+//! Run every day at once.
+
+use crate::days::{parts_of, solve, Part, DAYS};
+use rayon::prelude::*;
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+    time::Duration,
+};
+use util::{
+    color::{Color, Painter},
+    output::Record,
+    timing::format_duration,
+    verify::{ExpectedAnswers, Verdict},
+    Answer, Timed,
+};
+
+/// How a single part turned out.
+#[derive(Debug)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: Part,
+    pub result: Result<Timed<Answer>, String>,
+}
+
+impl Outcome {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    pub fn record(&self) -> Record {
+        let part = self.part.number();
+        match &self.result {
+            Ok(timed) => Record::solved(self.day, part, timed),
+            Err(err) => Record::failed(self.day, part, err),
+        }
+    }
+}
+
+/// Describe a panic payload.
+pub fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    let message = if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    };
+    format!("panicked: {}", message)
+}
+
+/// Solve one part against its input, turning errors and panics into a failed outcome.
+pub fn run_part(day: u8, part: Part, input: &Result<PathBuf, String>) -> Outcome {
+    let result = match input {
+        Ok(input) => {
+            match catch_unwind(AssertUnwindSafe(|| {
+                Timed::measure(|| solve(day, part, input)).transpose()
+            })) {
+                Ok(Ok(timed)) => Ok(timed),
+                Ok(Err(err)) => Err(err.to_string()),
+                Err(payload) => Err(panic_message(payload)),
+            }
+        }
+        Err(err) => Err(err.clone()),
+    };
+    Outcome { day, part, result }
+}
+
+/// Solve every part of every day concurrently.
+///
+/// A failure in one part doesn't prevent the others from running. Outcomes are returned in order
+/// of day and part.
+pub fn run_all() -> Vec<Outcome> {
+    DAYS.into_par_iter()
+        .flat_map(|day| {
+            let input = util::config::input_for(day).map_err(|err| err.to_string());
+            parts_of(day)
+                .par_iter()
+                .map(|&part| run_part(day, part, &input))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Treat any answer which doesn't match the expected answer as a failure.
+pub fn check(outcomes: &mut [Outcome], expected: &ExpectedAnswers) {
+    for outcome in outcomes {
+        if let Ok(timed) = &outcome.result {
+            let verdict = expected.check(outcome.day, outcome.part.number(), &timed.value);
+            if let Verdict::Incorrect { expected } = verdict {
+                outcome.result = Err(format!("expected {}, got {}", expected, timed.value));
+            }
+        }
+    }
+}
+
+/// Print one JSON record per outcome.
+pub fn print_json(outcomes: &[Outcome]) {
+    for outcome in outcomes {
+        println!("{}", outcome.record().to_json());
+    }
+}
+
+/// Print a table summarizing `outcomes`.
+pub fn print_summary(outcomes: &[Outcome]) {
+    let painter = Painter::stdout();
+    let answer_width = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.result.as_ref().ok())
+        .map(|timed| timed.value.to_string().len())
+        .max()
+        .unwrap_or_default()
+        .max("answer".len());
+
+    println!(
+        "{:<4} {:>3} {:>4}  {:>width$}  {:>10}",
+        "",
+        "day",
+        "part",
+        "answer",
+        "time",
+        width = answer_width
+    );
+    for outcome in outcomes {
+        match &outcome.result {
+            Ok(timed) => println!(
+                "{} {:>3} {:>4}  {:>width$}  {:>10}",
+                painter.paint(format!("{:<4}", "ok"), Color::Green),
+                outcome.day,
+                outcome.part,
+                timed.value.to_string(),
+                format_duration(timed.duration),
+                width = answer_width
+            ),
+            Err(err) => println!(
+                "{} {:>3} {:>4}  {}",
+                painter.paint(format!("{:<4}", "FAIL"), Color::Red),
+                outcome.day,
+                outcome.part,
+                err
+            ),
+        }
+    }
+
+    let total: Duration = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.result.as_ref().ok())
+        .map(|timed| timed.duration)
+        .sum();
+    let failures = outcomes.iter().filter(|outcome| !outcome.is_ok()).count();
+    println!(
+        "{} parts solved, {} failed; {} of solver time",
+        outcomes.len() - failures,
+        failures,
+        format_duration(total)
+    );
+}
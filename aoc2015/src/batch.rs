@@ -0,0 +1,139 @@
+//! Run one day against every input in a directory.
+
+use crate::{
+    all::{run_part, Outcome},
+    days::Part,
+};
+use rayon::prelude::*;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use util::{
+    color::{Color, Painter},
+    timing::format_duration,
+};
+
+/// How a single part turned out for a single input file.
+#[derive(Debug)]
+pub struct FileOutcome {
+    pub file: PathBuf,
+    pub outcome: Outcome,
+}
+
+impl FileOutcome {
+    /// The name of the input file, without its directory.
+    fn name(&self) -> String {
+        self.file
+            .file_name()
+            .unwrap_or_else(|| self.file.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Every file in `dir`, sorted by name. Subdirectories are skipped.
+pub fn inputs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Solve the given parts of `day` against every file concurrently.
+///
+/// A failure for one file doesn't prevent the others from running. Outcomes are returned in the
+/// order of `files`, then of `parts`.
+pub fn run_files(day: u8, parts: &[Part], files: &[PathBuf]) -> Vec<FileOutcome> {
+    files
+        .par_iter()
+        .flat_map(|file| {
+            let input = Ok(file.clone());
+            parts
+                .par_iter()
+                .map(|&part| FileOutcome {
+                    file: file.clone(),
+                    outcome: run_part(day, part, &input),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Print a line per outcome, labeled by its input file.
+pub fn print_text(outcomes: &[FileOutcome], time: bool) {
+    let painter = Painter::stdout();
+    let name_width = outcomes
+        .iter()
+        .map(|outcome| outcome.name().len())
+        .max()
+        .unwrap_or_default();
+
+    for file_outcome in outcomes {
+        let label = format!(
+            "{:<width$}  part {}:",
+            file_outcome.name(),
+            file_outcome.outcome.part,
+            width = name_width
+        );
+        let ok = painter.paint(format!("{:<4}", "ok"), Color::Green);
+        match &file_outcome.outcome.result {
+            Ok(timed) if time => println!(
+                "{} {} {} ({})",
+                ok,
+                label,
+                timed.value,
+                format_duration(timed.duration)
+            ),
+            Ok(timed) => println!("{} {} {}", ok, label, timed.value),
+            Err(err) => println!(
+                "{} {} {}",
+                painter.paint(format!("{:<4}", "FAIL"), Color::Red),
+                label,
+                err
+            ),
+        }
+    }
+}
+
+/// Print one JSON record per outcome, including its input file.
+pub fn print_json(outcomes: &[FileOutcome]) {
+    for file_outcome in outcomes {
+        let record = file_outcome
+            .outcome
+            .record()
+            .with_input(file_outcome.file.display());
+        println!("{}", record.to_json());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_files() {
+        let dir = std::env::temp_dir().join("aoc2015-batch-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("subdir")).unwrap();
+        std::fs::write(dir.join("bob.txt"), "150\n").unwrap();
+        std::fs::write(dir.join("alice.txt"), "").unwrap();
+
+        let files = inputs(&dir).unwrap();
+        assert_eq!(files, vec![dir.join("alice.txt"), dir.join("bob.txt")]);
+
+        let outcomes = run_files(20, &[Part::One], &files);
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[0].outcome.result.is_err());
+        let bob = outcomes[1].outcome.result.as_ref().unwrap();
+        assert_eq!(bob.value.to_string(), "8");
+        assert_eq!(outcomes[1].name(), "bob.txt");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+use color_eyre::eyre::{bail, Result};
+use std::{io::Read, path::Path};
+use util::{lint::Diagnostic, Answer, InputSource};
+
+pub use aoc2015_all::{parts_of, Part, DAYS};
+
+/// Solve the given part of the given day's puzzle for the input file at `input`.
+pub fn solve(day: u8, part: Part, input: &Path) -> Result<Answer> {
+    solve_reader(
+        day,
+        part,
+        util::rng::DEFAULT_SEED,
+        None,
+        InputSource::File(input.to_path_buf()).reader()?,
+    )
+}
+
+/// Solve the given part of the given day's puzzle, reading the input from `input`.
+///
+/// Days which search randomly are seeded with `seed`. Days with several algorithms use the one
+/// named `algo`, if given.
+pub fn solve_reader(
+    day: u8,
+    part: Part,
+    seed: u64,
+    algo: Option<&str>,
+    input: impl Read,
+) -> Result<Answer> {
+    let input = util::input::read_all(input)?;
+    Ok(match algo {
+        Some(algo) => aoc2015_all::solve_with_algo(day, part, &input, seed, algo)?,
+        None => aoc2015_all::solve_with_seed(day, part, &input, seed)?,
+    })
+}
+
+/// Report every malformed line of `input` for the given day.
+///
+/// Only days whose input is parsed line by line can be linted.
+pub fn lint(day: u8, input: &str) -> Result<Vec<Diagnostic>> {
+    Ok(match day {
+        6 => day06::lint(input),
+        9 => day09::lint(input),
+        13 => day13::lint(input),
+        14 => day14::lint(input),
+        16 => day16::lint(input),
+        19 => day19::lint(input),
+        21 => day21::lint(input),
+        23 => day23::lint(input),
+        _ => bail!("day {} has no linter", day),
+    })
+}
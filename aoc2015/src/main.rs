@@ -0,0 +1,295 @@
+mod all;
+mod batch;
+mod days;
+mod selftest;
+
+use days::{lint, parts_of, solve_reader, Part, DAYS};
+
+use color_eyre::eyre::{bail, Result};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use util::{
+    cache::Cache,
+    cli::{CacheArgs, CheckArgs, RunArgs},
+    color::Painter,
+    config::Config,
+    output::OutputFormat,
+    submit::{submit, Rejection, Rejections},
+    verify::{ExpectedAnswers, Verdict},
+};
+
+/// Advent of Code 2015
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// run a single day's solution
+    Run {
+        /// day to run
+        day: u8,
+
+        /// run against every file in this directory, reporting a result per file
+        #[structopt(long, parse(from_os_str), conflicts_with = "input")]
+        input_dir: Option<PathBuf>,
+
+        #[structopt(flatten)]
+        args: RunArgs,
+    },
+    /// run every day concurrently, summarizing the results
+    All {
+        /// output format: `text`, or `json` for one record per part
+        #[structopt(long, default_value = "text")]
+        output: OutputFormat,
+
+        #[structopt(flatten)]
+        check: CheckArgs,
+
+        /// log what the solvers are doing to stderr; repeat for more detail
+        #[structopt(short, long, parse(from_occurrences))]
+        verbose: u8,
+    },
+    /// check every day against the worked examples from its puzzle text
+    Selftest,
+    /// solve a single part and submit the answer to adventofcode.com
+    Submit {
+        /// day to submit
+        day: u8,
+
+        /// part to submit: 1 or 2
+        part: Part,
+
+        /// input file, or `-` to read standard input
+        #[structopt(long, parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// file of expected answers; an answer which disagrees with it isn't submitted
+        #[structopt(long, parse(from_os_str), default_value = "answers.toml")]
+        answers: PathBuf,
+
+        /// also where the answers already rejected by the site are kept
+        #[structopt(flatten)]
+        cache: CacheArgs,
+
+        /// log what the solvers are doing to stderr; repeat for more detail
+        #[structopt(short, long, parse(from_occurrences))]
+        verbose: u8,
+    },
+    /// print a random, valid input for a day
+    Gen {
+        /// day to generate input for
+        day: u8,
+
+        /// size of the input; what this counts depends on the day
+        #[structopt(long, default_value = "100")]
+        size: usize,
+
+        /// seed for the generator; chosen at random and reported on stderr if omitted
+        #[structopt(long)]
+        seed: Option<u64>,
+    },
+    /// report every malformed line of a day's input
+    Lint {
+        /// day to check
+        day: u8,
+
+        /// input file, or `-` to read standard input
+        #[structopt(long, parse(from_os_str))]
+        input: Option<PathBuf>,
+    },
+    /// manage the cache of answers
+    Cache {
+        #[structopt(subcommand)]
+        command: CacheCommand,
+    },
+}
+
+#[derive(StructOpt, Debug)]
+enum CacheCommand {
+    /// forget every cached answer
+    Clear {
+        /// directory in which answers are cached
+        #[structopt(long, parse(from_os_str), default_value = util::cache::DEFAULT_DIR)]
+        cache_dir: PathBuf,
+    },
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    match Command::from_args() {
+        Command::Run {
+            day,
+            input_dir: Some(input_dir),
+            args,
+        } => {
+            if !DAYS.contains(&day) {
+                bail!("day must be in {}..={}", DAYS.start(), DAYS.end());
+            }
+            if args.check.check {
+                bail!("inputs in a directory have no expected answers to check");
+            }
+            args.init_tracing();
+            let parts: Vec<Part> = parts_of(day)
+                .iter()
+                .copied()
+                .filter(|part| match part {
+                    Part::One => !args.no_part1,
+                    Part::Two => args.part2,
+                })
+                .collect();
+            let files = batch::inputs(&input_dir)?;
+            if files.is_empty() {
+                bail!("no input files in {}", input_dir.display());
+            }
+            let outcomes = batch::run_files(day, &parts, &files);
+            match args.output {
+                OutputFormat::Text => batch::print_text(&outcomes, args.time),
+                OutputFormat::Json => batch::print_json(&outcomes),
+            }
+            let failures = outcomes
+                .iter()
+                .filter(|file_outcome| !file_outcome.outcome.is_ok())
+                .count();
+            if failures > 0 {
+                bail!("{} of {} parts failed", failures, outcomes.len());
+            }
+        }
+        Command::Run {
+            day,
+            input_dir: None,
+            args,
+        } => {
+            if !DAYS.contains(&day) {
+                bail!("day must be in {}..={}", DAYS.start(), DAYS.end());
+            }
+            args.init_tracing();
+            let input = args.source(day)?;
+            let seed = args.seed();
+            let algo = args.force_algo();
+            let answers = args.run(
+                day,
+                "",
+                &input,
+                |input| solve_reader(day, Part::One, seed, algo, input),
+                |input| solve_reader(day, Part::Two, seed, algo, input),
+            )?;
+            args.check(day, &answers)?;
+        }
+        Command::All {
+            output,
+            check,
+            verbose,
+        } => {
+            util::trace::init(verbose);
+            let mut outcomes = all::run_all();
+            if let Some(expected) = check.expected()? {
+                all::check(&mut outcomes, &expected);
+            }
+            match output {
+                OutputFormat::Text => all::print_summary(&outcomes),
+                OutputFormat::Json => all::print_json(&outcomes),
+            }
+            let failures = outcomes.iter().filter(|outcome| !outcome.is_ok()).count();
+            if failures > 0 {
+                bail!("{} of {} parts failed", failures, outcomes.len());
+            }
+        }
+        Command::Selftest => {
+            let outcomes = selftest::run_examples();
+            selftest::print_matrix(&outcomes);
+            let failures = outcomes.iter().filter(|outcome| !outcome.is_ok()).count();
+            if failures > 0 {
+                bail!("{} of {} examples failed", failures, outcomes.len());
+            }
+        }
+        Command::Submit {
+            day,
+            part,
+            input,
+            answers,
+            cache,
+            verbose,
+        } => {
+            if !DAYS.contains(&day) {
+                bail!("day must be in {}..={}", DAYS.start(), DAYS.end());
+            }
+            let rejections = Rejections::new(&cache.cache_dir);
+            let args = RunArgs {
+                input,
+                cache,
+                verbose,
+                ..RunArgs::default()
+            };
+            args.init_tracing();
+            let input = args.source(day)?;
+            let answer = args.solve(day, part.number(), "", &input, |input| {
+                solve_reader(day, part, args.seed(), args.force_algo(), input)
+            })?;
+
+            let painter = Painter::stdout();
+            if answers.exists() {
+                let expected = ExpectedAnswers::load(&answers)?;
+                if let Verdict::Incorrect { expected } = expected.check(day, part.number(), &answer)
+                {
+                    println!(
+                        "{}",
+                        painter.failure(format!("{} expects {}", answers.display(), expected))
+                    );
+                    bail!("not submitting an answer known to be wrong");
+                }
+            }
+            if let Some(rejection) = rejections.ruling_out(day, part.number(), &answer) {
+                println!("{}", painter.failure(&rejection));
+                bail!("not submitting an answer known to be wrong");
+            }
+
+            println!(
+                "submitting {} for day {} part {}",
+                answer,
+                day,
+                part.number()
+            );
+            let config = Config::load()?;
+            let response = submit(&config, day, part.number(), &answer)?;
+            if response.is_correct() {
+                println!("{}", painter.success(&response));
+            } else {
+                println!("{}", painter.failure(&response));
+                if let Some(rejection) = Rejection::from_response(&answer, &response) {
+                    rejections.record(day, part.number(), rejection)?;
+                }
+                bail!("answer was not accepted");
+            }
+        }
+        Command::Gen { day, size, seed } => {
+            let seed = seed.unwrap_or_else(|| {
+                let seed = rand::random();
+                eprintln!("seed: {}", seed);
+                seed
+            });
+            print!("{}", inputgen::generate(day, size, seed)?);
+        }
+        Command::Lint { day, input } => {
+            let args = RunArgs {
+                input,
+                ..RunArgs::default()
+            };
+            let text = args.source(day)?.read_to_string()?;
+            let diagnostics = lint(day, &text)?;
+            let painter = Painter::stdout();
+            if diagnostics.is_empty() {
+                println!("{}", painter.success("no problems found"));
+            } else {
+                for diagnostic in &diagnostics {
+                    println!("{}\n", diagnostic);
+                }
+                bail!("{} malformed line(s)", diagnostics.len());
+            }
+        }
+        Command::Cache {
+            command: CacheCommand::Clear { cache_dir },
+        } => {
+            let removed = Cache::new(cache_dir).clear()?;
+            println!("removed {} cached answer(s)", removed);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Check every day against the worked examples from its puzzle text.
+
+use crate::{
+    all::panic_message,
+    days::{parts_of, Part, DAYS},
+};
+use aoc2015_all::{examples, Example};
+use rayon::prelude::*;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use util::color::{Color, Painter};
+
+/// How a single example turned out.
+#[derive(Debug)]
+pub struct ExampleOutcome {
+    pub day: u8,
+    pub part: u8,
+    /// Which of the day's examples this was, counting from 1.
+    pub number: usize,
+    pub result: Result<(), String>,
+}
+
+impl ExampleOutcome {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Solve an example, turning wrong answers, errors, and panics into a message.
+fn check(example: &Example) -> Result<(), String> {
+    match catch_unwind(AssertUnwindSafe(|| example.solve())) {
+        Ok(Ok(answer)) if example.is_expected(&answer) => Ok(()),
+        Ok(Ok(answer)) => Err(format!("expected {}, got {}", example.expected, answer)),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(payload) => Err(panic_message(payload)),
+    }
+}
+
+/// Check every example of every day concurrently.
+///
+/// Outcomes are returned in order of day, then in the order each day lists its examples.
+pub fn run_examples() -> Vec<ExampleOutcome> {
+    let examples: Vec<_> = DAYS
+        .flat_map(|day| {
+            examples(day)
+                .iter()
+                .enumerate()
+                .map(move |(idx, example)| (day, idx + 1, example))
+        })
+        .collect();
+    examples
+        .into_par_iter()
+        .map(|(day, number, example)| ExampleOutcome {
+            day,
+            part: example.part,
+            number,
+            result: check(example),
+        })
+        .collect()
+}
+
+/// Print a matrix of days by parts, showing how many examples passed, followed by every failure.
+pub fn print_matrix(outcomes: &[ExampleOutcome]) {
+    let painter = Painter::stdout();
+    println!("{:>3}  {:<8}  {:<8}", "day", "part 1", "part 2");
+    for day in DAYS {
+        let cells: Vec<String> = [Part::One, Part::Two]
+            .iter()
+            .map(|part| {
+                let of_part: Vec<_> = outcomes
+                    .iter()
+                    .filter(|outcome| outcome.day == day && outcome.part == part.number())
+                    .collect();
+                let passed = of_part.iter().filter(|outcome| outcome.is_ok()).count();
+                let cell = format!("{}/{}", passed, of_part.len());
+                if of_part.is_empty() {
+                    let absent = if parts_of(day).contains(part) {
+                        "-"
+                    } else {
+                        ""
+                    };
+                    format!("{:<8}", absent)
+                } else if passed == of_part.len() {
+                    painter.paint(format!("{:<8}", format!("ok {}", cell)), Color::Green)
+                } else {
+                    painter.paint(format!("{:<8}", format!("FAIL {}", cell)), Color::Red)
+                }
+            })
+            .collect();
+        println!("{:>3}  {}  {}", day, cells[0], cells[1]);
+    }
+
+    for outcome in outcomes {
+        if let Err(err) = &outcome.result {
+            println!(
+                "{} day {} part {} example {}: {}",
+                painter.paint("FAIL", Color::Red),
+                outcome.day,
+                outcome.part,
+                outcome.number,
+                err
+            );
+        }
+    }
+
+    let failures = outcomes.iter().filter(|outcome| !outcome.is_ok()).count();
+    println!(
+        "{} examples passed, {} failed",
+        outcomes.len() - failures,
+        failures
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::{Answer, Error};
+
+    fn wrong(_: &str) -> Result<Answer, Error> {
+        Ok(Answer::Int(2))
+    }
+
+    fn fails(_: &str) -> Result<Answer, Error> {
+        Err(Error::NoInput)
+    }
+
+    fn panics(_: &str) -> Result<Answer, Error> {
+        panic!("oh no")
+    }
+
+    #[test]
+    fn test_check() {
+        let example = |solver| Example {
+            part: 1,
+            input: "",
+            expected: "1",
+            solver,
+        };
+        assert_eq!(check(&example(wrong)), Err("expected 1, got 2".into()));
+        assert!(check(&example(fails)).is_err());
+        assert_eq!(check(&example(panics)), Err("panicked: oh no".to_string()));
+        assert_eq!(check(&aoc2015_all::examples(1)[0]), Ok(()));
+    }
+}
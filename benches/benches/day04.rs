@@ -0,0 +1,38 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day04::{mine_coin, mine_coin_sequential, mine_coin_with_cores};
+
+const SECRET: &str = "abcdef";
+
+fn mining(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day04");
+    group.sample_size(10);
+    for leading_zeros in [4, 5].iter() {
+        group.bench_with_input(
+            BenchmarkId::new("parallel", leading_zeros),
+            leading_zeros,
+            |b, &leading_zeros| b.iter(|| mine_coin(SECRET, leading_zeros)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("sequential", leading_zeros),
+            leading_zeros,
+            |b, &leading_zeros| b.iter(|| mine_coin_sequential(SECRET, leading_zeros)),
+        );
+    }
+    group.finish();
+}
+
+fn scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day04 threads");
+    group.sample_size(10);
+    for threads in [1, 2, 4].iter() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            threads,
+            |b, &threads| b.iter(|| mine_coin_with_cores(SECRET, 5, threads)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, mining, scaling);
+criterion_main!(benches);
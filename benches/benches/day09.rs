@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day09::Routes;
+use std::cmp::Ordering;
+
+fn route_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day09");
+    for places in [6, 8].iter() {
+        let routes: Routes = inputgen::generate(9, *places, 0)
+            .expect("day 9 is supported")
+            .parse()
+            .expect("generated input is valid");
+        group.bench_with_input(
+            BenchmarkId::new("separate", places),
+            &routes,
+            |b, routes| b.iter(|| (routes.find_shortest(), routes.find_longest())),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("single pass", places),
+            &routes,
+            |b, routes| b.iter(|| routes.find_extremes()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("held-karp", places),
+            &routes,
+            |b, routes| {
+                b.iter(|| {
+                    (
+                        routes.held_karp(Ordering::Less).unwrap(),
+                        routes.held_karp(Ordering::Greater).unwrap(),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, route_search);
+criterion_main!(benches);
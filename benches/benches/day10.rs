@@ -0,0 +1,22 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day10::{look_and_say_digits_n, look_and_say_n};
+
+const SEQUENCE: &str = "1113222113";
+
+fn look_and_say(c: &mut Criterion) {
+    let digits: Vec<u8> = SEQUENCE.bytes().map(|b| b - b'0').collect();
+
+    let mut group = c.benchmark_group("day10");
+    for rounds in [20, 30, 40].iter() {
+        group.bench_with_input(BenchmarkId::new("string", rounds), rounds, |b, &rounds| {
+            b.iter(|| look_and_say_n(SEQUENCE, rounds))
+        });
+        group.bench_with_input(BenchmarkId::new("digits", rounds), rounds, |b, &rounds| {
+            b.iter(|| look_and_say_digits_n(&digits, rounds))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, look_and_say);
+criterion_main!(benches);
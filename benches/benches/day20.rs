@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day20::{first_house_by_factoring, first_house_with_n_presents};
+
+fn first_house(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day20");
+    group.sample_size(10);
+    for presents in [10_000, 100_000, 1_000_000].iter() {
+        group.bench_with_input(
+            BenchmarkId::new("house array", presents),
+            presents,
+            |b, &presents| b.iter(|| first_house_with_n_presents(presents)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("factoring", presents),
+            presents,
+            |b, &presents| b.iter(|| first_house_by_factoring(presents)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, first_house);
+criterion_main!(benches);
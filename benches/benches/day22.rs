@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day22::{
+    breadth_first_victory_search_with_difficulty, cheapest_first_victory_search, Arena, Character,
+};
+
+fn arena_search(c: &mut Criterion) {
+    let arena = Arena::new(Character::player(), Character::boss());
+
+    let mut group = c.benchmark_group("day22");
+    group.sample_size(10);
+    for &(name, hard) in [("easy", false), ("hard", true)].iter() {
+        group.bench_function(format!("breadth first/{}", name), |b| {
+            b.iter(|| breadth_first_victory_search_with_difficulty(arena.clone(), hard))
+        });
+        group.bench_function(format!("cheapest first/{}", name), |b| {
+            b.iter(|| cheapest_first_victory_search(arena.clone(), hard))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, arena_search);
+criterion_main!(benches);
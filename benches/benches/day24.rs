@@ -0,0 +1,19 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// A puzzle-shaped input: distinct package weights, mostly primes.
+const PACKAGES: &str = "1\n2\n3\n7\n11\n13\n17\n19\n23\n31\n37\n41\n43\n47\n53\n59\n61\n67\n71\n73\n79\n83\n89\n97\n101\n103\n107\n109\n113\n";
+
+fn packing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day24");
+    group.sample_size(10);
+    group.bench_function("three compartments", |b| {
+        b.iter(|| day24::part1_str(PACKAGES))
+    });
+    group.bench_function("four compartments", |b| {
+        b.iter(|| day24::part2_str(PACKAGES))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, packing);
+criterion_main!(benches);
@@ -0,0 +1,10 @@
+//! Criterion benchmarks for the slowest days.
+//!
+//! Each day has its own bench target, so a single day can be run in isolation:
+//!
+//! ```sh
+//! cargo bench -p benches --bench day22
+//! ```
+//!
+//! Where a day library offers alternative algorithms, they are benchmarked side by side in the
+//! same group, so criterion's report compares them directly.
@@ -65,69 +65,642 @@
 //! assert_eq!(-3, count_parens(")())())" ));
 //! ```
 
-use aoclib::parse;
-use std::path::Path;
-use thiserror::Error;
+use aoclib::input::parse_str;
+use rayon::prelude::*;
+use std::{
+    io::{self, BufRead, Read},
+    path::Path,
+};
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
+
+/// The floors of a building, from `lowest` to `highest` inclusive.
+///
+/// Parses from and displays as a range, like `-3..=100`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display)]
+#[display("{lowest}..={highest}")]
+pub struct Floors {
+    pub lowest: i32,
+    pub highest: i32,
+}
+
+impl Floors {
+    pub fn contains(&self, floor: i32) -> bool {
+        (self.lowest..=self.highest).contains(&floor)
+    }
+}
+
+/// What the elevator does when an instruction would take Santa outside the building.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display,
+)]
+#[display(style = "snake_case")]
+pub enum Policy {
+    /// Stay on the top or bottom floor.
+    Saturate,
+    /// Carry on from the other end of the building.
+    Wrap,
+    /// Stop following instructions.
+    #[default]
+    Error,
+}
+
+/// An instruction would have taken Santa outside the building.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("instruction {index} would take Santa to floor {floor}, outside the building")]
+pub struct OutOfBounds {
+    /// The 0-based character index of the instruction.
+    pub index: usize,
+    /// The floor the instruction would have taken Santa to.
+    pub floor: i32,
+}
+
+/// A building which Santa can't leave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bounds {
+    floors: Floors,
+    policy: Policy,
+}
+
+impl Bounds {
+    /// Where Santa ends up when an instruction sends him to `floor`.
+    ///
+    /// `None` means he can't go there, and the elevator stops.
+    fn confine(&self, floor: i32) -> Option<i32> {
+        let Floors { lowest, highest } = self.floors;
+        if self.floors.contains(floor) {
+            return Some(floor);
+        }
+        match self.policy {
+            Policy::Saturate => Some(floor.clamp(lowest, highest)),
+            Policy::Wrap => Some(lowest + (floor - lowest).rem_euclid(highest - lowest + 1)),
+            Policy::Error => None,
+        }
+    }
+}
+
+/// The elevator's progress through the instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Santa is on this floor, and follows the next instruction.
+    On(i32),
+    /// Santa is stuck on `floor`: the instruction at `violation.index` would have left the building.
+    Stopped { floor: i32, violation: OutOfBounds },
+}
+
+/// Iterator over the floors Santa visits, yielding `(index, floor)` for each instruction.
+///
+/// `index` is the 0-based character index of the instruction within the input, and `floor` is
+/// where it takes Santa. Characters other than parens aren't instructions, and yield nothing.
+///
+/// ```
+/// # use day01::FloorTracker;
+/// let floors: Vec<_> = FloorTracker::new("(()").collect();
+/// assert_eq!(floors, vec![(0, 1), (1, 2), (2, 1)]);
+/// ```
+///
+/// A tracker in a building with [`Floors`] keeps Santa inside it according to its [`Policy`].
+/// Under [`Policy::Error`], it stops at the first instruction which would leave the building.
+///
+/// ```
+/// # use day01::{FloorTracker, Floors, Policy};
+/// let floors = Floors { lowest: -1, highest: 1 };
+/// let mut tracker = FloorTracker::bounded("((()", floors, Policy::Error);
+/// assert_eq!(tracker.by_ref().count(), 1);
+/// assert_eq!(tracker.violation().map(|violation| violation.index), Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FloorTracker<'a> {
+    chars: std::iter::Enumerate<std::str::Chars<'a>>,
+    bounds: Option<Bounds>,
+    state: State,
+}
+
+impl<'a> FloorTracker<'a> {
+    /// Track Santa from the ground floor through the instructions in `input`.
+    pub fn new(input: &'a str) -> Self {
+        FloorTracker {
+            chars: input.chars().enumerate(),
+            bounds: None,
+            state: State::On(0),
+        }
+    }
+
+    /// Track Santa from the ground floor through the instructions in `input`, within `floors`.
+    ///
+    /// The ground floor should be among `floors`; Santa starts there regardless.
+    pub fn bounded(input: &'a str, floors: Floors, policy: Policy) -> Self {
+        FloorTracker {
+            bounds: Some(Bounds { floors, policy }),
+            ..FloorTracker::new(input)
+        }
+    }
+
+    /// The floor Santa is on after the instructions followed so far.
+    pub fn floor(&self) -> i32 {
+        match self.state {
+            State::On(floor) | State::Stopped { floor, .. } => floor,
+        }
+    }
+
+    /// The instruction which stopped the elevator, if any has.
+    pub fn violation(&self) -> Option<OutOfBounds> {
+        match self.state {
+            State::On(_) => None,
+            State::Stopped { violation, .. } => Some(violation),
+        }
+    }
+}
+
+impl<'a> Iterator for FloorTracker<'a> {
+    type Item = (usize, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let floor = match self.state {
+            State::On(floor) => floor,
+            State::Stopped { .. } => return None,
+        };
+        for (index, ch) in &mut self.chars {
+            let target = match ch {
+                '(' => floor + 1,
+                ')' => floor - 1,
+                _ => continue,
+            };
+            let confined = match self.bounds {
+                Some(bounds) => bounds.confine(target),
+                None => Some(target),
+            };
+            return match confined {
+                Some(next) => {
+                    self.state = State::On(next);
+                    Some((index, next))
+                }
+                None => {
+                    self.state = State::Stopped {
+                        floor,
+                        violation: OutOfBounds {
+                            index,
+                            floor: target,
+                        },
+                    };
+                    None
+                }
+            };
+        }
+        None
+    }
+}
 
 /// Returns <number of open parens> - <number of close parens> in the given string
 pub fn count_parens(input: &str) -> i32 {
-    input.chars().fold(0, |sum, ch| {
-        if ch == '(' {
-            sum + 1
-        } else if ch == ')' {
-            sum - 1
-        } else {
-            sum
+    FloorTracker::new(input)
+        .last()
+        .map_or(0, |(_, floor)| floor)
+}
+
+/// Summary statistics of Santa's trip through the building.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TripStats {
+    /// The highest floor Santa visits, counting the ground floor he starts on.
+    pub max_floor: i32,
+    /// The lowest floor Santa visits, counting the ground floor he starts on.
+    pub min_floor: i32,
+    /// The floor Santa ends up on.
+    pub final_floor: i32,
+    /// How many times Santa switches between going up and going down.
+    pub reversals: usize,
+    /// The most instructions in a row which all go the same way.
+    pub longest_run: usize,
+}
+
+impl TripStats {
+    /// Compute statistics for a trip in a single pass.
+    pub fn of(input: &str) -> Self {
+        let mut stats = TripStats::default();
+        let mut direction = 0;
+        let mut run = 0;
+
+        for (_, floor) in FloorTracker::new(input) {
+            let step = floor - stats.final_floor;
+            if step == direction {
+                run += 1;
+            } else {
+                if direction != 0 {
+                    stats.reversals += 1;
+                }
+                direction = step;
+                run = 1;
+            }
+            stats.longest_run = stats.longest_run.max(run);
+            stats.max_floor = stats.max_floor.max(floor);
+            stats.min_floor = stats.min_floor.min(floor);
+            stats.final_floor = floor;
         }
-    })
+
+        stats
+    }
+}
+
+/// How the parens in some text nest, reading `(` as opening a group and `)` as closing one.
+///
+/// A `)` with no open group to close is unmatched, and ignored thereafter.
+///
+/// ```
+/// # use day01::Balance;
+/// let balance = Balance::of("(()())) (");
+/// assert_eq!(balance.histogram, vec![2, 2]);
+/// assert_eq!(balance.max_depth(), 2);
+/// assert_eq!(balance.first_unmatched_close, Some(7));
+/// assert_eq!(balance.unmatched_opens, 1);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Balance {
+    /// `histogram[d]` counts the groups opened at nesting depth `d + 1`.
+    pub histogram: Vec<usize>,
+    /// The position of the first unmatched `)`, counting characters from 1.
+    pub first_unmatched_close: Option<usize>,
+    /// How many `(` are never closed.
+    pub unmatched_opens: usize,
+}
+
+impl Balance {
+    /// Check the nesting of `input` in a single pass.
+    pub fn of(input: &str) -> Self {
+        let mut balance = Balance::default();
+        let mut depth = 0;
+
+        for (index, ch) in input.chars().enumerate() {
+            match ch {
+                '(' => {
+                    depth += 1;
+                    if balance.histogram.len() < depth {
+                        balance.histogram.push(0);
+                    }
+                    balance.histogram[depth - 1] += 1;
+                }
+                ')' if depth > 0 => depth -= 1,
+                ')' => {
+                    balance.first_unmatched_close.get_or_insert(index + 1);
+                }
+                _ => {}
+            }
+        }
+
+        balance.unmatched_opens = depth;
+        balance
+    }
+
+    /// The deepest nesting of groups.
+    pub fn max_depth(&self) -> usize {
+        self.histogram.len()
+    }
+
+    /// Whether every `(` is closed by a later `)`, and every `)` closes an earlier `(`.
+    pub fn is_balanced(&self) -> bool {
+        self.first_unmatched_close.is_none() && self.unmatched_opens == 0
+    }
+}
+
+/// Statistics about Santa's trip through the building, following every instruction in `input`.
+pub fn stats(input: &InputSource) -> Result<TripStats, Error> {
+    Ok(TripStats::of(&input.read_to_string()?))
+}
+
+/// The floor Santa ends up on in a building with only `floors`.
+pub fn bounded_floor(input: &str, floors: Floors, policy: Policy) -> Result<i32, OutOfBounds> {
+    let mut tracker = FloorTracker::bounded(input, floors, policy);
+    tracker.by_ref().for_each(drop);
+    match tracker.violation() {
+        Some(violation) => Err(violation),
+        None => Ok(tracker.floor()),
+    }
+}
+
+/// How part 1 combines the floors of an input with several lines of instructions.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display,
+)]
+#[display(style = "kebab-case")]
+pub enum Aggregation {
+    /// The floor each line leads to, starting each from the ground floor.
+    PerLine,
+    /// The total of the floors each line leads to from the ground floor.
+    #[default]
+    Sum,
+    /// The floor all the lines lead to, followed one after another.
+    #[display("continue-across-lines")]
+    Continue,
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let floor: i32 = parse::<String>(input)?
+/// The floor each line of instructions leads Santa to, starting each from the ground floor.
+pub fn floors_per_line(input: &str) -> Result<Vec<i32>, Error> {
+    Ok(parse_str::<String>(input)?
         .map(|line| count_parens(&line))
-        .sum();
-    println!("arrived at floor: {}", floor);
-    Ok(())
+        .collect())
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_aggregated(input, Aggregation::default())
+}
+
+pub fn part1_aggregated(
+    input: impl std::io::Read,
+    aggregation: Aggregation,
+) -> Result<Answer, Error> {
+    part1_str_aggregated(&util::input::read_all(input)?, aggregation)
 }
 
-fn find_basement_entry(input: &str) -> usize {
-    let mut floor = 0;
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    part1_str_aggregated(input, Aggregation::default())
+}
+
+pub fn part1_str_aggregated(input: &str, aggregation: Aggregation) -> Result<Answer, Error> {
+    Ok(match aggregation {
+        Aggregation::PerLine => floors_per_line(input)?
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+            .into(),
+        Aggregation::Sum => floors_per_line(input)?.iter().sum::<i32>().into(),
+        // line breaks aren't instructions, so Santa carries on from wherever the last line left him
+        Aggregation::Continue => count_parens(input).into(),
+    })
+}
+
+/// The position of every instruction which takes Santa to `floor`, from whichever direction.
+///
+/// Positions count characters from 1, as the puzzle does.
+pub fn visits_to(input: &str, floor: i32) -> Vec<usize> {
+    FloorTracker::new(input)
+        .filter(|&(_, visited)| visited == floor)
+        .map(|(index, _)| index + 1)
+        .collect()
+}
 
-    for (i, ch) in input.chars().enumerate() {
-        if ch == '(' {
-            floor += 1;
-        } else if ch == ')' {
-            floor -= 1
+/// The position of the first instruction which takes Santa to `floor`, if he ever gets there.
+///
+/// Positions count characters from 1, as the puzzle does.
+pub fn first_visit_to(input: &str, floor: i32) -> Option<usize> {
+    FloorTracker::new(input)
+        .find(|&(_, visited)| visited == floor)
+        .map(|(index, _)| index + 1)
+}
+
+/// The position of every instruction which takes Santa down from the ground floor into the
+/// basement.
+///
+/// Positions count characters from 1, as the puzzle does.
+pub fn basement_entries(input: &str) -> Vec<usize> {
+    let mut previous = 0;
+    FloorTracker::new(input)
+        .filter_map(|(index, floor)| {
+            let entered = previous == 0 && floor == -1;
+            previous = floor;
+            entered.then(|| index + 1)
+        })
+        .collect()
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let line = parse_str::<String>(input)?.next().ok_or(Error::NoInput)?;
+    let position = first_visit_to(&line, -1)
+        .ok_or_else(|| Error::no_solution("Santa never enters the basement"))?;
+    Ok(position.into())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "(()(()(",
+        expected: "3",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: ")())())",
+        expected: "-3",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: ")",
+        expected: "1",
+        solver: part2_str,
+    },
+    Example {
+        part: 2,
+        input: "()())",
+        expected: "5",
+        solver: part2_str,
+    },
+];
+
+/// Summary of an instruction stream, computed without holding the stream in memory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StreamSummary {
+    /// The floor Santa arrives at after following every instruction.
+    pub floor: i64,
+    /// The 1-based position of the character which first takes Santa into the basement, if any.
+    pub basement_entry: Option<u64>,
+}
+
+/// Follow every instruction from `reader` as a single sequence, in constant memory.
+///
+/// Positions are counted in characters, as in [`count_parens`]; everything other than parens
+/// (including newlines) is ignored, but still occupies a position.
+pub fn scan_reader<R: BufRead>(mut reader: R) -> io::Result<StreamSummary> {
+    let mut summary = StreamSummary::default();
+    let mut position = 0_u64;
+
+    loop {
+        let buffer = match reader.fill_buf() {
+            Ok(buffer) => buffer,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        if buffer.is_empty() {
+            break;
+        }
+
+        for &byte in buffer {
+            if is_char_start(byte) {
+                position += 1;
+            }
+            match byte {
+                b'(' => summary.floor += 1,
+                b')' => summary.floor -= 1,
+                _ => continue,
+            }
+            if summary.floor == -1 && summary.basement_entry.is_none() {
+                summary.basement_entry = Some(position);
+            }
         }
 
+        let consumed = buffer.len();
+        reader.consume(consumed);
+    }
+
+    Ok(summary)
+}
+
+/// Bytes per chunk when scanning in parallel.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// The effect of a chunk of instructions, whichever floor Santa starts it on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ChunkSummary {
+    /// How many floors the chunk moves Santa.
+    delta: i64,
+    /// The lowest floor the chunk takes Santa to, relative to where he starts it. Never above 0.
+    min_prefix: i64,
+    /// How many characters the chunk holds.
+    chars: u64,
+}
+
+impl ChunkSummary {
+    fn of(chunk: &[u8]) -> Self {
+        let mut summary = ChunkSummary::default();
+        for &byte in chunk {
+            if is_char_start(byte) {
+                summary.chars += 1;
+            }
+            match byte {
+                b'(' => summary.delta += 1,
+                b')' => summary.delta -= 1,
+                _ => continue,
+            }
+            summary.min_prefix = summary.min_prefix.min(summary.delta);
+        }
+        summary
+    }
+
+    /// The effect of this chunk followed by `next`.
+    ///
+    /// This is associative, so summaries of adjacent chunks can be combined in any grouping.
+    fn then(self, next: ChunkSummary) -> ChunkSummary {
+        ChunkSummary {
+            delta: self.delta + next.delta,
+            min_prefix: self.min_prefix.min(self.delta + next.min_prefix),
+            chars: self.chars + next.chars,
+        }
+    }
+}
+
+/// `false` for UTF-8 continuation bytes, which don't begin a new character.
+fn is_char_start(byte: u8) -> bool {
+    byte & 0b1100_0000 != 0b1000_0000
+}
+
+/// The 1-based position within `chunk` of the character which first takes Santa from `floor` to
+/// floor -1.
+fn basement_entry_within(chunk: &[u8], mut floor: i64) -> Option<u64> {
+    let mut position = 0;
+    for &byte in chunk {
+        if is_char_start(byte) {
+            position += 1;
+        }
+        match byte {
+            b'(' => floor += 1,
+            b')' => floor -= 1,
+            _ => continue,
+        }
         if floor == -1 {
-            return i + 1;
+            return Some(position);
         }
     }
-    0
+    None
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for (idx, line) in parse::<String>(input)?.enumerate() {
-        println!(
-            "line {}: basement entry at {}",
-            idx,
-            find_basement_entry(&line)
-        );
+/// As [`scan_reader`], but summarizing chunks of the input on every core.
+///
+/// Reading stays sequential, and only a chunk per thread is held in memory at once. Each chunk is
+/// reduced to its change of floor and the lowest floor it reaches; only the chunk in which Santa
+/// first enters the basement is scanned a second time, to find the position.
+pub fn scan_reader_parallel<R: Read>(reader: R) -> io::Result<StreamSummary> {
+    scan_chunks_parallel(reader, CHUNK_SIZE)
+}
+
+fn scan_chunks_parallel<R: Read>(mut reader: R, chunk_size: usize) -> io::Result<StreamSummary> {
+    let batch_len = rayon::current_num_threads();
+    let mut total = ChunkSummary::default();
+    let mut basement_entry = None;
+
+    loop {
+        let mut batch = Vec::with_capacity(batch_len);
+        while batch.len() < batch_len {
+            let mut chunk = Vec::with_capacity(chunk_size);
+            reader
+                .by_ref()
+                .take(chunk_size as u64)
+                .read_to_end(&mut chunk)?;
+            if chunk.is_empty() {
+                break;
+            }
+            batch.push(chunk);
+        }
+        if batch.is_empty() {
+            break;
+        }
+
+        let summaries: Vec<_> = batch
+            .par_iter()
+            .map(|chunk| ChunkSummary::of(chunk))
+            .collect();
+        for (chunk, summary) in batch.iter().zip(summaries) {
+            let next = total.then(summary);
+            if basement_entry.is_none() && next.min_prefix < 0 {
+                basement_entry = basement_entry_within(chunk, total.delta)
+                    .map(|position| total.chars + position);
+            }
+            total = next;
+        }
     }
-    Ok(())
+
+    Ok(StreamSummary {
+        floor: total.delta,
+        basement_entry,
+    })
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+/// Count parens from `reader` in constant memory.
+pub fn count_parens_reader<R: BufRead>(reader: R) -> io::Result<i64> {
+    scan_reader(reader).map(|summary| summary.floor)
+}
+
+/// Find the position of the first basement entry from `reader` in constant memory.
+pub fn find_basement_entry_reader<R: BufRead>(reader: R) -> io::Result<Option<u64>> {
+    scan_reader(reader).map(|summary| summary.basement_entry)
+}
+
+/// Solve both parts for an instruction stream of arbitrary size.
+///
+/// With `parallel`, chunks of the stream are scanned on every core.
+pub fn stream<R: BufRead>(reader: R, parallel: bool) -> Result<StreamSummary, Error> {
+    let summary = if parallel {
+        scan_reader_parallel(reader)
+    } else {
+        scan_reader(reader)
+    };
+    Ok(summary?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::count_parens;
-    use super::find_basement_entry;
+    use super::*;
+    use std::io::{BufReader, Cursor};
 
     #[test]
     fn count_to_floor_0() {
@@ -163,18 +736,250 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tracker_skips_non_parens() {
+        let floors: Vec<_> = FloorTracker::new("(x))\n(").collect();
+        assert_eq!(floors, vec![(0, 1), (2, 0), (3, -1), (5, 0)]);
+        assert_eq!(FloorTracker::new("").next(), None);
+    }
+
+    #[test]
+    fn tracker_answers_other_questions() {
+        // the first time Santa reaches the third floor
+        let first_visit = FloorTracker::new("(()(()(").find(|&(_, floor)| floor == 3);
+        assert_eq!(first_visit, Some((4, 3)));
+        // the floor Santa is on after four instructions
+        let after_four = FloorTracker::new("))(((((").nth(3);
+        assert_eq!(after_four, Some((3, 0)));
+    }
+
+    #[test]
+    fn trip_stats() {
+        assert_eq!(
+            TripStats::of("((()))((())\n)))"),
+            TripStats {
+                max_floor: 3,
+                min_floor: -2,
+                final_floor: -2,
+                reversals: 3,
+                longest_run: 5,
+            }
+        );
+        assert_eq!(TripStats::of(""), TripStats::default());
+    }
+
     #[test]
     fn find_basement_first_char() {
-        assert_eq!(1, find_basement_entry(")"));
+        assert_eq!(Some(1), first_visit_to(")", -1));
     }
 
     #[test]
     fn find_basement_fifth_char() {
-        assert_eq!(5, find_basement_entry("()())"));
+        assert_eq!(Some(5), first_visit_to("()())", -1));
     }
 
     #[test]
     fn find_basement_never_enters() {
-        assert_eq!(0, find_basement_entry("(((())(()))((())"));
+        assert_eq!(None, first_visit_to("(((())(()))((())", -1));
+        assert!(part2_str("(((())(()))((())").is_err());
+    }
+
+    #[test]
+    fn visits_to_any_floor() {
+        assert_eq!(visits_to("(()(()(", 2), vec![2, 4, 6]);
+        assert_eq!(visits_to("(()(()(", 0), Vec::<usize>::new());
+        assert_eq!(first_visit_to("(()(()(", 3), Some(5));
+        assert_eq!(first_visit_to("(()(()(", 4), None);
+    }
+
+    #[test]
+    fn find_every_basement_entry() {
+        // climbing back up from -2 to -1 isn't an entry
+        assert_eq!(basement_entries("))(()(()))"), vec![1, 5, 9]);
+        assert_eq!(visits_to("))(()(()))", -1), vec![1, 3, 5, 9]);
+        assert!(basement_entries("(()").is_empty());
+    }
+
+    #[test]
+    fn reader_matches_string_api() {
+        for input in &[
+            "(())", "()()", "(((", "(()(()(", "))(((((", "())", "))(", ")))", ")())())",
+        ] {
+            let count = count_parens_reader(Cursor::new(input)).unwrap();
+            assert_eq!(count, count_parens(input) as i64);
+
+            let entry = find_basement_entry_reader(Cursor::new(input)).unwrap();
+            assert_eq!(
+                entry,
+                first_visit_to(input, -1).map(|position| position as u64)
+            );
+        }
+    }
+
+    #[test]
+    fn reader_across_chunk_boundaries() {
+        let input = "(((())))()))((";
+        // a tiny buffer forces many refills
+        let reader = BufReader::with_capacity(3, Cursor::new(input));
+        let summary = scan_reader(reader).unwrap();
+        assert_eq!(summary.floor, count_parens(input) as i64);
+        assert_eq!(summary.basement_entry, Some(11));
+    }
+
+    #[test]
+    fn reader_counts_characters_not_bytes() {
+        assert_eq!(
+            find_basement_entry_reader(Cursor::new("é)")).unwrap(),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let long = "(()".repeat(100) + &")".repeat(150) + "é)(";
+        for input in &[
+            "",
+            "(()",
+            ")",
+            "()())",
+            "((é)))",
+            "(((())))()))((",
+            long.as_str(),
+        ] {
+            let sequential = scan_reader(Cursor::new(input)).unwrap();
+            // tiny chunks split the input, and even its characters, many ways
+            for &chunk_size in &[1, 2, 3, 7, 64, 1 << 20] {
+                let parallel = scan_chunks_parallel(Cursor::new(input), chunk_size).unwrap();
+                assert_eq!(
+                    parallel, sequential,
+                    "{:?} in chunks of {}",
+                    input, chunk_size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_summaries_combine() {
+        let input = b"(()))((()))))(";
+        let whole = ChunkSummary::of(input);
+        for split in 0..input.len() {
+            let (left, right) = input.split_at(split);
+            assert_eq!(ChunkSummary::of(left).then(ChunkSummary::of(right)), whole);
+        }
+    }
+
+    #[test]
+    fn reader_never_enters() {
+        assert_eq!(
+            find_basement_entry_reader(Cursor::new("(()")).unwrap(),
+            None
+        );
+        assert_eq!(find_basement_entry_reader(Cursor::new("")).unwrap(), None);
+    }
+
+    const BUILDING: Floors = Floors {
+        lowest: -2,
+        highest: 2,
+    };
+
+    #[test]
+    fn floors_parse() {
+        assert_eq!(
+            "-3..=100".parse::<Floors>().unwrap(),
+            Floors {
+                lowest: -3,
+                highest: 100
+            }
+        );
+        assert_eq!(BUILDING.to_string(), "-2..=2");
+        assert_eq!("wrap".parse::<Policy>().unwrap(), Policy::Wrap);
+    }
+
+    #[test]
+    fn bounded_within_building() {
+        for &policy in &[Policy::Saturate, Policy::Wrap, Policy::Error] {
+            assert_eq!(bounded_floor("(()))", BUILDING, policy), Ok(-1));
+        }
+    }
+
+    #[test]
+    fn bounded_saturate() {
+        let floors: Vec<_> = FloorTracker::bounded("((((x)", BUILDING, Policy::Saturate)
+            .map(|(_, floor)| floor)
+            .collect();
+        assert_eq!(floors, vec![1, 2, 2, 2, 1]);
+        assert_eq!(bounded_floor(")))))", BUILDING, Policy::Saturate), Ok(-2));
+    }
+
+    #[test]
+    fn bounded_wrap() {
+        let floors: Vec<_> = FloorTracker::bounded("((()))))", BUILDING, Policy::Wrap)
+            .map(|(_, floor)| floor)
+            .collect();
+        assert_eq!(floors, vec![1, 2, -2, 2, 1, 0, -1, -2]);
+        assert_eq!(bounded_floor(")))", BUILDING, Policy::Wrap), Ok(2));
+    }
+
+    #[test]
+    fn bounded_error() {
+        let mut tracker = FloorTracker::bounded("(x(((", BUILDING, Policy::Error);
+        assert_eq!(tracker.by_ref().count(), 2);
+        assert_eq!(tracker.floor(), 2);
+        assert_eq!(
+            tracker.violation(),
+            Some(OutOfBounds { index: 3, floor: 3 })
+        );
+        assert_eq!(tracker.next(), None);
+        assert_eq!(
+            bounded_floor("())))", BUILDING, Policy::Error)
+                .unwrap_err()
+                .to_string(),
+            "instruction 4 would take Santa to floor -3, outside the building"
+        );
+    }
+
+    #[test]
+    fn balance_of_balanced() {
+        let balance = Balance::of("(()(()))()");
+        assert_eq!(balance.histogram, vec![2, 2, 1]);
+        assert_eq!(balance.max_depth(), 3);
+        assert!(balance.is_balanced());
+        assert_eq!(Balance::of("no parens"), Balance::default());
+        assert!(Balance::default().is_balanced());
+    }
+
+    #[test]
+    fn balance_of_unbalanced() {
+        let balance = Balance::of("())(()");
+        assert_eq!(balance.histogram, vec![2, 1]);
+        assert_eq!(balance.first_unmatched_close, Some(3));
+        assert_eq!(balance.unmatched_opens, 1);
+        assert!(!balance.is_balanced());
+
+        // the puzzle's instructions rarely balance
+        let balance = Balance::of(")())())");
+        assert_eq!(balance.first_unmatched_close, Some(1));
+        assert_eq!(balance.unmatched_opens, 0);
+        assert_eq!(balance.max_depth(), 1);
+    }
+
+    #[test]
+    fn aggregation_modes() {
+        let input = "(((\n))\n)\n";
+        assert_eq!(floors_per_line(input).unwrap(), vec![3, -2, -1]);
+        let aggregate = |aggregation| {
+            part1_str_aggregated(input, aggregation)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(aggregate(Aggregation::PerLine), "3, -2, -1");
+        assert_eq!(aggregate(Aggregation::Sum), "0");
+        assert_eq!(aggregate(Aggregation::Continue), "0");
+        assert_eq!(
+            "continue-across-lines".parse::<Aggregation>().unwrap(),
+            Aggregation::Continue
+        );
+        assert_eq!(Aggregation::PerLine.to_string(), "per-line");
     }
 }
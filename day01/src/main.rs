@@ -1,52 +1,89 @@
-use aoclib::{config::Config, website::get_input};
-use day01::{part1, part2};
+use day01::{
+    bounded_floor, part1_aggregated, part2_from_reader, stats, Aggregation, Floors, Policy,
+};
 
-use color_eyre::eyre::Result;
-use std::path::PathBuf;
+use color_eyre::eyre::{ensure, Result};
 use structopt::StructOpt;
+use util::cache::Variant;
 
 const DAY: u8 = 1;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// read the instructions as a stream in constant memory, solving both parts
+    #[structopt(long)]
+    stream: bool,
+
+    /// with --stream, scan chunks of the instructions on every core
+    #[structopt(long, requires = "stream")]
+    parallel: bool,
+
+    /// how part 1 combines several lines of instructions: per-line, sum, or continue-across-lines
+    #[structopt(long, default_value)]
+    aggregate: Aggregation,
 
-    /// run part 2
+    /// print statistics about Santa's trip through the building
     #[structopt(long)]
-    part2: bool,
-}
+    stats: bool,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// also find Santa's final floor in a building with only these floors, like -3..=100
+    #[structopt(long)]
+    floors: Option<Floors>,
+
+    /// with --floors, what to do when Santa would leave the building: saturate, wrap, or error
+    #[structopt(long, default_value)]
+    on_violation: Policy,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day01::EXAMPLES)?;
+        return Ok(());
+    }
+    if args.stream {
+        let summary = day01::stream(args.common.source(DAY)?.reader()?, args.parallel)?;
+        println!("arrived at floor: {}", summary.floor);
+        match summary.basement_entry {
+            Some(position) => println!("basement entry at {}", position),
+            None => println!("never entered the basement"),
+        }
+        return Ok(());
+    }
+    let variant = Variant::new().param("aggregate", &args.aggregate);
+    let input = args.common.source(DAY)?.buffered()?;
 
-    if !args.no_part1 {
-        part1(&input_path)?;
+    let answers = args.common.run(
+        DAY,
+        &variant.to_string(),
+        &input,
+        |input| part1_aggregated(input, args.aggregate),
+        part2_from_reader,
+    )?;
+    if variant.is_usual() {
+        args.common.check(DAY, &answers)?;
+    }
+    if args.stats {
+        let stats = stats(&input)?;
+        println!("final floor: {}", stats.final_floor);
+        println!("highest floor: {}", stats.max_floor);
+        println!("lowest floor: {}", stats.min_floor);
+        println!("reversals: {}", stats.reversals);
+        println!("longest run: {}", stats.longest_run);
     }
-    if args.part2 {
-        part2(&input_path)?;
+    if let Some(floors) = args.floors {
+        ensure!(
+            floors.contains(0),
+            "the building must include the ground floor"
+        );
+        let text = input.read_to_string()?;
+        let floor = bounded_floor(&text, floors, args.on_violation)?;
+        println!("final floor within {}: {}", floors, floor);
     }
     Ok(())
 }
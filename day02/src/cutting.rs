@@ -0,0 +1,140 @@
+//! Cutting the paper for each box from sheets of a fixed size.
+//!
+//! Each box's paper must come from a single sheet. Sheets are filled by first-fit decreasing:
+//! the boxes needing the most paper are placed first, each on the first sheet with room for it.
+
+use crate::{overflowed, GiftBox};
+use util::Error;
+
+/// One sheet of paper, and the boxes wrapped with it.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Sheet {
+    /// The boxes cut from this sheet, in the order they were placed.
+    pub boxes: Vec<GiftBox>,
+    /// The area of paper cut from this sheet.
+    pub used: i64,
+}
+
+/// How to cut the paper for an order of boxes from sheets.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CuttingPlan {
+    /// The area of each sheet.
+    pub sheet_size: i64,
+    pub sheets: Vec<Sheet>,
+}
+
+impl CuttingPlan {
+    /// Plan how to cut the paper for `boxes` from sheets of area `sheet_size`, by first-fit
+    /// decreasing.
+    ///
+    /// Fails if any box needs more paper than a whole sheet.
+    pub fn first_fit_decreasing(boxes: &[GiftBox], sheet_size: i64) -> Result<Self, Error> {
+        if sheet_size <= 0 {
+            return Err(Error::no_solution("sheets must have a positive area"));
+        }
+        let mut needs = boxes
+            .iter()
+            .map(|gift_box| Ok((*gift_box, gift_box.widen().paper().map_err(overflowed)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        // the sort is stable, so boxes needing equal paper are placed in input order
+        needs.sort_by_key(|&(_, paper)| std::cmp::Reverse(paper));
+
+        let mut sheets: Vec<Sheet> = Vec::new();
+        for (gift_box, paper) in needs {
+            if paper > sheet_size {
+                return Err(Error::no_solution(format!(
+                    "{} needs {} paper, more than a sheet of {}",
+                    gift_box, paper, sheet_size
+                )));
+            }
+            let sheet = match sheets
+                .iter()
+                .position(|sheet| sheet.used + paper <= sheet_size)
+            {
+                Some(idx) => &mut sheets[idx],
+                None => {
+                    sheets.push(Sheet::default());
+                    sheets.last_mut().expect("just pushed a sheet")
+                }
+            };
+            sheet.boxes.push(gift_box);
+            sheet.used += paper;
+        }
+
+        Ok(CuttingPlan { sheet_size, sheets })
+    }
+
+    pub fn sheets_needed(&self) -> usize {
+        self.sheets.len()
+    }
+
+    /// The area of paper left over on all the sheets.
+    pub fn waste(&self) -> i64 {
+        self.sheets
+            .iter()
+            .map(|sheet| self.sheet_size - sheet.used)
+            .sum()
+    }
+
+    /// The fewest sheets which could possibly hold all the paper, however it were cut.
+    pub fn lower_bound(&self) -> usize {
+        let paper: i64 = self.sheets.iter().map(|sheet| sheet.used).sum();
+        ((paper + self.sheet_size - 1) / self.sheet_size) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boxes() -> Vec<GiftBox> {
+        // paper: 58, 43, 7, 58
+        vec![
+            GiftBox::new(2, 3, 4).unwrap(),
+            GiftBox::new(1, 1, 10).unwrap(),
+            GiftBox::new(1, 1, 1).unwrap(),
+            GiftBox::new(4, 3, 2).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_first_fit_decreasing() {
+        let plan = CuttingPlan::first_fit_decreasing(&boxes(), 101).unwrap();
+        assert_eq!(plan.sheets_needed(), 2);
+        assert_eq!(
+            plan.sheets[0].boxes,
+            vec![
+                GiftBox::new(2, 3, 4).unwrap(),
+                GiftBox::new(1, 1, 10).unwrap()
+            ]
+        );
+        assert_eq!(plan.sheets[0].used, 101);
+        assert_eq!(plan.sheets[1].used, 65);
+        assert_eq!(plan.waste(), 36);
+        assert_eq!(plan.lower_bound(), 2);
+
+        // the two largest boxes can't share a sheet of 100, nor can either with the next
+        let plan = CuttingPlan::first_fit_decreasing(&boxes(), 100).unwrap();
+        assert_eq!(plan.sheets_needed(), 3);
+        assert_eq!(plan.sheets[0].used, 65);
+        assert_eq!(plan.lower_bound(), 2);
+    }
+
+    #[test]
+    fn test_box_larger_than_sheet() {
+        let err = CuttingPlan::first_fit_decreasing(&boxes(), 50).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2x3x4 needs 58 paper, more than a sheet of 50"
+        );
+    }
+
+    #[test]
+    fn test_no_boxes() {
+        let plan = CuttingPlan::first_fit_decreasing(&[], 10).unwrap();
+        assert_eq!(plan.sheets_needed(), 0);
+        assert_eq!(plan.waste(), 0);
+        assert_eq!(plan.lower_bound(), 0);
+        assert!(CuttingPlan::first_fit_decreasing(&[], 0).is_err());
+    }
+}
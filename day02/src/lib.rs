@@ -16,100 +16,612 @@
 //! - A present with dimensions `1x1x10` requires `2*1 + 2*10 + 2*10 = 42` square feet of wrapping
 //!   paper plus `1` square foot of slack, for a total of `43` square feet.
 
-use aoclib::{geometry::vector3::Vector3, parse};
-use std::path::Path;
-use thiserror::Error;
+mod cutting;
+mod scalar;
 
-#[derive(PartialEq, Eq, Debug, parse_display::Display, parse_display::FromStr)]
-#[display("{dimensions.x}x{dimensions.y}x{dimensions.z}")]
-pub struct GiftBox {
-    #[from_str(default)]
-    dimensions: Vector3,
+pub use cutting::{CuttingPlan, Sheet};
+pub use scalar::{Overflow, ParseScalarError, Scalar};
+
+use rayon::prelude::*;
+use std::{
+    cmp::Reverse, collections::BinaryHeap, convert::TryFrom, fmt, io::Write, path::Path,
+    str::FromStr,
+};
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
+
+/// A box with `N` dimensions measured in `T`, written like `2x3x4x5`.
+///
+/// The paper and ribbon requirements generalize those of ordinary boxes: a "side" is a face of
+/// dimension `N - 1`, and its perimeter is the area of its own boundary.
+///
+/// Measurements which don't fit in `T` are an [`Overflow`]; measure in `i64` or `f64` for very
+/// large boxes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HyperBox<const N: usize, T = i32> {
+    dimensions: [T; N],
 }
 
-impl GiftBox {
-    /// Construct a new GiftBox
-    pub fn new(x: i32, y: i32, z: i32) -> Result<GiftBox, &'static str> {
-        if x > 0 && y > 0 && z > 0 {
-            Ok(GiftBox {
-                dimensions: Vector3 { x, y, z },
-            })
+/// An ordinary box, such as the elves wrap.
+pub type GiftBox = HyperBox<3>;
+
+/// The area of the boundary of a box with these dimensions.
+fn boundary<T: Scalar>(dimensions: &[T]) -> Result<T, Overflow> {
+    let mut sum = T::ZERO;
+    for omit in 0..dimensions.len() {
+        sum = sum.checked_add(product_omitting(dimensions, omit)?)?;
+    }
+    sum.checked_add(sum)
+}
+
+/// The product of all dimensions but the one at index `omit`.
+fn product_omitting<T: Scalar>(dimensions: &[T], omit: usize) -> Result<T, Overflow> {
+    dimensions
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _)| idx != omit)
+        .try_fold(T::ONE, |product, (_, &dimension)| {
+            product.checked_mul(dimension)
+        })
+}
+
+impl<const N: usize, T: Scalar> HyperBox<N, T> {
+    /// Construct a new box with the given dimensions.
+    pub fn from_dimensions(dimensions: [T; N]) -> Result<Self, &'static str> {
+        if dimensions.iter().all(|&dimension| dimension > T::ZERO) {
+            Ok(HyperBox { dimensions })
         } else {
             Err("Can't construct a box with dimension 0 or less!")
         }
     }
 
-    /// Return the surface area.
-    pub fn surface_area(&self) -> i32 {
-        2 * ((self.dimensions.x * self.dimensions.z)
-            + (self.dimensions.x * self.dimensions.y)
-            + (self.dimensions.y * self.dimensions.z))
+    pub fn dimensions(&self) -> [T; N] {
+        self.dimensions
+    }
+
+    /// Return the surface area: the total area of the box's sides.
+    pub fn surface_area(&self) -> Result<T, Overflow> {
+        boundary(&self.dimensions)
+    }
+
+    /// The index of the largest dimension, which the smallest side omits.
+    fn largest_index(&self) -> usize {
+        // dimensions are positive, and so never NaN
+        (1..N).fold(0, |largest, idx| {
+            if self.dimensions[idx] >= self.dimensions[largest] {
+                idx
+            } else {
+                largest
+            }
+        })
     }
 
     /// Return the margin: the area of the smallest side
-    pub fn smallest_side(&self) -> i32 {
-        [
-            (self.dimensions.x * self.dimensions.z),
-            (self.dimensions.x * self.dimensions.y),
-            (self.dimensions.y * self.dimensions.z),
-        ]
-        .iter()
-        .min()
-        .cloned()
-        .expect("non-empty input array; qed")
+    pub fn smallest_side(&self) -> Result<T, Overflow> {
+        product_omitting(&self.dimensions, self.largest_index())
     }
 
     /// Return the paper requirement for this box
     ///
     /// Defined in the problem as the surface area plus the area of the smallest side.
-    pub fn paper(&self) -> i32 {
-        self.surface_area() + self.smallest_side()
+    pub fn paper(&self) -> Result<T, Overflow> {
+        self.surface_area()?.checked_add(self.smallest_side()?)
     }
 
-    pub fn volume(&self) -> i32 {
-        self.dimensions.x * self.dimensions.y * self.dimensions.z
+    pub fn volume(&self) -> Result<T, Overflow> {
+        self.dimensions
+            .iter()
+            .try_fold(T::ONE, |product, &dimension| product.checked_mul(dimension))
     }
 
-    pub fn largest_dimension(&self) -> i32 {
-        [self.dimensions.x, self.dimensions.y, self.dimensions.z]
-            .iter()
-            .max()
-            .copied()
-            .expect("non-empty array; qed")
+    pub fn largest_dimension(&self) -> T {
+        self.dimensions[self.largest_index()]
     }
 
-    pub fn smallest_side_perimeter(&self) -> i32 {
-        2 * (self.dimensions.x + self.dimensions.y + self.dimensions.z - self.largest_dimension())
+    pub fn smallest_side_perimeter(&self) -> Result<T, Overflow> {
+        let omit = self.largest_index();
+        let side: Vec<_> = (0..N)
+            .filter(|&idx| idx != omit)
+            .map(|idx| self.dimensions[idx])
+            .collect();
+        boundary(&side)
     }
 
     /// Return the ribbon requirement for this box
     ///
     /// Definted in the problem as the volume plus the perimeter of the smallest side.
-    pub fn ribbon(&self) -> i32 {
-        self.volume() + self.smallest_side_perimeter()
+    pub fn ribbon(&self) -> Result<T, Overflow> {
+        self.volume()?.checked_add(self.smallest_side_perimeter()?)
+    }
+}
+
+impl<const N: usize, T: fmt::Display> fmt::Display for HyperBox<N, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, dimension) in self.dimensions.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "x")?;
+            }
+            write!(f, "{}", dimension)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why some text isn't a box.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseBoxError {
+    #[error("expected {expected} dimensions separated by 'x', found {found}")]
+    WrongCount { expected: usize, found: usize },
+    #[error("{field} is missing")]
+    Missing { field: String },
+    #[error("{field} \"{text}\" is not a number")]
+    NotANumber { field: String, text: String },
+    #[error("{field} {text} is out of range")]
+    Overflow { field: String, text: String },
+    #[error("{field} must be positive, not {value}")]
+    NotPositive { field: String, value: String },
+}
+
+/// The name of the dimension at `index` of a box with `count` dimensions.
+fn field_name(index: usize, count: usize) -> String {
+    match (count, index) {
+        (3, 0) => "length".into(),
+        (3, 1) => "width".into(),
+        (3, 2) => "height".into(),
+        _ => format!("dimension {}", index + 1),
+    }
+}
+
+impl<const N: usize, T: Scalar> FromStr for HyperBox<N, T> {
+    type Err = ParseBoxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let found = s.split('x').count();
+        if found != N {
+            return Err(ParseBoxError::WrongCount { expected: N, found });
+        }
+
+        let mut dimensions = [T::ZERO; N];
+        for (index, (dimension, text)) in dimensions.iter_mut().zip(s.split('x')).enumerate() {
+            let field = field_name(index, N);
+            *dimension = T::parse(text).map_err(|err| match err {
+                ParseScalarError::Empty => ParseBoxError::Missing { field },
+                ParseScalarError::OutOfRange => ParseBoxError::Overflow {
+                    field,
+                    text: text.to_string(),
+                },
+                ParseScalarError::Invalid => ParseBoxError::NotANumber {
+                    field,
+                    text: text.to_string(),
+                },
+            })?;
+            if *dimension <= T::ZERO {
+                return Err(ParseBoxError::NotPositive {
+                    field: field_name(index, N),
+                    value: text.to_string(),
+                });
+            }
+        }
+        Ok(HyperBox { dimensions })
+    }
+}
+
+/// Boxes serialize as an array of their dimensions.
+impl<const N: usize, T: serde::Serialize> serde::Serialize for HyperBox<N, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.dimensions.iter())
+    }
+}
+
+impl<'de, const N: usize, T> serde::Deserialize<'de> for HyperBox<N, T>
+where
+    T: Scalar + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        let found = Vec::<T>::deserialize(deserializer)?;
+        if found.len() != N {
+            return Err(D::Error::custom(ParseBoxError::WrongCount {
+                expected: N,
+                found: found.len(),
+            }));
+        }
+        let mut dimensions = [T::ZERO; N];
+        for (index, (dimension, value)) in dimensions.iter_mut().zip(found).enumerate() {
+            if value <= T::ZERO {
+                return Err(D::Error::custom(ParseBoxError::NotPositive {
+                    field: field_name(index, N),
+                    value: value.to_string(),
+                }));
+            }
+            *dimension = value;
+        }
+        Ok(HyperBox { dimensions })
+    }
+}
+
+/// Parse each non-blank line of `input` as a box.
+///
+/// Fails at the first malformed line, saying which it was.
+///
+/// Lines are parsed in parallel.
+pub fn parse_boxes<const N: usize, T: Scalar>(input: &str) -> Result<Vec<HyperBox<N, T>>, Error> {
+    let lines: Vec<_> = util::lint::numbered_lines(input).collect();
+    let parsed: Vec<_> = lines
+        .par_iter()
+        .map(|&(line, text)| {
+            text.trim()
+                .parse()
+                .map_err(|err| Error::parse(format!("line {}: \"{}\"", line, text.trim()), err))
+        })
+        .collect();
+    // collecting in order reports the first malformed line, whichever thread found it
+    parsed.into_iter().collect()
+}
+
+/// Convert an overflowing measurement into the shared error type.
+fn overflowed(err: Overflow) -> Error {
+    Error::Conversion(err.into())
+}
+
+impl GiftBox {
+    /// Construct a new GiftBox
+    pub fn new(x: i32, y: i32, z: i32) -> Result<GiftBox, &'static str> {
+        GiftBox::from_dimensions([x, y, z])
+    }
+
+    /// This box, measured in `i64` so that its measurements overflow only when enormous.
+    pub fn widen(&self) -> HyperBox<3, i64> {
+        let [x, y, z] = self.dimensions;
+        HyperBox {
+            dimensions: [x.into(), y.into(), z.into()],
+        }
+    }
+
+    /// Find the box which needs the least paper among those holding at least this box's volume.
+    ///
+    /// `tolerance` is the fraction by which the volume may grow: `0.0` requires exactly the same
+    /// volume, while `0.1` permits up to 10% more. Ties are broken by ribbon requirement.
+    ///
+    /// Dimensions are always integers, so a box may already be as efficient as possible.
+    pub fn most_efficient(&self, tolerance: f64) -> GiftBox {
+        let min_volume = match self.volume() {
+            Ok(volume) => volume as i64,
+            // nothing holding as much can be measured either
+            Err(_) => return *self,
+        };
+        let max_volume = ((min_volume as f64) * (1.0 + tolerance.max(0.0))).floor() as i64;
+        let max_volume = max_volume.max(min_volume).min(i32::MAX as i64);
+
+        // boxes whose measurements overflow are the least efficient of all
+        let key = |gift_box: &GiftBox| {
+            (
+                gift_box.paper().unwrap_or(i32::MAX),
+                gift_box.ribbon().unwrap_or(i32::MAX),
+            )
+        };
+        let mut best = *self;
+        let mut best_key = key(self);
+
+        // enumerate `a <= b <= c`; for each `a` and `b`, the smallest legal `c` is best, because
+        // both paper and ribbon grow with `c`.
+        let mut a = 1_i64;
+        while a * a * a <= max_volume {
+            let mut b = a;
+            while a * b * b <= max_volume {
+                let c = ((min_volume + a * b - 1) / (a * b)).max(b);
+                if a * b * c <= max_volume {
+                    let candidate = GiftBox {
+                        dimensions: [a as i32, b as i32, c as i32],
+                    };
+                    let candidate_key = key(&candidate);
+                    if candidate_key < best_key {
+                        best = candidate;
+                        best_key = candidate_key;
+                    }
+                }
+                b += 1;
+            }
+            a += 1;
+        }
+
+        best
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let paper: i32 = parse::<GiftBox>(input)?
-        .map(|gift_box| gift_box.paper())
-        .sum();
-    println!("total paper required: {}", paper);
+/// The result of re-dimensioning a box to its most efficient shape.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Reshaping {
+    pub original: GiftBox,
+    pub efficient: GiftBox,
+}
+
+impl Reshaping {
+    /// Paper saved by using the efficient shape. Never negative.
+    pub fn paper_saved(&self) -> Result<i32, Overflow> {
+        Ok(self.original.paper()? - self.efficient.paper()?)
+    }
+
+    /// Ribbon saved by using the efficient shape. May be negative.
+    pub fn ribbon_saved(&self) -> Result<i32, Overflow> {
+        Ok(self.original.ribbon()? - self.efficient.ribbon()?)
+    }
+}
+
+/// Compute the most efficient reshaping of each box.
+///
+/// See [`GiftBox::most_efficient`] for the meaning of `tolerance`.
+pub fn reshape<I>(boxes: I, tolerance: f64) -> Vec<Reshaping>
+where
+    I: IntoIterator<Item = GiftBox>,
+{
+    boxes
+        .into_iter()
+        .map(|original| Reshaping {
+            original,
+            efficient: original.most_efficient(tolerance),
+        })
+        .collect()
+}
+
+/// The paper and ribbon each box in `input` needs, as `(box, paper, ribbon)`.
+pub fn breakdown(
+    input: &str,
+) -> Result<impl '_ + Iterator<Item = Result<(GiftBox, i64, i64), Overflow>>, Error> {
+    Ok(parse_boxes::<3, i32>(input)?.into_iter().map(|gift_box| {
+        let wide = gift_box.widen();
+        Ok((gift_box, wide.paper()?, wide.ribbon()?))
+    }))
+}
+
+/// Write the paper and ribbon each box in `input` needs as CSV, followed by the totals.
+///
+/// The header is `box,paper,ribbon`; the final row's box is `total`.
+pub fn write_report(input: &str, mut writer: impl Write) -> Result<(), Error> {
+    writeln!(writer, "box,paper,ribbon")?;
+    let (mut total_paper, mut total_ribbon) = (0_i64, 0_i64);
+    for item in breakdown(input)? {
+        let (gift_box, paper, ribbon) = item.map_err(overflowed)?;
+        writeln!(writer, "{},{},{}", gift_box, paper, ribbon)?;
+        total_paper = Scalar::checked_add(total_paper, paper).map_err(overflowed)?;
+        total_ribbon = Scalar::checked_add(total_ribbon, ribbon).map_err(overflowed)?;
+    }
+    writeln!(writer, "total,{},{}", total_paper, total_ribbon)?;
     Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let ribbon: i32 = parse::<GiftBox>(input)?
-        .map(|gift_box| gift_box.ribbon())
-        .sum();
-    println!("total ribbon required: {}", ribbon);
+/// Write the per-box breakdown of `input` as CSV to `output`.
+pub fn report(input: &InputSource, output: &Path) -> Result<(), Error> {
+    let input = input.read_to_string()?;
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(output)?);
+    write_report(&input, &mut writer)?;
+    writer.flush()?;
     Ok(())
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+/// Statistics about an order of boxes.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct OrderStats {
+    /// How many boxes there are.
+    pub boxes: usize,
+    /// The volume of all the boxes together.
+    pub total_volume: i64,
+    /// The box with the greatest volume; the first, among equals.
+    pub largest: Option<GiftBox>,
+    /// The box with the least volume; the first, among equals.
+    pub smallest: Option<GiftBox>,
+    /// The mean of each dimension, in the order the dimensions are given.
+    pub mean_dimensions: [f64; 3],
+    /// The boxes which need the most paper, with how much they need, neediest first.
+    pub hungriest: Vec<(GiftBox, i64)>,
+}
+
+impl OrderStats {
+    /// Compute statistics for `boxes` in a single pass, keeping the `top` hungriest boxes.
+    ///
+    /// Measurements are widened to `i64`, but may still overflow.
+    pub fn of(boxes: impl IntoIterator<Item = GiftBox>, top: usize) -> Result<Self, Overflow> {
+        let mut stats = OrderStats::default();
+        let mut sums = [0_i64; 3];
+        // a min-heap of the hungriest so far; among equals, later boxes are evicted first
+        let mut hungriest = BinaryHeap::with_capacity(top + 1);
+
+        let mut largest_volume = 0;
+        let mut smallest_volume = 0;
+
+        for (idx, gift_box) in boxes.into_iter().enumerate() {
+            let wide = gift_box.widen();
+            let volume = wide.volume()?;
+            stats.boxes += 1;
+            stats.total_volume = Scalar::checked_add(stats.total_volume, volume)?;
+            for (sum, dimension) in sums.iter_mut().zip(wide.dimensions().iter()) {
+                *sum += *dimension;
+            }
+            if stats.largest.is_none() || volume > largest_volume {
+                stats.largest = Some(gift_box);
+                largest_volume = volume;
+            }
+            if stats.smallest.is_none() || volume < smallest_volume {
+                stats.smallest = Some(gift_box);
+                smallest_volume = volume;
+            }
+
+            hungriest.push(Reverse((
+                wide.paper()?,
+                Reverse(idx),
+                gift_box.dimensions(),
+            )));
+            if hungriest.len() > top {
+                hungriest.pop();
+            }
+        }
+
+        if stats.boxes > 0 {
+            for (mean, sum) in stats.mean_dimensions.iter_mut().zip(sums.iter()) {
+                *mean = *sum as f64 / stats.boxes as f64;
+            }
+        }
+        stats.hungriest = hungriest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((paper, _, dimensions))| (HyperBox { dimensions }, paper))
+            .collect();
+
+        Ok(stats)
+    }
+}
+
+/// Compute statistics for the order in `input`, keeping the `top` hungriest boxes.
+pub fn order_stats(input: &str, top: usize) -> Result<OrderStats, Error> {
+    OrderStats::of(parse_boxes(input)?, top).map_err(overflowed)
+}
+
+/// Compute statistics for the order in `input`, keeping the `top` hungriest boxes.
+pub fn stats(input: &InputSource, top: usize) -> Result<OrderStats, Error> {
+    order_stats(&input.read_to_string()?, top)
+}
+
+/// Plan how to cut the paper for `input` from sheets of area `sheet_size`.
+pub fn cut(input: &InputSource, sheet_size: i64) -> Result<CuttingPlan, Error> {
+    let boxes = parse_boxes(&input.read_to_string()?)?;
+    CuttingPlan::first_fit_decreasing(&boxes, sheet_size)
+}
+
+/// How an order of boxes is written.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "lowercase")]
+pub enum InputFormat {
+    /// One box per line, like `2x3x4`.
+    #[default]
+    Lines,
+    /// A JSON array of boxes, each an array of dimensions, like `[[2, 3, 4], [1, 1, 10]]`.
+    Json,
+}
+
+/// Parse the boxes in `input`, written in `format`.
+pub fn parse_order(input: &str, format: InputFormat) -> Result<Vec<GiftBox>, Error> {
+    match format {
+        InputFormat::Lines => parse_boxes(input),
+        InputFormat::Json => {
+            serde_json::from_str(input).map_err(|err| Error::parse("JSON boxes", err))
+        }
+    }
+}
+
+/// The paper and ribbon an order of boxes needs altogether.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Totals {
+    pub boxes: usize,
+    pub paper: u64,
+    pub ribbon: u64,
+}
+
+impl Totals {
+    pub fn of(boxes: &[GiftBox]) -> Result<Self, Error> {
+        Ok(Totals {
+            boxes: boxes.len(),
+            paper: total(boxes, HyperBox::paper)?,
+            ribbon: total(boxes, HyperBox::ribbon)?,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("totals always serialize")
+    }
+}
+
+/// Compute the totals for the boxes in `input`, written in `format`.
+pub fn totals(input: &str, format: InputFormat) -> Result<Totals, Error> {
+    Totals::of(&parse_order(input, format)?)
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+/// The total of `measure` over `boxes`, in widened arithmetic.
+///
+/// Boxes are measured in parallel, and the total accumulates in a `u64`.
+fn total(
+    boxes: &[GiftBox],
+    measure: impl Fn(&HyperBox<3, i64>) -> Result<i64, Overflow> + Sync,
+) -> Result<u64, Error> {
+    boxes
+        .par_iter()
+        .map(|gift_box| -> Result<u64, Error> {
+            let measurement = measure(&gift_box.widen()).map_err(overflowed)?;
+            Ok(u64::try_from(measurement)?)
+        })
+        .try_reduce(
+            || 0,
+            |sum, measurement| {
+                sum.checked_add(measurement)
+                    .ok_or_else(|| overflowed(Overflow))
+            },
+        )
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    Ok(total(&parse_boxes(input)?, HyperBox::paper)?.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    Ok(total(&parse_boxes(input)?, HyperBox::ribbon)?.into())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "\
+2x3x4
+",
+        expected: "58",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: "\
+1x1x10
+",
+        expected: "43",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: "\
+2x3x4
+",
+        expected: "34",
+        solver: part2_str,
+    },
+    Example {
+        part: 2,
+        input: "\
+1x1x10
+",
+        expected: "14",
+        solver: part2_str,
+    },
+];
+
+/// The most efficient reshaping of each box in `input`, allowing `tolerance` more volume.
+pub fn optimize(input: &InputSource, tolerance: f64) -> Result<Vec<Reshaping>, Error> {
+    Ok(reshape(parse_boxes(&input.read_to_string()?)?, tolerance))
 }
 
 #[cfg(test)]
@@ -143,7 +655,7 @@ mod tests {
         let expected = vec![6, 52, 42];
 
         for (g, e) in get_boxes().iter().zip(expected) {
-            assert_eq!(g.surface_area(), e);
+            assert_eq!(g.surface_area(), Ok(e));
         }
     }
 
@@ -152,7 +664,7 @@ mod tests {
         let expected = vec![1, 6, 1];
 
         for (g, e) in get_boxes().iter().zip(expected) {
-            assert_eq!(g.smallest_side(), e);
+            assert_eq!(g.smallest_side(), Ok(e));
         }
     }
 
@@ -161,7 +673,7 @@ mod tests {
         let expected = vec![7, 58, 43];
 
         for (g, e) in get_boxes().iter().zip(expected) {
-            assert_eq!(g.paper(), e);
+            assert_eq!(g.paper(), Ok(e));
         }
     }
 
@@ -170,7 +682,7 @@ mod tests {
         let expected = vec![1, 24, 10];
 
         for (g, e) in get_boxes().iter().zip(expected) {
-            assert_eq!(g.volume(), e);
+            assert_eq!(g.volume(), Ok(e));
         }
     }
 
@@ -179,7 +691,7 @@ mod tests {
         let expected = vec![4, 10, 4];
 
         for (g, e) in get_boxes().iter().zip(expected) {
-            assert_eq!(g.smallest_side_perimeter(), e);
+            assert_eq!(g.smallest_side_perimeter(), Ok(e));
         }
     }
 
@@ -188,7 +700,202 @@ mod tests {
         let expected = vec![5, 34, 14];
 
         for (g, e) in get_boxes().iter().zip(expected) {
-            assert_eq!(g.ribbon(), e);
+            assert_eq!(g.ribbon(), Ok(e));
         }
     }
+
+    #[test]
+    fn test_most_efficient_exact_volume() {
+        // 1x1x10 can only be reshaped into 1x2x5 at the same volume
+        let efficient = GiftBox::new(1, 1, 10).unwrap().most_efficient(0.0);
+        assert_eq!(efficient, GiftBox::new(1, 2, 5).unwrap());
+        assert_eq!(efficient.volume(), Ok(10));
+
+        // 2x3x4 is already as good as it gets at volume 24
+        let gift_box = GiftBox::new(2, 3, 4).unwrap();
+        assert_eq!(gift_box.most_efficient(0.0), gift_box);
+    }
+
+    #[test]
+    fn test_most_efficient_with_tolerance() {
+        // 1x1x7 is prime: no reshaping possible without growing, but 2x2x2 holds it with 8/7
+        let gift_box = GiftBox::new(1, 1, 7).unwrap();
+        assert_eq!(gift_box.most_efficient(0.0), gift_box);
+        assert_eq!(
+            gift_box.most_efficient(0.15),
+            GiftBox::new(2, 2, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reshape_savings() {
+        let reshapings = reshape(get_boxes(), 0.0);
+        let paper: Vec<_> = reshapings.iter().map(Reshaping::paper_saved).collect();
+        // 1x1x10 (43 paper, 14 ribbon) becomes 1x2x5 (36 paper, 16 ribbon)
+        assert_eq!(paper, vec![Ok(0), Ok(0), Ok(7)]);
+        assert_eq!(reshapings[2].ribbon_saved(), Ok(-2));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let gift_box: GiftBox = "2x3x4".parse().unwrap();
+        assert_eq!(gift_box, GiftBox::new(2, 3, 4).unwrap());
+        assert_eq!(gift_box.to_string(), "2x3x4");
+        assert!("2x3".parse::<GiftBox>().is_err());
+        assert!("2x3x4x5".parse::<GiftBox>().is_err());
+        let tesseract: HyperBox<4> = "2x3x4x5".parse().unwrap();
+        assert_eq!(tesseract.dimensions(), [2, 3, 4, 5]);
+        assert_eq!(tesseract.to_string(), "2x3x4x5");
+    }
+
+    #[test]
+    fn test_other_dimensions() {
+        // a rectangle's sides are line segments, whose boundaries are their two ends
+        let rectangle = HyperBox::from_dimensions([3, 5]).unwrap();
+        assert_eq!(rectangle.surface_area(), Ok(16));
+        assert_eq!(rectangle.smallest_side(), Ok(3));
+        assert_eq!(rectangle.paper(), Ok(19));
+        assert_eq!(rectangle.ribbon(), Ok(15 + 2));
+
+        // 2x3x4x5 has sides 60, 40, 30, 24; the smallest side is the box 2x3x4
+        let tesseract = HyperBox::from_dimensions([2, 3, 4, 5]).unwrap();
+        assert_eq!(tesseract.surface_area(), Ok(2 * (60 + 40 + 30 + 24)));
+        assert_eq!(tesseract.smallest_side(), Ok(24));
+        assert_eq!(tesseract.smallest_side_perimeter(), Ok(52));
+        assert_eq!(tesseract.ribbon(), Ok(120 + 52));
+        assert!(HyperBox::from_dimensions([1, 0, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let input = "2x3x4\n1x1x10\n";
+        let items: Vec<_> = breakdown(input).unwrap().collect();
+        assert_eq!(
+            items,
+            vec![
+                Ok((GiftBox::new(2, 3, 4).unwrap(), 58, 34)),
+                Ok((GiftBox::new(1, 1, 10).unwrap(), 43, 14)),
+            ]
+        );
+
+        let mut csv = Vec::new();
+        write_report(input, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "box,paper,ribbon\n\
+             2x3x4,58,34\n\
+             1x1x10,43,14\n\
+             total,101,48\n"
+        );
+    }
+
+    #[test]
+    fn test_order_stats() {
+        let stats = order_stats("2x3x4\n1x1x10\n1x1x1\n4x3x2\n", 2).unwrap();
+        assert_eq!(stats.boxes, 4);
+        assert_eq!(stats.total_volume, 24 + 10 + 1 + 24);
+        assert_eq!(stats.largest, GiftBox::new(2, 3, 4).ok());
+        assert_eq!(stats.smallest, GiftBox::new(1, 1, 1).ok());
+        assert_eq!(stats.mean_dimensions, [2.0, 2.0, 4.25]);
+        // 2x3x4 and 4x3x2 need the same paper; the earlier one comes first
+        assert_eq!(
+            stats.hungriest,
+            vec![
+                (GiftBox::new(2, 3, 4).unwrap(), 58),
+                (GiftBox::new(4, 3, 2).unwrap(), 58),
+            ]
+        );
+
+        let stats = order_stats("2x3x4\n1x1x10\n", 5).unwrap();
+        assert_eq!(stats.hungriest.len(), 2);
+        assert_eq!(stats.hungriest[1], (GiftBox::new(1, 1, 10).unwrap(), 43));
+        assert_eq!(order_stats("", 3).unwrap(), OrderStats::default());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| s.parse::<GiftBox>().unwrap_err().to_string();
+        assert_eq!(
+            parse("2x3"),
+            "expected 3 dimensions separated by 'x', found 2"
+        );
+        assert_eq!(parse("2xx4"), "width is missing");
+        assert_eq!(parse("2x3xfour"), "height \"four\" is not a number");
+        assert_eq!(parse("2x3x9999999999"), "height 9999999999 is out of range");
+        assert_eq!(parse("0x3x4"), "length must be positive, not 0");
+        assert_eq!(parse("2x-3x4"), "width must be positive, not -3");
+        assert_eq!(
+            "1x2x3x0".parse::<HyperBox<4>>().unwrap_err().to_string(),
+            "dimension 4 must be positive, not 0"
+        );
+    }
+
+    #[test]
+    fn test_part1_reports_bad_line() {
+        let err = part1_str("2x3x4\n\n1x0x10\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parsing line 3: \"1x0x10\": width must be positive, not 0"
+        );
+        assert!(part2_str("2x3\n").is_err());
+    }
+
+    #[test]
+    fn test_other_scalars() {
+        let fractional: HyperBox<3, f64> = "0.5x1x2".parse().unwrap();
+        assert_eq!(fractional.surface_area(), Ok(7.0));
+        assert_eq!(fractional.paper(), Ok(7.5));
+        assert_eq!(fractional.ribbon(), Ok(1.0 + 3.0));
+        assert_eq!(fractional.to_string(), "0.5x1x2");
+        assert!("0.0x1x2".parse::<HyperBox<3, f64>>().is_err());
+
+        // too large to measure in i32, but not in i64
+        let huge = GiftBox::new(100_000, 100_000, 100_000).unwrap();
+        assert_eq!(huge.volume(), Err(Overflow));
+        assert_eq!(huge.paper(), Err(Overflow));
+        assert_eq!(huge.widen().volume(), Ok(1_000_000_000_000_000));
+        assert_eq!(huge.widen().paper(), Ok(70_000_000_000));
+        assert_eq!(
+            part1_str("100000x100000x100000\n").unwrap(),
+            Answer::Int(70_000_000_000)
+        );
+
+        let enormous: HyperBox<3, i64> = "3000000x3000000x3000000".parse().unwrap();
+        assert_eq!(enormous.volume(), Err(Overflow));
+    }
+
+    #[test]
+    fn test_totals_exceed_i32() {
+        // 2 * 30000 * 30000 * 3 + 30000 * 30000 paper per box
+        let input = "30000x30000x30000\n".repeat(1000);
+        assert_eq!(part1_str(&input).unwrap(), Answer::Int(6_300_000_000_000));
+        assert_eq!(
+            part2_str(&input).unwrap(),
+            Answer::Int(27_000_000_000_000_000 + 120_000_000)
+        );
+        let err = part1_str(&format!("{}1x1\n", "1x1x1\n".repeat(100))).unwrap_err();
+        assert!(err.to_string().starts_with("parsing line 101"));
+    }
+
+    #[test]
+    fn test_json() {
+        let boxes = parse_order("[[2, 3, 4], [1, 1, 10]]", InputFormat::Json).unwrap();
+        assert_eq!(
+            boxes,
+            parse_order("2x3x4\n1x1x10\n", InputFormat::Lines).unwrap()
+        );
+        assert_eq!(serde_json::to_string(&boxes).unwrap(), "[[2,3,4],[1,1,10]]");
+
+        let totals = Totals::of(&boxes).unwrap();
+        assert_eq!(totals.to_json(), r#"{"boxes":2,"paper":101,"ribbon":48}"#);
+
+        let err = |input| {
+            parse_order(input, InputFormat::Json)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(err("[[2, 3]]").contains("expected 3 dimensions separated by 'x', found 2"));
+        assert!(err("[[2, 0, 4]]").contains("width must be positive, not 0"));
+        assert!(err("[\"2x3x4\"]").starts_with("parsing JSON boxes"));
+    }
 }
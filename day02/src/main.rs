@@ -1,5 +1,6 @@
-use aoclib::{config::Config, website::get_input};
-use day02::{part1, part2};
+use day02::{
+    cut, optimize, part1_from_reader, part2_from_reader, report, stats, totals, InputFormat,
+};
 
 use color_eyre::eyre::Result;
 use std::path::PathBuf;
@@ -9,44 +10,99 @@ const DAY: u8 = 2;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// how the boxes are written: lines, or json to read an array of boxes and print the totals
+    /// as json
+    #[structopt(long, default_value)]
+    format: InputFormat,
 
-    /// run part 2
+    /// report savings from reshaping each box, allowing this fractional volume increase
     #[structopt(long)]
-    part2: bool,
-}
+    optimize: Option<f64>,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// write the paper and ribbon for each box, and their totals, as CSV to this file
+    #[structopt(long, parse(from_os_str))]
+    report: Option<PathBuf>,
+
+    /// print statistics about the order of boxes
+    #[structopt(long)]
+    stats: bool,
+
+    /// with --stats, how many of the boxes needing the most paper to list
+    #[structopt(long, default_value = "5")]
+    top: usize,
+
+    /// report how many sheets of paper of this area the boxes' paper must be cut from
+    #[structopt(long)]
+    sheet: Option<i64>,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day02::EXAMPLES)?;
+        return Ok(());
+    }
+    let input = args.common.source(DAY)?.buffered()?;
+    if args.format == InputFormat::Json {
+        println!(
+            "{}",
+            totals(&input.read_to_string()?, args.format)?.to_json()
+        );
+        return Ok(());
+    }
 
-    if !args.no_part1 {
-        part1(&input_path)?;
+    let answers = args
+        .common
+        .run(DAY, "", &input, part1_from_reader, part2_from_reader)?;
+    args.common.check(DAY, &answers)?;
+    if let Some(tolerance) = args.optimize {
+        let mut paper = 0_i64;
+        let mut ribbon = 0_i64;
+        for reshaping in optimize(&input, tolerance)? {
+            let paper_saved = reshaping.paper_saved()?;
+            let ribbon_saved = reshaping.ribbon_saved()?;
+            if reshaping.efficient != reshaping.original {
+                println!(
+                    "{:>10} -> {:<10} saves {:5} paper, {:5} ribbon",
+                    reshaping.original.to_string(),
+                    reshaping.efficient.to_string(),
+                    paper_saved,
+                    ribbon_saved,
+                );
+            }
+            paper += i64::from(paper_saved);
+            ribbon += i64::from(ribbon_saved);
+        }
+        println!("total paper saved: {}", paper);
+        println!("total ribbon saved: {}", ribbon);
+    }
+    if let Some(ref output) = args.report {
+        report(&input, output)?;
+    }
+    if args.stats {
+        let stats = stats(&input, args.top)?;
+        println!("boxes: {}", stats.boxes);
+        println!("total volume: {}", stats.total_volume);
+        if let (Some(largest), Some(smallest)) = (stats.largest, stats.smallest) {
+            println!("largest box: {}", largest);
+            println!("smallest box: {}", smallest);
+        }
+        let [x, y, z] = stats.mean_dimensions;
+        println!("mean dimensions: {:.2}x{:.2}x{:.2}", x, y, z);
+        for (gift_box, paper) in &stats.hungriest {
+            println!("{:>10} needs {} paper", gift_box.to_string(), paper);
+        }
     }
-    if args.part2 {
-        part2(&input_path)?;
+    if let Some(sheet_size) = args.sheet {
+        let plan = cut(&input, sheet_size)?;
+        println!("sheets needed: {}", plan.sheets_needed());
+        println!("at least: {}", plan.lower_bound());
+        println!("waste: {}", plan.waste());
     }
     Ok(())
 }
@@ -0,0 +1,139 @@
+//! The numbers a box's dimensions may be measured in.
+
+use std::{
+    fmt,
+    num::{IntErrorKind, ParseIntError},
+};
+
+/// A measurement was too large for its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("box measurement overflowed")]
+pub struct Overflow;
+
+/// Why some text isn't a dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseScalarError {
+    Empty,
+    Invalid,
+    OutOfRange,
+}
+
+/// A type of number in which boxes are measured.
+///
+/// Arithmetic is checked: measurements which don't fit in the type are an [`Overflow`].
+pub trait Scalar: Copy + PartialOrd + Send + Sync + fmt::Debug + fmt::Display {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn parse(text: &str) -> Result<Self, ParseScalarError>;
+
+    fn checked_add(self, other: Self) -> Result<Self, Overflow>;
+
+    fn checked_mul(self, other: Self) -> Result<Self, Overflow>;
+}
+
+fn parse_int<T: std::str::FromStr<Err = ParseIntError>>(text: &str) -> Result<T, ParseScalarError> {
+    text.parse().map_err(|err: ParseIntError| match err.kind() {
+        IntErrorKind::Empty => ParseScalarError::Empty,
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => ParseScalarError::OutOfRange,
+        _ => ParseScalarError::Invalid,
+    })
+}
+
+macro_rules! impl_scalar_int {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn parse(text: &str) -> Result<Self, ParseScalarError> {
+                    parse_int(text)
+                }
+
+                fn checked_add(self, other: Self) -> Result<Self, Overflow> {
+                    <$t>::checked_add(self, other).ok_or(Overflow)
+                }
+
+                fn checked_mul(self, other: Self) -> Result<Self, Overflow> {
+                    <$t>::checked_mul(self, other).ok_or(Overflow)
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_int!(i32, i64);
+
+/// Floating point arithmetic overflows to infinity.
+fn finite(value: f64) -> Result<f64, Overflow> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(Overflow)
+    }
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn parse(text: &str) -> Result<Self, ParseScalarError> {
+        if text.is_empty() {
+            return Err(ParseScalarError::Empty);
+        }
+        let value: f64 = text.parse().map_err(|_| ParseScalarError::Invalid)?;
+        if value.is_nan() {
+            Err(ParseScalarError::Invalid)
+        } else if value.is_infinite() {
+            Err(ParseScalarError::OutOfRange)
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn checked_add(self, other: Self) -> Result<Self, Overflow> {
+        finite(self + other)
+    }
+
+    fn checked_mul(self, other: Self) -> Result<Self, Overflow> {
+        finite(self * other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(<i32 as Scalar>::parse("12"), Ok(12));
+        assert_eq!(<i32 as Scalar>::parse(""), Err(ParseScalarError::Empty));
+        assert_eq!(
+            <i32 as Scalar>::parse("1.5"),
+            Err(ParseScalarError::Invalid)
+        );
+        assert_eq!(
+            <i32 as Scalar>::parse("9999999999"),
+            Err(ParseScalarError::OutOfRange)
+        );
+        assert_eq!(<i64 as Scalar>::parse("9999999999"), Ok(9999999999));
+        assert_eq!(<f64 as Scalar>::parse("1.5"), Ok(1.5));
+        assert_eq!(
+            <f64 as Scalar>::parse("1e999"),
+            Err(ParseScalarError::OutOfRange)
+        );
+        assert_eq!(
+            <f64 as Scalar>::parse("NaN"),
+            Err(ParseScalarError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(Scalar::checked_mul(i32::MAX, 2), Err(Overflow));
+        assert_eq!(Scalar::checked_mul(i32::MAX as i64, 2), Ok(4294967294));
+        assert_eq!(Scalar::checked_add(f64::MAX, f64::MAX), Err(Overflow));
+        assert_eq!(Scalar::checked_mul(1.5, 3.0), Ok(4.5));
+    }
+}
@@ -0,0 +1,315 @@
+//! The alphabets in which Santa's directions may be written.
+//!
+//! The puzzle's directions are the four arrows `^v<>`, but paths recorded elsewhere may use other
+//! symbols, or move diagonally too. An [`Alphabet`] turns each symbol into a [`Heading`], one of
+//! the eight compass points.
+
+use aoclib::geometry::{Direction, Point};
+use std::{collections::HashMap, fmt, str::FromStr};
+use util::Error;
+
+/// One of the eight directions in which Santa can move to a neighboring house.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, parse_display::FromStr, parse_display::Display,
+)]
+pub enum Heading {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Heading {
+    /// The change in position from moving one house in this heading.
+    pub fn offset(self) -> Point {
+        let (vertical, horizontal) = match self {
+            Heading::N => (Some(Direction::Up), None),
+            Heading::NE => (Some(Direction::Up), Some(Direction::Right)),
+            Heading::E => (None, Some(Direction::Right)),
+            Heading::SE => (Some(Direction::Down), Some(Direction::Right)),
+            Heading::S => (Some(Direction::Down), None),
+            Heading::SW => (Some(Direction::Down), Some(Direction::Left)),
+            Heading::W => (None, Some(Direction::Left)),
+            Heading::NW => (Some(Direction::Up), Some(Direction::Left)),
+        };
+        vertical
+            .into_iter()
+            .chain(horizontal)
+            .fold(Point::new(0, 0), |offset, direction| offset + direction)
+    }
+}
+
+/// How directions are written.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Alphabet {
+    /// The puzzle's own: `^`, `v`, `<`, and `>`, with no diagonals.
+    #[default]
+    Arrows,
+    /// The digits as laid out on a numeric keypad, so `8` is north and `3` is southeast.
+    Numpad,
+    /// Compass points such as `N` or `SW`, separated by whitespace or commas.
+    Compass,
+    /// Whichever symbols the user chooses, each standing for a heading.
+    Custom(HashMap<char, Heading>),
+}
+
+impl Alphabet {
+    /// The heading a single symbol stands for.
+    pub fn heading(&self, ch: char) -> Result<Heading, Error> {
+        let heading = match self {
+            Alphabet::Arrows => match ch {
+                '^' => Some(Heading::N),
+                'v' => Some(Heading::S),
+                '<' => Some(Heading::W),
+                '>' => Some(Heading::E),
+                _ => None,
+            },
+            Alphabet::Numpad => match ch {
+                '8' => Some(Heading::N),
+                '9' => Some(Heading::NE),
+                '6' => Some(Heading::E),
+                '3' => Some(Heading::SE),
+                '2' => Some(Heading::S),
+                '1' => Some(Heading::SW),
+                '4' => Some(Heading::W),
+                '7' => Some(Heading::NW),
+                _ => None,
+            },
+            Alphabet::Compass => ch.to_string().parse().ok(),
+            Alphabet::Custom(symbols) => symbols.get(&ch).copied(),
+        };
+        heading.ok_or_else(|| Error::parse(format!("'{}'", ch), "not a direction"))
+    }
+
+    /// The headings written in `directions`, in order.
+    ///
+    /// An unknown symbol is yielded as an error, and ends the iteration. Whitespace is unknown
+    /// unless `mode` ignores it, or the alphabet separates its symbols with it anyway.
+    ///
+    /// ```
+    /// # use day03::alphabet::{Alphabet, Heading, ParseMode};
+    /// let headings = Alphabet::Compass
+    ///     .moves("N, ne se", ParseMode::Strict)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(headings, vec![Heading::N, Heading::NE, Heading::SE]);
+    /// ```
+    pub fn moves<'a>(&'a self, directions: &'a str, mode: ParseMode) -> Moves<'a> {
+        Moves {
+            alphabet: self,
+            rest: directions,
+            mode,
+            failed: false,
+        }
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = Error;
+
+    /// Either the name of a built-in alphabet, or a list of symbols and the headings they stand
+    /// for, like `U=N,D=S,L=W,R=E`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "arrows" => return Ok(Alphabet::Arrows),
+            "numpad" => return Ok(Alphabet::Numpad),
+            "compass" => return Ok(Alphabet::Compass),
+            _ => {}
+        }
+        let mut symbols = HashMap::new();
+        for pair in s.split(',') {
+            let mut parts = pair.splitn(2, '=');
+            let symbol = parts.next().unwrap_or_default().trim();
+            let heading = parts
+                .next()
+                .ok_or_else(|| Error::parse(pair, "expected a symbol, '=', and a heading"))?;
+            let mut chars = symbol.chars();
+            let ch = match (chars.next(), chars.next()) {
+                (Some(ch), None) => ch,
+                _ => return Err(Error::parse(pair, "symbols must be a single character")),
+            };
+            let heading = heading
+                .trim()
+                .to_uppercase()
+                .parse()
+                .map_err(|_| Error::parse(pair, "not a heading, like N or SE"))?;
+            if symbols.insert(ch, heading).is_some() {
+                return Err(Error::parse(pair, "symbol given more than one heading"));
+            }
+        }
+        Ok(Alphabet::Custom(symbols))
+    }
+}
+
+impl fmt::Display for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alphabet::Arrows => write!(f, "arrows"),
+            Alphabet::Numpad => write!(f, "numpad"),
+            Alphabet::Compass => write!(f, "compass"),
+            Alphabet::Custom(symbols) => {
+                // sorted, so that equal alphabets display the same way
+                let mut symbols: Vec<_> = symbols.iter().collect();
+                symbols.sort_unstable_by_key(|&(ch, _)| ch);
+                for (idx, (ch, heading)) in symbols.into_iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}={}", ch, heading)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// What to make of whitespace among the directions.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display,
+)]
+#[display(style = "lowercase")]
+pub enum ParseMode {
+    /// Every symbol must be a direction.
+    #[default]
+    Strict,
+    /// Whitespace, such as the line breaks in piped input, is skipped. Anything else which isn't a
+    /// direction is still an error.
+    Ignore,
+}
+
+/// Iterator over the headings written in some directions, from [`Alphabet::moves`].
+#[derive(Clone, Debug)]
+pub struct Moves<'a> {
+    alphabet: &'a Alphabet,
+    rest: &'a str,
+    mode: ParseMode,
+    failed: bool,
+}
+
+impl<'a> Iterator for Moves<'a> {
+    type Item = Result<Heading, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let heading = match self.alphabet {
+            Alphabet::Compass => {
+                let is_separator = |ch: char| ch.is_whitespace() || ch == ',';
+                let rest = self.rest.trim_start_matches(is_separator);
+                if rest.is_empty() {
+                    return None;
+                }
+                let len = rest.find(is_separator).unwrap_or(rest.len());
+                let (token, rest) = rest.split_at(len);
+                self.rest = rest;
+                token
+                    .to_uppercase()
+                    .parse()
+                    .map_err(|_| Error::parse(format!("'{}'", token), "not a direction"))
+            }
+            _ => {
+                let mut chars = self.rest.chars();
+                let mut ch = chars.next()?;
+                while self.mode == ParseMode::Ignore && ch.is_whitespace() {
+                    ch = chars.next()?;
+                }
+                self.rest = chars.as_str();
+                self.alphabet.heading(ch)
+            }
+        };
+        self.failed = heading.is_err();
+        Some(heading)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(Heading::N.offset(), Point::from(Direction::Up));
+        assert_eq!(Heading::W.offset(), Point::from(Direction::Left));
+        assert_eq!(
+            Heading::SE.offset(),
+            Point::from(Direction::Down) + Point::from(Direction::Right)
+        );
+        let sum = [Heading::NE, Heading::SW, Heading::NW, Heading::SE]
+            .iter()
+            .fold(Point::new(0, 0), |sum, heading| sum + heading.offset());
+        assert_eq!(sum, Point::new(0, 0));
+    }
+
+    #[test]
+    fn test_numpad() {
+        let headings = Alphabet::Numpad
+            .moves("1379", ParseMode::Strict)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            headings,
+            vec![Heading::SW, Heading::SE, Heading::NW, Heading::NE]
+        );
+        assert!(Alphabet::Numpad.heading('5').is_err());
+    }
+
+    #[test]
+    fn test_compass_rejects_unknown_tokens() {
+        let mut moves = Alphabet::Compass.moves("N NNE S", ParseMode::Strict);
+        assert_eq!(moves.next().unwrap().unwrap(), Heading::N);
+        assert!(moves.next().unwrap().is_err());
+        assert!(moves.next().is_none());
+        assert_eq!(
+            Alphabet::Compass.moves(" ,\n", ParseMode::Strict).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let directions = "^>\n v<\r\n";
+        let strict: Vec<_> = Alphabet::Arrows
+            .moves(directions, ParseMode::Strict)
+            .collect();
+        assert_eq!(strict.len(), 3);
+        assert!(strict[2].is_err());
+
+        let lenient = Alphabet::Arrows
+            .moves(directions, ParseMode::Ignore)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            lenient,
+            vec![Heading::N, Heading::E, Heading::S, Heading::W]
+        );
+
+        // garbage is still garbage
+        let mut moves = Alphabet::Arrows.moves("^ x", ParseMode::Ignore);
+        assert!(moves.next().unwrap().is_ok());
+        assert!(moves.next().unwrap().is_err());
+        assert!(moves.next().is_none());
+
+        assert_eq!("ignore".parse(), Ok(ParseMode::Ignore));
+        assert_eq!(ParseMode::default().to_string(), "strict");
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let alphabet: Alphabet = "U=N, D=s,L=W,R=E".parse().unwrap();
+        assert_eq!(alphabet.heading('D').unwrap(), Heading::S);
+        assert!(alphabet.heading('^').is_err());
+        assert_eq!(alphabet.to_string(), "D=S,L=W,R=E,U=N");
+        assert_eq!(alphabet.to_string().parse::<Alphabet>().unwrap(), alphabet);
+
+        assert_eq!("numpad".parse::<Alphabet>().unwrap(), Alphabet::Numpad);
+        assert!("U=N,U=S".parse::<Alphabet>().is_err());
+        assert!("UP=N".parse::<Alphabet>().is_err());
+        assert!("U=UP".parse::<Alphabet>().is_err());
+        assert!("U".parse::<Alphabet>().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Writing a trail out, to analyze the walk with other tools.
+
+use crate::{trail::Trail, CookieCrumbs, Error};
+use aoclib::geometry::Point;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{io::Write, path::Path};
+
+/// The widest or tallest trail which may be drawn as text.
+pub const MAX_ASCII_SIDE: usize = 120;
+
+/// How a trail is written.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "lowercase")]
+pub enum ExportFormat {
+    /// One `x,y,presents` row per house, after a header.
+    #[default]
+    Csv,
+    /// An array of objects like `{"x":0,"y":0,"presents":1}`.
+    Json,
+    /// A picture of the trail, north up, for trails no larger than [`MAX_ASCII_SIDE`] on a side.
+    ///
+    /// Each house shows how many presents it received, with `*` for ten or more; `.` marks houses
+    /// which received none.
+    Ascii,
+}
+
+impl ExportFormat {
+    /// The format implied by the extension of `path`: `.csv`, `.json`, or `.txt`.
+    pub fn from_path(path: &Path) -> Result<ExportFormat, Error> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "txt" => Ok(ExportFormat::Ascii),
+            _ => Err(Error::parse(
+                path.display().to_string(),
+                "unknown export format; use .csv, .json, or .txt",
+            )),
+        }
+    }
+}
+
+/// One house, as exported.
+struct Row {
+    x: i32,
+    y: i32,
+    presents: u32,
+}
+
+impl Serialize for Row {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut row = serializer.serialize_struct("Row", 3)?;
+        row.serialize_field("x", &self.x)?;
+        row.serialize_field("y", &self.y)?;
+        row.serialize_field("presents", &self.presents)?;
+        row.end()
+    }
+}
+
+impl<T: Trail> CookieCrumbs<T> {
+    fn rows(&self) -> impl '_ + Iterator<Item = Row> {
+        self.trail
+            .in_reading_order()
+            .into_iter()
+            .map(|(house, presents)| Row {
+                x: house.x,
+                y: house.y,
+                presents,
+            })
+    }
+
+    /// Write every house on the trail, and the presents it received, to `writer` in `format`.
+    ///
+    /// Houses are written in reading order.
+    ///
+    /// ```
+    /// # use day03::{export::ExportFormat, follow_santa, ParseMode};
+    /// let mut csv = Vec::new();
+    /// follow_santa("^v", ParseMode::Strict)
+    ///     .unwrap()
+    ///     .to_writer(&mut csv, ExportFormat::Csv)
+    ///     .unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "x,y,presents\n0,0,2\n0,1,1\n");
+    /// ```
+    pub fn to_writer(&self, mut writer: impl Write, format: ExportFormat) -> Result<(), Error> {
+        match format {
+            ExportFormat::Csv => {
+                writeln!(writer, "x,y,presents")?;
+                for row in self.rows() {
+                    writeln!(writer, "{},{},{}", row.x, row.y, row.presents)?;
+                }
+            }
+            ExportFormat::Json => {
+                let mut serializer = serde_json::Serializer::new(&mut writer);
+                serializer
+                    .collect_seq(self.rows())
+                    .map_err(std::io::Error::from)?;
+                writeln!(writer)?;
+            }
+            ExportFormat::Ascii => writer.write_all(self.to_ascii()?.as_bytes())?,
+        }
+        Ok(())
+    }
+
+    /// Draw the trail as text, north up; see [`ExportFormat::Ascii`].
+    ///
+    /// Fails if the trail is more than [`MAX_ASCII_SIDE`] houses wide or tall.
+    pub fn to_ascii(&self) -> Result<String, Error> {
+        let (min, max) = self.bounding_box();
+        let width = (max.x as i64 - min.x as i64 + 1) as usize;
+        let height = (max.y as i64 - min.y as i64 + 1) as usize;
+        if width > MAX_ASCII_SIDE || height > MAX_ASCII_SIDE {
+            return Err(Error::no_solution(format!(
+                "trail spans {}x{} houses; at most {}x{} can be drawn as text",
+                width, height, MAX_ASCII_SIDE, MAX_ASCII_SIDE
+            )));
+        }
+
+        let mut picture = String::with_capacity((width + 1) * height);
+        for y in (min.y..=max.y).rev() {
+            for x in min.x..=max.x {
+                picture.push(match self.trail.presents(Point::new(x, y)) {
+                    None => '.',
+                    Some(presents) if presents < 10 => {
+                        std::char::from_digit(presents, 10).expect("presents is one digit")
+                    }
+                    Some(_) => '*',
+                });
+            }
+            picture.push('\n');
+        }
+        Ok(picture)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{follow_santa, ParseMode};
+
+    #[test]
+    fn test_json() {
+        let mut json = Vec::new();
+        follow_santa(">^", ParseMode::Strict)
+            .unwrap()
+            .to_writer(&mut json, ExportFormat::Json)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            r#"[{"x":0,"y":0,"presents":1},{"x":1,"y":0,"presents":1},{"x":1,"y":1,"presents":1}]"#
+                .to_string()
+                + "\n"
+        );
+    }
+
+    #[test]
+    fn test_ascii() {
+        let directions = "><".repeat(9) + "^^<";
+        let cc = follow_santa(&directions, ParseMode::Strict).unwrap();
+        assert_eq!(cc.to_ascii().unwrap(), "11.\n.1.\n.*9\n");
+
+        let far = follow_santa(&">".repeat(MAX_ASCII_SIDE), ParseMode::Strict).unwrap();
+        assert!(far.to_ascii().is_err());
+        assert!(far.to_writer(Vec::new(), ExportFormat::Ascii).is_err());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("trail.JSON")).unwrap(),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("trail.txt")).unwrap(),
+            ExportFormat::Ascii
+        );
+        assert!(ExportFormat::from_path(Path::new("trail")).is_err());
+    }
+}
@@ -18,29 +18,111 @@
 //!   starting/ending location.
 //! - `^v^v^v^v^v` delivers a bunch of presents to some very lucky children at only 2 houses.
 
-use aoclib::{
-    geometry::{Direction, Point},
-    parse,
+pub use alphabet::ParseMode;
+use alphabet::{Alphabet, Heading, Moves};
+use aoclib::{geometry::Point, input::parse_str};
+use export::ExportFormat;
+use schedule::{RoundRobin, Scheduler};
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    io::Write,
+    ops::{Add, AddAssign},
+    path::Path,
 };
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
-use thiserror::Error;
+use trail::Trail;
+pub use util::Error;
+use util::{example::Example, grid::DenseMap, Answer, InputSource};
 
+pub mod alphabet;
+pub mod export;
+#[cfg(feature = "viz")]
+pub mod render;
+pub mod schedule;
+pub mod trail;
+
+/// The most houses a dense trail may cover with its bounding box, per house Santa delivers to.
+///
+/// See [`follow_santa_dense`].
+pub const MAX_DENSE_HOUSES_PER_STEP: usize = 64;
+
+/// Iterator over the houses Santa delivers to, in order, starting with the origin.
+///
+/// Directions are parsed as they're needed, so aggregates over enormous inputs need no more memory
+/// than they keep themselves. An invalid direction is yielded as an error, and ends the iteration.
+///
+/// ```
+/// # use day03::DeliveryIter;
+/// # use aoclib::geometry::Point;
+/// let returns = DeliveryIter::new("^>v<^v")
+///     .filter(|house| matches!(house, Ok(house) if *house == Point::new(0, 0)))
+///     .count();
+/// assert_eq!(returns, 3);
+/// ```
 #[derive(Clone, Debug)]
-pub struct CookieCrumbs {
+pub struct DeliveryIter<'a> {
+    moves: Moves<'a>,
+    /// Where Santa is, once he has delivered his first present.
+    santa: Option<Point>,
+    failed: bool,
+}
+
+impl<'a> DeliveryIter<'a> {
+    pub fn new(directions: &'a str) -> Self {
+        Self::with_alphabet(directions, &Alphabet::Arrows, ParseMode::Strict)
+    }
+
+    /// Like [`DeliveryIter::new`], but for directions written in `alphabet`, parsed per `mode`.
+    pub fn with_alphabet(directions: &'a str, alphabet: &'a Alphabet, mode: ParseMode) -> Self {
+        DeliveryIter {
+            moves: alphabet.moves(directions, mode),
+            santa: None,
+            failed: false,
+        }
+    }
+}
+
+impl<'a> Iterator for DeliveryIter<'a> {
+    type Item = Result<Point, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let house = match self.santa {
+            None => Point::new(0, 0),
+            Some(santa) => match self.moves.next()? {
+                Ok(heading) => santa + heading.offset(),
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            },
+        };
+        self.santa = Some(house);
+        Some(Ok(house))
+    }
+}
+
+/// The houses Santa has visited, and where he is now.
+///
+/// The trail is a `HashMap` unless another [`Trail`] is chosen, as by [`CookieCrumbs::dense`].
+#[derive(Clone, Debug)]
+pub struct CookieCrumbs<T = HashMap<Point, u32>> {
     pub santa: Point,
-    pub trail: HashMap<Point, u32>,
+    pub trail: T,
 }
 
-impl Default for CookieCrumbs {
+impl<T: Trail> Default for CookieCrumbs<T> {
     fn default() -> Self {
         let mut cc = CookieCrumbs {
             santa: Point::new(0, 0),
-            trail: HashMap::new(),
+            trail: T::default(),
         };
 
         // by the problem definition, Santa has already visited the house at the origin
-        cc.trail.insert(cc.santa, 1);
+        cc.trail.deliver(cc.santa, 1);
         cc
     }
 }
@@ -50,62 +132,374 @@ impl CookieCrumbs {
         Self::default()
     }
 
-    pub fn move_from_char(&mut self, ch: char) -> Result<(), Error> {
-        let direction = match ch {
-            '^' => Ok(Direction::Up),
-            'v' => Ok(Direction::Down),
-            '<' => Ok(Direction::Left),
-            '>' => Ok(Direction::Right),
-            _ => Err(Error::ParseDirection(ch)),
-        }?;
+    /// Like [`CookieCrumbs::new`]: the trail is kept in a `HashMap`, which costs memory only for
+    /// the houses visited.
+    pub fn hashed() -> CookieCrumbs {
+        Self::default()
+    }
+}
 
-        self.santa += direction;
-        *self.trail.entry(self.santa).or_default() += 1;
+impl CookieCrumbs<DenseMap<u32>> {
+    /// Like [`CookieCrumbs::new`], but keeping the trail in a grid covering its bounding box.
+    ///
+    /// That's faster when Santa keeps close to home, but needs memory for every house in the
+    /// bounding box, visited or not.
+    pub fn dense() -> CookieCrumbs<DenseMap<u32>> {
+        Self::default()
+    }
+}
 
+impl<T: Trail> CookieCrumbs<T> {
+    pub fn move_from_char(&mut self, ch: char) -> Result<(), Error> {
+        self.move_from_char_in(ch, &Alphabet::Arrows)
+    }
+
+    /// Like [`CookieCrumbs::move_from_char`], but for a symbol from `alphabet`.
+    pub fn move_from_char_in(&mut self, ch: char, alphabet: &Alphabet) -> Result<(), Error> {
+        self.move_towards(alphabet.heading(ch)?);
         Ok(())
     }
+
+    /// Move Santa one house towards `heading`, and deliver a present there.
+    pub fn move_towards(&mut self, heading: Heading) {
+        self.deliver(self.santa + heading.offset());
+    }
+
+    /// Move Santa to `house`, and deliver a present there.
+    fn deliver(&mut self, house: Point) {
+        self.santa = house;
+        self.trail.deliver(house, 1);
+    }
+
+    /// The minimum and maximum corners of the smallest rectangle containing every house visited.
+    pub fn bounding_box(&self) -> (Point, Point) {
+        let mut houses = self.trail.visits().map(|(house, _)| house);
+        let first = houses.next().unwrap_or(self.santa);
+        houses.fold((first, first), |(min, max), house| {
+            (
+                Point::new(min.x.min(house.x), min.y.min(house.y)),
+                Point::new(max.x.max(house.x), max.y.max(house.y)),
+            )
+        })
+    }
+
+    /// The house which received the most presents, and how many it received.
+    ///
+    /// Among equals, the first house in reading order wins.
+    pub fn most_visited(&self) -> (Point, u32) {
+        self.trail
+            .visits()
+            .max_by_key(|&(house, presents)| (presents, Reverse((house.y, house.x))))
+            .unwrap_or((self.santa, 0))
+    }
+
+    /// The `n` houses which received the most presents, and how many each received, luckiest
+    /// first.
+    ///
+    /// Among equals, houses earlier in reading order come first, as for
+    /// [`CookieCrumbs::most_visited`].
+    ///
+    /// ```
+    /// # use day03::{follow_santa, ParseMode};
+    /// # use aoclib::geometry::Point;
+    /// let cc = follow_santa("^v^>", ParseMode::Strict).unwrap();
+    /// assert_eq!(
+    ///     cc.luckiest(2),
+    ///     vec![(Point::new(0, 0), 2), (Point::new(0, 1), 2)],
+    /// );
+    /// ```
+    pub fn luckiest(&self, n: usize) -> Vec<(Point, u32)> {
+        // a min-heap of the luckiest so far; among equals, later houses are evicted first
+        let mut luckiest = BinaryHeap::with_capacity(n + 1);
+        for (house, presents) in self.trail.visits() {
+            luckiest.push(Reverse((presents, Reverse((house.y, house.x)))));
+            if luckiest.len() > n {
+                luckiest.pop();
+            }
+        }
+        luckiest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((presents, Reverse((y, x))))| (Point::new(x, y), presents))
+            .collect()
+    }
+
+    /// The number of presents delivered to all houses together.
+    pub fn total_presents(&self) -> u64 {
+        self.trail
+            .visits()
+            .map(|(_, presents)| presents as u64)
+            .sum()
+    }
+
+    /// The number of houses which received at least `k` presents.
+    pub fn houses_with_at_least(&self, k: u32) -> usize {
+        self.trail
+            .visits()
+            .filter(|&(_, presents)| presents >= k)
+            .count()
+    }
+
+    /// The manhattan distance from the origin to where Santa is now.
+    pub fn distance_from_origin(&self) -> i32 {
+        self.santa.manhattan()
+    }
+
+    /// The houses on this trail which aren't on `other`, in reading order.
+    ///
+    /// ```
+    /// # use day03::{follow_santa, ParseMode};
+    /// # use aoclib::geometry::Point;
+    /// let north = follow_santa("^^", ParseMode::Strict).unwrap();
+    /// let round = follow_santa("^>v", ParseMode::Strict).unwrap();
+    /// assert_eq!(north.diff(&round).collect::<Vec<_>>(), vec![Point::new(0, 2)]);
+    /// ```
+    pub fn diff<'a, U: Trail>(
+        &'a self,
+        other: &'a CookieCrumbs<U>,
+    ) -> impl 'a + Iterator<Item = Point> {
+        self.trail
+            .in_reading_order()
+            .into_iter()
+            .map(|(house, _)| house)
+            .filter(move |&house| other.trail.presents(house).is_none())
+    }
+}
+
+/// Merging trails adds up the presents each house received from both.
+///
+/// The merged trail's santa is wherever the left-hand santa is.
+impl<'a, T: Trail, U: Trail> AddAssign<&'a CookieCrumbs<U>> for CookieCrumbs<T> {
+    fn add_assign(&mut self, other: &'a CookieCrumbs<U>) {
+        for (house, presents) in other.trail.visits() {
+            self.trail.deliver(house, presents);
+        }
+    }
+}
+
+impl<T: Trail> AddAssign for CookieCrumbs<T> {
+    fn add_assign(&mut self, other: CookieCrumbs<T>) {
+        *self += &other;
+    }
+}
+
+impl<T: Trail> Add for CookieCrumbs<T> {
+    type Output = CookieCrumbs<T>;
+
+    fn add(mut self, other: CookieCrumbs<T>) -> CookieCrumbs<T> {
+        self += &other;
+        self
+    }
+}
+
+impl<'a, T: Trail> Extend<&'a CookieCrumbs<T>> for CookieCrumbs<T> {
+    fn extend<I: IntoIterator<Item = &'a CookieCrumbs<T>>>(&mut self, iter: I) {
+        for other in iter {
+            *self += other;
+        }
+    }
+}
+
+impl<T: Trail> Extend<CookieCrumbs<T>> for CookieCrumbs<T> {
+    fn extend<I: IntoIterator<Item = CookieCrumbs<T>>>(&mut self, iter: I) {
+        for other in iter {
+            *self += &other;
+        }
+    }
 }
 
 /// Main point of entry to this lib: given a string of directions, follow santa and return the
 /// pattern of cookie crumbs.
 ///
+/// With [`ParseMode::Strict`], any symbol which isn't a direction is an error; with
+/// [`ParseMode::Ignore`], whitespace is skipped.
+///
 /// - `>` delivers presents to 2 houses: one at the starting location, and one to the east.
 ///
 ///   ```
-///   # use day03::follow_santa;
-///   assert_eq!(follow_santa(">").unwrap().trail.len(), 2);
+///   # use day03::{follow_santa, ParseMode};
+///   assert_eq!(follow_santa(">", ParseMode::Strict).unwrap().trail.len(), 2);
 ///   ```
 ///
 /// - `^>v<` delivers presents to 4 houses in a square, including twice to the house at his
 ///   starting/ending location.
 ///
 ///   ```
-///   # use day03::follow_santa;
-///   assert_eq!(follow_santa("^>v<").unwrap().trail.len(), 4);
+///   # use day03::{follow_santa, ParseMode};
+///   assert_eq!(follow_santa("^>v<", ParseMode::Strict).unwrap().trail.len(), 4);
 ///   ```
 ///
 /// - `^v^v^v^v^v` delivers a bunch of presents to some very lucky children at only 2 houses.
 ///
 ///   ```
-///   # use day03::follow_santa;
-///   assert_eq!(follow_santa("^v^v^v^v^v").unwrap().trail.len(), 2);
+///   # use day03::{follow_santa, ParseMode};
+///   assert_eq!(follow_santa("^v^v^v^v^v", ParseMode::Strict).unwrap().trail.len(), 2);
+///   ```
+///
+/// - Piped input often ends in a line break, which only [`ParseMode::Ignore`] tolerates.
+///
+///   ```
+///   # use day03::{follow_santa, ParseMode};
+///   assert!(follow_santa("^>v<\n", ParseMode::Strict).is_err());
+///   assert_eq!(follow_santa("^>v<\n", ParseMode::Ignore).unwrap().trail.len(), 4);
 ///   ```
-pub fn follow_santa(path: &str) -> Result<CookieCrumbs, Error> {
-    let mut cc = CookieCrumbs::new();
-    for ch in path.chars() {
-        cc.move_from_char(ch)?;
+pub fn follow_santa(path: &str, mode: ParseMode) -> Result<CookieCrumbs, Error> {
+    follow_santa_in(path, &Alphabet::Arrows, mode)
+}
+
+/// Like [`follow_santa`], but for directions written in `alphabet`.
+///
+/// ```
+/// # use day03::{alphabet::Alphabet, follow_santa_in, ParseMode};
+/// // a diamond, walked along the diagonals
+/// let cc = follow_santa_in("9317", &Alphabet::Numpad, ParseMode::Strict).unwrap();
+/// assert_eq!(cc.trail.len(), 4);
+/// ```
+pub fn follow_santa_in(
+    path: &str,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+) -> Result<CookieCrumbs, Error> {
+    let mut cc = CookieCrumbs {
+        santa: Point::new(0, 0),
+        trail: HashMap::new(),
+    };
+    for house in DeliveryIter::with_alphabet(path, alphabet, mode) {
+        cc.deliver(house?);
     }
     Ok(cc)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for (idx, line) in parse::<String>(input)?.enumerate() {
-        let delivered = follow_santa(&line)?.trail.len();
-        println!("line {}: {} houses delivered to", idx, delivered);
+/// Like [`follow_santa_in`], but keeping the trail in a grid, as by [`CookieCrumbs::dense`].
+///
+/// A first pass finds the extent of the trail, so that the second can record it in a grid which
+/// never needs to grow. Fails without allocating the grid if it would hold more than
+/// [`MAX_DENSE_HOUSES_PER_STEP`] houses for each direction: a trail which wanders off rather
+/// than doubling back is better kept in a map.
+///
+/// ```
+/// # use day03::{alphabet::Alphabet, follow_santa, follow_santa_dense, ParseMode};
+/// let directions = "^>>vv<<<^^^";
+/// assert_eq!(
+///     follow_santa_dense(directions, &Alphabet::Arrows, ParseMode::Strict).unwrap().trail.len(),
+///     follow_santa(directions, ParseMode::Strict).unwrap().trail.len(),
+/// );
+/// // a staircase covers only a sliver of its bounding box
+/// let staircase = "^>".repeat(1000);
+/// assert!(follow_santa_dense(&staircase, &Alphabet::Arrows, ParseMode::Strict).is_err());
+/// ```
+pub fn follow_santa_dense(
+    path: &str,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+) -> Result<CookieCrumbs<DenseMap<u32>>, Error> {
+    let origin = Point::new(0, 0);
+    let (min, max, houses) = DeliveryIter::with_alphabet(path, alphabet, mode).try_fold(
+        (origin, origin, 0_u64),
+        |(min, max, houses), house| {
+            let house = house?;
+            Ok::<_, Error>((
+                Point::new(min.x.min(house.x), min.y.min(house.y)),
+                Point::new(max.x.max(house.x), max.y.max(house.y)),
+                houses + 1,
+            ))
+        },
+    )?;
+
+    let area = (max.x as i64 - min.x as i64 + 1) as u64 * (max.y as i64 - min.y as i64 + 1) as u64;
+    let limit = houses.saturating_mul(MAX_DENSE_HOUSES_PER_STEP as u64);
+    if area > limit {
+        return Err(Error::TooLarge {
+            algorithm: "a dense trail",
+            max: limit as usize,
+            what: "houses in its bounding box",
+        });
+    }
+
+    let mut cc = CookieCrumbs {
+        santa: origin,
+        trail: DenseMap::with_bounds(min, max),
+    };
+    for house in DeliveryIter::with_alphabet(path, alphabet, mode) {
+        cc.deliver(house?);
     }
+    Ok(cc)
+}
+
+/// Santa's trail following the directions in `input`, for its statistics.
+pub fn stats(input: &InputSource) -> Result<CookieCrumbs, Error> {
+    let line = first_line(input)?;
+    follow_santa(&line, ParseMode::Strict)
+}
+
+/// The `top` houses receiving the most presents in each part: from Santa alone, and from Santa
+/// and Robo-Santa together.
+pub fn luckiest(input: &InputSource, top: usize) -> Result<[Vec<(Point, u32)>; 2], Error> {
+    let line = first_line(input)?;
+    let alone = follow_santa(&line, ParseMode::Strict)?;
+    let together = merge(&follow_n_santas(&line, 2, ParseMode::Strict)?);
+    Ok([alone.luckiest(top), together.luckiest(top)])
+}
+
+/// Write the houses Santa visits following the directions in `input` to `output`, in the format
+/// its extension implies.
+pub fn export(input: &InputSource, output: &Path) -> Result<(), Error> {
+    let format = ExportFormat::from_path(output)?;
+    let line = first_line(input)?;
+    let crumbs = follow_santa(&line, ParseMode::Strict)?;
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(output)?);
+    crumbs.to_writer(&mut writer, format)?;
+    writer.flush()?;
     Ok(())
 }
 
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    part1_str_in(input, &Alphabet::Arrows, ParseMode::Strict)
+}
+
+/// Solve part 1 for directions written in `alphabet`, parsed per `mode`.
+pub fn part1_in(
+    input: impl std::io::Read,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+) -> Result<Answer, Error> {
+    part1_str_in(&util::input::read_all(input)?, alphabet, mode)
+}
+
+pub fn part1_str_in(input: &str, alphabet: &Alphabet, mode: ParseMode) -> Result<Answer, Error> {
+    let delivered = follow_santa_in(&directions(input, mode)?, alphabet, mode)?
+        .trail
+        .len();
+    Ok(delivered.into())
+}
+
+/// The first line of `input`, which holds the puzzle's directions.
+pub(crate) fn first_line(input: &InputSource) -> Result<String, Error> {
+    parse_str::<String>(&input.read_to_string()?)?
+        .next()
+        .ok_or(Error::NoInput)
+}
+
+/// The directions in some puzzle input: its first line, or when ignoring whitespace, all of it.
+fn directions(input: &str, mode: ParseMode) -> Result<Cow<'_, str>, Error> {
+    match mode {
+        ParseMode::Strict => Ok(parse_str::<String>(input)?
+            .next()
+            .ok_or(Error::NoInput)?
+            .into()),
+        ParseMode::Ignore if input.trim().is_empty() => Err(Error::NoInput),
+        ParseMode::Ignore => Ok(input.into()),
+    }
+}
+
 /// Given a string of directions, divide them among `n` anonymous santas.
 /// Each receives one direction in turn following the last.
 ///
@@ -124,60 +518,197 @@ pub fn part1(input: &Path) -> Result<(), Error> {
 ///
 /// # Code Examples
 /// ```
-/// # use day03::follow_n_santas;
-/// # use day03::unique_houses;
-/// let uh = unique_houses(&follow_n_santas("^v", 2).unwrap());
+/// # use day03::{follow_n_santas, unique_houses, ParseMode};
+/// let uh = unique_houses(&follow_n_santas("^v", 2, ParseMode::Strict).unwrap());
 /// assert_eq!(uh, 3);
 /// ```
 /// ```
-/// # use day03::follow_n_santas;
-/// # use day03::unique_houses;
-/// let uh = unique_houses(&follow_n_santas("^>v<", 2).unwrap());
+/// # use day03::{follow_n_santas, unique_houses, ParseMode};
+/// let uh = unique_houses(&follow_n_santas("^>v<", 2, ParseMode::Strict).unwrap());
 /// assert_eq!(uh, 3);
 /// ```
 /// ```
-/// # use day03::follow_n_santas;
-/// # use day03::unique_houses;
-/// let uh = unique_houses(&follow_n_santas("^v^v^v^v^v", 2).unwrap());
+/// # use day03::{follow_n_santas, unique_houses, ParseMode};
+/// let uh = unique_houses(&follow_n_santas("^v^v^v^v^v", 2, ParseMode::Strict).unwrap());
 /// assert_eq!(uh, 11);
 /// ```
-pub fn follow_n_santas(path: &str, n: usize) -> Result<Vec<CookieCrumbs>, Error> {
+pub fn follow_n_santas(path: &str, n: usize, mode: ParseMode) -> Result<Vec<CookieCrumbs>, Error> {
+    follow_n_santas_in(path, n, &Alphabet::Arrows, mode)
+}
+
+/// Like [`follow_n_santas`], but for directions written in `alphabet`.
+pub fn follow_n_santas_in(
+    path: &str,
+    n: usize,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+) -> Result<Vec<CookieCrumbs>, Error> {
+    follow_n_santas_scheduled(path, n, alphabet, mode, &mut RoundRobin)
+}
+
+/// Like [`follow_n_santas_in`], but `scheduler` decides which santa follows each direction.
+///
+/// ```
+/// # use day03::{alphabet::Alphabet, follow_n_santas_scheduled, schedule::BlockSplit, ParseMode};
+/// # use aoclib::geometry::Point;
+/// let santas =
+///     follow_n_santas_scheduled("^^>>", 2, &Alphabet::Arrows, ParseMode::Strict, &mut BlockSplit)
+///         .unwrap();
+/// assert_eq!(santas[0].santa, Point::new(0, 2));
+/// assert_eq!(santas[1].santa, Point::new(2, 0));
+/// ```
+pub fn follow_n_santas_scheduled(
+    path: &str,
+    n: usize,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+    scheduler: &mut dyn Scheduler,
+) -> Result<Vec<CookieCrumbs>, Error> {
     // initialize the output vector
     let mut vout = vec![CookieCrumbs::default(); n];
 
-    for (i, ch) in path.chars().enumerate() {
-        vout[i % n].move_from_char(ch)?;
+    let total = alphabet.moves(path, mode).count();
+    for (i, heading) in alphabet.moves(path, mode).enumerate() {
+        let heading = heading?;
+        if vout.is_empty() {
+            return Err(Error::no_solution("no santas to follow the directions"));
+        }
+        let santa = scheduler.assign(i, total, &vout);
+        vout[santa].move_towards(heading);
     }
 
     Ok(vout)
 }
 
-pub fn unique_houses(v: &[CookieCrumbs]) -> usize {
-    let mut houses: HashSet<Point> = HashSet::new();
+/// Given a string of directions divided among `n` santas as by [`follow_n_santas`], list the
+/// houses each santa visits, in order, starting from the origin.
+///
+/// ```
+/// # use day03::routes;
+/// # use aoclib::geometry::Point;
+/// let routes = routes("^>v", 2).unwrap();
+/// assert_eq!(routes[0], vec![Point::new(0, 0), Point::new(0, 1), Point::new(0, 0)]);
+/// assert_eq!(routes[1], vec![Point::new(0, 0), Point::new(1, 0)]);
+/// ```
+pub fn routes(path: &str, n: usize) -> Result<Vec<Vec<Point>>, Error> {
+    let mut routes = vec![vec![Point::new(0, 0)]; n];
+    for (i, heading) in Alphabet::Arrows.moves(path, ParseMode::Strict).enumerate() {
+        let route = &mut routes[i % n];
+        let position = *route.last().expect("routes are never empty") + heading?.offset();
+        route.push(position);
+    }
+    Ok(routes)
+}
 
-    for cc in v {
-        houses.extend(cc.trail.keys());
+/// Every santa's trail, merged into one.
+///
+/// When there are no santas, that's an empty trail: not even the origin has had a present.
+pub fn merge(v: &[CookieCrumbs]) -> CookieCrumbs {
+    match v.split_first() {
+        Some((first, rest)) => {
+            let mut merged = first.clone();
+            merged.extend(rest);
+            merged
+        }
+        None => CookieCrumbs {
+            santa: Point::new(0, 0),
+            trail: HashMap::new(),
+        },
     }
+}
 
-    houses.len()
+pub fn unique_houses(v: &[CookieCrumbs]) -> usize {
+    merge(v).trail.len()
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for (idx, line) in parse::<String>(input)?.enumerate() {
-        let unique = unique_houses(&follow_n_santas(&line, 2)?);
-        println!("2 santas: line {}: {} houses delivered to", idx, unique);
-    }
-    Ok(())
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    part2_str_in(input, &Alphabet::Arrows, ParseMode::Strict)
+}
+
+/// Solve part 2 for directions written in `alphabet`, parsed per `mode`.
+pub fn part2_in(
+    input: impl std::io::Read,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+) -> Result<Answer, Error> {
+    part2_str_in(&util::input::read_all(input)?, alphabet, mode)
+}
+
+pub fn part2_str_in(input: &str, alphabet: &Alphabet, mode: ParseMode) -> Result<Answer, Error> {
+    part2_str_scheduled(input, alphabet, mode, &mut RoundRobin)
+}
+
+/// Solve part 2 for directions written in `alphabet` and parsed per `mode`, shared out by
+/// `scheduler`.
+pub fn part2_scheduled(
+    input: impl std::io::Read,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+    scheduler: &mut dyn Scheduler,
+) -> Result<Answer, Error> {
+    part2_str_scheduled(&util::input::read_all(input)?, alphabet, mode, scheduler)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("parsing direction from: {0}")]
-    ParseDirection(char),
+pub fn part2_str_scheduled(
+    input: &str,
+    alphabet: &Alphabet,
+    mode: ParseMode,
+    scheduler: &mut dyn Scheduler,
+) -> Result<Answer, Error> {
+    let path = directions(input, mode)?;
+    let unique = unique_houses(&follow_n_santas_scheduled(
+        &path, 2, alphabet, mode, scheduler,
+    )?);
+    Ok(unique.into())
 }
 
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: ">",
+        expected: "2",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: "^>v<",
+        expected: "4",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: "^v^v^v^v^v",
+        expected: "2",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: "^v",
+        expected: "3",
+        solver: part2_str,
+    },
+    Example {
+        part: 2,
+        input: "^>v<",
+        expected: "3",
+        solver: part2_str,
+    },
+    Example {
+        part: 2,
+        input: "^v^v^v^v^v",
+        expected: "11",
+        solver: part2_str,
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,4 +728,198 @@ mod tests {
         let first_visits = cc.trail.values().next().unwrap();
         assert_eq!(first_visits, &1);
     }
+
+    #[test]
+    fn test_trail_stats() {
+        let cc = follow_santa("^v^>v<<", ParseMode::Strict).unwrap();
+        assert_eq!(cc.bounding_box(), (Point::new(-1, 0), Point::new(1, 1)));
+        assert_eq!(cc.most_visited(), (Point::new(0, 0), 3));
+        assert_eq!(cc.total_presents(), 8);
+        assert_eq!(cc.houses_with_at_least(1), cc.trail.len());
+        assert_eq!(cc.houses_with_at_least(2), 2);
+        assert_eq!(cc.houses_with_at_least(3), 1);
+        assert_eq!(cc.distance_from_origin(), 1);
+
+        let cc = CookieCrumbs::new();
+        assert_eq!(cc.bounding_box(), (Point::default(), Point::default()));
+        assert_eq!(cc.total_presents(), 1);
+    }
+
+    #[test]
+    fn test_luckiest() {
+        let cc = follow_santa("^v^v^>v<v<>", ParseMode::Strict).unwrap();
+        assert_eq!(
+            cc.luckiest(3),
+            vec![
+                (Point::new(0, 0), 4),
+                (Point::new(0, 1), 3),
+                (Point::new(0, -1), 2)
+            ]
+        );
+        assert_eq!(cc.luckiest(1), vec![cc.most_visited()]);
+        assert_eq!(cc.luckiest(100).len(), cc.trail.len());
+        assert!(cc.luckiest(0).is_empty());
+    }
+
+    #[test]
+    fn test_merge() {
+        let santas = follow_n_santas("^>>v", 2, ParseMode::Strict).unwrap();
+        let merged = merge(&santas);
+        assert_eq!(merged.total_presents(), 6);
+        assert_eq!(merged.trail.get(&Point::new(0, 0)), Some(&2));
+        assert_eq!(merged.trail.len(), 5);
+        assert_eq!(merged.trail.len(), unique_houses(&santas));
+        assert_eq!(merged.santa, santas[0].santa);
+
+        let mut extended = CookieCrumbs::new();
+        extended.extend(santas.clone());
+        assert_eq!(extended.total_presents(), 7);
+        let added = santas[0].clone() + santas[1].clone();
+        assert_eq!(added.trail, merged.trail);
+
+        assert!(merge(&[]).trail.is_empty());
+        assert_eq!(unique_houses(&[]), 0);
+    }
+
+    #[test]
+    fn test_diff() {
+        let santas = follow_n_santas("^>>v", 2, ParseMode::Strict).unwrap();
+        assert_eq!(
+            santas[0].diff(&santas[1]).collect::<Vec<_>>(),
+            vec![Point::new(0, 1), Point::new(1, 1)]
+        );
+        assert_eq!(
+            santas[1].diff(&santas[0]).collect::<Vec<_>>(),
+            vec![Point::new(1, -1), Point::new(1, 0)]
+        );
+        assert_eq!(santas[0].diff(&santas[0]).count(), 0);
+    }
+
+    #[test]
+    fn test_parts_ignoring_whitespace() {
+        let input = "^v^v^\nv^v^v\n";
+        // strictly, only the first line counts
+        assert_eq!(part1_str(input).unwrap(), Answer::from(2_usize));
+        assert_eq!(
+            part2_str_in(input, &Alphabet::Arrows, ParseMode::Ignore).unwrap(),
+            part2_str("^v^v^v^v^v").unwrap()
+        );
+        assert!(part1_str_in("^ v", &Alphabet::Arrows, ParseMode::Strict).is_err());
+        assert!(part1_str_in(" \n", &Alphabet::Arrows, ParseMode::Ignore).is_err());
+
+        let santas = follow_n_santas("^ v\t^", 2, ParseMode::Ignore).unwrap();
+        assert_eq!(santas[0].santa, Point::new(0, 2));
+        assert_eq!(santas[1].santa, Point::new(0, -1));
+    }
+
+    #[test]
+    fn test_delivery_iter() {
+        let houses: Vec<_> = DeliveryIter::new("^>v").collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            houses,
+            vec![
+                Point::new(0, 0),
+                Point::new(0, 1),
+                Point::new(1, 1),
+                Point::new(1, 0)
+            ]
+        );
+        assert_eq!(DeliveryIter::new("").count(), 1);
+
+        let mut iter = DeliveryIter::new("^x^");
+        assert!(matches!(iter.next(), Some(Ok(_))));
+        assert!(matches!(iter.next(), Some(Ok(_))));
+        assert!(matches!(iter.next(), Some(Err(_))));
+        assert!(iter.next().is_none());
+        assert!(follow_santa("^x^", ParseMode::Strict).is_err());
+        assert!(follow_santa("^x^", ParseMode::Ignore).is_err());
+    }
+
+    #[test]
+    fn test_alphabets_agree() {
+        let arrows = follow_santa("^^>v<<vv>", ParseMode::Strict).unwrap();
+        let compass =
+            follow_santa_in("N N E S W W S S E", &Alphabet::Compass, ParseMode::Strict).unwrap();
+        let custom: Alphabet = "U=N,D=S,L=W,R=E".parse().unwrap();
+        let custom = follow_santa_in("UURDLLDDR", &custom, ParseMode::Strict).unwrap();
+        for cc in &[compass, custom] {
+            assert_eq!(cc.santa, arrows.santa);
+            assert_eq!(cc.trail, arrows.trail);
+        }
+
+        let diagonal = follow_n_santas_in("97", 2, &Alphabet::Numpad, ParseMode::Strict).unwrap();
+        assert_eq!(diagonal[0].santa, Point::new(1, 1));
+        assert_eq!(diagonal[1].santa, Point::new(-1, 1));
+
+        let mut cc = CookieCrumbs::new();
+        assert!(cc.move_from_char_in('^', &Alphabet::Numpad).is_err());
+        cc.move_from_char_in('3', &Alphabet::Numpad).unwrap();
+        assert_eq!(cc.santa, Point::new(1, -1));
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        let directions = "^^<<v>>>>vvvv<^<<<<^";
+        let mut hashed = CookieCrumbs::hashed();
+        let mut dense = CookieCrumbs::dense();
+        for ch in directions.chars() {
+            hashed.move_from_char(ch).unwrap();
+            dense.move_from_char(ch).unwrap();
+        }
+        assert_eq!(dense.santa, hashed.santa);
+        assert_eq!(
+            dense.trail.in_reading_order(),
+            hashed.trail.in_reading_order()
+        );
+        assert_eq!(dense.bounding_box(), hashed.bounding_box());
+        assert_eq!(dense.most_visited(), hashed.most_visited());
+        assert_eq!(dense.luckiest(4), hashed.luckiest(4));
+        assert_eq!(dense.total_presents(), hashed.total_presents());
+        assert_eq!(dense.diff(&hashed).count(), 0);
+
+        let presized =
+            follow_santa_dense(directions, &Alphabet::Arrows, ParseMode::Strict).unwrap();
+        assert_eq!(presized.santa, hashed.santa);
+        assert_eq!(
+            presized.trail.in_reading_order(),
+            hashed.trail.in_reading_order()
+        );
+
+        let merged = dense.clone() + dense;
+        let mut doubled = hashed.clone();
+        doubled += &merged;
+        assert_eq!(doubled.total_presents(), 3 * hashed.total_presents());
+    }
+
+    #[test]
+    fn test_dense_alphabets() {
+        let compass =
+            follow_santa_dense("N E\nS W", &Alphabet::Compass, ParseMode::Ignore).unwrap();
+        assert_eq!(compass.trail.len(), 4);
+        assert_eq!(compass.santa, Point::new(0, 0));
+        let arrows = follow_santa_dense("^>\nv<", &Alphabet::Arrows, ParseMode::Ignore).unwrap();
+        assert_eq!(
+            arrows.trail.in_reading_order(),
+            compass.trail.in_reading_order()
+        );
+        assert!(follow_santa_dense("^x^", &Alphabet::Arrows, ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_dense_rejects_sparse_trails() {
+        // a long straight line fills its bounding box exactly
+        let line = ">".repeat(10_000);
+        assert_eq!(
+            follow_santa_dense(&line, &Alphabet::Arrows, ParseMode::Strict)
+                .unwrap()
+                .trail
+                .len(),
+            10_001
+        );
+        let staircase = "^>".repeat(1000);
+        assert!(matches!(
+            follow_santa_dense(&staircase, &Alphabet::Arrows, ParseMode::Strict),
+            Err(Error::TooLarge { .. })
+        ));
+    }
 }
@@ -1,52 +1,137 @@
-use aoclib::{config::Config, website::get_input};
-use day03::{part1, part2};
+use day03::{
+    alphabet::Alphabet, export, luckiest, part1_in, part2_scheduled, schedule::Schedule, stats,
+    ParseMode,
+};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
+use util::cache::Variant;
 
 const DAY: u8 = 3;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
+
+    /// how the directions are written: arrows, numpad, compass, or symbols and the headings they
+    /// stand for, like U=N,D=S,L=W,R=E
+    #[structopt(long, default_value)]
+    alphabet: Alphabet,
+
+    /// strict, or ignore to skip whitespace among the directions and read every line of the input
+    #[structopt(long, default_value)]
+    parse_mode: ParseMode,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// how part 2 shares the directions between the santas: round-robin, block-split, random
+    /// (with --seed), or closest (to the origin)
+    #[structopt(long, default_value)]
+    schedule: Schedule,
 
-    /// run part 2
+    /// print statistics about the houses Santa visits
     #[structopt(long)]
-    part2: bool,
-}
+    stats: bool,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// list the houses receiving the most presents in each part
+    #[structopt(long)]
+    luckiest: bool,
+
+    /// with --luckiest, how many houses to list
+    #[structopt(long, default_value = "5")]
+    top: usize,
+
+    /// write the houses Santa visits, and the presents each receives, to the specified .csv or
+    /// .json path; or draw them to a .txt path
+    #[structopt(long, parse(from_os_str))]
+    export: Option<std::path::PathBuf>,
+
+    /// render the santas' trails to the specified .png or .svg path; or animate them to a .gif
+    /// path
+    #[structopt(long, parse(from_os_str))]
+    #[cfg(feature = "viz")]
+    trail: Option<std::path::PathBuf>,
+
+    /// draw the houses visited, shaded by presents received, to the specified .png path; or
+    /// animate the walk to a .gif path
+    #[structopt(long, parse(from_os_str))]
+    #[cfg(feature = "viz")]
+    render: Option<std::path::PathBuf>,
+
+    /// number of santas sharing the directions when rendering trails or houses
+    #[structopt(long, default_value = "1")]
+    #[cfg(feature = "viz")]
+    santas: usize,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day03::EXAMPLES)?;
+        return Ok(());
+    }
+    // the seed, which the random schedule depends on, is part of every cache key already
+    let variant = Variant::new()
+        .param("alphabet", &args.alphabet)
+        .param("parse-mode", &args.parse_mode)
+        .param("schedule", &args.schedule);
+    let input = args.common.source(DAY)?.buffered()?;
 
-    if !args.no_part1 {
-        part1(&input_path)?;
+    let alphabet = &args.alphabet;
+    let mode = args.parse_mode;
+    let mut scheduler = args.schedule.scheduler(args.common.seed());
+    let answers = args.common.run(
+        DAY,
+        &variant.to_string(),
+        &input,
+        |input| part1_in(input, alphabet, mode),
+        |input| part2_scheduled(input, alphabet, mode, scheduler.as_mut()),
+    )?;
+    if variant.is_usual() {
+        args.common.check(DAY, &answers)?;
+    }
+    if args.stats {
+        let crumbs = stats(&input)?;
+        let (min, max) = crumbs.bounding_box();
+        let (house, presents) = crumbs.most_visited();
+        println!("houses visited: {}", crumbs.trail.len());
+        println!("presents delivered: {}", crumbs.total_presents());
+        println!(
+            "bounding box: ({}, {}) to ({}, {})",
+            min.x, min.y, max.x, max.y
+        );
+        println!(
+            "most visited: ({}, {}) with {} presents",
+            house.x, house.y, presents
+        );
+        println!(
+            "houses with at least 2 presents: {}",
+            crumbs.houses_with_at_least(2)
+        );
+        println!(
+            "final distance from origin: {}",
+            crumbs.distance_from_origin()
+        );
+    }
+    if args.luckiest {
+        for (part, houses) in (1..).zip(&luckiest(&input, args.top)?) {
+            println!("part {} luckiest houses:", part);
+            for (house, presents) in houses {
+                println!("  ({}, {}) with {} presents", house.x, house.y, presents);
+            }
+        }
+    }
+    if let Some(output_path) = args.export {
+        export(&input, &output_path)?;
+    }
+    #[cfg(feature = "viz")]
+    if let Some(output_path) = args.trail {
+        day03::render::render_trails(&input, &output_path, args.santas)?;
     }
-    if args.part2 {
-        part2(&input_path)?;
+    #[cfg(feature = "viz")]
+    if let Some(output_path) = args.render {
+        day03::render::render_deliveries(&input, &output_path, args.santas)?;
     }
     Ok(())
 }
@@ -0,0 +1,191 @@
+use aoclib::geometry::Point;
+use std::{collections::HashMap, path::Path, time::Duration};
+use viz::{blend, Animation, Canvas, Format, Rgb, Style, Trail, GREEN, RED, WARM_WHITE};
+
+use super::{first_line, routes, Error};
+use util::InputSource;
+
+const COLORS: [viz::Rgb; 3] = [RED, GREEN, WARM_WHITE];
+
+const STYLE: Style = Style {
+    cell: 2,
+    gap: 0,
+    border: 4,
+    background: viz::BLACK,
+};
+
+/// Render the trails of `santas` santas sharing the directions in `input` to `output`.
+///
+/// The format is chosen by the extension of `output`: `.png` or `.svg` show the whole trails,
+/// while `.gif` animates the santas laying them down.
+pub fn render_trails(input: &InputSource, output: &Path, santas: usize) -> Result<(), Error> {
+    let line = first_line(input)?;
+    let routes = routes(&line, santas.max(1))?;
+    let trails: Vec<Trail> = routes
+        .iter()
+        .zip(COLORS.iter().cycle())
+        .map(|(points, color)| Trail {
+            points: points.clone(),
+            color: *color,
+        })
+        .collect();
+
+    match Format::from_path(output)? {
+        Format::Svg => std::fs::write(output, viz::trails_svg(&trails, &STYLE))?,
+        Format::Png => viz::trails_canvas(&trails, &STYLE).write_png(output)?,
+        Format::Gif => animate_trails(&routes, &trails, output)?,
+    }
+    Ok(())
+}
+
+pub const FRAME_DURATION: Duration = Duration::from_millis(40);
+
+/// Long walks are sampled down to about this many frames.
+const MAX_FRAMES: usize = 240;
+
+/// Houses which received a single present are drawn in this color, shading up to warm white for
+/// the house which received the most.
+const FEW_PRESENTS: Rgb = [70, 30, 30];
+
+/// The extent of the houses visited, in cells.
+///
+/// Like [`Trail`]s, larger `y` values are drawn nearer the top of the image.
+struct Grid {
+    min_x: i32,
+    max_y: i32,
+    width: usize,
+    height: usize,
+}
+
+impl Grid {
+    fn of(routes: &[Vec<Point>]) -> Grid {
+        let mut points = routes.iter().flatten();
+        let first = *points.next().expect("routes are never empty");
+        let (mut min, mut max) = (first, first);
+        for point in points {
+            min.x = min.x.min(point.x);
+            min.y = min.y.min(point.y);
+            max.x = max.x.max(point.x);
+            max.y = max.y.max(point.y);
+        }
+        Grid {
+            min_x: min.x,
+            max_y: max.y,
+            width: (max.x - min.x + 1) as usize,
+            height: (max.y - min.y + 1) as usize,
+        }
+    }
+
+    /// Column and row of the cell containing `point`.
+    fn cell(&self, point: Point) -> (usize, usize) {
+        (
+            (point.x - self.min_x) as usize,
+            (self.max_y - point.y) as usize,
+        )
+    }
+
+    fn image_size(&self) -> (usize, usize) {
+        STYLE.image_size(self.width, self.height)
+    }
+
+    /// Paint each house by its presents, relative to `max`, with the santas where they stand.
+    fn draw(&self, presents: &HashMap<Point, u32>, max: u32, santas: &[Point]) -> Canvas {
+        let (width, height) = self.image_size();
+        let mut canvas = Canvas::new(width, height, STYLE.background);
+        for (&house, &count) in presents {
+            let (x, y) = self.cell(house);
+            canvas.fill_cell(&STYLE, x, y, shade(count, max));
+        }
+        for (&santa, color) in santas.iter().zip(COLORS.iter()) {
+            let (x, y) = self.cell(santa);
+            canvas.fill_cell(&STYLE, x, y, *color);
+        }
+        canvas
+    }
+}
+
+/// Animate the santas walking `routes`, each leaving behind its trail from `trails`.
+fn animate_trails(routes: &[Vec<Point>], trails: &[Trail], output: &Path) -> Result<(), Error> {
+    let grid = Grid::of(routes);
+    let (width, height) = grid.image_size();
+    let mut animation = Animation::create(output, width, height, FRAME_DURATION)?;
+    let mut canvas = Canvas::new(width, height, STYLE.background);
+    let santas = routes.len();
+    for trail in trails {
+        let (x, y) = grid.cell(trail.points[0]);
+        canvas.fill_cell(&STYLE, x, y, trail.color);
+    }
+    animation.push(&canvas)?;
+
+    let moves = routes.iter().map(|route| route.len() - 1).sum::<usize>();
+    let per_frame = moves / MAX_FRAMES + 1;
+    for step in 0..moves {
+        let santa = step % santas;
+        let (x, y) = grid.cell(routes[santa][step / santas + 1]);
+        canvas.fill_cell(&STYLE, x, y, trails[santa].color);
+        if (step + 1) % per_frame == 0 || step + 1 == moves {
+            animation.push(&canvas)?;
+        }
+    }
+    // finish on the still image, where later trails are drawn over earlier ones
+    animation.hold(&viz::trails_canvas(trails, &STYLE), 20)?;
+    Ok(())
+}
+
+/// Shade a house which received `presents`, relative to the most any house received.
+///
+/// The scale is logarithmic, so that houses visited a few times stand out from those visited once.
+fn shade(presents: u32, max: u32) -> Rgb {
+    let t = if max > 1 {
+        (presents as f64).ln() / (max as f64).ln()
+    } else {
+        1.0
+    };
+    blend(FEW_PRESENTS, WARM_WHITE, t)
+}
+
+/// Render the houses visited by `santas` santas sharing the directions in `input` to `output`,
+/// colored by how many presents each received.
+///
+/// The format is chosen by the extension of `output`. `.png` shows every delivery, while `.gif`
+/// animates the walk, marking where each santa is.
+pub fn render_deliveries(input: &InputSource, output: &Path, santas: usize) -> Result<(), Error> {
+    let line = first_line(input)?;
+    let santas = santas.max(1);
+    let routes = routes(&line, santas)?;
+    let grid = Grid::of(&routes);
+
+    let mut presents: HashMap<Point, u32> = HashMap::new();
+    for house in routes.iter().flatten() {
+        *presents.entry(*house).or_default() += 1;
+    }
+    let max = presents.values().copied().max().unwrap_or_default();
+
+    match Format::from_path(output)? {
+        Format::Png => grid.draw(&presents, max, &[]).write_png(output)?,
+        Format::Gif => {
+            let (width, height) = grid.image_size();
+            let mut animation = Animation::create(output, width, height, FRAME_DURATION)?;
+            // every santa starts by delivering to the origin
+            let mut delivered: HashMap<Point, u32> = HashMap::new();
+            delivered.insert(Point::new(0, 0), santas as u32);
+            let mut positions = vec![Point::new(0, 0); santas];
+            animation.push(&grid.draw(&delivered, max, &positions))?;
+
+            let moves = routes.iter().map(|route| route.len() - 1).sum::<usize>();
+            let per_frame = moves / MAX_FRAMES + 1;
+            for step in 0..moves {
+                let santa = step % santas;
+                let house = routes[santa][step / santas + 1];
+                positions[santa] = house;
+                *delivered.entry(house).or_default() += 1;
+                if (step + 1) % per_frame == 0 || step + 1 == moves {
+                    animation.push(&grid.draw(&delivered, max, &positions))?;
+                }
+            }
+            animation.hold(&grid.draw(&delivered, max, &[]), 20)?;
+        }
+        format => return Err(viz::Error::Unsupported(format).into()),
+    }
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! Strategies for sharing one list of directions among several santas.
+//!
+//! The puzzle hands the directions out in turn, but that's only one way to dispatch them. Each
+//! [`Scheduler`] decides which santa follows each direction; comparing how many houses they cover
+//! shows how much the choice matters.
+
+use crate::CookieCrumbs;
+use util::rng::{seeded, Rng, SolverRng};
+
+/// Decides which santa follows each direction.
+pub trait Scheduler {
+    /// The index within `santas` of the santa who follows direction `step` of `total`.
+    ///
+    /// `santas` is never empty, and shows where each santa has got to so far.
+    fn assign(&mut self, step: usize, total: usize, santas: &[CookieCrumbs]) -> usize;
+}
+
+/// Each santa follows one direction in turn, as in the puzzle.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoundRobin;
+
+impl Scheduler for RoundRobin {
+    fn assign(&mut self, step: usize, _total: usize, santas: &[CookieCrumbs]) -> usize {
+        step % santas.len()
+    }
+}
+
+/// The directions are cut into one contiguous block per santa, as evenly as possible.
+///
+/// The first santa follows the first block, the second the next, and so on.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BlockSplit;
+
+impl Scheduler for BlockSplit {
+    fn assign(&mut self, step: usize, total: usize, santas: &[CookieCrumbs]) -> usize {
+        // step * n / total never reaches n, and block sizes differ by at most one
+        step * santas.len() / total.max(1)
+    }
+}
+
+/// Each direction goes to a santa chosen at random.
+#[derive(Debug, Clone)]
+pub struct Random {
+    rng: SolverRng,
+}
+
+impl Random {
+    /// The same seed always deals out the directions the same way.
+    pub fn seeded(seed: u64) -> Self {
+        Random { rng: seeded(seed) }
+    }
+}
+
+impl Scheduler for Random {
+    fn assign(&mut self, _step: usize, _total: usize, santas: &[CookieCrumbs]) -> usize {
+        self.rng.gen_range(0..santas.len())
+    }
+}
+
+/// Each direction goes to whichever santa is closest to the origin, keeping the team near home.
+///
+/// Among equally close santas, the first wins.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClosestSanta;
+
+impl Scheduler for ClosestSanta {
+    fn assign(&mut self, _step: usize, _total: usize, santas: &[CookieCrumbs]) -> usize {
+        santas
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, santa)| santa.distance_from_origin())
+            .map(|(idx, _)| idx)
+            .unwrap_or_default()
+    }
+}
+
+/// The name of a [`Scheduler`], to choose one from the command line.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display,
+)]
+#[display(style = "kebab-case")]
+pub enum Schedule {
+    #[default]
+    RoundRobin,
+    BlockSplit,
+    Random,
+    Closest,
+}
+
+impl Schedule {
+    /// A scheduler of this kind. Only [`Schedule::Random`] uses the seed.
+    pub fn scheduler(self, seed: u64) -> Box<dyn Scheduler> {
+        match self {
+            Schedule::RoundRobin => Box::new(RoundRobin),
+            Schedule::BlockSplit => Box::new(BlockSplit),
+            Schedule::Random => Box::new(Random::seeded(seed)),
+            Schedule::Closest => Box::new(ClosestSanta),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alphabet::Alphabet, follow_n_santas, follow_n_santas_scheduled, ParseMode};
+    use aoclib::geometry::Point;
+
+    fn santas(path: &str, n: usize, scheduler: &mut dyn Scheduler) -> Vec<Point> {
+        follow_n_santas_scheduled(path, n, &Alphabet::Arrows, ParseMode::Strict, scheduler)
+            .unwrap()
+            .iter()
+            .map(|cc| cc.santa)
+            .collect()
+    }
+
+    #[test]
+    fn test_round_robin_is_the_puzzle() {
+        let path = "^>v<<^^>";
+        let expect: Vec<_> = follow_n_santas(path, 3, ParseMode::Strict)
+            .unwrap()
+            .iter()
+            .map(|cc| cc.santa)
+            .collect();
+        assert_eq!(santas(path, 3, &mut RoundRobin), expect);
+    }
+
+    #[test]
+    fn test_block_split() {
+        let assigned: Vec<_> = (0..7)
+            .map(|step| BlockSplit.assign(step, 7, &vec![CookieCrumbs::new(); 3]))
+            .collect();
+        assert_eq!(assigned, vec![0, 0, 0, 1, 1, 2, 2]);
+        assert_eq!(
+            santas("^^^vvv", 2, &mut BlockSplit),
+            vec![Point::new(0, 3), Point::new(0, -3)]
+        );
+    }
+
+    #[test]
+    fn test_random_is_reproducible() {
+        let path = "^>v<^^>>vv<<^^^>>>";
+        let first = santas(path, 3, &mut Random::seeded(7));
+        assert_eq!(santas(path, 3, &mut Random::seeded(7)), first);
+        for step in 0..100 {
+            let santa = Random::seeded(step).assign(0, 1, &vec![CookieCrumbs::new(); 3]);
+            assert!(santa < 3);
+        }
+    }
+
+    #[test]
+    fn test_closest_santa() {
+        // whoever steps away first lets the other take the next step
+        assert_eq!(
+            santas(">>>>", 2, &mut ClosestSanta),
+            vec![Point::new(2, 0), Point::new(2, 0)]
+        );
+        // a santa brought back home takes the next direction too
+        assert_eq!(
+            santas("<>>>", 2, &mut ClosestSanta),
+            vec![Point::new(1, 0), Point::new(1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_schedule_names() {
+        assert_eq!("block-split".parse(), Ok(Schedule::BlockSplit));
+        assert_eq!(Schedule::default().to_string(), "round-robin");
+        assert!("fastest".parse::<Schedule>().is_err());
+    }
+}
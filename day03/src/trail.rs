@@ -0,0 +1,76 @@
+//! Where a [`CookieCrumbs`](crate::CookieCrumbs) keeps count of the presents each house received.
+//!
+//! A `HashMap` costs memory only for the houses visited, however far apart they are. A
+//! [`DenseMap`] avoids hashing on every delivery, but its grid covers the whole bounding box of the
+//! trail, so it only pays off when the trail doubles back over itself rather than wandering off.
+
+use aoclib::geometry::Point;
+use std::collections::HashMap;
+use util::grid::DenseMap;
+
+/// Storage for the presents each house received.
+pub trait Trail: Clone + Default {
+    /// The number of houses which received at least one present.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many presents `house` received, if any.
+    fn presents(&self, house: Point) -> Option<u32>;
+
+    /// Deliver `presents` more presents to `house`.
+    fn deliver(&mut self, house: Point, presents: u32);
+
+    /// Every house which received presents, and how many, in no particular order.
+    fn visits(&self) -> Box<dyn '_ + Iterator<Item = (Point, u32)>>;
+
+    /// Every house which received presents, and how many, in reading order.
+    fn in_reading_order(&self) -> Vec<(Point, u32)> {
+        let mut visits: Vec<_> = self.visits().collect();
+        visits.sort_unstable_by_key(|&(house, _)| (house.y, house.x));
+        visits
+    }
+}
+
+impl Trail for HashMap<Point, u32> {
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn presents(&self, house: Point) -> Option<u32> {
+        self.get(&house).copied()
+    }
+
+    fn deliver(&mut self, house: Point, presents: u32) {
+        *self.entry(house).or_default() += presents;
+    }
+
+    fn visits(&self) -> Box<dyn '_ + Iterator<Item = (Point, u32)>> {
+        Box::new(self.iter().map(|(&house, &presents)| (house, presents)))
+    }
+}
+
+impl Trail for DenseMap<u32> {
+    fn len(&self) -> usize {
+        DenseMap::len(self)
+    }
+
+    fn presents(&self, house: Point) -> Option<u32> {
+        self.get(house).copied()
+    }
+
+    fn deliver(&mut self, house: Point, presents: u32) {
+        *self.get_or_default(house) += presents;
+    }
+
+    fn visits(&self) -> Box<dyn '_ + Iterator<Item = (Point, u32)>> {
+        Box::new(self.iter().map(|(house, &presents)| (house, presents)))
+    }
+
+    /// The grid is already stored in reading order, so there's nothing to sort.
+    fn in_reading_order(&self) -> Vec<(Point, u32)> {
+        self.visits().collect()
+    }
+}
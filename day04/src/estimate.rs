@@ -0,0 +1,120 @@
+//! How long a search should take, worked out before starting it.
+
+use crate::{hasher::Hasher, Target};
+use rayon::prelude::*;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use util::timing::format_duration;
+
+/// How many suffixes [`calibrate`] hashes.
+const CALIBRATION_HASHES: u64 = 1 << 18;
+
+/// The work expected to mine a coin, and how long it should take on this machine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkEstimate {
+    /// How many suffixes are tried, on average, before one mines a coin.
+    pub hashes: u64,
+    /// How many suffixes this machine tries per second, once measured.
+    pub rate: Option<f64>,
+}
+
+impl WorkEstimate {
+    /// The work expected to mine `target`, if there's any telling; see [`Target::expected_work`].
+    ///
+    /// The hash rate is left unmeasured.
+    pub fn for_target(target: &Target) -> Option<WorkEstimate> {
+        target
+            .expected_work()
+            .map(|hashes| WorkEstimate { hashes, rate: None })
+    }
+
+    /// This estimate, with the hash rate measured by [`calibrate`].
+    pub fn calibrated(self) -> WorkEstimate {
+        WorkEstimate {
+            rate: Some(calibrate()),
+            ..self
+        }
+    }
+
+    /// How long the expected work should take, once the hash rate is known.
+    pub fn duration(&self) -> Option<Duration> {
+        self.rate
+            .filter(|&rate| rate > 0.0)
+            .map(|rate| Duration::from_secs_f64(self.hashes as f64 / rate))
+    }
+}
+
+impl fmt::Display for WorkEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expecting about {} hashes", self.hashes)?;
+        if let (Some(rate), Some(duration)) = (self.rate, self.duration()) {
+            write!(f, ": {} at {:.0}/s", format_duration(duration), rate)?;
+        }
+        Ok(())
+    }
+}
+
+/// The work expected to find a digest with `zeros` leading zeros in hex, with the time it should
+/// take on this machine.
+///
+/// Measuring the hash rate takes a moment.
+pub fn estimate_work(zeros: usize) -> WorkEstimate {
+    WorkEstimate::for_target(&Target::leading_zeros(zeros))
+        .expect("leading zeros fix a known number of bits")
+        .calibrated()
+}
+
+/// Measure how many suffixes per second the CPU miner tries, using every thread it would.
+pub fn calibrate() -> f64 {
+    // suffixes this long are typical of real searches
+    let start = 1 << 20;
+    let started = Instant::now();
+    let zero_bytes = (start..start + CALIBRATION_HASHES)
+        .into_par_iter()
+        .map_init(
+            || Hasher::new("calibrate"),
+            |hasher, suffix| hasher.digest(suffix)[0] == 0,
+        )
+        .filter(|&zero| zero)
+        .count();
+    // the count must be used, so the hashing can't be skipped
+    std::hint::black_box(zero_bytes);
+    CALIBRATION_HASHES as f64 / started.elapsed().as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_hashes() {
+        let estimate = WorkEstimate::for_target(&Target::leading_zeros(6)).unwrap();
+        assert_eq!(estimate.hashes, 1 << 24);
+        assert_eq!(estimate.duration(), None);
+        assert_eq!(estimate.to_string(), "expecting about 16777216 hashes");
+        assert!(WorkEstimate::for_target(&Target::custom(|_| true)).is_none());
+    }
+
+    #[test]
+    fn test_duration() {
+        let estimate = WorkEstimate {
+            hashes: 1 << 20,
+            rate: Some(524288.0),
+        };
+        assert_eq!(estimate.duration(), Some(Duration::from_secs(2)));
+        assert_eq!(
+            estimate.to_string(),
+            "expecting about 1048576 hashes: 2.00s at 524288/s"
+        );
+    }
+
+    #[test]
+    fn test_calibrated() {
+        let estimate = estimate_work(5);
+        assert_eq!(estimate.hashes, 1 << 20);
+        assert!(estimate.rate.unwrap() > 0.0);
+        assert!(estimate.duration().is_some());
+    }
+}
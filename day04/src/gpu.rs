@@ -0,0 +1,225 @@
+//! Mining on the GPU, with a compute shader which hashes one suffix per invocation.
+//!
+//! The shader only handles what's cheap to do there: targets which fix a prefix of the digest,
+//! secrets short enough that secret and suffix fit in one MD5 block, and suffixes which fit in a
+//! `u32`. [`crate::mine_target_gpu`] mines anything else on the CPU.
+
+use crate::target::{Digest, Target};
+use std::convert::TryInto;
+use util::progress::Progress;
+
+/// The suffixes below this may be mined on the GPU.
+pub const GPU_SUFFIXES: u64 = u32::MAX as u64;
+
+/// How many suffixes each dispatch hashes.
+const BATCH: u32 = 1 << 21;
+
+/// Must agree with the shader's `@workgroup_size`.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// The longest message which fits in one MD5 block, leaving room for its padding.
+const MAX_MESSAGE: usize = 55;
+
+/// The most digits a suffix below [`GPU_SUFFIXES`] can have.
+const MAX_SUFFIX_DIGITS: usize = 10;
+
+/// The size of the shader's `Params`.
+const PARAMS_SIZE: u64 = 28 * 4;
+
+/// A connection to a GPU, ready to mine.
+pub struct Gpu {
+    info: wgpu::AdapterInfo,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl Gpu {
+    /// Connect to the best adapter available, if there is one.
+    pub fn new() -> Option<Gpu> {
+        pollster::block_on(Gpu::connect())
+    }
+
+    async fn connect() -> Option<Gpu> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await?;
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("day04"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::downlevel_defaults(),
+                },
+                None,
+            )
+            .await
+            .ok()?;
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("md5"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("md5.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("md5"),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+        });
+        Some(Gpu {
+            info: adapter.get_info(),
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    /// The name of the adapter in use.
+    pub fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    /// Whether the shader can mine `target` with `secret`.
+    pub fn can_mine(secret: &str, target: &Target) -> bool {
+        secret.len() + MAX_SUFFIX_DIGITS <= MAX_MESSAGE && target.mask().is_some()
+    }
+
+    /// Find the lowest suffix below [`GPU_SUFFIXES`] whose digest, with `secret`, matches `target`.
+    ///
+    /// Only the puzzles which [`Gpu::can_mine`] accepts may be mined.
+    pub fn mine(&self, secret: &str, target: &Target, progress: &dyn Progress) -> Option<u64> {
+        assert!(Gpu::can_mine(secret, target), "the GPU can't mine this");
+        let (mask, value) = target.mask().expect("checked by can_mine");
+
+        let params = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("params"),
+            size: PARAMS_SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let found = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("found"),
+            size: 4,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("md5"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: found.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut base = 0;
+        while u64::from(base) < GPU_SUFFIXES {
+            let count = BATCH.min(u32::MAX - base);
+            self.queue.write_buffer(
+                &params,
+                0,
+                &params_bytes(base, count, secret, &mask, &value),
+            );
+            self.queue.write_buffer(&found, 0, &u32::MAX.to_le_bytes());
+
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: None,
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(count / WORKGROUP_SIZE + 1, 1, 1);
+            }
+            encoder.copy_buffer_to_buffer(&found, 0, &readback, 0, 4);
+            self.queue.submit(Some(encoder.finish()));
+
+            let slice = readback.slice(..);
+            slice.map_async(wgpu::MapMode::Read, |_| ());
+            self.device.poll(wgpu::Maintain::Wait);
+            let coin = u32::from_le_bytes(
+                slice.get_mapped_range()[..4]
+                    .try_into()
+                    .expect("the buffer holds one u32"),
+            );
+            readback.unmap();
+
+            // no suffix below GPU_SUFFIXES is u32::MAX, so it's free to mean "none found"
+            if coin != u32::MAX {
+                return Some(coin.into());
+            }
+            base += count;
+            progress.advance(base.into());
+        }
+        None
+    }
+}
+
+/// The shader's `Params`, laid out as the uniform buffer expects.
+fn params_bytes(base: u32, count: u32, secret: &str, mask: &Digest, value: &Digest) -> Vec<u8> {
+    let mut secret_bytes = [0; 64];
+    secret_bytes[..secret.len()].copy_from_slice(secret.as_bytes());
+
+    let mut bytes = Vec::with_capacity(PARAMS_SIZE as usize);
+    for word in &[base, count, secret.len() as u32, 0] {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+    // digests and the secret are both little-endian words already, so go in byte for byte
+    bytes.extend_from_slice(mask);
+    bytes.extend_from_slice(value);
+    bytes.extend_from_slice(&secret_bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mine_target;
+    use util::progress::Silent;
+
+    #[test]
+    fn test_agrees_with_cpu() {
+        let gpu = match Gpu::new() {
+            Some(gpu) => gpu,
+            // nothing to test without an adapter
+            None => return,
+        };
+        for target in &[
+            Target::leading_zeros(3),
+            Target::hex_prefix("dead").unwrap(),
+            Target::bit_prefix("1010 1").unwrap(),
+        ] {
+            assert_eq!(
+                gpu.mine("abcdef", target, &Silent),
+                mine_target("abcdef", target)
+            );
+        }
+    }
+
+    #[test]
+    fn test_can_mine() {
+        assert!(Gpu::can_mine("abcdef", &Target::leading_zeros(5)));
+        assert!(!Gpu::can_mine("abcdef", &Target::custom(|_| true)));
+        assert!(!Gpu::can_mine(&"x".repeat(50), &Target::leading_zeros(5)));
+    }
+}
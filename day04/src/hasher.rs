@@ -0,0 +1,139 @@
+//! The hot loop of the miner: hashing the secret with one suffix after another.
+//!
+//! Every candidate shares the secret, so its MD5 state is computed once and copied for each
+//! suffix. Suffixes are encoded into a reusable buffer of decimal digits, which is incremented in
+//! place when suffixes are tried in order, as they are within each of rayon's splits.
+
+use crate::target::Digest;
+use crypto::{digest::Digest as _, md5::Md5};
+
+/// The longest decimal rendering of a `u64`.
+const MAX_DIGITS: usize = 20;
+
+/// A number written in decimal ASCII digits, cheap to increment.
+#[derive(Clone, Copy, Debug)]
+struct Decimal {
+    value: u64,
+    digits: [u8; MAX_DIGITS],
+    /// The index of the most significant digit.
+    start: usize,
+}
+
+impl Decimal {
+    fn new(value: u64) -> Decimal {
+        let mut decimal = Decimal {
+            value,
+            digits: [b'0'; MAX_DIGITS],
+            start: MAX_DIGITS - 1,
+        };
+        decimal.encode(value);
+        decimal
+    }
+
+    fn encode(&mut self, mut value: u64) {
+        self.value = value;
+        self.start = MAX_DIGITS;
+        loop {
+            self.start -= 1;
+            self.digits[self.start] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Add one, carrying as far as necessary.
+    ///
+    /// Must not be called on `u64::MAX`.
+    fn increment(&mut self) {
+        self.value += 1;
+        let mut idx = MAX_DIGITS;
+        loop {
+            idx -= 1;
+            if self.digits[idx] < b'9' {
+                self.digits[idx] += 1;
+                return;
+            }
+            self.digits[idx] = b'0';
+            if idx == self.start {
+                // every digit was a nine: the number grows a digit
+                self.start -= 1;
+                self.digits[self.start] = b'1';
+                return;
+            }
+        }
+    }
+
+    fn set(&mut self, value: u64) {
+        if self.value != u64::MAX && value == self.value + 1 {
+            self.increment();
+        } else if value != self.value {
+            self.encode(value);
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.digits[self.start..]
+    }
+}
+
+/// Computes the digests of one secret followed by many suffixes.
+#[derive(Clone, Copy)]
+pub(crate) struct Hasher {
+    /// The MD5 state after hashing the secret, ready for a suffix.
+    primed: Md5,
+    suffix: Decimal,
+    digest: Digest,
+}
+
+impl Hasher {
+    pub(crate) fn new(secret: &str) -> Hasher {
+        let mut primed = Md5::new();
+        primed.input_str(secret);
+        Hasher {
+            primed,
+            suffix: Decimal::new(0),
+            digest: [0; 16],
+        }
+    }
+
+    /// The digest of the secret followed by `suffix` in decimal.
+    pub(crate) fn digest(&mut self, suffix: u64) -> &Digest {
+        self.suffix.set(suffix);
+        let mut md5 = self.primed;
+        md5.input(self.suffix.as_bytes());
+        md5.result(&mut self.digest);
+        &self.digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal() {
+        let mut decimal = Decimal::new(0);
+        for value in 0..=1001_u64 {
+            decimal.set(value);
+            assert_eq!(decimal.as_bytes(), value.to_string().as_bytes());
+        }
+        for &value in &[99_999, 100_000, 7, u64::MAX - 1, u64::MAX, 0] {
+            decimal.set(value);
+            assert_eq!(decimal.as_bytes(), value.to_string().as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_digest_matches_plain_md5() {
+        let mut hasher = Hasher::new("abcdef");
+        for &suffix in &[609043, 609044, 9, 10, 0] {
+            let mut md5 = Md5::new();
+            md5.input_str(&format!("abcdef{}", suffix));
+            let mut expect = [0; 16];
+            md5.result(&mut expect);
+            assert_eq!(hasher.digest(suffix), &expect);
+        }
+    }
+}
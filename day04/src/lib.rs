@@ -16,58 +16,295 @@
 //!   starting with five zeroes is `1048970`; that is, the MD5 hash of `pqrstuv1048970` looks like
 //!   `000006136ef....`
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 
-use crypto::digest::Digest;
-use crypto::md5::Md5;
+pub use estimate::{estimate_work, WorkEstimate};
+use hasher::Hasher;
 use rayon::prelude::*;
-use std::path::Path;
-use thiserror::Error;
+use std::{
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+pub use target::Target;
+pub use util::Error;
+use util::{
+    example::Example,
+    progress::{Progress, Silent},
+    Answer, InputSource,
+};
 
+pub mod estimate;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+mod hasher;
+pub mod target;
+
+/// Hashes tried are reported to the progress tracker in multiples of this.
+const PROGRESS_INTERVAL: u64 = 1 << 16;
+
+/// [`coins`] searches this many suffixes at a time, in parallel.
+const COIN_BLOCK: u64 = 1 << 16;
+
+/// Find the lowest suffix whose digest, with `secret`, starts with `leading_zeros` zeros in hex.
 pub fn mine_coin(secret: &str, leading_zeros: usize) -> Option<u64> {
-    (0..=u64::MAX)
-        .into_par_iter()
-        .map(|suffix| {
-            let mut md5 = Md5::new();
-            md5.input_str(secret);
-            md5.input_str(&suffix.to_string());
-            (suffix, md5.result_str())
-        })
-        .find_first(|(_suffix, digest)| {
-            digest.chars().take_while(|&ch| ch == '0').count() >= leading_zeros
-        })
-        .map(|(suffix, _digest)| suffix)
+    mine_coin_with_progress(secret, leading_zeros, &Silent)
+}
+
+/// Mine a coin, reporting the suffixes checked so far to `progress`.
+pub fn mine_coin_with_progress(
+    secret: &str,
+    leading_zeros: usize,
+    progress: &dyn Progress,
+) -> Option<u64> {
+    mine_target_with_progress(secret, &Target::leading_zeros(leading_zeros), progress)
+}
+
+/// Mine a coin with no more than `cores` threads.
+///
+/// `0` means one thread per CPU, as with [`mine_coin`].
+pub fn mine_coin_with_cores(
+    secret: &str,
+    leading_zeros: usize,
+    cores: usize,
+) -> Result<Option<u64>, Error> {
+    with_threads(cores, || mine_coin(secret, leading_zeros))
+}
+
+/// Run `f` on a pool of its own with `threads` threads, so any mining it does uses no more.
+///
+/// `0` means one thread per CPU, like rayon's global pool.
+pub fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> Result<T, Error> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(std::io::Error::other)?;
+    Ok(pool.install(f))
+}
+
+/// Find the lowest suffix whose digest, with `secret`, matches `target`.
+///
+/// ```
+/// # use day04::{mine_target, Target};
+/// let suffix = mine_target("abcdef", &Target::hex_prefix("dead").unwrap()).unwrap();
+/// assert_eq!(suffix, 60030);
+/// ```
+pub fn mine_target(secret: &str, target: &Target) -> Option<u64> {
+    mine_target_with_progress(secret, target, &Silent)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for (row, secret) in parse::<String>(input)?.enumerate() {
-        let coin = mine_coin(&secret, 5).ok_or(Error::NoCoin(secret))?;
-        println!("row {} coin (5 leading): {}", row, coin);
+/// Mine for a digest matching `target`, reporting the suffixes checked so far to `progress`.
+pub fn mine_target_with_progress(
+    secret: &str,
+    target: &Target,
+    progress: &dyn Progress,
+) -> Option<u64> {
+    if let Some(expected) = target.expected_work() {
+        progress.set_expected(expected);
     }
-    Ok(())
+    let coin = mine_from(secret, target, 0, progress);
+    progress.finish();
+    coin
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for (row, secret) in parse::<String>(input)?.enumerate() {
-        let coin = mine_coin(&secret, 6).ok_or(Error::NoCoin(secret))?;
-        println!("row {} coin (6 leading): {}", row, coin);
+/// Mine for a digest matching `target` on the GPU, if there is one, reporting the suffixes
+/// checked so far to `progress`.
+///
+/// Falls back to the CPU when there's no GPU, when the GPU can't mine this target or secret, or
+/// when no suffix small enough for the GPU matches.
+#[cfg(feature = "gpu")]
+pub fn mine_target_gpu(secret: &str, target: &Target, progress: &dyn Progress) -> Option<u64> {
+    use gpu::{Gpu, GPU_SUFFIXES};
+
+    if let Some(expected) = target.expected_work() {
+        progress.set_expected(expected);
+    }
+    let mut start = 0;
+    match Gpu::new() {
+        None => tracing::info!("no GPU found; mining on the CPU"),
+        Some(_) if !Gpu::can_mine(secret, target) => {
+            tracing::info!("the GPU can't mine this secret and target; mining on the CPU")
+        }
+        Some(gpu) => {
+            tracing::info!(adapter = gpu.name(), "mining on the GPU");
+            if let Some(coin) = gpu.mine(secret, target, progress) {
+                progress.finish();
+                return Some(coin);
+            }
+            tracing::info!("no coin small enough for the GPU; mining on the CPU");
+            start = GPU_SUFFIXES;
+        }
     }
-    Ok(())
+    let coin = mine_from(secret, target, start, progress);
+    progress.finish();
+    coin
+}
+
+/// The lowest suffix from `start` whose digest, with `secret`, matches `target`.
+///
+/// Progress is the number of hashes tried, counting the `start` suffixes skipped. It's not the
+/// suffixes themselves: threads search from all across the range at once, so the highest suffix
+/// tried says nothing about how much work has been done.
+fn mine_from(secret: &str, target: &Target, start: u64, progress: &dyn Progress) -> Option<u64> {
+    let tried = AtomicU64::new(start);
+    (start..=u64::MAX)
+        .into_par_iter()
+        .map_init(
+            || Hasher::new(secret),
+            |hasher, suffix| {
+                // each thread searches a contiguous run of suffixes, so this is once per interval
+                // tried
+                if suffix % PROGRESS_INTERVAL == 0 {
+                    let done = tried.fetch_add(PROGRESS_INTERVAL, Ordering::Relaxed);
+                    progress.advance(done + PROGRESS_INTERVAL);
+                }
+                (suffix, target.matches(hasher.digest(suffix)))
+            },
+        )
+        .find_first(|&(_suffix, matched)| matched)
+        .map(|(suffix, _matched)| suffix)
+}
+
+/// Every suffix whose digest, with `secret`, starts with `leading_zeros` zeros in hex, lowest first.
+///
+/// Unlike [`mine_coin`], the search can carry on past the first coin.
+///
+/// ```
+/// # use day04::coins;
+/// let first: Vec<_> = coins("abcdef", 3).take(3).collect();
+/// assert_eq!(first[0], 3337);
+/// assert!(first[0] < first[1] && first[1] < first[2]);
+/// ```
+pub fn coins(secret: &str, leading_zeros: usize) -> impl '_ + Iterator<Item = u64> {
+    coins_matching(secret, Target::leading_zeros(leading_zeros))
+}
+
+/// Every suffix whose digest, with `secret`, matches `target`, lowest first.
+///
+/// Suffixes are searched in parallel a block at a time, so each coin costs at most a block's
+/// worth of searching beyond the coin itself.
+pub fn coins_matching(secret: &str, target: Target) -> impl '_ + Iterator<Item = u64> {
+    (0..=u64::MAX / COIN_BLOCK).flat_map(move |block| {
+        let target = &target;
+        let start = block * COIN_BLOCK;
+        let mut found: Vec<u64> = (start..=start + (COIN_BLOCK - 1))
+            .into_par_iter()
+            .map_init(
+                || Hasher::new(secret),
+                |hasher, suffix| (suffix, target.matches(hasher.digest(suffix))),
+            )
+            .filter_map(|(suffix, matched)| if matched { Some(suffix) } else { None })
+            .collect();
+        found.sort_unstable();
+        found
+    })
+}
+
+/// Mine a coin on a single thread, inspecting the raw digest instead of its hex rendering.
+///
+/// This is an alternative to [`mine_coin`] which exists mainly to be benchmarked against it.
+pub fn mine_coin_sequential(secret: &str, leading_zeros: usize) -> Option<u64> {
+    let mut hasher = Hasher::new(secret);
+    (0..=u64::MAX).find(|&suffix| leading_zero_nibbles(hasher.digest(suffix)) >= leading_zeros)
+}
+
+/// Count the leading zero hex digits of `digest`.
+fn leading_zero_nibbles(digest: &[u8]) -> usize {
+    let mut zeros = 0;
+    for byte in digest {
+        if *byte == 0 {
+            zeros += 2;
+        } else {
+            if byte >> 4 == 0 {
+                zeros += 1;
+            }
+            break;
+        }
+    }
+    zeros
+}
+
+fn solve(input: &str, leading_zeros: usize, progress: &dyn Progress) -> Result<Answer, Error> {
+    solve_for(input, &Target::leading_zeros(leading_zeros), progress)
+}
+
+/// Mine the puzzle's secret for a digest matching `target`, reporting progress to `progress`.
+///
+/// With the `gpu` feature, this mines on the GPU when it can; see [`mine_target_gpu`].
+pub fn solve_for(input: &str, target: &Target, progress: &dyn Progress) -> Result<Answer, Error> {
+    let secret = parse_str::<String>(input)?.next().ok_or(Error::NoInput)?;
+    #[cfg(feature = "gpu")]
+    let coin = mine_target_gpu(&secret, target, progress);
+    #[cfg(not(feature = "gpu"))]
+    let coin = mine_target_with_progress(&secret, target, progress);
+    let coin =
+        coin.ok_or_else(|| Error::no_solution(format!("no coin found for prefix \"{}\"", secret)))?;
+    Ok(coin.into())
+}
+
+/// The first `count` suffixes whose digests, with the puzzle's secret, match `target`.
+pub fn first_coins(input: &str, target: Target, count: usize) -> Result<Vec<u64>, Error> {
+    let secret = parse_str::<String>(input)?.next().ok_or(Error::NoInput)?;
+    Ok(coins_matching(&secret, target).take(count).collect())
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_with_progress(InputSource::from(input).reader()?, &Silent)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_with_progress(input, &Silent)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    solve(input, 5, &Silent)
+}
+
+pub fn part1_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, 5, progress)
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_with_progress(InputSource::from(input).reader()?, &Silent)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("no coin found for prefix \"{0}\"")]
-    NoCoin(String),
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_with_progress(input, &Silent)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    solve(input, 6, &Silent)
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "abcdef",
+        expected: "609043",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: "pqrstuv",
+        expected: "1048970",
+        solver: part1_str,
+    },
+];
+
+pub fn part2_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, 6, progress)
 }
 
 /// these tests are by default ignored when built with debug, and not ignored when built with release
 #[cfg(test)]
 mod tests {
     use super::*;
-    // use test::Bencher;
+    use crypto::{digest::Digest as _, md5::Md5};
 
     fn test_known(secret: &str, expected: u64, leading_zeros: usize) {
         let coin = mine_coin(secret, leading_zeros).unwrap();
@@ -92,22 +329,94 @@ mod tests {
         test_known("bgvyzdsv", 1038736, 6);
     }
 
-    // Benchmarks disabled due to not compiling in the stable compiler (!)
-    // #[bench]
-    // fn bench_one_core(b: &mut Bencher) {
-    //     b.iter(|| mine_coin_with_cores("coriolinus", 1));
-    // }
-
-    // #[bench]
-    // fn bench_four_cores(b: &mut Bencher) {
-    //     if num_cpus::get() >= 4 {
-    //         b.iter(|| mine_coin_with_cores("coriolinus", 4));
-    //     }
-    // }
-
-    // #[bench]
-    // fn bench_all_cores(b: &mut Bencher) {
-    //     let cpus = num_cpus::get();
-    //     b.iter(|| mine_coin_with_cores("coriolinus", cpus));
-    // }
+    #[test]
+    fn test_sequential_agrees() {
+        for secret in &["abcdef", "pqrstuv"] {
+            assert_eq!(mine_coin_sequential(secret, 3), mine_coin(secret, 3));
+        }
+    }
+
+    #[test]
+    fn test_progress_counts_hashes() {
+        #[derive(Default)]
+        struct Recorder(AtomicU64);
+
+        impl Progress for Recorder {
+            fn advance(&self, done: u64) {
+                self.0.fetch_max(done, Ordering::SeqCst);
+            }
+        }
+
+        let target = Target::zero_bits(19).unwrap();
+        let coin = mine_target("bgvyzdsv", &target).unwrap();
+        assert!(coin > 2 * PROGRESS_INTERVAL, "coin {} is too easy", coin);
+        let threads = 4;
+        let recorder = Recorder::default();
+        let found = with_threads(threads, || {
+            mine_target_with_progress("bgvyzdsv", &target, &recorder)
+        })
+        .unwrap();
+        assert_eq!(found, Some(coin));
+
+        // every suffix up to the coin was tried, and each thread tried no more than that
+        let reported = recorder.0.load(Ordering::SeqCst);
+        assert!(
+            reported + PROGRESS_INTERVAL >= coin,
+            "{} < {}",
+            reported,
+            coin
+        );
+        assert!(
+            reported <= threads as u64 * (coin + 2 * PROGRESS_INTERVAL),
+            "{} hashes reported for coin {}",
+            reported,
+            coin
+        );
+    }
+
+    #[test]
+    fn test_with_threads() {
+        assert_eq!(with_threads(2, rayon::current_num_threads).unwrap(), 2);
+        assert_eq!(
+            mine_coin_with_cores("abcdef", 3, 1).unwrap(),
+            mine_coin("abcdef", 3)
+        );
+    }
+
+    #[test]
+    fn test_coins() {
+        let first: Vec<_> = coins("abcdef", 3).take(4).collect();
+        assert_eq!(first[0], mine_coin("abcdef", 3).unwrap());
+        assert!(first.windows(2).all(|pair| pair[0] < pair[1]));
+        let mut hasher = Hasher::new("abcdef");
+        for &coin in &first {
+            assert!(leading_zero_nibbles(hasher.digest(coin)) >= 3);
+        }
+        // nothing was skipped between the coins
+        let between = (first[0] + 1..first[1])
+            .filter(|&suffix| leading_zero_nibbles(hasher.digest(suffix)) >= 3)
+            .count();
+        assert_eq!(between, 0);
+    }
+
+    #[test]
+    fn test_targets_agree() {
+        let target = Target::hex_prefix("000").unwrap();
+        assert_eq!(mine_target("abcdef", &target), mine_coin("abcdef", 3));
+        assert_eq!(
+            mine_target("abcdef", &Target::zero_bits(12).unwrap()),
+            mine_coin("abcdef", 3)
+        );
+        let suffix = mine_target("abcdef", &Target::bit_prefix("1111").unwrap()).unwrap();
+        let mut md5 = Md5::new();
+        md5.input_str(&format!("abcdef{}", suffix));
+        assert!(md5.result_str().starts_with('f'));
+    }
+
+    #[test]
+    fn test_leading_zero_nibbles() {
+        assert_eq!(leading_zero_nibbles(&[0x00, 0x00, 0x01, 0xdb]), 5);
+        assert_eq!(leading_zero_nibbles(&[0x00, 0x10]), 2);
+        assert_eq!(leading_zero_nibbles(&[0xff]), 0);
+    }
 }
@@ -1,52 +1,87 @@
-use aoclib::{config::Config, website::get_input};
-use day04::{part1, part2};
+use day04::{
+    first_coins, part1_with_progress, part2_with_progress, solve_for, with_threads, Target,
+    WorkEstimate,
+};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
 
 const DAY: u8 = 4;
 
+/// Searches expected to take at least this many hashes announce how long they should take.
+const LONG_SEARCH_HASHES: u64 = 1 << 24;
+
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// instead of solving the puzzle, find the lowest suffix whose digest matches this target:
+    /// hex:PREFIX, bits:PREFIX, or zero-bits:N
+    #[structopt(long)]
+    target: Option<Target>,
 
-    /// run part 2
+    /// instead of solving the puzzle, list the lowest N suffixes mining a coin: with five leading
+    /// zeros, or matching --target
     #[structopt(long)]
-    part2: bool,
-}
+    count: Option<usize>,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// mine with at most N threads, instead of one per CPU
+    #[structopt(long)]
+    threads: Option<usize>,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    args.common.init_tracing();
+    match args.threads {
+        Some(threads) => with_threads(threads, move || run(args))?,
+        None => run(args),
+    }
+}
 
-    if !args.no_part1 {
-        part1(&input_path)?;
+/// Before a long search for `target`, say how long it should take.
+fn announce(target: &Target) {
+    if let Some(estimate) = WorkEstimate::for_target(target) {
+        if estimate.hashes >= LONG_SEARCH_HASHES {
+            eprintln!("{}", estimate.calibrated());
+        }
+    }
+}
+
+fn run(args: RunArgs) -> Result<()> {
+    if args.common.example {
+        args.common.run_examples(day04::EXAMPLES)?;
+        return Ok(());
+    }
+    if let Some(count) = args.count {
+        let input = args.common.source(DAY)?.read_to_string()?;
+        let target = args.target.unwrap_or_else(|| Target::leading_zeros(5));
+        for coin in first_coins(&input, target, count)? {
+            println!("{}", coin);
+        }
+        return Ok(());
+    }
+    if let Some(target) = args.target {
+        let input = args.common.source(DAY)?.read_to_string()?;
+        announce(&target);
+        let suffix = solve_for(&input, &target, args.common.progress(1).as_ref())?;
+        println!("{}", suffix);
+        return Ok(());
     }
-    if args.part2 {
-        part2(&input_path)?;
+    let input = args.common.source(DAY)?;
+    if args.common.part2 {
+        // outside the solver, so that calibrating doesn't count towards its time
+        announce(&Target::leading_zeros(6));
     }
+    let answers = args.common.run(
+        DAY,
+        "",
+        &input,
+        |input| part1_with_progress(input, args.common.progress(1).as_ref()),
+        |input| part2_with_progress(input, args.common.progress(2).as_ref()),
+    )?;
+    args.common.check(DAY, &answers)?;
     Ok(())
 }
@@ -0,0 +1,114 @@
+// The MD5 digest of the secret followed by a suffix in decimal, one suffix per invocation.
+//
+// The secret and suffix must fit in a single block with its padding: at most 55 bytes.
+// Each invocation whose digest matches the target lowers `found` to its suffix.
+
+struct Params {
+    // the suffix hashed by the first invocation
+    base: u32,
+    // how many suffixes this dispatch hashes
+    count: u32,
+    secret_len: u32,
+    _padding: u32,
+    // a digest matches when `digest & mask == value`
+    mask: vec4<u32>,
+    value: vec4<u32>,
+    // the secret's bytes, packed little-endian, four to a word
+    secret: array<vec4<u32>, 4>,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> found: atomic<u32>;
+
+var<private> K: array<u32, 64> = array<u32, 64>(
+    0xd76aa478u, 0xe8c7b756u, 0x242070dbu, 0xc1bdceeeu,
+    0xf57c0fafu, 0x4787c62au, 0xa8304613u, 0xfd469501u,
+    0x698098d8u, 0x8b44f7afu, 0xffff5bb1u, 0x895cd7beu,
+    0x6b901122u, 0xfd987193u, 0xa679438eu, 0x49b40821u,
+    0xf61e2562u, 0xc040b340u, 0x265e5a51u, 0xe9b6c7aau,
+    0xd62f105du, 0x02441453u, 0xd8a1e681u, 0xe7d3fbc8u,
+    0x21e1cde6u, 0xc33707d6u, 0xf4d50d87u, 0x455a14edu,
+    0xa9e3e905u, 0xfcefa3f8u, 0x676f02d9u, 0x8d2a4c8au,
+    0xfffa3942u, 0x8771f681u, 0x6d9d6122u, 0xfde5380cu,
+    0xa4beea44u, 0x4bdecfa9u, 0xf6bb4b60u, 0xbebfbc70u,
+    0x289b7ec6u, 0xeaa127fau, 0xd4ef3085u, 0x04881d05u,
+    0xd9d4d039u, 0xe6db99e5u, 0x1fa27cf8u, 0xc4ac5665u,
+    0xf4292244u, 0x432aff97u, 0xab9423a7u, 0xfc93a039u,
+    0x655b59c3u, 0x8f0ccc92u, 0xffeff47du, 0x85845dd1u,
+    0x6fa87e4fu, 0xfe2ce6e0u, 0xa3014314u, 0x4e0811a1u,
+    0xf7537e82u, 0xbd3af235u, 0x2ad7d2bbu, 0xeb86d391u,
+);
+
+// the rotation for each step of a round
+var<private> S: array<u32, 16> = array<u32, 16>(
+    7u, 12u, 17u, 22u,
+    5u, 9u, 14u, 20u,
+    4u, 11u, 16u, 23u,
+    6u, 10u, 15u, 21u,
+);
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.count) {
+        return;
+    }
+    let suffix = params.base + id.x;
+
+    var m: array<u32, 16>;
+    for (var i = 0u; i < 4u; i++) {
+        let words = params.secret[i];
+        m[4u * i] = words.x;
+        m[4u * i + 1u] = words.y;
+        m[4u * i + 2u] = words.z;
+        m[4u * i + 3u] = words.w;
+    }
+
+    var digits = 1u;
+    var rest = suffix / 10u;
+    while (rest > 0u) {
+        digits++;
+        rest /= 10u;
+    }
+    rest = suffix;
+    for (var i = 0u; i < digits; i++) {
+        let pos = params.secret_len + digits - 1u - i;
+        m[pos >> 2u] |= (48u + rest % 10u) << ((pos & 3u) * 8u);
+        rest /= 10u;
+    }
+    let len = params.secret_len + digits;
+    m[len >> 2u] |= 0x80u << ((len & 3u) * 8u);
+    m[14] = len * 8u;
+
+    var a = 0x67452301u;
+    var b = 0xefcdab89u;
+    var c = 0x98badcfeu;
+    var d = 0x10325476u;
+    for (var i = 0u; i < 64u; i++) {
+        var f: u32;
+        var g: u32;
+        if (i < 16u) {
+            f = (b & c) | (~b & d);
+            g = i;
+        } else if (i < 32u) {
+            f = (d & b) | (~d & c);
+            g = (5u * i + 1u) & 15u;
+        } else if (i < 48u) {
+            f = b ^ c ^ d;
+            g = (3u * i + 5u) & 15u;
+        } else {
+            f = c ^ (b | ~d);
+            g = (7u * i) & 15u;
+        }
+        let x = a + f + K[i] + m[g];
+        let s = S[(i >> 4u) * 4u + (i & 3u)];
+        a = d;
+        d = c;
+        c = b;
+        b = b + ((x << s) | (x >> (32u - s)));
+    }
+
+    let digest = vec4<u32>(a + 0x67452301u, b + 0xefcdab89u, c + 0x98badcfeu, d + 0x10325476u);
+    if (all((digest & params.mask) == params.value)) {
+        atomicMin(&found, suffix);
+    }
+}
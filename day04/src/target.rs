@@ -0,0 +1,288 @@
+//! What a digest must look like for its suffix to mine a coin.
+
+use std::{fmt, str::FromStr};
+use util::Error;
+
+/// An MD5 digest.
+pub type Digest = [u8; 16];
+
+/// A predicate which a digest must satisfy for its suffix to mine a coin.
+///
+/// The puzzle's targets are a number of leading zeros in hex, but any prefix or predicate will do.
+///
+/// Targets may be parsed from strings like `hex:dead`, `bits:00001010`, or `zero-bits:20`.
+pub struct Target(Kind);
+
+enum Kind {
+    /// The hex rendering of the digest starts with these nibbles.
+    HexPrefix(Vec<u8>),
+    /// The digest's bits, most significant first, start with these.
+    BitPrefix(Vec<bool>),
+    /// At least this many of the digest's leading bits are zero.
+    ZeroBits(u32),
+    Custom(Box<dyn Fn(&Digest) -> bool + Send + Sync>),
+}
+
+impl Target {
+    /// The hex rendering of the digest starts with at least `n` zeros.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is more than the 32 hex digits of a digest.
+    pub fn leading_zeros(n: usize) -> Target {
+        assert!(n <= 32, "an MD5 digest has only 32 hex digits");
+        Target(Kind::ZeroBits((n * 4) as u32))
+    }
+
+    /// At least `n` of the digest's leading bits are zero.
+    pub fn zero_bits(n: u32) -> Result<Target, Error> {
+        Target::fits(n as usize, &format!("{} zero bits", n))?;
+        Ok(Target(Kind::ZeroBits(n)))
+    }
+
+    /// The hex rendering of the digest starts with `prefix`, in either case.
+    pub fn hex_prefix(prefix: &str) -> Result<Target, Error> {
+        let nibbles = prefix
+            .chars()
+            .map(|ch| {
+                ch.to_digit(16)
+                    .map(|nibble| nibble as u8)
+                    .ok_or_else(|| Error::parse(prefix, format!("'{}' is not a hex digit", ch)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Target::fits(nibbles.len() * 4, prefix)?;
+        Ok(Target(Kind::HexPrefix(nibbles)))
+    }
+
+    /// The digest's bits, most significant first, start with `prefix`: a string of `0` and `1`.
+    /// Spaces and underscores between the bits are ignored.
+    pub fn bit_prefix(prefix: &str) -> Result<Target, Error> {
+        let bits = prefix
+            .chars()
+            .filter(|&ch| ch != ' ' && ch != '_')
+            .map(|ch| match ch {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(Error::parse(prefix, format!("'{}' is not a bit", ch))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Target::fits(bits.len(), prefix)?;
+        Ok(Target(Kind::BitPrefix(bits)))
+    }
+
+    /// The digest satisfies `predicate`.
+    pub fn custom(predicate: impl Fn(&Digest) -> bool + Send + Sync + 'static) -> Target {
+        Target(Kind::Custom(Box::new(predicate)))
+    }
+
+    /// A prefix longer than the digest could never match.
+    fn fits(bits: usize, prefix: &str) -> Result<(), Error> {
+        if bits > 128 {
+            Err(Error::parse(prefix, "longer than an MD5 digest"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// How many suffixes must be tried, on average, to find a digest matching this target.
+    ///
+    /// Digests are as good as random, so a target fixing `n` bits expects `2^n` tries. There's no
+    /// telling for custom predicates.
+    pub fn expected_work(&self) -> Option<u64> {
+        let fixed_bits = match &self.0 {
+            Kind::HexPrefix(nibbles) => nibbles.len() as u32 * 4,
+            Kind::BitPrefix(bits) => bits.len() as u32,
+            Kind::ZeroBits(n) => *n,
+            Kind::Custom(_) => return None,
+        };
+        Some(1_u64.checked_shl(fixed_bits).unwrap_or(u64::MAX))
+    }
+
+    /// The bits of a matching digest, as a `(mask, value)` pair: a digest matches when each of its
+    /// bytes masked by `mask` equals the same byte of `value`.
+    ///
+    /// Custom targets can't be written this way.
+    #[cfg(feature = "gpu")]
+    pub(crate) fn mask(&self) -> Option<(Digest, Digest)> {
+        let mut mask = [0; 16];
+        let mut value = [0; 16];
+        let mut fix = |idx: usize, bit: bool| {
+            mask[idx / 8] |= 0x80 >> (idx % 8);
+            value[idx / 8] |= (bit as u8) << (7 - idx % 8);
+        };
+        match &self.0 {
+            Kind::HexPrefix(nibbles) => {
+                for (idx, &nibble) in nibbles.iter().enumerate() {
+                    for bit in 0..4 {
+                        fix(idx * 4 + bit, (nibble >> (3 - bit)) & 1 == 1);
+                    }
+                }
+            }
+            Kind::BitPrefix(bits) => {
+                for (idx, &bit) in bits.iter().enumerate() {
+                    fix(idx, bit);
+                }
+            }
+            Kind::ZeroBits(n) => {
+                for idx in 0..(*n).min(128) as usize {
+                    fix(idx, false);
+                }
+            }
+            Kind::Custom(_) => return None,
+        }
+        Some((mask, value))
+    }
+
+    pub fn matches(&self, digest: &Digest) -> bool {
+        match &self.0 {
+            Kind::HexPrefix(nibbles) => nibbles
+                .iter()
+                .enumerate()
+                .all(|(idx, &nibble)| nibble_at(digest, idx) == nibble),
+            Kind::BitPrefix(bits) => bits
+                .iter()
+                .enumerate()
+                .all(|(idx, &bit)| (digest[idx / 8] >> (7 - idx % 8)) & 1 == bit as u8),
+            Kind::ZeroBits(n) => leading_zero_bits(digest) >= *n,
+            Kind::Custom(predicate) => predicate(digest),
+        }
+    }
+}
+
+/// The `idx`th hex digit of `digest`, most significant first.
+fn nibble_at(digest: &Digest, idx: usize) -> u8 {
+    // the high nibble of each byte comes first
+    let shift = 4 * (1 - idx % 2);
+    (digest[idx / 2] >> shift) & 0xf
+}
+
+fn leading_zero_bits(digest: &Digest) -> u32 {
+    u128::from_be_bytes(*digest).leading_zeros()
+}
+
+impl fmt::Debug for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Kind::HexPrefix(nibbles) => f.debug_tuple("HexPrefix").field(nibbles).finish(),
+            Kind::BitPrefix(bits) => f.debug_tuple("BitPrefix").field(bits).finish(),
+            Kind::ZeroBits(n) => f.debug_tuple("ZeroBits").field(n).finish(),
+            Kind::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ':');
+        let kind = parts.next().unwrap_or_default();
+        let value = parts
+            .next()
+            .ok_or_else(|| Error::parse(s, "expected a kind of target, ':', and a value"))?;
+        match kind {
+            "hex" => Target::hex_prefix(value),
+            "bits" => Target::bit_prefix(value),
+            "zero-bits" => Target::zero_bits(value.parse().map_err(|err| Error::parse(s, err))?),
+            _ => Err(Error::parse(
+                s,
+                "unknown kind of target; use hex, bits, or zero-bits",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the digest of "abcdef609043"
+    const DIGEST: Digest = [
+        0x00, 0x00, 0x01, 0xdb, 0xbf, 0xa3, 0xa5, 0xc8, 0x3a, 0x2d, 0x50, 0x64, 0x29, 0xc7, 0xb0,
+        0x0e,
+    ];
+
+    #[test]
+    fn test_leading_zeros() {
+        assert!(Target::leading_zeros(5).matches(&DIGEST));
+        assert!(!Target::leading_zeros(6).matches(&DIGEST));
+        assert!(Target::zero_bits(23).unwrap().matches(&DIGEST));
+        assert!(!Target::zero_bits(24).unwrap().matches(&DIGEST));
+        assert!(Target::zero_bits(128).unwrap().matches(&[0; 16]));
+        assert!(Target::zero_bits(129).is_err());
+    }
+
+    #[test]
+    fn test_prefixes() {
+        assert!(Target::hex_prefix("000001DB").unwrap().matches(&DIGEST));
+        assert!(!Target::hex_prefix("000001dc").unwrap().matches(&DIGEST));
+        assert!(Target::hex_prefix("").unwrap().matches(&DIGEST));
+        assert!(Target::hex_prefix("dead").unwrap().matches(&{
+            let mut digest = [0; 16];
+            digest[0] = 0xde;
+            digest[1] = 0xad;
+            digest
+        }));
+        assert!(Target::hex_prefix("xyz").is_err());
+        assert!(Target::hex_prefix(&"0".repeat(33)).is_err());
+
+        assert!(Target::bit_prefix("0000 0000 0000 0000 0000 0001 1101")
+            .unwrap()
+            .matches(&DIGEST));
+        assert!(!Target::bit_prefix("1").unwrap().matches(&DIGEST));
+        assert!(Target::bit_prefix("0012").is_err());
+    }
+
+    #[test]
+    fn test_expected_work() {
+        assert_eq!(Target::leading_zeros(6).expected_work(), Some(1 << 24));
+        assert_eq!(
+            Target::hex_prefix("dead").unwrap().expected_work(),
+            Some(65536)
+        );
+        assert_eq!(Target::bit_prefix("101").unwrap().expected_work(), Some(8));
+        assert_eq!(
+            Target::zero_bits(100).unwrap().expected_work(),
+            Some(u64::MAX)
+        );
+        assert_eq!(Target::custom(|_| true).expected_work(), None);
+    }
+
+    #[test]
+    fn test_custom() {
+        let even = Target::custom(|digest| digest[15] % 2 == 0);
+        assert!(even.matches(&DIGEST));
+        assert!(!even.matches(&[1; 16]));
+    }
+
+    #[cfg(feature = "gpu")]
+    #[test]
+    fn test_mask() {
+        let masked = |target: Target, digest: &Digest| {
+            let (mask, value) = target.mask().unwrap();
+            (0..16).all(|idx| digest[idx] & mask[idx] == value[idx])
+        };
+        assert!(masked(Target::leading_zeros(5), &DIGEST));
+        assert!(!masked(Target::leading_zeros(6), &DIGEST));
+        assert!(masked(Target::hex_prefix("000001db").unwrap(), &DIGEST));
+        assert!(!masked(Target::hex_prefix("000001dc").unwrap(), &DIGEST));
+        assert!(masked(
+            Target::bit_prefix("0000 0000 0000 0000 0000 0001 1101").unwrap(),
+            &DIGEST
+        ));
+        assert!(Target::custom(|_| true).mask().is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = |s: &str| s.parse::<Target>().map(|target| format!("{:?}", target));
+        assert_eq!(parsed("hex:0a").unwrap(), "HexPrefix([0, 10])");
+        assert_eq!(parsed("bits:10").unwrap(), "BitPrefix([true, false])");
+        assert_eq!(parsed("zero-bits:20").unwrap(), "ZeroBits(20)");
+        assert!(parsed("zero-bits:many").is_err());
+        assert!(parsed("zero-bits:128").is_ok());
+        assert!(parsed("zero-bits:200").is_err());
+        assert!(parsed("dead").is_err());
+        assert!(parsed("suffix:dead").is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Rule sets loaded from a file, so that the policy can change without recompiling.
+//!
+//! A rule file lists rules in TOML or JSON. Each rule has a name and exactly one of:
+//!
+//! - `must_match`: a regex which nice strings match somewhere;
+//! - `must_not_match`: a regex which nice strings never match;
+//! - `builtin`: the name of one of the puzzle's [rules](crate::rules), for those which regexes
+//!   can't express. Some take parameters, which default to the puzzle's:
+//!   - `vowels`, with `min` (3);
+//!   - `double letter` and `naughty pairs`;
+//!   - `letter between`, with `gap` (1);
+//!   - `repeated pair`, with `n` (2) and `min_separation` (0).
+//!
+//! Any rule may also have a `weight`, 1 by default, which counts towards the
+//! [score](RuleSet::score) of the strings meeting it.
+//!
+//! ```toml
+//! [[rule]]
+//! name = "vowels"
+//! must_match = "([aeiou].*){3}"
+//!
+//! [[rule]]
+//! builtin = "double letter"
+//!
+//! [[rule]]
+//! name = "naughty pairs"
+//! must_not_match = "ab|cd|pq|xy"
+//! ```
+//!
+//! The same rules in JSON are `{"rule": [{"name": "vowels", "must_match": ...}, ...]}`.
+
+use crate::rules::{
+    Check, DoubleLetter, NoNaughtyPairs, RepeatWithGap, RepeatedNgram, Rule, RuleSet, Vowels,
+};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+use util::Error;
+
+/// A whole rule file, as written.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(rename = "rule", default)]
+    rules: Vec<RuleSpec>,
+}
+
+/// One rule, as written.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: Option<String>,
+    must_match: Option<String>,
+    must_not_match: Option<String>,
+    builtin: Option<String>,
+    min: Option<usize>,
+    gap: Option<usize>,
+    n: Option<usize>,
+    min_separation: Option<usize>,
+    weight: Option<u32>,
+}
+
+impl RuleSpec {
+    /// Compile this rule, with its weight; `idx` identifies it in errors when it has no name.
+    fn compile(self, idx: usize) -> Result<(Box<dyn Rule>, u32), Error> {
+        let what = self
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("rule {}", idx + 1));
+        let regex = |pattern: &str| Regex::new(pattern).map_err(|err| Error::parse(&what, err));
+        let rule: Box<dyn Rule> = match (&self.must_match, &self.must_not_match, &self.builtin) {
+            (Some(pattern), None, None) => Box::new(Pattern {
+                name: what.clone(),
+                regex: regex(pattern)?,
+                must_match: true,
+            }),
+            (None, Some(pattern), None) => Box::new(Pattern {
+                name: what.clone(),
+                regex: regex(pattern)?,
+                must_match: false,
+            }),
+            (None, None, Some(builtin)) => self.builtin_rule(builtin, &what)?,
+            _ => {
+                return Err(Error::parse(
+                    what,
+                    "expected exactly one of must_match, must_not_match, or builtin",
+                ))
+            }
+        };
+        let builtin = self.builtin.as_deref().unwrap_or_default();
+        let parameters = [
+            ("min", self.min, "vowels"),
+            ("gap", self.gap, "letter between"),
+            ("n", self.n, "repeated pair"),
+            ("min_separation", self.min_separation, "repeated pair"),
+        ];
+        for &(parameter, value, takes_it) in &parameters {
+            if value.is_some() && builtin != takes_it {
+                return Err(Error::parse(
+                    what,
+                    format!("only the {} rule takes {}", takes_it, parameter),
+                ));
+            }
+        }
+        Ok((rule, self.weight.unwrap_or(1)))
+    }
+
+    /// One of the puzzle's rules, by name, with this rule's parameters.
+    fn builtin_rule(&self, name: &str, what: &str) -> Result<Box<dyn Rule>, Error> {
+        let rule: Box<dyn Rule> = match name {
+            "vowels" => Box::new(Vowels {
+                min: self.min.unwrap_or(3),
+            }),
+            "double letter" => Box::new(DoubleLetter),
+            "naughty pairs" => Box::new(NoNaughtyPairs::default()),
+            "letter between" => Box::new(RepeatWithGap {
+                gap: self.gap.unwrap_or(1),
+            }),
+            "repeated pair" => {
+                let n = self.n.unwrap_or(2);
+                if n == 0 {
+                    return Err(Error::parse(what, "n must be at least 1"));
+                }
+                Box::new(RepeatedNgram {
+                    n,
+                    min_separation: self.min_separation.unwrap_or_default(),
+                })
+            }
+            _ => return Err(Error::parse(what, format!("no builtin rule '{}'", name))),
+        };
+        Ok(rule)
+    }
+}
+
+/// A rule which a regex decides.
+#[derive(Debug, Clone)]
+struct Pattern {
+    name: String,
+    regex: Regex,
+    /// Whether nice strings must match the regex, rather than never match it.
+    must_match: bool,
+}
+
+impl Rule for Pattern {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn check(&self, chars: &[char]) -> Check {
+        let text: String = chars.iter().collect();
+        let found = self.regex.find(&text).map(|found| {
+            // report where the match starts in chars, as the other rules do
+            let at = text[..found.start()].chars().count();
+            (at, found.as_str())
+        });
+        let (passed, reason) = match (found, self.must_match) {
+            (Some((at, matched)), true) => (true, format!("matches '{}' at {}", matched, at)),
+            (Some((at, matched)), false) => (false, format!("contains '{}' at {}", matched, at)),
+            (None, true) => (false, format!("doesn't match /{}/", self.regex)),
+            (None, false) => (true, format!("doesn't contain /{}/", self.regex)),
+        };
+        Check {
+            rule: self.name(),
+            passed,
+            at: found.map(|(at, _)| at),
+            reason,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Compile the rules in a TOML rule file.
+    pub fn from_toml(s: &str) -> Result<RuleSet, Error> {
+        let file: RuleFile = toml::from_str(s).map_err(|err| Error::parse("rule file", err))?;
+        RuleSet::compile(file)
+    }
+
+    /// Compile the rules in a JSON rule file.
+    pub fn from_json(s: &str) -> Result<RuleSet, Error> {
+        let file: RuleFile =
+            serde_json::from_str(s).map_err(|err| Error::parse("rule file", err))?;
+        RuleSet::compile(file)
+    }
+
+    /// Load the rules in `path`: JSON if it ends in `.json`, and TOML otherwise.
+    pub fn load(path: &Path) -> Result<RuleSet, Error> {
+        let contents = std::fs::read_to_string(path)?;
+        let is_json = path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("json"))
+            .unwrap_or_default();
+        if is_json {
+            RuleSet::from_json(&contents)
+        } else {
+            RuleSet::from_toml(&contents)
+        }
+    }
+
+    fn compile(file: RuleFile) -> Result<RuleSet, Error> {
+        let rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(idx, spec)| spec.compile(idx))
+            .collect::<Result<_, _>>()?;
+        Ok(RuleSet::weighted(rules))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1_rules, EXAMPLES};
+
+    const PART1: &str = r#"
+[[rule]]
+name = "vowels"
+must_match = "([aeiou].*){3}"
+
+[[rule]]
+builtin = "double letter"
+
+[[rule]]
+name = "naughty pairs"
+must_not_match = "ab|cd|pq|xy"
+"#;
+
+    #[test]
+    fn test_part1_from_toml() {
+        let rules = RuleSet::from_toml(PART1).unwrap();
+        for line in EXAMPLES[0].input.lines() {
+            assert_eq!(rules.is_nice(line), part1_rules(line), "{}", line);
+        }
+        let checks = rules.check(&"haegwjzuvuyypxyu".chars().collect::<Vec<_>>());
+        assert_eq!(checks[2].reason, "contains 'xy' at 13");
+        assert_eq!(checks[2].at, Some(13));
+    }
+
+    #[test]
+    fn test_json() {
+        let rules = RuleSet::from_json(
+            r#"{"rule": [{"builtin": "vowels", "min": 1}, {"name": "no z", "must_not_match": "z"}]}"#,
+        )
+        .unwrap();
+        assert!(rules.is_nice("a"));
+        assert!(!rules.is_nice("az"));
+        assert!(!rules.is_nice("b"));
+    }
+
+    #[test]
+    fn test_bad_rules() {
+        let bad = |s: &str| RuleSet::from_toml(s).is_err();
+        assert!(bad("[[rule]]\nname = \"empty\""));
+        assert!(bad("[[rule]]\nmust_match = \"a\"\nmust_not_match = \"b\""));
+        assert!(bad("[[rule]]\nmust_match = \"(\""));
+        assert!(bad("[[rule]]\nbuiltin = \"palindrome\""));
+        assert!(bad("[[rule]]\nmust_match = \"a\"\nmin = 2"));
+        assert!(bad("[[rule]]\nmust_match = \"a\"\ncolour = \"red\""));
+        assert!(bad("[[rule]]\nbuiltin = \"vowels\"\ngap = 2"));
+        assert!(bad("[[rule]]\nbuiltin = \"repeated pair\"\nn = 0"));
+    }
+
+    #[test]
+    fn test_parameters() {
+        let rules = RuleSet::from_toml(
+            "[[rule]]\nbuiltin = \"repeated pair\"\nn = 3\n\n\
+             [[rule]]\nbuiltin = \"letter between\"\ngap = 0",
+        )
+        .unwrap();
+        assert!(rules.is_nice("abcabcc"));
+        assert!(!rules.is_nice("abcabc"));
+        assert!(!rules.is_nice("abbcab"));
+    }
+
+    #[test]
+    fn test_weights() {
+        let rules = RuleSet::from_toml(
+            "[[rule]]\nbuiltin = \"vowels\"\nweight = 5\n\n\
+             [[rule]]\nname = \"no z\"\nmust_not_match = \"z\"",
+        )
+        .unwrap();
+        assert_eq!(rules.weights(), &[5, 1]);
+        assert_eq!(rules.score(&['a', 'e', 'i', 'z']), 5);
+    }
+}
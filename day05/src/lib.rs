@@ -21,13 +21,25 @@
 //! - `haegwjzuvuyypxyu` is naughty because it contains the string `xy`.
 //! - `dvszwmarrgswjxmb` is naughty because it contains only one vowel.
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 
 use lazy_static::lazy_static;
 use maplit::hashset;
+use rayon::prelude::*;
+pub use rules::{Check, Rule, RuleSet};
 use std::collections::HashSet;
-use std::path::Path;
-use thiserror::Error;
+use std::{
+    fmt,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+pub use unicode::{is_nice2_unicode, is_nice_unicode, VowelSet};
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
+
+pub mod config;
+pub mod rules;
+pub mod unicode;
 
 pub struct CharVec(Vec<char>);
 
@@ -39,6 +51,18 @@ impl std::str::FromStr for CharVec {
     }
 }
 
+impl CharVec {
+    /// Whether these letters are nice under the part 1 rules.
+    pub fn is_nice(&self) -> bool {
+        nice_chars(&self.0)
+    }
+
+    /// Whether these letters are nice under the part 2 rules; see [`part2_rules`].
+    pub fn is_nice2(&self) -> bool {
+        nice2_chars(&self.0)
+    }
+}
+
 lazy_static! {
     static ref VOWELS: HashSet<char> = hashset! {'a', 'e', 'i', 'o', 'u'};
 }
@@ -57,27 +81,119 @@ fn contains_naughty_sequence(input: &[char]) -> bool {
     input.windows(2).any(|window| NAUGHTY.contains(&window))
 }
 
-pub fn is_nice(input: &CharVec) -> bool {
-    has_enough_vowels(&input.0)
-        && contains_double_letter(&input.0)
-        && !contains_naughty_sequence(&input.0)
+fn nice_chars(chars: &[char]) -> bool {
+    has_enough_vowels(chars) && contains_double_letter(chars) && !contains_naughty_sequence(chars)
+}
+
+/// Whether a string is nice under the part 1 rules.
+///
+/// ```
+/// # use day05::is_nice;
+/// assert!(is_nice("ugknbfddgicrmopn"));
+/// assert!(!is_nice("haegwjzuvuyypxyu"));
+/// ```
+pub fn is_nice(input: &str) -> bool {
+    nice_chars(&input.chars().collect::<Vec<_>>())
+}
+
+/// Apply the part 1 rules directly to a string; the same as [`is_nice`].
+///
+/// ```
+/// # use day05::part1_rules;
+/// assert!(part1_rules("ugknbfddgicrmopn"));
+/// assert!(!part1_rules("haegwjzuvuyypxyu"));
+/// ```
+pub fn part1_rules(input: &str) -> bool {
+    is_nice(input)
+}
+
+/// Count the lines of `input` which are nice under the part 1 rules, reading one line at a time.
+///
+/// Unlike [`part1_str`], the input needn't fit in memory all at once.
+///
+/// ```
+/// # use day05::count_nice_reader;
+/// let input = "ugknbfddgicrmopn\naaa\njchzalrnumimnmhp\n";
+/// assert_eq!(count_nice_reader(input.as_bytes()).unwrap(), 2);
+/// ```
+pub fn count_nice_reader(input: impl BufRead) -> Result<usize, Error> {
+    count_lines(input, nice_chars)
+}
+
+/// Count the non-blank lines of `input` whose chars are `nice`.
+fn count_lines(input: impl BufRead, nice: fn(&[char]) -> bool) -> Result<usize, Error> {
+    let mut chars = Vec::new();
+    count_lines_where(input, |line| {
+        chars.clear();
+        chars.extend(line.chars());
+        nice(&chars)
+    })
+}
+
+/// Count the non-blank lines of `input` which are `nice`, once trimmed.
+fn count_lines_where(
+    mut input: impl BufRead,
+    mut nice: impl FnMut(&str) -> bool,
+) -> Result<usize, Error> {
+    let mut line = String::new();
+    let mut count = 0;
+    while input.read_line(&mut line)? > 0 {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && nice(trimmed) {
+            count += 1;
+        }
+        line.clear();
+    }
+    Ok(count)
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_from_reader(InputSource::from(input).reader()?)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let nice = parse::<CharVec>(input)?.filter(is_nice).count();
-    println!("part 1 nice strings count: {}", nice);
-    Ok(())
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    Ok(count_nice_reader(BufReader::new(input))?.into())
 }
 
-fn contains_eye_pattern(chars: &[char]) -> bool {
-    chars.windows(3).any(|window| window[0] == window[2])
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let nice = parse_str::<CharVec>(input)?
+        .filter(CharVec::is_nice)
+        .count();
+    Ok(nice.into())
 }
 
-fn contains_repeated_double(chars: &[char]) -> bool {
-    chars.windows(2).enumerate().any(|(idx, outer_window)| {
-        chars[idx + 2..]
-            .windows(2)
-            .any(|inner_window| inner_window == outer_window)
+/// Where some letter first repeats with exactly `gap` letters between, like `xyx` for a gap of 1.
+///
+/// ```
+/// # use day05::repeat_with_gap;
+/// assert_eq!(repeat_with_gap(&['a', 'b', 'c', 'b'], 1), Some(1));
+/// assert_eq!(repeat_with_gap(&['a', 'b', 'c', 'b'], 2), None);
+/// ```
+pub fn repeat_with_gap<T: PartialEq>(chars: &[T], gap: usize) -> Option<usize> {
+    chars
+        .windows(gap + 2)
+        .position(|window| window[0] == window[gap + 1])
+}
+
+/// Where some run of `n` letters first appears twice with at least `min_separation` letters
+/// between, like `xy..xy` for `n` of 2: the start of each appearance.
+///
+/// With no separation, the appearances may touch but not overlap.
+pub fn repeated_ngram<T: PartialEq>(
+    chars: &[T],
+    n: usize,
+    min_separation: usize,
+) -> Option<(usize, usize)> {
+    if n == 0 {
+        return None;
+    }
+    chars.windows(n).enumerate().find_map(|(idx, ngram)| {
+        let from = idx + n + min_separation;
+        chars
+            .get(from..)?
+            .windows(n)
+            .position(|later| later == ngram)
+            .map(|offset| (idx, from + offset))
     })
 }
 
@@ -91,27 +207,267 @@ fn contains_repeated_double(chars: &[char]) -> bool {
 ///   overlaps).
 /// - It contains at least one letter which repeats with exactly one letter between them, like
 ///   `xyx`, `abcdefeghi` (`efe`), or even `aaa`.
-fn is_nice2(input: &CharVec) -> bool {
-    contains_eye_pattern(&input.0) && contains_repeated_double(&input.0)
+fn nice2_chars(chars: &[char]) -> bool {
+    repeat_with_gap(chars, 1).is_some() && repeated_ngram(chars, 2, 0).is_some()
+}
+
+/// Apply the part 2 rules directly to a string.
+///
+/// ```
+/// # use day05::part2_rules;
+/// assert!(part2_rules("qjhvhtzxzqqjkmpb"));
+/// assert!(!part2_rules("ieodomkazucvgmuy"));
+/// ```
+pub fn part2_rules(input: &str) -> bool {
+    nice2_chars(&input.chars().collect::<Vec<_>>())
+}
+
+/// How a string fared under both sets of rules, rule by rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    pub part1: Vec<Check>,
+    pub part2: Vec<Check>,
+}
+
+impl Verdict {
+    /// Whether the string is nice under the rules of part 1.
+    pub fn nice1(&self) -> bool {
+        self.part1.iter().all(|check| check.passed)
+    }
+
+    /// Whether the string is nice under the rules of part 2.
+    pub fn nice2(&self) -> bool {
+        self.part2.iter().all(|check| check.passed)
+    }
+}
+
+impl fmt::Display for Verdict {
+    /// Whether the string is nice under each part's rules, and if not, which rules it broke.
+    ///
+    /// ```
+    /// # use day05::classify;
+    /// assert_eq!(
+    ///     classify("jchzalrnumimnmhp").to_string(),
+    ///     "part 1: naughty (double letter: no letter appears twice in a row); \
+    ///      part 2: naughty (repeated pair: no pair of letters appears twice without overlapping)",
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, checks) in [&self.part1, &self.part2].iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "part {}: {}", idx + 1, rules::describe(checks))?;
+        }
+        Ok(())
+    }
+}
+
+/// Check a string against each rule of both parts.
+pub fn classify(input: &str) -> Verdict {
+    let chars: Vec<char> = input.chars().collect();
+    Verdict {
+        part1: RuleSet::part1().check(&chars),
+        part2: RuleSet::part2().check(&chars),
+    }
+}
+
+/// Classify each line under both sets of rules.
+pub fn classify_all<I, S>(lines: I) -> Vec<Verdict>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    lines
+        .into_iter()
+        .map(|line| classify(line.as_ref()))
+        .collect()
+}
+
+/// Explain the verdict on each line of `input`, one line each.
+pub fn explain(input: &str) -> Vec<String> {
+    lines(input)
+        .map(|line| format!("{}: {}", line, classify(line)))
+        .collect()
+}
+
+/// Explain the verdict of `rules` on each line of `input`, one line each.
+pub fn explain_with(input: &str, rules: &RuleSet) -> Vec<String> {
+    lines(input)
+        .map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            format!("{}: {}", line, rules::describe(&rules.check(&chars)))
+        })
+        .collect()
+}
+
+/// Count the lines of `input` which meet every one of `rules`, in parallel.
+pub fn count_nice(input: &str, rules: &RuleSet) -> usize {
+    let lines: Vec<_> = lines(input).collect();
+    lines.par_iter().filter(|line| rules.is_nice(line)).count()
+}
+
+/// How many strings were nice under a set of rules, and how many failed each rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    pub strings: usize,
+    pub nice: usize,
+    /// Each rule's name, and how many strings failed it, in the order of the rules.
+    ///
+    /// A string which fails several rules counts against each of them.
+    pub failures: Vec<(String, usize)>,
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} nice", self.nice, self.strings)?;
+        for (rule, failed) in &self.failures {
+            write!(f, "\n  {}: {} failed", rule, failed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check every line of `input` against `rules` in parallel, counting the failures of each rule.
+///
+/// ```
+/// # use day05::{tally, RuleSet};
+/// let tally = tally("aaa\njchzalrnumimnmhp\ndvszwmarrgswjxmb\n", &RuleSet::part1());
+/// assert_eq!(tally.nice, 1);
+/// assert_eq!(tally.failures[1], ("double letter".to_string(), 1));
+/// ```
+pub fn tally(input: &str, rules: &RuleSet) -> Tally {
+    let lines: Vec<_> = lines(input).collect();
+    let zeros = || (0, vec![0; rules.rules().len()]);
+    let (nice, failures) = lines
+        .par_iter()
+        .map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            let failed: Vec<usize> = rules
+                .check(&chars)
+                .iter()
+                .map(|check| (!check.passed) as usize)
+                .collect();
+            let nice = failed.iter().all(|&failed| failed == 0) as usize;
+            (nice, failed)
+        })
+        .reduce(zeros, |(nice_a, mut failed_a), (nice_b, failed_b)| {
+            for (a, b) in failed_a.iter_mut().zip(failed_b) {
+                *a += b;
+            }
+            (nice_a + nice_b, failed_a)
+        });
+    Tally {
+        strings: lines.len(),
+        nice,
+        failures: rules
+            .rules()
+            .iter()
+            .map(|rule| rule.name())
+            .zip(failures)
+            .collect(),
+    }
+}
+
+/// A string, and its score under some rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scored<'a> {
+    pub line: &'a str,
+    pub score: u32,
+}
+
+impl fmt::Display for Scored<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.score, self.line)
+    }
+}
+
+/// Score every line of `input` against `rules` in parallel; see [`RuleSet::score`].
+///
+/// The scores are in the order of the lines.
+pub fn score_all<'a>(input: &'a str, rules: &RuleSet) -> Vec<Scored<'a>> {
+    let lines: Vec<_> = lines(input).collect();
+    lines
+        .par_iter()
+        .map(|&line| {
+            let chars: Vec<char> = line.chars().collect();
+            Scored {
+                line,
+                score: rules.score(&chars),
+            }
+        })
+        .collect()
+}
+
+/// The `k` nicest lines of `input` under `rules`: those with the highest scores, highest first.
+///
+/// Lines with equal scores keep their order in the input.
+///
+/// ```
+/// # use day05::{top_k, RuleSet};
+/// let input = "jchzalrnumimnmhp\nhaegwjzuvuyypxyu\naaa\n";
+/// let top = top_k(input, &RuleSet::part1(), 2);
+/// assert_eq!(top[0].line, "aaa");
+/// assert_eq!(top[1].line, "jchzalrnumimnmhp");
+/// ```
+pub fn top_k<'a>(input: &'a str, rules: &RuleSet, k: usize) -> Vec<Scored<'a>> {
+    let mut scored = score_all(input, rules);
+    scored.sort_by_key(|scored| std::cmp::Reverse(scored.score));
+    scored.truncate(k);
+    scored
+}
+
+/// The non-blank lines of `input`, trimmed.
+fn lines(input: &str) -> impl '_ + Iterator<Item = &str> {
+    input.lines().map(str::trim).filter(|line| !line.is_empty())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_from_reader(InputSource::from(input).reader()?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let nice = parse::<CharVec>(input)?.filter(is_nice2).count();
-    println!("part 2 nice strings count: {}", nice);
-    Ok(())
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    Ok(count_lines(BufReader::new(input), nice2_chars)?.into())
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let nice = parse_str::<CharVec>(input)?
+        .filter(CharVec::is_nice2)
+        .count();
+    Ok(nice.into())
 }
 
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "\
+ugknbfddgicrmopn
+aaa
+jchzalrnumimnmhp
+haegwjzuvuyypxyu
+dvszwmarrgswjxmb
+",
+        expected: "2",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: "\
+qjhvhtzxzqqjkmpb
+xxyxx
+uurcxstgmygtbstg
+ieodomkazucvgmuy
+",
+        expected: "2",
+        solver: part2_str,
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use crate::CharVec;
 
-    use super::{is_nice, is_nice2};
+    use super::{classify, classify_all, is_nice, part1_rules, part2_rules};
     use rstest::rstest;
 
     /// - `ugknbfddgicrmopn` is nice because it has at least three vowels (`u...i...o...`), a double
@@ -132,7 +488,9 @@ mod tests {
     )]
     fn test_examples(input: &str, expect: bool) {
         let charvec: CharVec = input.parse().unwrap();
-        assert_eq!(is_nice(&charvec), expect);
+        assert_eq!(charvec.is_nice(), expect);
+        assert_eq!(is_nice(input), expect);
+        assert_eq!(part1_rules(input), expect);
     }
 
     /// - `qjhvhtzxzqqjkmpb` is nice because is has a pair that appears twice (`qj`) and a letter
@@ -153,6 +511,128 @@ mod tests {
     )]
     fn test_examples2(input: &str, expect: bool) {
         let charvec: CharVec = input.parse().unwrap();
-        assert_eq!(is_nice2(&charvec), expect);
+        assert_eq!(charvec.is_nice2(), expect);
+        assert_eq!(part2_rules(input), expect);
+    }
+
+    #[test]
+    fn test_classify() {
+        let verdicts = classify_all(vec!["ugknbfddgicrmopn", "qjhvhtzxzqqjkmpb", "aaa", "xxyxx"]);
+        assert_eq!(
+            verdicts
+                .iter()
+                .map(|verdict| (verdict.nice1(), verdict.nice2()))
+                .collect::<Vec<_>>(),
+            vec![(true, false), (false, true), (true, false), (false, true)]
+        );
+    }
+
+    #[test]
+    fn test_rules_agree() {
+        for line in super::EXAMPLES
+            .iter()
+            .flat_map(|example| example.input.lines())
+        {
+            let verdict = classify(line);
+            assert_eq!(verdict.nice1(), part1_rules(line));
+            assert_eq!(verdict.nice2(), part2_rules(line));
+        }
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = super::RuleSet::part1();
+        let input = super::EXAMPLES[0].input;
+        assert_eq!(super::count_nice(input, &rules), 2);
+        assert_eq!(
+            super::explain_with(input, &rules)[2],
+            "jchzalrnumimnmhp: naughty (double letter: no letter appears twice in a row)"
+        );
+    }
+
+    #[test]
+    fn test_input_styles_agree() {
+        for example in super::EXAMPLES {
+            let from_str = (example.solver)(example.input).unwrap();
+            let from_reader = match example.part {
+                1 => super::part1_from_reader(example.input.as_bytes()),
+                _ => super::part2_from_reader(example.input.as_bytes()),
+            }
+            .unwrap();
+            assert_eq!(from_reader, from_str);
+        }
+        // blank lines and surrounding whitespace don't count
+        assert_eq!(
+            super::count_nice_reader("  aaa\n\n\naaa  \r\n".as_bytes()).unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_repeats() {
+        use super::{repeat_with_gap, repeated_ngram};
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(repeat_with_gap(&chars("abcdefeghi"), 1), Some(4));
+        assert_eq!(repeat_with_gap(&chars("xyzx"), 2), Some(0));
+        assert_eq!(repeat_with_gap(&chars("aabb"), 0), Some(0));
+        assert_eq!(repeat_with_gap(&chars("ab"), 1), None);
+
+        assert_eq!(repeated_ngram(&chars("abcab"), 2, 0), Some((0, 3)));
+        assert_eq!(repeated_ngram(&chars("abcab"), 2, 1), Some((0, 3)));
+        assert_eq!(repeated_ngram(&chars("abcab"), 2, 2), None);
+        assert_eq!(repeated_ngram(&chars("aaa"), 2, 0), None);
+        assert_eq!(repeated_ngram(&chars("aaaa"), 2, 0), Some((0, 2)));
+        assert_eq!(repeated_ngram(&chars("abcxabc"), 3, 0), Some((0, 4)));
+        assert_eq!(repeated_ngram(&chars("abc"), 0, 0), None);
+    }
+
+    #[test]
+    fn test_tally() {
+        let input = super::EXAMPLES[0].input;
+        let tally = super::tally(input, &super::RuleSet::part1());
+        assert_eq!(tally.strings, 5);
+        assert_eq!(tally.nice, 2);
+        assert_eq!(
+            tally.failures,
+            vec![
+                ("vowels".to_string(), 1),
+                ("double letter".to_string(), 1),
+                ("naughty pairs".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            tally.to_string(),
+            "2 of 5 nice\n  vowels: 1 failed\n  double letter: 1 failed\n  naughty pairs: 1 failed"
+        );
+        assert_eq!(super::tally("", &super::RuleSet::part2()).nice, 0);
+    }
+
+    #[test]
+    fn test_top_k() {
+        let input = super::EXAMPLES[0].input;
+        let top = super::top_k(input, &super::RuleSet::part1(), 10);
+        assert_eq!(
+            top.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "3: ugknbfddgicrmopn",
+                "3: aaa",
+                "2: jchzalrnumimnmhp",
+                "2: haegwjzuvuyypxyu",
+                "2: dvszwmarrgswjxmb",
+            ]
+        );
+        assert!(super::top_k(input, &super::RuleSet::part1(), 0).is_empty());
+    }
+
+    #[test]
+    fn test_explain() {
+        let verdict = classify("haegwjzuvuyypxyu");
+        let naughty = verdict.part1.iter().find(|check| !check.passed).unwrap();
+        assert_eq!(naughty.at, Some(13));
+        assert_eq!(
+            super::explain("ugknbfddgicrmopn\n\nhaegwjzuvuyypxyu\n")[1],
+            "haegwjzuvuyypxyu: part 1: naughty (naughty pairs: contains 'xy' at 13); \
+             part 2: naughty (repeated pair: no pair of letters appears twice without overlapping)"
+        );
     }
 }
@@ -1,52 +1,119 @@
-use aoclib::{config::Config, website::get_input};
-use day05::{part1, part2};
+use day05::{
+    count_nice, explain, explain_with, part1_from_reader, part2_from_reader, tally, top_k,
+    unicode::{part1_unicode, part2_unicode},
+    RuleSet, VowelSet,
+};
 
 use color_eyre::eyre::Result;
 use std::path::PathBuf;
 use structopt::StructOpt;
+use util::cache::Variant;
 
 const DAY: u8 = 5;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// instead of counting nice strings, say whether each string is nice under each part's rules,
+    /// and which rules the naughty ones break
+    #[structopt(long)]
+    explain: bool,
 
-    /// run part 2
+    /// instead of counting nice strings, count how many strings fail each rule
     #[structopt(long)]
-    part2: bool,
-}
+    tally: bool,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// instead of counting nice strings, list the K nicest: those meeting the most rules, weighted
+    #[structopt(long, value_name = "K")]
+    top: Option<usize>,
+
+    /// instead of the puzzle's rules, count the strings meeting the rules in this TOML or JSON file
+    #[structopt(long, parse(from_os_str))]
+    rules: Option<PathBuf>,
+
+    /// treat each grapheme cluster as a letter, rather than each char, so that accented letters
+    /// count once however they're written
+    #[structopt(long)]
+    unicode: bool,
+
+    /// with --unicode, the letters which count as vowels (default: aeiou)
+    #[structopt(long, requires = "unicode")]
+    vowels: Option<VowelSet>,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
-    let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
+    let mut args = RunArgs::from_args();
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day05::EXAMPLES)?;
+        return Ok(());
+    }
+    if let Some(path) = &args.rules {
+        let rules = RuleSet::load(path)?;
+        let input = args.common.source(DAY)?.read_to_string()?;
+        if args.explain {
+            for line in explain_with(&input, &rules) {
+                println!("{}", line);
+            }
+        } else if args.tally {
+            println!("{}", tally(&input, &rules));
+        } else if let Some(k) = args.top {
+            rank(&input, &rules, k);
+        } else {
+            println!("{}", count_nice(&input, &rules));
+        }
+        return Ok(());
+    }
+    if args.tally {
+        let input = args.common.source(DAY)?.read_to_string()?;
+        println!("part 1: {}", tally(&input, &RuleSet::part1()));
+        println!("part 2: {}", tally(&input, &RuleSet::part2()));
+        return Ok(());
     }
-    if args.part2 {
-        part2(&input_path)?;
+    if let Some(k) = args.top {
+        let input = args.common.source(DAY)?.read_to_string()?;
+        println!("part 1:");
+        rank(&input, &RuleSet::part1(), k);
+        println!("part 2:");
+        rank(&input, &RuleSet::part2(), k);
+        return Ok(());
+    }
+    if args.explain {
+        let input = args.common.source(DAY)?.read_to_string()?;
+        for line in explain(&input) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+    let vowels = args.vowels.take().unwrap_or_default();
+    let variant = Variant::new()
+        .param("unicode", &args.unicode)
+        .param("vowels", &vowels);
+    let input = args.common.source(DAY)?;
+    let answers = if variant.is_usual() {
+        args.common
+            .run(DAY, "", &input, part1_from_reader, part2_from_reader)?
+    } else {
+        args.common.run(
+            DAY,
+            &variant.to_string(),
+            &input,
+            |input| part1_unicode(input, &vowels),
+            part2_unicode,
+        )?
+    };
+    if variant.is_usual() {
+        args.common.check(DAY, &answers)?;
     }
     Ok(())
 }
+
+/// Print the `k` nicest lines of `input`, with their scores out of the most possible.
+fn rank(input: &str, rules: &RuleSet, k: usize) {
+    for scored in top_k(input, rules, k) {
+        println!("{}/{} {}", scored.score, rules.max_score(), scored.line);
+    }
+}
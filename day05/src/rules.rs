@@ -0,0 +1,414 @@
+//! The niceness rules, one at a time, with an explanation of why each string passes or fails.
+//!
+//! The puzzle only asks whether a string is nice. Checking each [`Rule`] separately says which
+//! rules a naughty string broke, and where.
+
+use crate::{repeat_with_gap, repeated_ngram};
+
+/// The outcome of checking one rule against one string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// The name of the rule checked.
+    pub rule: String,
+    pub passed: bool,
+    /// The index, in chars, of whatever decided the outcome: where the rule was met, or where a
+    /// forbidden pattern was found. `None` when nothing in particular did.
+    pub at: Option<usize>,
+    /// A short explanation of the outcome.
+    pub reason: String,
+}
+
+/// A requirement which a nice string must meet.
+pub trait Rule: Send + Sync {
+    /// A short name for the rule, as used in reports.
+    fn name(&self) -> String;
+
+    /// Check `chars` against this rule.
+    fn check(&self, chars: &[char]) -> Check;
+}
+
+fn check(rule: &dyn Rule, passed: bool, at: Option<usize>, reason: String) -> Check {
+    Check {
+        rule: rule.name(),
+        passed,
+        at,
+        reason,
+    }
+}
+
+fn quote(chars: &[char]) -> String {
+    chars.iter().collect()
+}
+
+/// The string contains at least `min` vowels (`aeiou` only).
+#[derive(Debug, Clone, Copy)]
+pub struct Vowels {
+    pub min: usize,
+}
+
+impl Rule for Vowels {
+    fn name(&self) -> String {
+        "vowels".into()
+    }
+
+    fn check(&self, chars: &[char]) -> Check {
+        let vowels: Vec<usize> = chars
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| "aeiou".contains(**ch))
+            .map(|(idx, _)| idx)
+            .collect();
+        if vowels.len() >= self.min {
+            let at = self.min.checked_sub(1).map(|nth| vowels[nth]);
+            check(
+                self,
+                true,
+                at,
+                format!("has {} vowels; needs {}", vowels.len(), self.min),
+            )
+        } else {
+            check(
+                self,
+                false,
+                None,
+                format!("needs {} vowels; has {}", self.min, vowels.len()),
+            )
+        }
+    }
+}
+
+/// Some letter appears twice in a row.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DoubleLetter;
+
+impl Rule for DoubleLetter {
+    fn name(&self) -> String {
+        "double letter".into()
+    }
+
+    fn check(&self, chars: &[char]) -> Check {
+        match chars.windows(2).position(|window| window[0] == window[1]) {
+            Some(idx) => check(
+                self,
+                true,
+                Some(idx),
+                format!("'{}' at {}", quote(&chars[idx..idx + 2]), idx),
+            ),
+            None => check(self, false, None, "no letter appears twice in a row".into()),
+        }
+    }
+}
+
+/// None of these pairs of letters appear anywhere in the string.
+#[derive(Debug, Clone)]
+pub struct NoNaughtyPairs {
+    pub pairs: Vec<[char; 2]>,
+}
+
+impl Default for NoNaughtyPairs {
+    /// The pairs forbidden by the puzzle: `ab`, `cd`, `pq`, and `xy`.
+    fn default() -> Self {
+        NoNaughtyPairs {
+            pairs: crate::NAUGHTY
+                .iter()
+                .map(|pair| [pair[0], pair[1]])
+                .collect(),
+        }
+    }
+}
+
+impl Rule for NoNaughtyPairs {
+    fn name(&self) -> String {
+        "naughty pairs".into()
+    }
+
+    fn check(&self, chars: &[char]) -> Check {
+        match chars
+            .windows(2)
+            .position(|window| self.pairs.iter().any(|pair| pair == window))
+        {
+            Some(idx) => check(
+                self,
+                false,
+                Some(idx),
+                format!("contains '{}' at {}", quote(&chars[idx..idx + 2]), idx),
+            ),
+            None => check(self, true, None, "contains no naughty pair".into()),
+        }
+    }
+}
+
+/// Some letter repeats with exactly `gap` letters between, like `xyx` for a gap of 1.
+///
+/// Part 2 wants a gap of 1; a gap of 0 is a double letter.
+#[derive(Debug, Clone, Copy)]
+pub struct RepeatWithGap {
+    pub gap: usize,
+}
+
+impl Rule for RepeatWithGap {
+    fn name(&self) -> String {
+        match self.gap {
+            1 => "letter between".into(),
+            gap => format!("{} letters between", gap),
+        }
+    }
+
+    fn check(&self, chars: &[char]) -> Check {
+        match repeat_with_gap(chars, self.gap) {
+            Some(idx) => check(
+                self,
+                true,
+                Some(idx),
+                format!("'{}' at {}", quote(&chars[idx..idx + self.gap + 2]), idx),
+            ),
+            None if self.gap == 1 => check(
+                self,
+                false,
+                None,
+                "no letter repeats with exactly one letter between".into(),
+            ),
+            None => check(
+                self,
+                false,
+                None,
+                format!(
+                    "no letter repeats with exactly {} letters between",
+                    self.gap
+                ),
+            ),
+        }
+    }
+}
+
+/// Some run of `n` letters appears twice with at least `min_separation` letters between, like
+/// `xyxy` for `n` of 2 and no separation.
+///
+/// Part 2 wants a pair appearing twice without overlapping.
+#[derive(Debug, Clone, Copy)]
+pub struct RepeatedNgram {
+    pub n: usize,
+    pub min_separation: usize,
+}
+
+impl RepeatedNgram {
+    /// What must repeat, in words.
+    fn ngram(&self) -> String {
+        match self.n {
+            2 => "pair of letters".into(),
+            n => format!("run of {} letters", n),
+        }
+    }
+}
+
+impl Rule for RepeatedNgram {
+    fn name(&self) -> String {
+        match (self.n, self.min_separation) {
+            (2, 0) => "repeated pair".into(),
+            (n, 0) => format!("repeated {}-letter run", n),
+            (n, separation) => format!("repeated {}-letter run {} apart", n, separation),
+        }
+    }
+
+    fn check(&self, chars: &[char]) -> Check {
+        match repeated_ngram(chars, self.n, self.min_separation) {
+            Some((first, second)) => check(
+                self,
+                true,
+                Some(first),
+                format!(
+                    "'{}' at {} and {}",
+                    quote(&chars[first..first + self.n]),
+                    first,
+                    second
+                ),
+            ),
+            None if self.min_separation == 0 => check(
+                self,
+                false,
+                None,
+                format!("no {} appears twice without overlapping", self.ngram()),
+            ),
+            None => check(
+                self,
+                false,
+                None,
+                format!(
+                    "no {} appears twice with at least {} letters between",
+                    self.ngram(),
+                    self.min_separation
+                ),
+            ),
+        }
+    }
+}
+
+/// A set of rules which a nice string must all meet.
+///
+/// Each rule has a weight, 1 unless given otherwise, which counts towards a string's
+/// [score](RuleSet::score) when it meets the rule.
+pub struct RuleSet {
+    rules: Vec<Box<dyn Rule>>,
+    weights: Vec<u32>,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<Box<dyn Rule>>) -> RuleSet {
+        let weights = vec![1; rules.len()];
+        RuleSet { rules, weights }
+    }
+
+    /// A set of rules, each with its weight.
+    pub fn weighted(rules: Vec<(Box<dyn Rule>, u32)>) -> RuleSet {
+        let (rules, weights) = rules.into_iter().unzip();
+        RuleSet { rules, weights }
+    }
+
+    /// The rules of part 1.
+    pub fn part1() -> RuleSet {
+        RuleSet::new(vec![
+            Box::new(Vowels { min: 3 }),
+            Box::new(DoubleLetter),
+            Box::new(NoNaughtyPairs::default()),
+        ])
+    }
+
+    /// The rules of part 2.
+    pub fn part2() -> RuleSet {
+        RuleSet::new(vec![
+            Box::new(RepeatedNgram {
+                n: 2,
+                min_separation: 0,
+            }),
+            Box::new(RepeatWithGap { gap: 1 }),
+        ])
+    }
+
+    pub fn rules(&self) -> &[Box<dyn Rule>] {
+        &self.rules
+    }
+
+    /// The weight of each rule, in the order of the rules.
+    pub fn weights(&self) -> &[u32] {
+        &self.weights
+    }
+
+    /// Check `chars` against every rule, in order.
+    pub fn check(&self, chars: &[char]) -> Vec<Check> {
+        self.rules.iter().map(|rule| rule.check(chars)).collect()
+    }
+
+    /// Whether `input` meets every rule.
+    pub fn is_nice(&self, input: &str) -> bool {
+        let chars: Vec<char> = input.chars().collect();
+        self.rules.iter().all(|rule| rule.check(&chars).passed)
+    }
+
+    /// The total weight of the rules which `chars` meets.
+    pub fn score(&self, chars: &[char]) -> u32 {
+        self.rules
+            .iter()
+            .zip(&self.weights)
+            .filter(|(rule, _)| rule.check(chars).passed)
+            .map(|(_, weight)| weight)
+            .sum()
+    }
+
+    /// The score of a string which meets every rule.
+    pub fn max_score(&self) -> u32 {
+        self.weights.iter().sum()
+    }
+}
+
+/// Summarize `checks`: `nice`, or `naughty` and the reasons why.
+pub fn describe(checks: &[Check]) -> String {
+    let failures: Vec<_> = checks
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| format!("{}: {}", check.rule, check.reason))
+        .collect();
+    if failures.is_empty() {
+        "nice".into()
+    } else {
+        format!("naughty ({})", failures.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_part1_checks() {
+        let checks = RuleSet::part1().check(&chars("haegwjzuvuyypxyu"));
+        assert_eq!(
+            checks
+                .iter()
+                .map(|check| (check.passed, check.at))
+                .collect::<Vec<_>>(),
+            vec![(true, Some(7)), (true, Some(10)), (false, Some(13))]
+        );
+        assert_eq!(checks[2].reason, "contains 'xy' at 13");
+
+        let checks = RuleSet::part1().check(&chars("dvszwmarrgswjxmb"));
+        assert!(!checks[0].passed);
+        assert_eq!(checks[0].reason, "needs 3 vowels; has 1");
+    }
+
+    #[test]
+    fn test_part2_checks() {
+        let checks = RuleSet::part2().check(&chars("qjhvhtzxzqqjkmpb"));
+        assert_eq!(checks[0].reason, "'qj' at 0 and 10");
+        assert_eq!(checks[1].reason, "'hvh' at 2");
+
+        let checks = RuleSet::part2().check(&chars("aaa"));
+        assert!(!checks[0].passed);
+        assert!(checks[1].passed);
+    }
+
+    #[test]
+    fn test_variants() {
+        // triples appearing twice, and pairs separated by at least two letters
+        let rules = RuleSet::new(vec![
+            Box::new(RepeatedNgram {
+                n: 3,
+                min_separation: 0,
+            }),
+            Box::new(RepeatedNgram {
+                n: 2,
+                min_separation: 2,
+            }),
+        ]);
+        assert!(rules.is_nice("abcxyabc"));
+        assert!(!rules.is_nice("abcabc"));
+        let checks = rules.check(&chars("abcabc"));
+        assert_eq!(checks[0].reason, "'abc' at 0 and 3");
+        assert_eq!(checks[1].rule, "repeated 2-letter run 2 apart");
+        assert_eq!(
+            checks[1].reason,
+            "no pair of letters appears twice with at least 2 letters between"
+        );
+
+        let checks = RuleSet::new(vec![Box::new(RepeatWithGap { gap: 2 })]).check(&chars("xyzx"));
+        assert_eq!(checks[0].reason, "'xyzx' at 0");
+    }
+
+    #[test]
+    fn test_score() {
+        let rules = RuleSet::weighted(vec![
+            (Box::new(Vowels { min: 3 }), 1),
+            (Box::new(DoubleLetter), 2),
+            (Box::new(NoNaughtyPairs::default()), 4),
+        ]);
+        assert_eq!(rules.max_score(), 7);
+        assert_eq!(rules.score(&chars("ugknbfddgicrmopn")), 7);
+        assert_eq!(rules.score(&chars("jchzalrnumimnmhp")), 5);
+        assert_eq!(rules.score(&chars("haegwjzuvuyypxyu")), 3);
+        assert_eq!(rules.score(&chars("dvszwmarrgswjxmb")), 6);
+        assert_eq!(RuleSet::part1().score(&chars("dvszwmarrgswjxmb")), 2);
+    }
+}
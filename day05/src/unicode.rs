@@ -0,0 +1,184 @@
+//! Niceness for strings beyond ASCII.
+//!
+//! The puzzle's rules treat each `char` as a letter, so an accented letter written with a
+//! combining mark counts as two, and a double `é` may not look double at all. Here each letter is
+//! a grapheme cluster of the string in normalization form C, and which letters are vowels is up to
+//! the caller. On ASCII input with the default vowels, the verdicts are the puzzle's.
+
+use crate::{count_lines_where, repeat_with_gap, repeated_ngram};
+use std::{
+    collections::BTreeSet,
+    convert::Infallible,
+    fmt,
+    io::{BufReader, Read},
+    str::FromStr,
+};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use util::{Answer, Error};
+
+/// The pairs of letters forbidden by part 1.
+const NAUGHTY: &[[&str; 2]] = &[["a", "b"], ["c", "d"], ["p", "q"], ["x", "y"]];
+
+/// The letters of `input`: the grapheme clusters of its normalization form C, so that a letter is
+/// the same however it was composed.
+///
+/// ```
+/// # use day05::unicode::letters;
+/// assert_eq!(letters("cafe\u{301}"), vec!["c", "a", "f", "é"]);
+/// ```
+pub fn letters(input: &str) -> Vec<String> {
+    input
+        .nfc()
+        .collect::<String>()
+        .graphemes(true)
+        .map(str::to_owned)
+        .collect()
+}
+
+/// The letters which count as vowels.
+///
+/// Parsed from a string, each of its letters is a vowel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VowelSet(BTreeSet<String>);
+
+impl VowelSet {
+    pub fn new<I, S>(vowels: I) -> VowelSet
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        VowelSet(
+            vowels
+                .into_iter()
+                .map(|vowel| vowel.as_ref().nfc().collect())
+                .collect(),
+        )
+    }
+
+    /// Whether `letter`, a single grapheme cluster in normalization form C, is a vowel.
+    pub fn contains(&self, letter: &str) -> bool {
+        self.0.contains(letter)
+    }
+}
+
+impl Default for VowelSet {
+    /// The puzzle's vowels: `aeiou`.
+    fn default() -> Self {
+        VowelSet::new(["a", "e", "i", "o", "u"])
+    }
+}
+
+impl FromStr for VowelSet {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(VowelSet::new(letters(s)))
+    }
+}
+
+impl fmt::Display for VowelSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for vowel in &self.0 {
+            f.write_str(vowel)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `input` is nice under the part 1 rules, letter by letter, with these `vowels`.
+///
+/// ```
+/// # use day05::{is_nice, is_nice_unicode, VowelSet};
+/// // a double é, written with combining accents
+/// let input = "be\u{301}e\u{301}a";
+/// let vowels: VowelSet = "aeé".parse().unwrap();
+/// assert!(is_nice_unicode(input, &vowels));
+/// assert!(!is_nice(input));
+/// ```
+pub fn is_nice_unicode(input: &str, vowels: &VowelSet) -> bool {
+    let letters = letters(input);
+    letters
+        .iter()
+        .filter(|letter| vowels.contains(letter))
+        .count()
+        >= 3
+        && repeat_with_gap(&letters, 0).is_some()
+        && !letters.windows(2).any(|pair| {
+            NAUGHTY
+                .iter()
+                .any(|naughty| pair[0] == naughty[0] && pair[1] == naughty[1])
+        })
+}
+
+/// Whether `input` is nice under the part 2 rules, letter by letter.
+pub fn is_nice2_unicode(input: &str) -> bool {
+    let letters = letters(input);
+    repeat_with_gap(&letters, 1).is_some() && repeated_ngram(&letters, 2, 0).is_some()
+}
+
+/// Count the lines nice under the part 1 rules, letter by letter, with these `vowels`.
+pub fn part1_unicode(input: impl Read, vowels: &VowelSet) -> Result<Answer, Error> {
+    let nice = count_lines_where(BufReader::new(input), |line| is_nice_unicode(line, vowels))?;
+    Ok(nice.into())
+}
+
+/// Count the lines nice under the part 2 rules, letter by letter.
+pub fn part2_unicode(input: impl Read) -> Result<Answer, Error> {
+    Ok(count_lines_where(BufReader::new(input), is_nice2_unicode)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1_rules, part2_rules, EXAMPLES};
+
+    #[test]
+    fn test_ascii_agrees() {
+        let vowels = VowelSet::default();
+        for line in EXAMPLES.iter().flat_map(|example| example.input.lines()) {
+            assert_eq!(
+                is_nice_unicode(line, &vowels),
+                part1_rules(line),
+                "{}",
+                line
+            );
+            assert_eq!(is_nice2_unicode(line), part2_rules(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_combining_marks() {
+        // x́yx́yx́ repeats a pair and a letter around y, but not char by char
+        let input = "x\u{301}yx\u{301}yx\u{301}";
+        assert!(is_nice2_unicode(input));
+        assert!(!part2_rules(input));
+        // the same letter, however it's composed
+        assert!(is_nice2_unicode("\u{e9}y\u{e9}ye\u{301}"));
+    }
+
+    #[test]
+    fn test_vowels() {
+        let vowels: VowelSet = "aeiouäöü".parse().unwrap();
+        assert_eq!(vowels.to_string(), "aeiouäöü");
+        assert!(vowels.contains("ä"));
+        assert!(!vowels.contains("y"));
+        assert_eq!(
+            "a\u{308}".parse::<VowelSet>().unwrap(),
+            VowelSet::new(["ä"])
+        );
+        // ä, ö, and ü are vowels, and the double ss makes it nice
+        assert!(is_nice_unicode("grüssöä", &vowels));
+        assert!(!is_nice_unicode("grüssöä", &VowelSet::default()));
+    }
+
+    #[test]
+    fn test_counts() {
+        let input = "aaa\ne\u{301}e\u{301}e\u{301}\n\nxyzzy\n";
+        assert_eq!(
+            part1_unicode(input.as_bytes(), &"aeé".parse().unwrap()).unwrap(),
+            Answer::Int(2)
+        );
+        assert_eq!(part2_unicode(input.as_bytes()).unwrap(), Answer::Int(0));
+    }
+}
@@ -0,0 +1,370 @@
+//! Leaving out commands which make no difference, before following the rest.
+//!
+//! Commands are redundant in three ways:
+//!
+//! - some do nothing at all, like `dim 0`;
+//! - a later command may overwrite every light a command covers, like a `turn on` whose lights are
+//!   all turned off later, whatever happens in between;
+//! - consecutive commands covering the same lights may do what a single one would, like two
+//!   toggles, which together do nothing to lights only on or off.
+//!
+//! Which commands overwrite or combine depends on what a light is: `turn off` overwrites lights
+//! only on or off, but only dims brightness. [`ManipulateLight`] says which for each.
+
+use crate::{Command, GridSize, Instruction, ManipulateLight};
+use aoclib::geometry::Point;
+
+/// What an instruction does to a light which is only on or off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Switch {
+    Keep,
+    On,
+    Off,
+    Toggle,
+}
+
+impl Switch {
+    pub(crate) fn of(instruction: &Instruction) -> Switch {
+        match *instruction {
+            Instruction::TurnOn => Switch::On,
+            Instruction::TurnOff => Switch::Off,
+            Instruction::Toggle => Switch::Toggle,
+            Instruction::SetBrightness(0) => Switch::Off,
+            Instruction::SetBrightness(_) => Switch::On,
+            Instruction::Dim(0) | Instruction::Brighten(0) => Switch::Keep,
+            Instruction::Dim(_) => Switch::Off,
+            Instruction::Brighten(_) => Switch::On,
+        }
+    }
+
+    pub(crate) fn overwrites(self) -> bool {
+        matches!(self, Switch::On | Switch::Off)
+    }
+
+    /// What this and then `next` do together.
+    pub(crate) fn then(self, next: Switch) -> Switch {
+        match (self, next) {
+            (first, Switch::Keep) => first,
+            (_, Switch::On) => Switch::On,
+            (_, Switch::Off) => Switch::Off,
+            (Switch::Keep, Switch::Toggle) => Switch::Toggle,
+            (Switch::On, Switch::Toggle) => Switch::Off,
+            (Switch::Off, Switch::Toggle) => Switch::On,
+            (Switch::Toggle, Switch::Toggle) => Switch::Keep,
+        }
+    }
+}
+
+impl From<Switch> for Option<Instruction> {
+    fn from(switch: Switch) -> Self {
+        match switch {
+            Switch::Keep => None,
+            Switch::On => Some(Instruction::TurnOn),
+            Switch::Off => Some(Instruction::TurnOff),
+            Switch::Toggle => Some(Instruction::Toggle),
+        }
+    }
+}
+
+/// What an instruction does to a light's brightness.
+#[derive(Debug, Clone, Copy)]
+enum Change {
+    Raise(u32),
+    Lower(u32),
+    Set(u32),
+}
+
+impl Change {
+    fn of(instruction: &Instruction) -> Change {
+        match *instruction {
+            Instruction::TurnOn => Change::Raise(1),
+            Instruction::TurnOff => Change::Lower(1),
+            Instruction::Toggle => Change::Raise(2),
+            Instruction::SetBrightness(level) => Change::Set(level),
+            Instruction::Dim(level) => Change::Lower(level),
+            Instruction::Brighten(level) => Change::Raise(level),
+        }
+    }
+}
+
+/// A single instruction doing to a light's brightness, of a type holding up to `max`, what `first`
+/// and then `next` do, unless it takes both. The combined instruction overflows exactly when
+/// `first` or `next` would.
+///
+/// Raising and then lowering brightness can't combine: how much lowering is held back at 0 depends
+/// on how bright the light was. Nor can setting a brightness which overflows and then lowering it.
+pub(crate) fn then_brightness(
+    first: &Instruction,
+    next: &Instruction,
+    max: u64,
+) -> Option<Instruction> {
+    match (Change::of(first), Change::of(next)) {
+        (_, Change::Set(_)) => Some(*next),
+        (Change::Raise(a), Change::Raise(b)) => a.checked_add(b).map(Instruction::Brighten),
+        (Change::Lower(a), Change::Lower(b)) => Some(Instruction::Dim(a.saturating_add(b))),
+        (Change::Set(level), Change::Raise(b)) => {
+            level.checked_add(b).map(Instruction::SetBrightness)
+        }
+        (Change::Set(level), Change::Lower(b)) if u64::from(level) <= max => {
+            Some(Instruction::SetBrightness(level.saturating_sub(b)))
+        }
+        _ => None,
+    }
+}
+
+/// Whether `instruction` does nothing to any light.
+fn is_noop(instruction: &Instruction) -> bool {
+    matches!(instruction, Instruction::Dim(0) | Instruction::Brighten(0))
+}
+
+/// Whether the lights between `outer`'s corners include all those between `inner`'s.
+fn contains(outer: (Point, Point), inner: (Point, Point)) -> bool {
+    outer.0.x <= inner.0.x
+        && outer.0.y <= inner.0.y
+        && inner.1.x <= outer.1.x
+        && inner.1.y <= outer.1.y
+}
+
+/// Merge each run of commands covering the same lights, as far as they combine.
+fn merge<Light>(commands: Vec<Command>, size: GridSize) -> Vec<Command>
+where
+    Instruction: ManipulateLight<Light>,
+{
+    let mut merged: Vec<Command> = Vec::with_capacity(commands.len());
+    for mut command in commands {
+        let mut cancelled = false;
+        while let Some(last) = merged.last() {
+            if last.corners(size) != command.corners(size) {
+                break;
+            }
+            match ManipulateLight::<Light>::then(&last.instruction, &command.instruction) {
+                None => break,
+                Some(combined) => {
+                    merged.pop();
+                    match combined {
+                        Some(instruction) => command.instruction = instruction,
+                        None => {
+                            cancelled = true;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        if !cancelled {
+            merged.push(command);
+        }
+    }
+    merged
+}
+
+/// Leave out each command whose lights a later command all overwrites.
+fn overwritten<Light>(commands: Vec<Command>, size: GridSize) -> Vec<Command>
+where
+    Instruction: ManipulateLight<Light>,
+{
+    let mut overwrites: Vec<(Point, Point)> = Vec::new();
+    let mut kept: Vec<Command> = commands
+        .into_iter()
+        .rev()
+        .filter(|command| {
+            let corners = command.corners(size);
+            if overwrites.iter().any(|&outer| contains(outer, corners)) {
+                return false;
+            }
+            if ManipulateLight::<Light>::overwrites(&command.instruction) {
+                overwrites.push(corners);
+            }
+            true
+        })
+        .collect();
+    kept.reverse();
+    kept
+}
+
+/// The commands which make a difference to lights of type `Light` in a grid of `size`, with runs
+/// of commands covering the same lights merged. Following them leaves the lights as following all
+/// of `commands` would.
+///
+/// Lights overflow just as they would, except that a command which would overflow is left out if
+/// later commands overwrite all its lights, so it can't stop the rest with
+/// [`Overflow::Error`](crate::Overflow::Error).
+///
+/// ```
+/// # use day06::{compact::compact, Command, GridSize};
+/// let commands: Vec<Command> = [
+///     "turn on 0,0 through 9,9",
+///     "toggle 3,3 through 4,4",
+///     "toggle 3,3 through 4,4",
+///     "turn off 0,0 through 99,99",
+/// ]
+/// .iter()
+/// .map(|command| command.parse().unwrap())
+/// .collect();
+/// assert_eq!(compact::<bool>(&commands, GridSize::default()), &commands[3..]);
+/// assert_eq!(compact::<u8>(&commands, GridSize::default()).len(), 3);
+/// ```
+pub fn compact<Light>(commands: &[Command], size: GridSize) -> Vec<Command>
+where
+    Instruction: ManipulateLight<Light>,
+{
+    let mut commands: Vec<Command> = commands
+        .iter()
+        .copied()
+        .filter(|command| !is_noop(&command.instruction))
+        .collect();
+    // leaving out commands can bring others together which merge, and merging can make commands
+    // which overwrite others, so repeat until neither changes anything
+    loop {
+        let len = commands.len();
+        commands = overwritten::<Light>(merge::<Light>(commands, size), size);
+        if commands.len() == len {
+            return commands;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Overflow;
+    use aoclib::geometry::Map;
+
+    fn commands(commands: &[&str]) -> Vec<Command> {
+        commands
+            .iter()
+            .map(|command| command.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_on_off() {
+        let size: GridSize = "10x10".parse().unwrap();
+        let compacted = compact::<bool>(
+            &commands(&[
+                "turn on 2,2 through 3,3",
+                "toggle 0,0 through 9,9",
+                "turn off 1,1 through 5,5",
+                "turn on row 7",
+                "toggle row 7",
+                "dim 0 column 2",
+                "toggle column 4",
+                "toggle column 4",
+            ]),
+            size,
+        );
+        assert_eq!(
+            compacted,
+            commands(&[
+                "toggle 0,0 through 9,9",
+                "turn off 1,1 through 5,5",
+                "turn off row 7"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_brightness() {
+        let size: GridSize = "10x10".parse().unwrap();
+        let compacted = compact::<u16>(
+            &commands(&[
+                "turn on 2,2 through 3,3",
+                "toggle 0,0 through 9,9",
+                "turn off 1,1 through 5,5",
+                "set brightness 3 row 7",
+                "toggle row 7",
+                "dim 9 row 7",
+                "toggle column 4",
+                "turn on column 4",
+                "turn off column 4",
+                "set brightness 2 1,1 through 3,3",
+            ]),
+            size,
+        );
+        assert_eq!(
+            compacted,
+            commands(&[
+                "toggle 0,0 through 9,9",
+                "turn off 1,1 through 5,5",
+                "set brightness 0 row 7",
+                "brighten 3 column 4",
+                "turn off column 4",
+                "set brightness 2 1,1 through 3,3",
+            ])
+        );
+    }
+
+    /// Following the compacted commands must leave the same lights as following them all.
+    #[test]
+    fn test_same_lights() {
+        let size: GridSize = "8x8".parse().unwrap();
+        let instructions = [
+            "turn on",
+            "turn off",
+            "toggle",
+            "set brightness 2",
+            "set brightness 300",
+            "dim 1",
+            "dim 120",
+            "brighten 3",
+            "brighten 200",
+            "dim 0",
+        ];
+        // a few small regions, so that they often coincide
+        let regions = [
+            "0,0 through 3,3",
+            "2,2 through 5,5",
+            "0,0 through 7,7",
+            "row 1",
+            "column 2",
+        ];
+        let mut seed = 6_u64;
+        let mut random = |n: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % n
+        };
+        for _ in 0..200 {
+            let commands: Vec<Command> = (0..random(12))
+                .map(|_| {
+                    let instruction = instructions[random(instructions.len())];
+                    let region = regions[random(regions.len())];
+                    format!("{} {}", instruction, region).parse().unwrap()
+                })
+                .collect();
+
+            /// The lights after following `commands`, unless some overflow.
+            fn follow<Light: Default + Clone>(
+                commands: &[Command],
+                size: GridSize,
+                overflow: Overflow,
+            ) -> Option<Vec<Light>>
+            where
+                Instruction: ManipulateLight<Light>,
+            {
+                let mut map = Map::new(size.width, size.height);
+                for command in commands {
+                    command.apply(&mut map, overflow).ok()?;
+                }
+                Some(map.iter().cloned().collect())
+            }
+            let compacted = compact::<bool>(&commands, size);
+            assert_eq!(
+                follow::<bool>(&compacted, size, Overflow::Error),
+                follow::<bool>(&commands, size, Overflow::Error),
+                "{:?}",
+                commands
+            );
+            let compacted = compact::<u8>(&commands, size);
+            for &overflow in &[Overflow::Error, Overflow::Saturate, Overflow::Wrap] {
+                let expect = follow::<u8>(&commands, size, overflow);
+                let got = follow::<u8>(&compacted, size, overflow);
+                // a command which would overflow may be left out, when later ones overwrite it
+                if expect.is_some() || overflow != Overflow::Error {
+                    assert_eq!(got, expect, "{:?} with {}", commands, overflow);
+                }
+            }
+        }
+    }
+}
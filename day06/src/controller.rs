@@ -0,0 +1,256 @@
+//! A grid of lights which remembers the commands it has followed, so they can be undone.
+//!
+//! Most instructions can't be reversed: once a light is turned off, there's no telling whether it
+//! was on before. Instead, the controller keeps a snapshot of the grid every
+//! [`CHECKPOINT_INTERVAL`] commands, and works out any earlier state by replaying the commands
+//! since the last snapshot before it.
+
+use crate::{Command, Error, GridSize, Instruction, ManipulateLight, Overflow, TooBright};
+use aoclib::geometry::{Map, Point};
+
+/// How many commands apart the controller keeps snapshots of the grid.
+pub const CHECKPOINT_INTERVAL: usize = 32;
+
+/// A grid of lights, with a journal of the commands followed which supports undo and redo.
+///
+/// ```
+/// # use day06::{Command, GridSize, LightController};
+/// let mut lights: LightController<bool> = LightController::new(GridSize::default());
+/// let command: Command = "turn on 0,0 through 9,9".parse().unwrap();
+/// lights.apply(command).unwrap();
+/// assert_eq!(lights.total(), 100);
+/// assert_eq!(lights.undo(), Some(command));
+/// assert_eq!(lights.total(), 0);
+/// assert_eq!(lights.redo(), Some(command));
+/// assert_eq!(lights.total(), 100);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LightController<Light> {
+    size: GridSize,
+    overflow: Overflow,
+    /// Every command in the journal, including those undone, which can be redone.
+    commands: Vec<Command>,
+    /// How many of `commands` the grid reflects.
+    applied: usize,
+    lights: Map<Light>,
+    /// The grid after each multiple of [`CHECKPOINT_INTERVAL`] commands, starting from none.
+    checkpoints: Vec<Map<Light>>,
+}
+
+impl<Light> LightController<Light>
+where
+    Instruction: ManipulateLight<Light>,
+    Light: Clone + Default,
+{
+    /// A grid of `size` dark lights, with an empty journal.
+    pub fn new(size: GridSize) -> Self {
+        Self::with_overflow(size, Overflow::default())
+    }
+
+    /// A grid of `size` dark lights, with an empty journal, which handles overflow as `overflow`
+    /// says.
+    pub fn with_overflow(size: GridSize, overflow: Overflow) -> Self {
+        let lights = Map::new(size.width, size.height);
+        LightController {
+            size,
+            overflow,
+            commands: Vec::new(),
+            applied: 0,
+            checkpoints: vec![lights.clone()],
+            lights,
+        }
+    }
+
+    /// Follow `command`, forgetting any undone commands; they can no longer be redone.
+    ///
+    /// A command which makes a light [`TooBright`] is left out of the journal, and leaves the grid
+    /// as it was.
+    pub fn apply(&mut self, command: Command) -> Result<(), Error> {
+        command
+            .check_range(self.size, self.applied + 1)
+            .map_err(|err| Error::parse("command", err))?;
+        self.commands.truncate(self.applied);
+        self.checkpoints
+            .truncate(self.applied / CHECKPOINT_INTERVAL + 1);
+        self.commands.push(command);
+        if let Err(point) = self.step() {
+            // some lights may have changed before the one which overflowed
+            self.commands.pop();
+            self.lights = self.replay(self.applied);
+            return Err(TooBright::error(self.applied + 1, point));
+        }
+        Ok(())
+    }
+
+    /// Undo the last command followed, returning it, unless there's none.
+    pub fn undo(&mut self) -> Option<Command> {
+        let command = *self.commands.get(self.applied.checked_sub(1)?)?;
+        self.lights = self.state_after(self.applied - 1);
+        self.applied -= 1;
+        Some(command)
+    }
+
+    /// Follow the last command undone again, returning it, unless there's none.
+    pub fn redo(&mut self) -> Option<Command> {
+        let command = *self.commands.get(self.applied)?;
+        self.step()
+            .expect("commands in the journal were followed before");
+        Some(command)
+    }
+
+    /// Follow the next command in the journal, taking a snapshot if it's due.
+    fn step(&mut self) -> Result<(), Point> {
+        self.commands[self.applied].apply(&mut self.lights, self.overflow)?;
+        self.applied += 1;
+        // unless redoing, when the snapshot is already there
+        if self.checkpoints.len() * CHECKPOINT_INTERVAL == self.applied {
+            self.checkpoints.push(self.lights.clone());
+        }
+        Ok(())
+    }
+
+    /// The grid once the first `n` commands in the journal are followed, whether or not they're
+    /// currently undone.
+    ///
+    /// # Panics
+    ///
+    /// If the journal holds fewer than `n` commands.
+    pub fn state_after(&self, n: usize) -> Map<Light> {
+        assert!(
+            n <= self.commands.len(),
+            "only {} commands",
+            self.commands.len()
+        );
+        if n == self.applied {
+            return self.lights.clone();
+        }
+        self.replay(n)
+    }
+
+    /// Work out the grid after the first `n` commands in the journal from the last snapshot before.
+    fn replay(&self, n: usize) -> Map<Light> {
+        let checkpoint = (n / CHECKPOINT_INTERVAL).min(self.checkpoints.len() - 1);
+        let mut lights = self.checkpoints[checkpoint].clone();
+        for command in &self.commands[checkpoint * CHECKPOINT_INTERVAL..n] {
+            command
+                .apply(&mut lights, self.overflow)
+                .expect("commands in the journal were followed before");
+        }
+        lights
+    }
+
+    /// The grid as it stands.
+    pub fn lights(&self) -> &Map<Light> {
+        &self.lights
+    }
+
+    /// The commands followed, in order, leaving out those undone.
+    pub fn applied(&self) -> &[Command] {
+        &self.commands[..self.applied]
+    }
+
+    /// The commands undone, which [`redo`](Self::redo) would follow again, in order.
+    pub fn undone(&self) -> &[Command] {
+        &self.commands[self.applied..]
+    }
+
+    /// The total of every light as it stands: how many are lit, or their total brightness.
+    pub fn total(&self) -> u64
+    where
+        Light: Copy + Into<u64>,
+    {
+        self.lights.iter().map(|&light| light.into()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(map: &Map<u8>) -> u64 {
+        map.iter().map(|&light| light as u64).sum()
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let size: GridSize = "10x10".parse().unwrap();
+        let mut lights: LightController<u8> = LightController::new(size);
+        assert_eq!(lights.undo(), None);
+        assert_eq!(lights.redo(), None);
+        // enough commands to need a few snapshots
+        let commands: Vec<Command> = (0..100)
+            .map(|idx| {
+                let instruction = ["turn on", "toggle", "turn off"][idx % 3];
+                format!("{} {},0 through 9,{}", instruction, idx % 10, idx % 7)
+                    .parse()
+                    .unwrap()
+            })
+            .collect();
+        let mut totals = vec![0];
+        for &command in &commands {
+            lights.apply(command).unwrap();
+            totals.push(lights.total());
+        }
+        assert_eq!(lights.checkpoints.len(), 4);
+
+        for n in (0..100).rev() {
+            assert_eq!(lights.undo(), Some(commands[n]));
+            assert_eq!(lights.total(), totals[n], "after {} commands", n);
+        }
+        for n in 0..50 {
+            assert_eq!(lights.redo(), Some(commands[n]));
+            assert_eq!(lights.total(), totals[n + 1]);
+        }
+        for (n, &expect) in totals.iter().enumerate() {
+            assert_eq!(total(&lights.state_after(n)), expect);
+        }
+        assert_eq!(lights.applied().len(), 50);
+        assert_eq!(lights.undone().len(), 50);
+
+        // following a new command forgets what was undone
+        lights.apply(commands[0]).unwrap();
+        assert_eq!(lights.applied().len(), 51);
+        assert!(lights.undone().is_empty());
+        assert_eq!(lights.redo(), None);
+        assert_eq!(lights.checkpoints.len(), 2);
+    }
+
+    #[test]
+    fn test_too_bright() {
+        let mut lights: LightController<u8> = LightController::new("10x10".parse().unwrap());
+        for command in &[
+            "turn on 0,0 through 9,9",
+            "set brightness 250 5,5 through 5,5",
+        ] {
+            lights.apply(command.parse().unwrap()).unwrap();
+        }
+        let err = lights
+            .apply("brighten 10 0,0 through 9,9".parse().unwrap())
+            .unwrap_err();
+        assert_eq!(
+            std::error::Error::source(&err).unwrap().to_string(),
+            "command 3 makes light 5,5 brighter than it can get"
+        );
+        // the lights before 5,5 mustn't stay brightened
+        assert_eq!(lights.total(), 99 + 250);
+        assert_eq!(lights.applied().len(), 2);
+
+        let mut lights: LightController<u8> =
+            LightController::with_overflow("10x10".parse().unwrap(), Overflow::Saturate);
+        for _ in 0..200 {
+            lights
+                .apply("toggle 0,0 through 1,1".parse().unwrap())
+                .unwrap();
+        }
+        assert_eq!(lights.total(), 4 * 255);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut lights: LightController<bool> = LightController::new("10x10".parse().unwrap());
+        assert!(lights
+            .apply("toggle 0,0 through 10,10".parse().unwrap())
+            .is_err());
+        assert!(lights.applied().is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! Lights grouped into blocks which every command treats alike.
+//!
+//! Only the edges of the commands' regions divide the grid, so a few hundred commands cut it
+//! into blocks numbering far fewer than the million lights, and every light in a block is always in
+//! the same state. Following a command then changes each block it covers once, however many lights
+//! that block holds, and totals weigh each block by its area.
+
+use crate::{Command, GridSize, Instruction, ManipulateLight, Overflow};
+use aoclib::geometry::Point;
+
+/// A grid of lights, compressed to the blocks which the commands divide it into.
+pub(crate) struct Blocks<Light> {
+    size: GridSize,
+    /// Where each column of blocks starts, followed by the width of the grid.
+    xs: Vec<i32>,
+    /// Where each row of blocks starts, followed by the height of the grid.
+    ys: Vec<i32>,
+    /// The state of each block, in reading order.
+    lights: Vec<Light>,
+}
+
+/// The edges dividing `0..extent` at the start and just past the end of each of `ranges`.
+fn edges(extent: usize, ranges: impl Iterator<Item = (i32, i32)>) -> Vec<i32> {
+    let extent = extent as i32;
+    let mut edges: Vec<i32> = ranges
+        .flat_map(|(low, high)| vec![low, high + 1])
+        .map(|edge| edge.clamp(0, extent))
+        .chain(vec![0, extent])
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+/// The indices of the blocks between `edges` covering `low..=high`.
+fn span(edges: &[i32], low: i32, high: i32) -> std::ops::Range<usize> {
+    let start = edges.partition_point(|&edge| edge <= low).saturating_sub(1);
+    let end = edges.partition_point(|&edge| edge <= high);
+    start..end.min(edges.len() - 1)
+}
+
+impl<Light> Blocks<Light>
+where
+    Light: Default + Clone,
+{
+    /// A grid of `size` dark lights, divided along the edges of every command.
+    pub(crate) fn new(size: GridSize, commands: &[Command]) -> Self {
+        let corners: Vec<_> = commands
+            .iter()
+            .map(|command| command.corners(size))
+            .collect();
+        let xs = edges(size.width, corners.iter().map(|(min, max)| (min.x, max.x)));
+        let ys = edges(size.height, corners.iter().map(|(min, max)| (min.y, max.y)));
+        let lights = vec![Light::default(); (xs.len() - 1) * (ys.len() - 1)];
+        Blocks {
+            size,
+            xs,
+            ys,
+            lights,
+        }
+    }
+
+    /// Follow `command`, which must be one of those this grid was divided by, handling overflow as
+    /// `overflow` says, or return the first light in reading order which overflows.
+    pub(crate) fn apply(&mut self, command: &Command, overflow: Overflow) -> Result<(), Point>
+    where
+        Instruction: ManipulateLight<Light>,
+    {
+        let (min, max) = command.corners(self.size);
+        let columns = self.xs.len() - 1;
+        for row in span(&self.ys, min.y, max.y) {
+            for column in span(&self.xs, min.x, max.x) {
+                command
+                    .instruction
+                    .manipulate(&mut self.lights[row * columns + column], overflow)
+                    .map_err(|_| Point::new(self.xs[column].max(min.x), self.ys[row].max(min.y)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The total of every light: how many are lit, or their total brightness.
+    pub(crate) fn total(&self) -> u128
+    where
+        Light: Copy + Into<u64>,
+    {
+        let columns = self.xs.len() - 1;
+        self.lights
+            .iter()
+            .enumerate()
+            .map(|(idx, &light)| {
+                let (row, column) = (idx / columns, idx % columns);
+                let width = self.xs[column + 1] - self.xs[column];
+                let height = self.ys[row + 1] - self.ys[row];
+                u128::from(light.into()) * (width as u128) * (height as u128)
+            })
+            .sum()
+    }
+
+    /// Each block, as its least and greatest corners, inclusive, and the state of its lights.
+    pub(crate) fn rects(&self) -> impl '_ + Iterator<Item = (Point, Point, Light)>
+    where
+        Light: Copy,
+    {
+        let columns = self.xs.len() - 1;
+        self.lights.iter().enumerate().map(move |(idx, &light)| {
+            let (row, column) = (idx / columns, idx % columns);
+            (
+                Point::new(self.xs[column], self.ys[row]),
+                Point::new(self.xs[column + 1] - 1, self.ys[row + 1] - 1),
+                light,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        let commands: Vec<Command> = ["turn on 1,1 through 2,2", "toggle 2,0 through 3,3"]
+            .iter()
+            .map(|command| command.parse().unwrap())
+            .collect();
+        let mut blocks: Blocks<bool> = Blocks::new("5x4".parse().unwrap(), &commands);
+        assert_eq!(blocks.xs, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(blocks.ys, vec![0, 1, 3, 4]);
+        assert_eq!(blocks.lights.len(), 15);
+
+        blocks.apply(&commands[0], Overflow::Error).unwrap();
+        assert_eq!(blocks.total(), 4);
+        blocks.apply(&commands[1], Overflow::Error).unwrap();
+        // 2 of the lit lights are toggled off, and 6 dark lights on
+        assert_eq!(blocks.total(), 8);
+    }
+
+    #[test]
+    fn test_span() {
+        let edges = [0, 3, 5, 10];
+        assert_eq!(span(&edges, 0, 2), 0..1);
+        assert_eq!(span(&edges, 3, 9), 1..3);
+        assert_eq!(span(&edges, 0, 9), 0..3);
+    }
+}
@@ -26,106 +26,626 @@
 
 use aoclib::{
     geometry::{Map, Point},
-    parse,
+    input::parse_str,
 };
 
-use lalrpop_util::lalrpop_mod;
-use std::{path::Path, str::FromStr};
-use thiserror::Error;
+pub use controller::LightController;
+use lalrpop_util::{lalrpop_mod, ParseError};
+pub use lights::Lights;
+use std::{collections::BTreeMap, convert::TryFrom, fmt, ops::Range, path::Path, str::FromStr};
+pub use util::Error;
+use util::{cache::Variant, example::Example, lint::Diagnostic, Answer, InputSource};
 
 lalrpop_mod!(#[allow(clippy::all)] pub parser);
 
+pub mod compact;
+pub mod controller;
+mod intervals;
+pub mod lights;
+#[cfg(feature = "viz")]
+pub mod render;
+mod sparse;
+
+/// Width and height of the puzzle's grid of lights.
+pub const GRID_SIZE: usize = 1000;
+
+/// The width and height of a grid of lights.
+///
+/// Parses from and displays as `WIDTHxHEIGHT`, like `1000x1000`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display)]
+#[display("{width}x{height}")]
+pub struct GridSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for GridSize {
+    /// The puzzle's grid: [`GRID_SIZE`] lights each way.
+    fn default() -> Self {
+        GridSize {
+            width: GRID_SIZE,
+            height: GRID_SIZE,
+        }
+    }
+}
+
+impl GridSize {
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.width as i64).contains(&(point.x as i64))
+            && (0..self.height as i64).contains(&(point.y as i64))
+    }
+}
+
+/// A command reaches lights past the edge of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("command {command} reaches {x},{y}, outside the {size} grid")]
+pub struct OutOfRange {
+    /// The 1-based position of the command among the commands.
+    pub command: usize,
+    pub x: i32,
+    pub y: i32,
+    pub size: GridSize,
+}
+
+/// What a command does to each light it covers.
+///
+/// The puzzle's instructions are `turn on`, `turn off`, and `toggle`. The rest set brightness
+/// directly; with lights which are only on or off, any brightness above 0 is on.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
     TurnOn,
     TurnOff,
     Toggle,
+    /// `set brightness N`: set each light to brightness `N`.
+    SetBrightness(u32),
+    /// `dim N`: lower each light's brightness by `N`, to no lower than 0.
+    Dim(u32),
+    /// `brighten N`: raise each light's brightness by `N`.
+    Brighten(u32),
+}
+
+/// The lights a command covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    /// `X,Y through X,Y`: the rectangle with these opposite corners, inclusive.
+    Rectangle { from: Point, to: Point },
+    /// `row Y`: every light with this `y`.
+    Row(i32),
+    /// `column X`: every light with this `x`.
+    Column(i32),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Command {
     pub(crate) instruction: Instruction,
-    pub(crate) from: Point,
-    pub(crate) to: Point,
+    pub(crate) region: Region,
+}
+
+/// A command couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} at {}..{}", span.start, span.end)]
+pub struct ParseCommandError {
+    /// The byte range of the offending text in the command.
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl ParseCommandError {
+    /// Describe `err` in parsing `command`, which should start with a `first`.
+    fn new(
+        command: &str,
+        first: &str,
+        err: ParseError<usize, lalrpop_util::lexer::Token<'_>, ParseCommandError>,
+    ) -> Self {
+        // the word starting at `start`, for tokens the lexer couldn't make out
+        let word = |start: usize| {
+            let len = command[start..]
+                .find(char::is_whitespace)
+                .unwrap_or(command.len() - start);
+            start..start + len
+        };
+        let is_first = |start: usize| command[..start].trim().is_empty();
+        let expected = |expected: &[String]| match expected {
+            [] => String::new(),
+            [one] => format!("; expected {}", one),
+            many => format!("; expected one of {}", many.join(", ")),
+        };
+        let (span, message) = match err {
+            ParseError::InvalidToken { location } if is_first(location) => {
+                let span = word(location);
+                let message = format!("unknown {} '{}'", first, &command[span.clone()]);
+                (span, message)
+            }
+            ParseError::InvalidToken { location } => {
+                let span = word(location);
+                let message = format!("unexpected '{}'", &command[span.clone()]);
+                (span, message)
+            }
+            ParseError::UnrecognizedToken {
+                token: (start, token, end),
+                ..
+            } if is_first(start) => (start..end, format!("unknown {} '{}'", first, token)),
+            ParseError::UnrecognizedToken {
+                token: (start, token, end),
+                expected: wanted,
+            } => (
+                start..end,
+                format!("unexpected '{}'{}", token, expected(&wanted)),
+            ),
+            ParseError::UnrecognizedEOF {
+                location,
+                expected: wanted,
+            } => (
+                location..location,
+                format!("unexpected end of command{}", expected(&wanted)),
+            ),
+            ParseError::ExtraToken {
+                token: (start, token, end),
+            } => (
+                start..end,
+                format!("unexpected '{}' after the command", token),
+            ),
+            ParseError::User { error } => return error,
+        };
+        ParseCommandError { span, message }
+    }
 }
 
 impl FromStr for Command {
-    type Err = lalrpop_util::ParseError<usize, String, &'static str>;
+    type Err = ParseCommandError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parser = parser::CommandParser::new();
         parser
             .parse(s)
-            .map_err(|err| err.map_token(|t| t.to_string()))
+            .map_err(|err| ParseCommandError::new(s, "instruction", err))
+    }
+}
+
+impl Region {
+    /// The least and greatest corners of the lights in this region of a grid of `size`,
+    /// inclusive.
+    pub(crate) fn corners(&self, size: GridSize) -> (Point, Point) {
+        let (width, height) = (size.width as i32, size.height as i32);
+        match *self {
+            Region::Rectangle { from, to } => (
+                Point::new(from.x.min(to.x), from.y.min(to.y)),
+                Point::new(from.x.max(to.x), from.y.max(to.y)),
+            ),
+            Region::Row(y) => (Point::new(0, y), Point::new(width - 1, y)),
+            Region::Column(x) => (Point::new(x, 0), Point::new(x, height - 1)),
+        }
+    }
+}
+
+impl FromStr for Region {
+    type Err = ParseCommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parser = parser::RegionParser::new();
+        parser
+            .parse(s)
+            .map_err(|err| ParseCommandError::new(s, "region", err))
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Region::Rectangle { from, to } => {
+                write!(f, "{},{} through {},{}", from.x, from.y, to.x, to.y)
+            }
+            Region::Row(y) => write!(f, "row {}", y),
+            Region::Column(x) => write!(f, "column {}", x),
+        }
     }
 }
 
 impl Command {
-    fn apply<Light>(&self, map: &mut Map<Light>)
+    /// The least and greatest corners of the lights this command covers in a grid of `size`,
+    /// inclusive.
+    pub(crate) fn corners(&self, size: GridSize) -> (Point, Point) {
+        self.region.corners(size)
+    }
+
+    /// Make sure this command, the `position`th, stays within a grid of `size`.
+    pub(crate) fn check_range(&self, size: GridSize, position: usize) -> Result<(), OutOfRange> {
+        // report the corner as written, when there is one
+        let (min, max) = self.corners(size);
+        let points = match self.region {
+            Region::Rectangle { from, to } => [from, to],
+            _ => [min, max],
+        };
+        match points.iter().find(|&&point| !size.contains(point)) {
+            Some(point) => Err(OutOfRange {
+                command: position,
+                x: point.x,
+                y: point.y,
+                size,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Follow this command, handling overflow as `overflow` says, or return the first light in
+    /// reading order which overflows.
+    pub(crate) fn apply<Light>(&self, map: &mut Map<Light>, overflow: Overflow) -> Result<(), Point>
     where
         Instruction: ManipulateLight<Light>,
     {
-        let min_x = self.from.x.min(self.to.x);
-        let max_x = self.from.x.max(self.to.x);
-        let min_y = self.from.y.min(self.to.y);
-        let max_y = self.from.y.max(self.to.y);
-
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                self.instruction.manipulate(&mut map[Point::new(x, y)])
+        let size = GridSize {
+            width: map.width(),
+            height: map.height(),
+        };
+        let (min, max) = self.corners(size);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let point = Point::new(x, y);
+                self.instruction
+                    .manipulate(&mut map[point], overflow)
+                    .map_err(|_| point)?;
             }
         }
+        Ok(())
     }
 }
 
-trait ManipulateLight<Light> {
-    fn manipulate(&self, light: &mut Light);
+/// What to do when an instruction would make a light brighter than its type can hold.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display,
+)]
+#[display(style = "snake_case")]
+pub enum Overflow {
+    /// Stop, reporting the command and the light.
+    #[default]
+    Error,
+    /// Leave the light as bright as it can get.
+    Saturate,
+    /// Wrap around past the greatest brightness, through 0.
+    Wrap,
+}
+
+/// A light would be brighter than its type can hold, and [`Overflow::Error`] says to stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflowed;
+
+impl Overflow {
+    /// Fit `brightness` to a type holding up to `max`.
+    fn fit(self, brightness: u64, max: u64) -> Result<u64, Overflowed> {
+        match self {
+            _ if brightness <= max => Ok(brightness),
+            Overflow::Error => Err(Overflowed),
+            Overflow::Saturate => Ok(max),
+            Overflow::Wrap => Ok(brightness % (max + 1)),
+        }
+    }
+}
+
+/// Following a command would make a light brighter than its type can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("command {command} makes light {x},{y} brighter than it can get")]
+pub struct TooBright {
+    /// The 1-based position of the command among those followed.
+    pub command: usize,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl TooBright {
+    pub(crate) fn error(command: usize, point: Point) -> Error {
+        Error::Conversion(
+            TooBright {
+                command,
+                x: point.x,
+                y: point.y,
+            }
+            .into(),
+        )
+    }
+}
+
+/// How an instruction changes a light whose state is a `Light`.
+///
+/// Besides following instructions, this says how to [`compact`](compact::compact) them, since
+/// which instructions make a difference depends on what a light is.
+pub trait ManipulateLight<Light> {
+    /// Change `light`, handling overflow as `overflow` says.
+    fn manipulate(&self, light: &mut Light, overflow: Overflow) -> Result<(), Overflowed>;
+
+    /// Whether this leaves each light the same, whatever its state before.
+    fn overwrites(&self) -> bool;
+
+    /// A single instruction doing what this and then `next` do to the same light: `Some(None)` if
+    /// together they do nothing, or `None` if it takes both.
+    fn then(&self, next: &Instruction) -> Option<Option<Instruction>>;
 }
 
 impl ManipulateLight<bool> for Instruction {
-    fn manipulate(&self, light: &mut bool) {
+    fn manipulate(&self, light: &mut bool, _overflow: Overflow) -> Result<(), Overflowed> {
         match self {
             Self::TurnOn => *light = true,
             Self::TurnOff => *light = false,
             Self::Toggle => *light = !*light,
+            Self::SetBrightness(level) => *light = *level > 0,
+            Self::Dim(level) => *light &= *level == 0,
+            Self::Brighten(level) => *light |= *level > 0,
         }
+        Ok(())
+    }
+
+    fn overwrites(&self) -> bool {
+        compact::Switch::of(self).overwrites()
+    }
+
+    fn then(&self, next: &Instruction) -> Option<Option<Instruction>> {
+        Some(
+            compact::Switch::of(self)
+                .then(compact::Switch::of(next))
+                .into(),
+        )
     }
 }
 
-impl ManipulateLight<u8> for Instruction {
-    fn manipulate(&self, light: &mut u8) {
-        match self {
-            Self::TurnOn => *light = light.checked_add(1).expect("overflow"),
-            Self::TurnOff => *light = light.saturating_sub(1),
-            Self::Toggle => *light = light.checked_add(2).expect("overflow"),
-        }
+macro_rules! manipulate_brightness {
+    ($($t:ty),*) => {
+        $(
+            impl ManipulateLight<$t> for Instruction {
+                fn manipulate(&self, light: &mut $t, overflow: Overflow) -> Result<(), Overflowed> {
+                    let fit = |brightness: u64| {
+                        overflow
+                            .fit(brightness, <$t>::MAX.into())
+                            .map(|brightness| brightness as $t)
+                    };
+                    let raise = |light: $t, by: u32| fit(u64::from(light) + u64::from(by));
+                    *light = match self {
+                        Self::TurnOn => raise(*light, 1)?,
+                        Self::TurnOff => light.saturating_sub(1),
+                        Self::Toggle => raise(*light, 2)?,
+                        Self::SetBrightness(level) => fit((*level).into())?,
+                        Self::Dim(level) => <$t>::try_from(*level)
+                            .map(|level| light.saturating_sub(level))
+                            .unwrap_or_default(),
+                        Self::Brighten(level) => raise(*light, *level)?,
+                    };
+                    Ok(())
+                }
+
+                fn overwrites(&self) -> bool {
+                    matches!(self, Self::SetBrightness(_))
+                }
+
+                fn then(&self, next: &Instruction) -> Option<Option<Instruction>> {
+                    compact::then_brightness(self, next, <$t>::MAX.into()).map(Some)
+                }
+            }
+        )*
+    };
+}
+
+manipulate_brightness!(u8, u16, u32);
+
+/// The type of each light's brightness in part 2, which bounds how bright a light can get.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display,
+)]
+#[display(style = "lowercase")]
+pub enum Brightness {
+    U8,
+    U16,
+    #[default]
+    U32,
+}
+
+/// How to work out the state of the lights.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display,
+)]
+#[display(style = "snake_case")]
+pub enum Backend {
+    /// Keep every light in a [`Map`], and have each command update each light it covers.
+    Map,
+    /// Group the lights into blocks which every command treats alike, and have each command update
+    /// each block it covers.
+    #[default]
+    Intervals,
+    /// Keep the lights as rectangles of lights alike, and have each command split the rectangles
+    /// it covers. Suits grids too large to hold every light.
+    Sparse,
+}
+
+/// How to set up the grid of lights and follow the commands.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Setup {
+    pub size: GridSize,
+    pub backend: Backend,
+    /// The type of each light's brightness, in part 2.
+    pub brightness: Brightness,
+    /// Whether to [`compact`](compact::compact) the commands before following them. Commands
+    /// reported as making lights [`TooBright`] are then counted among those compacted.
+    pub optimize: bool,
+    pub overflow: Overflow,
+}
+
+impl Setup {
+    /// The puzzle variant this setup solves, to key its cached answers by.
+    ///
+    /// Every field is included, even those which shouldn't change the answers, so that comparing
+    /// them never reuses an answer from another setup.
+    pub fn variant(&self) -> Variant {
+        Variant::new()
+            .param("size", &self.size)
+            .param("backend", &self.backend)
+            .param("brightness", &self.brightness)
+            .param("optimize", &self.optimize)
+            .param("overflow", &self.overflow)
+    }
+}
+
+/// Parse the commands in `input`, making sure each stays within a grid of `size`.
+fn commands(input: &str, size: GridSize) -> Result<Vec<Command>, Error> {
+    let commands: Vec<Command> = parse_str(input)?.collect();
+    for (idx, command) in commands.iter().enumerate() {
+        command
+            .check_range(size, idx + 1)
+            .map_err(|err| Error::parse("commands", err))?;
     }
+    Ok(commands)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let mut map: Map<bool> = Map::new(1000, 1000);
-    for command in parse::<Command>(input)? {
-        command.apply(&mut map);
+/// Follow every command in `input` as `setup` says, and total up the lights: how many are lit, or
+/// their total brightness.
+fn follow<Light>(input: &str, setup: Setup) -> Result<u128, Error>
+where
+    Instruction: ManipulateLight<Light>,
+    Light: Copy + Default + Ord + Into<u64>,
+{
+    Ok(Lights::<Light>::follow(input, setup)?.total())
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    part1_with(input, Setup::default())
+}
+
+/// Count the lights lit after following `input` as `setup` says.
+pub fn part1_with(input: &str, setup: Setup) -> Result<Answer, Error> {
+    Ok(follow::<bool>(input, setup)?.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    part2_with(input, Setup::default())
+}
+
+/// Total the brightness of the lights after following `input` as `setup` says.
+pub fn part2_with(input: &str, setup: Setup) -> Result<Answer, Error> {
+    let total = match setup.brightness {
+        Brightness::U8 => follow::<u8>(input, setup)?,
+        Brightness::U16 => follow::<u16>(input, setup)?,
+        Brightness::U32 => follow::<u32>(input, setup)?,
+    };
+    Ok(total.into())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "\
+turn on 0,0 through 999,999
+toggle 0,0 through 999,0
+turn off 499,499 through 500,500
+",
+        expected: "998996",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: "\
+turn on 0,0 through 0,0
+toggle 0,0 through 999,999
+",
+        expected: "2000001",
+        solver: part2_str,
+    },
+];
+
+/// Summary statistics of a grid of lights.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridStats {
+    /// How many lights have each brightness.
+    pub histogram: BTreeMap<u32, u128>,
+    /// The brightest light and its brightness. Ties go to the first in reading order.
+    pub brightest: Option<(Point, u32)>,
+    /// The brightest row, by `y`, and its total brightness. Ties go to the first.
+    pub brightest_row: Option<(i32, u128)>,
+    /// The brightest column, by `x`, and its total brightness. Ties go to the first.
+    pub brightest_column: Option<(i32, u128)>,
+    /// Total brightness of the whole grid.
+    pub total: u128,
+}
+
+impl GridStats {
+    /// Compute statistics for a grid from its rectangles of lights alike, so that they cost no
+    /// more than the backend holding the lights does.
+    pub fn of<Light>(lights: &Lights<Light>) -> Self
+    where
+        Instruction: ManipulateLight<Light>,
+        Light: Copy + Default + Ord + Into<u64> + Into<u32>,
+    {
+        let mut histogram = BTreeMap::new();
+        let mut rows = BTreeMap::new();
+        let mut columns = BTreeMap::new();
+        for (low, high, light) in lights.rects() {
+            let brightness: u32 = light.into();
+            let width = (high.x - low.x + 1) as u128;
+            let height = (high.y - low.y + 1) as u128;
+            *histogram.entry(brightness).or_default() += width * height;
+            span(&mut rows, low.y, high.y, u128::from(brightness) * width);
+            span(&mut columns, low.x, high.x, u128::from(brightness) * height);
+        }
+
+        GridStats {
+            histogram,
+            brightest: lights
+                .brightest_point()
+                .map(|(point, light)| (point, light.into())),
+            brightest_row: brightest_line(rows),
+            brightest_column: brightest_line(columns),
+            total: lights.total(),
+        }
     }
-    let lit = map.iter().filter(|light| **light).count();
-    println!("{} lit", lit);
-    Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut map: Map<u8> = Map::new(1000, 1000);
-    for command in parse::<Command>(input)? {
-        command.apply(&mut map);
+/// Note that each line from `first` to `last`, inclusive, gains `total` brightness, among the
+/// `changes` to the total brightness of a line from the line before: what each line gains and
+/// loses.
+fn span(changes: &mut BTreeMap<i32, (u128, u128)>, first: i32, last: i32, total: u128) {
+    changes.entry(first).or_default().0 += total;
+    changes.entry(last + 1).or_default().1 += total;
+}
+
+/// Find the line with the maximum total from the `changes` between lines, preferring the lowest
+/// index in case of ties.
+fn brightest_line(changes: BTreeMap<i32, (u128, u128)>) -> Option<(i32, u128)> {
+    let mut total = 0;
+    let mut brightest: Option<(i32, u128)> = None;
+    for (line, (gained, lost)) in changes {
+        total = total + gained - lost;
+        match brightest {
+            Some((_, best)) if best >= total => {}
+            _ => brightest = Some((line, total)),
+        }
     }
-    let brightness = map.iter().map(|light| *light as u64).sum::<u64>();
-    println!("brightness: {}", brightness);
-    Ok(())
+    brightest
+}
+
+/// Statistics about the brightness grid of part 2, once the commands in `input` are followed as
+/// `setup` says.
+pub fn stats(input: &str, setup: Setup) -> Result<GridStats, Error> {
+    Ok(match setup.brightness {
+        Brightness::U8 => GridStats::of(&Lights::<u8>::follow(input, setup)?),
+        Brightness::U16 => GridStats::of(&Lights::<u16>::follow(input, setup)?),
+        Brightness::U32 => GridStats::of(&Lights::<u32>::follow(input, setup)?),
+    })
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+/// Report every malformed line of `input`.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    util::lint::lines::<Command>(input, "turn on 0,0 through 999,999")
 }
 
 #[cfg(test)]
@@ -146,7 +666,7 @@ mod tests {
                     panic!()
                 }
             };
-            command.apply(&mut $map);
+            command.apply(&mut $map, Overflow::Error).unwrap();
         };
     }
 
@@ -164,7 +684,7 @@ mod tests {
             };
         }
 
-        let mut lts: Map<bool> = Map::new(1000, 1000);
+        let mut lts: Map<bool> = Map::new(GRID_SIZE, GRID_SIZE);
 
         expect!(0, lts);
 
@@ -193,7 +713,7 @@ mod tests {
             };
         }
 
-        let mut lts: Map<u8> = Map::new(1000, 1000);
+        let mut lts: Map<u8> = Map::new(GRID_SIZE, GRID_SIZE);
 
         expect!(0, lts);
 
@@ -203,4 +723,323 @@ mod tests {
         apply!("toggle 0,0 through 999,999", lts);
         expect!(2000001, lts);
     }
+
+    const BACKENDS: &[Backend] = &[Backend::Map, Backend::Intervals, Backend::Sparse];
+
+    #[test]
+    fn test_backends_agree() {
+        let input = "\
+turn on 0,0 through 999,999
+toggle 0,0 through 999,0
+turn off 499,499 through 500,500
+toggle 250,100 through 740,560
+turn on 300,900 through 999,950
+turn off 720,0 through 720,999
+";
+        let setups: Vec<_> = BACKENDS
+            .iter()
+            .flat_map(|&backend| {
+                vec![false, true].into_iter().map(move |optimize| Setup {
+                    backend,
+                    optimize,
+                    ..Setup::default()
+                })
+            })
+            .collect();
+        for &setup in &setups {
+            assert_eq!(
+                part1_with(EXAMPLES[0].input, setup).unwrap(),
+                Answer::Int(998996)
+            );
+            assert_eq!(
+                part2_with(EXAMPLES[1].input, setup).unwrap(),
+                Answer::Int(2000001)
+            );
+        }
+        for &setup in &setups[1..] {
+            assert_eq!(
+                part1_with(input, setups[0]).unwrap(),
+                part1_with(input, setup).unwrap()
+            );
+            assert_eq!(
+                part2_with(input, setups[0]).unwrap(),
+                part2_with(input, setup).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_huge_grid() {
+        let size: GridSize = "1000000000x1000000000".parse().unwrap();
+        let input = "\
+turn on 0,0 through 999999999,999999999
+toggle row 0
+brighten 1000 column 999999999
+turn off 499999999,499999999 through 500000000,500000000
+";
+        for &backend in &[Backend::Intervals, Backend::Sparse] {
+            let setup = Setup {
+                size,
+                backend,
+                brightness: Brightness::U32,
+                ..Setup::default()
+            };
+            assert_eq!(
+                part1_with(input, setup).unwrap(),
+                Answer::Int(999_999_998_999_999_997)
+            );
+            assert_eq!(
+                part2_with(input, setup).unwrap(),
+                Answer::Int(1_000_001_001_999_999_996)
+            );
+        }
+    }
+
+    #[test]
+    fn test_setup() {
+        let size: GridSize = "10x4".parse().unwrap();
+        assert_eq!(size.to_string(), "10x4");
+        assert_eq!(GridSize::default().to_string(), "1000x1000");
+        let input = "turn on 0,0 through 9,3\ntoggle 5,0 through 9,0\n";
+        for &backend in BACKENDS {
+            let setup = Setup {
+                size,
+                backend,
+                brightness: Brightness::U32,
+                ..Setup::default()
+            };
+            assert_eq!(part1_with(input, setup).unwrap(), Answer::Int(35));
+            assert_eq!(part2_with(input, setup).unwrap(), Answer::Int(50));
+        }
+        assert_eq!("u16".parse::<Brightness>().unwrap(), Brightness::U16);
+    }
+
+    #[test]
+    fn test_setup_variant() {
+        assert!(Setup::default().variant().is_usual());
+        let setups = [
+            Setup::default(),
+            Setup {
+                size: "10x4".parse().unwrap(),
+                ..Setup::default()
+            },
+            Setup {
+                backend: Backend::Sparse,
+                ..Setup::default()
+            },
+            Setup {
+                optimize: true,
+                ..Setup::default()
+            },
+            Setup {
+                overflow: Overflow::Wrap,
+                brightness: Brightness::U8,
+                ..Setup::default()
+            },
+            Setup {
+                overflow: Overflow::Wrap,
+                brightness: Brightness::U16,
+                ..Setup::default()
+            },
+        ];
+        let keys: std::collections::HashSet<_> = setups
+            .iter()
+            .map(|setup| setup.variant().to_string())
+            .collect();
+        assert_eq!(keys.len(), setups.len());
+        assert_eq!(
+            setups[4].variant().to_string(),
+            "brightness=u8,overflow=wrap"
+        );
+    }
+
+    #[test]
+    fn test_brighter_than_u8() {
+        let input = "toggle 0,0 through 1,1\n".repeat(200);
+        let setup = Setup {
+            brightness: Brightness::U16,
+            ..Setup::default()
+        };
+        assert_eq!(part2_with(&input, setup).unwrap(), Answer::Int(1600));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = "toggle 0,0 through 1,1\n".repeat(200);
+        let bright = "\
+set brightness 255 5,5 through 6,6
+set brightness 255 2,8 through 2,8
+turn on 0,0 through 9,9
+";
+        let too_bright = |input: &str, setup: Setup| {
+            let err = part2_with(input, setup).unwrap_err();
+            std::error::Error::source(&err).unwrap().to_string()
+        };
+        for &backend in BACKENDS {
+            let setup = |overflow| Setup {
+                backend,
+                brightness: Brightness::U8,
+                overflow,
+                ..Setup::default()
+            };
+            assert_eq!(
+                too_bright(&input, setup(Overflow::Error)),
+                "command 128 makes light 0,0 brighter than it can get"
+            );
+            assert_eq!(
+                too_bright(bright, setup(Overflow::Error)),
+                "command 3 makes light 5,5 brighter than it can get"
+            );
+            assert_eq!(
+                part2_with(&input, setup(Overflow::Saturate)).unwrap(),
+                Answer::Int(4 * 255)
+            );
+            assert_eq!(
+                part2_with(&input, setup(Overflow::Wrap)).unwrap(),
+                Answer::Int(4 * (400 % 256))
+            );
+        }
+        // the default brightness is wide enough
+        assert_eq!(part2_str(&input).unwrap(), Answer::Int(1600));
+        assert_eq!("saturate".parse::<Overflow>().unwrap(), Overflow::Saturate);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let setup = Setup {
+            size: "10x10".parse().unwrap(),
+            ..Setup::default()
+        };
+        let err =
+            part1_with("turn on 0,0 through 9,9\ntoggle 5,5 through 10,2\n", setup).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parsing commands: command 2 reaches 10,2, outside the 10x10 grid"
+        );
+        assert!(part2_str("turn off -1,0 through 0,0").is_err());
+    }
+
+    #[test]
+    fn test_new_instructions() {
+        let mut lts: Map<u16> = Map::new(4, 3);
+        apply!("set brightness 300 0,0 through 3,2", lts);
+        apply!("dim 100 row 1", lts);
+        apply!("brighten 5 column 3", lts);
+        apply!("dim 500 1,0 through 1,0", lts);
+        assert_eq!(lts[Point::new(0, 0)], 300);
+        assert_eq!(lts[Point::new(1, 0)], 0);
+        assert_eq!(lts[Point::new(2, 1)], 200);
+        assert_eq!(lts[Point::new(3, 1)], 205);
+        assert_eq!(lts.iter().map(|&light| light as u64).sum::<u64>(), 2915);
+
+        let mut lts: Map<bool> = Map::new(4, 3);
+        apply!("brighten 2 row 0", lts);
+        apply!("dim 1 column 0", lts);
+        apply!("set brightness 0 3,0 through 3,0", lts);
+        assert_eq!(lts.iter().filter(|light| **light).count(), 2);
+
+        let input = "brighten 3 row 999\ntoggle column 0\ndim 2 0,0 through 999,999\n";
+        for &backend in BACKENDS {
+            let setup = Setup {
+                backend,
+                ..Setup::default()
+            };
+            assert_eq!(part1_with(input, setup).unwrap(), Answer::Int(0));
+            assert_eq!(part2_with(input, setup).unwrap(), Answer::Int(1002));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| s.parse::<Command>().unwrap_err();
+        assert_eq!(
+            err("blink 0,0 through 1,1"),
+            ParseCommandError {
+                span: 0..5,
+                message: "unknown instruction 'blink'".into(),
+            }
+        );
+        assert_eq!(err("  through 0,0").span, 2..9);
+        assert_eq!(
+            err("toggle 0,0 past 1,1").to_string(),
+            "unexpected 'past' at 11..15"
+        );
+        assert_eq!(
+            err("dim -3 row 2").to_string(),
+            "brightness '-3' isn't a whole number from 0 to 4294967295 at 4..6"
+        );
+        assert!(err("turn on row")
+            .message
+            .starts_with("unexpected end of command"));
+        assert!(lint("toggle row 5\nflash row 2\n")[0]
+            .to_string()
+            .starts_with("line 2: unknown instruction 'flash' at 0..5"));
+        assert_eq!(
+            "blink".parse::<Region>().unwrap_err().to_string(),
+            "unknown region 'blink' at 0..5"
+        );
+        for region in &["1,2 through 3,4", "row 5", "column -1"] {
+            assert_eq!(region.parse::<Region>().unwrap().to_string(), *region);
+        }
+    }
+
+    #[test]
+    fn test_row_out_of_range() {
+        let setup = Setup {
+            size: "10x10".parse().unwrap(),
+            ..Setup::default()
+        };
+        assert_eq!(
+            part1_with("toggle row 3\ntoggle column 3\n", setup).unwrap(),
+            Answer::Int(18)
+        );
+        assert!(part1_with("toggle column 10", setup).is_err());
+    }
+
+    #[test]
+    fn test_grid_stats() {
+        let input = "\
+turn on 0,0 through 3,2
+toggle 1,1 through 2,1
+turn on 2,1 through 2,2
+turn off 3,0 through 3,2
+";
+        for backend in [Backend::Map, Backend::Intervals, Backend::Sparse] {
+            let setup = Setup {
+                size: "4x3".parse().unwrap(),
+                backend,
+                brightness: Brightness::U8,
+                ..Setup::default()
+            };
+            let stats = stats(input, setup).unwrap();
+            assert_eq!(stats.total, 15);
+            assert_eq!(stats.brightest, Some((Point::new(2, 1), 4)));
+            assert_eq!(stats.brightest_row, Some((1, 8)));
+            assert_eq!(stats.brightest_column, Some((2, 7)));
+
+            let histogram: Vec<_> = stats.histogram.into_iter().collect();
+            assert_eq!(histogram, vec![(0, 3), (1, 6), (2, 1), (3, 1), (4, 1)]);
+        }
+    }
+
+    #[test]
+    fn test_huge_grid_stats() {
+        let setup = Setup {
+            size: "1000000000x1000000000".parse().unwrap(),
+            backend: Backend::Sparse,
+            ..Setup::default()
+        };
+        let input = "turn on 0,0 through 999999999,999999999\ntoggle row 5\n";
+        let stats = stats(input, setup).unwrap();
+        assert_eq!(stats.total, 1_000_000_002_000_000_000);
+        assert_eq!(stats.brightest, Some((Point::new(0, 5), 3)));
+        assert_eq!(stats.brightest_row, Some((5, 3_000_000_000)));
+        assert_eq!(stats.brightest_column, Some((0, 1_000_000_002)));
+
+        let histogram: Vec<_> = stats.histogram.into_iter().collect();
+        assert_eq!(
+            histogram,
+            vec![(1, 999_999_999_000_000_000), (3, 1_000_000_000)]
+        );
+    }
 }
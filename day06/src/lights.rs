@@ -0,0 +1,263 @@
+//! The state of the lights once every command is followed, and queries about it.
+//!
+//! Each backend keeps the lights as rectangles of lights alike, whether single lights, blocks, or
+//! larger rectangles, so every query works the same way on each: by visiting the rectangles, and
+//! weighing each by how much of it the query covers.
+
+use crate::{
+    commands, compact::compact, intervals::Blocks, sparse::Sparse, Backend, Brightness, Error,
+    GridSize, Instruction, ManipulateLight, Region, Setup, TooBright,
+};
+use aoclib::geometry::{Map, Point};
+
+enum State<Light> {
+    Map(Map<Light>),
+    Blocks(Blocks<Light>),
+    Sparse(Sparse<Light>),
+}
+
+/// Every light in a grid, after following the commands with whichever [`Backend`] was chosen.
+///
+/// ```
+/// # use day06::{Lights, Setup};
+/// let input = "turn on 0,0 through 9,9\ntoggle 5,5 through 14,14\n";
+/// let lights: Lights<u8> = Lights::follow(input, Setup::default()).unwrap();
+/// let region = "0,0 through 9,9".parse().unwrap();
+/// assert_eq!(lights.lit_in(region), 100);
+/// assert_eq!(lights.brightness_of(region), 150);
+/// assert_eq!(lights.first_lit_row(), Some(0));
+/// ```
+pub struct Lights<Light> {
+    size: GridSize,
+    state: State<Light>,
+}
+
+impl<Light> Lights<Light>
+where
+    Instruction: ManipulateLight<Light>,
+    Light: Copy + Default + Ord + Into<u64>,
+{
+    /// Follow every command in `input` as `setup` says.
+    pub fn follow(input: &str, setup: Setup) -> Result<Self, Error> {
+        let size = setup.size;
+        let mut commands = commands(input, size)?;
+        if setup.optimize {
+            let compacted = compact::<Light>(&commands, size);
+            tracing::debug!(
+                eliminated = commands.len() - compacted.len(),
+                remaining = compacted.len(),
+                "compacted commands"
+            );
+            commands = compacted;
+        }
+        let state = match setup.backend {
+            Backend::Map => {
+                let mut map: Map<Light> = Map::new(size.width, size.height);
+                for (idx, command) in commands.iter().enumerate() {
+                    command
+                        .apply(&mut map, setup.overflow)
+                        .map_err(|point| TooBright::error(idx + 1, point))?;
+                }
+                State::Map(map)
+            }
+            Backend::Intervals => {
+                let mut blocks: Blocks<Light> = Blocks::new(size, &commands);
+                for (idx, command) in commands.iter().enumerate() {
+                    blocks
+                        .apply(command, setup.overflow)
+                        .map_err(|point| TooBright::error(idx + 1, point))?;
+                }
+                State::Blocks(blocks)
+            }
+            Backend::Sparse => {
+                let mut lights: Sparse<Light> = Sparse::new(size);
+                for (idx, command) in commands.iter().enumerate() {
+                    lights
+                        .apply(command, setup.overflow)
+                        .map_err(|point| TooBright::error(idx + 1, point))?;
+                }
+                State::Sparse(lights)
+            }
+        };
+        Ok(Lights { size, state })
+    }
+
+    /// The total of every light: how many are lit, or their total brightness.
+    pub fn total(&self) -> u128 {
+        match &self.state {
+            State::Map(map) => map.iter().map(|&light| u128::from(light.into())).sum(),
+            State::Blocks(blocks) => blocks.total(),
+            State::Sparse(lights) => lights.total(),
+        }
+    }
+
+    /// Each rectangle of lights alike, as its least and greatest corners, inclusive, and the state
+    /// of its lights.
+    pub(crate) fn rects(&self) -> Box<dyn '_ + Iterator<Item = (Point, Point, Light)>> {
+        match &self.state {
+            State::Map(map) => Box::new(map.points().map(move |point| (point, point, map[point]))),
+            State::Blocks(blocks) => Box::new(blocks.rects()),
+            State::Sparse(lights) => Box::new(lights.rects()),
+        }
+    }
+
+    /// Sum `value` of each light in `region`. Lights outside the grid count for nothing.
+    fn sum_in(&self, region: Region, value: impl Fn(Light) -> u128) -> u128 {
+        let (min, max) = region.corners(self.size);
+        self.rects()
+            .map(|(low, high, light)| {
+                let width = i64::from(high.x.min(max.x)) - i64::from(low.x.max(min.x)) + 1;
+                let height = i64::from(high.y.min(max.y)) - i64::from(low.y.max(min.y)) + 1;
+                if width > 0 && height > 0 {
+                    value(light) * width as u128 * height as u128
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// How many lights in `region` are lit: for brightness, how many are brighter than 0.
+    pub fn lit_in(&self, region: Region) -> u128 {
+        self.sum_in(region, |light| (light != Light::default()).into())
+    }
+
+    /// The total brightness of the lights in `region`: for lights only on or off, how many are on.
+    pub fn brightness_of(&self, region: Region) -> u128 {
+        self.sum_in(region, |light| u128::from(light.into()))
+    }
+
+    /// The brightest light and its brightness. Ties go to the first in reading order.
+    ///
+    /// `None` only if the grid holds no lights.
+    pub fn brightest_point(&self) -> Option<(Point, Light)> {
+        // the first light of a rectangle in reading order is its least corner
+        self.rects()
+            .map(|(low, _, light)| (low, light))
+            .min_by_key(|&(point, light)| (std::cmp::Reverse(light), point.y, point.x))
+    }
+
+    /// The first row, by `y`, with any light lit, unless none is.
+    pub fn first_lit_row(&self) -> Option<i32> {
+        self.rects()
+            .filter(|&(_, _, light)| light != Light::default())
+            .map(|(low, _, _)| low.y)
+            .min()
+    }
+}
+
+/// [`query`], with brightness of type `Light`.
+fn query_with<Light>(input: &str, setup: Setup, regions: &[Region]) -> Result<Vec<String>, Error>
+where
+    Instruction: ManipulateLight<Light>,
+    Light: Copy + Default + Ord + Into<u64> + std::fmt::Display,
+{
+    let lit = Lights::<bool>::follow(input, setup)?;
+    let bright = Lights::<Light>::follow(input, setup)?;
+    let mut lines: Vec<String> = regions
+        .iter()
+        .map(|&region| {
+            format!(
+                "{}: {} lit, brightness {}",
+                region,
+                lit.lit_in(region),
+                bright.brightness_of(region)
+            )
+        })
+        .collect();
+    if let Some((point, brightness)) = bright.brightest_point() {
+        lines.push(format!(
+            "brightest light: {},{} ({})",
+            point.x, point.y, brightness
+        ));
+    }
+    lines.push(match lit.first_lit_row() {
+        Some(y) => format!("first lit row: {}", y),
+        None => "first lit row: none".into(),
+    });
+    Ok(lines)
+}
+
+/// Answer queries about each of `regions` once the commands in `input` are followed as `setup`
+/// says, one line each, followed by the brightest light and the first lit row.
+///
+/// Lights lit are those of part 1, and brightness that of part 2.
+pub fn query(input: &str, setup: Setup, regions: &[Region]) -> Result<Vec<String>, Error> {
+    match setup.brightness {
+        Brightness::U8 => query_with::<u8>(input, setup, regions),
+        Brightness::U16 => query_with::<u16>(input, setup, regions),
+        Brightness::U32 => query_with::<u32>(input, setup, regions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+turn on 0,0 through 9,9
+toggle 2,3 through 6,7
+brighten 4 row 8
+turn off column 0
+";
+
+    fn setups() -> impl Iterator<Item = Setup> {
+        [Backend::Map, Backend::Intervals, Backend::Sparse]
+            .iter()
+            .map(|&backend| Setup {
+                size: "12x10".parse().unwrap(),
+                backend,
+                ..Setup::default()
+            })
+    }
+
+    #[test]
+    fn test_queries_agree() {
+        let region = |s: &str| s.parse::<Region>().unwrap();
+        for setup in setups() {
+            let lit: Lights<bool> = Lights::follow(INPUT, setup).unwrap();
+            assert_eq!(lit.total(), 67);
+            assert_eq!(lit.lit_in(region("0,0 through 11,9")), 67);
+            assert_eq!(lit.lit_in(region("6,7 through 2,3")), 0);
+            assert_eq!(lit.lit_in(region("row 8")), 11);
+            assert_eq!(lit.lit_in(region("5,5 through 20,20")), 21);
+            assert_eq!(lit.first_lit_row(), Some(0));
+            assert_eq!(lit.brightest_point(), Some((Point::new(1, 0), true)));
+
+            let bright: Lights<u8> = Lights::follow(INPUT, setup).unwrap();
+            assert_eq!(bright.lit_in(region("2,3 through 6,7")), 25);
+            assert_eq!(bright.brightness_of(region("2,3 through 6,7")), 75);
+            assert_eq!(bright.brightness_of(region("column 0")), 4);
+            assert_eq!(bright.brightness_of(region("row 8")), 57);
+            assert_eq!(bright.brightest_point(), Some((Point::new(1, 8), 5)));
+            assert_eq!(bright.total(), 188);
+        }
+    }
+
+    #[test]
+    fn test_dark_grid() {
+        for setup in setups() {
+            let lights: Lights<u8> = Lights::follow("turn off row 3\n", setup).unwrap();
+            assert_eq!(lights.first_lit_row(), None);
+            assert_eq!(lights.brightest_point(), Some((Point::new(0, 0), 0)));
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        let setup = Setup {
+            size: "12x10".parse().unwrap(),
+            ..Setup::default()
+        };
+        let regions = ["499,499 through 500,500".parse().unwrap(), Region::Row(8)];
+        assert_eq!(
+            query(INPUT, setup, &regions).unwrap(),
+            vec![
+                "499,499 through 500,500: 0 lit, brightness 0",
+                "row 8: 11 lit, brightness 57",
+                "brightest light: 1,8 (5)",
+                "first lit row: 0",
+            ]
+        );
+    }
+}
@@ -1,52 +1,119 @@
-use aoclib::{config::Config, website::get_input};
-use day06::{part1, part2};
+use day06::{
+    part1_with, part2_with, stats, Backend, Brightness, GridSize, Overflow, Region, Setup,
+};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
 
 const DAY: u8 = 6;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
+
+    /// how to work out the state of the lights: `intervals`, `sparse` for grids too large to hold
+    /// every light, or `map` to update each light in turn
+    #[structopt(long, default_value)]
+    backend: Backend,
+
+    /// the width and height of the grid of lights, like `1000x1000`
+    #[structopt(long, default_value)]
+    size: GridSize,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// the type of each light's brightness in part 2: `u32`, or `u8` or `u16` to save memory
+    #[structopt(long, default_value)]
+    brightness: Brightness,
 
-    /// run part 2
+    /// what to do when a light gets brighter than its type can hold in part 2: `error`,
+    /// `saturate`, or `wrap`
+    #[structopt(long, default_value)]
+    overflow: Overflow,
+
+    /// leave out commands which make no difference before following the rest; `-v` reports how
+    /// many
     #[structopt(long)]
-    part2: bool,
-}
+    optimize: bool,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// report how many lights are lit in part 1 and their brightness in part 2 within a region,
+    /// like `499,499 through 500,500` or `row 3`; may be repeated
+    #[structopt(long, number_of_values = 1)]
+    query: Vec<Region>,
+
+    /// print statistics about the brightness grid from part 2
+    #[structopt(long)]
+    stats: bool,
+
+    /// render the final brightness grid from part 2, in grayscale, to the specified .png or .svg
+    /// path
+    #[structopt(long, parse(from_os_str))]
+    #[cfg(feature = "viz")]
+    render: Option<std::path::PathBuf>,
+
+    /// animate the brightness grid from part 2 to the specified .gif path, one frame per command
+    #[structopt(long, parse(from_os_str))]
+    #[cfg(feature = "viz")]
+    animate: Option<std::path::PathBuf>,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day06::EXAMPLES)?;
+        return Ok(());
+    }
+    let input = args.common.source(DAY)?.buffered()?;
 
-    if !args.no_part1 {
-        part1(&input_path)?;
+    let setup = Setup {
+        size: args.size,
+        backend: args.backend,
+        brightness: args.brightness,
+        optimize: args.optimize,
+        overflow: args.overflow,
+    };
+    let variant = setup.variant();
+    let answers = args.common.run(
+        DAY,
+        &variant.to_string(),
+        &input,
+        |input| part1_with(&util::input::read_all(input)?, setup),
+        |input| part2_with(&util::input::read_all(input)?, setup),
+    )?;
+    if variant.is_usual() {
+        args.common.check(DAY, &answers)?;
+    }
+    if !args.query.is_empty() {
+        let input = input.read_to_string()?;
+        for line in day06::lights::query(&input, setup, &args.query)? {
+            println!("{}", line);
+        }
+    }
+    if args.stats {
+        let stats = stats(&input.read_to_string()?, setup)?;
+        println!("total brightness: {}", stats.total);
+        if let Some((point, brightness)) = stats.brightest {
+            println!("brightest light: {},{} ({})", point.x, point.y, brightness);
+        }
+        if let Some((y, total)) = stats.brightest_row {
+            println!("brightest row: {} ({})", y, total);
+        }
+        if let Some((x, total)) = stats.brightest_column {
+            println!("brightest column: {} ({})", x, total);
+        }
+        println!("histogram:");
+        for (brightness, count) in &stats.histogram {
+            println!("{:>5}: {}", brightness, count);
+        }
+    }
+    #[cfg(feature = "viz")]
+    if let Some(output_path) = args.render {
+        day06::render::render(&input, &output_path, setup)?;
     }
-    if args.part2 {
-        part2(&input_path)?;
+    #[cfg(feature = "viz")]
+    if let Some(output_path) = args.animate {
+        day06::render::animate(&input, &output_path, setup)?;
     }
     Ok(())
 }
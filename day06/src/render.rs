@@ -0,0 +1,138 @@
+use aoclib::geometry::{Map, Point};
+use std::{path::Path, time::Duration};
+use viz::{blend, Animation, Canvas, Format, Rgb, Style, BLACK, WHITE};
+
+use super::{
+    commands, Brightness, Error, GridSize, Instruction, Lights, ManipulateLight, Setup, TooBright,
+};
+use util::InputSource;
+
+pub const FRAME_DURATION: Duration = Duration::from_millis(50);
+
+/// The most lights across or down which can be drawn, at one pixel each.
+pub const MAX_SIDE: usize = u16::MAX as usize;
+
+// one pixel per light
+const STYLE: Style = Style {
+    cell: 1,
+    gap: 0,
+    border: 0,
+    background: BLACK,
+};
+
+/// Shade lights in gray by brightness relative to `max`. Dark lights are left as background.
+fn shade<Light>(max: u32) -> impl Fn(&Light) -> Option<Rgb>
+where
+    Light: Copy + Into<u32>,
+{
+    move |&light| {
+        let brightness: u32 = light.into();
+        if brightness == 0 {
+            None
+        } else {
+            Some(blend(BLACK, WHITE, brightness as f64 / max as f64))
+        }
+    }
+}
+
+fn max_brightness<Light>(map: &Map<Light>) -> u32
+where
+    Light: Copy + Into<u32>,
+{
+    map.iter()
+        .map(|&light| light.into())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Make sure a grid of `size` can be drawn, before holding every light to draw it.
+fn check_size(size: GridSize) -> Result<(), Error> {
+    if size.width > MAX_SIDE || size.height > MAX_SIDE {
+        return Err(Error::TooLarge {
+            algorithm: "rendering",
+            max: MAX_SIDE,
+            what: "lights across or down",
+        });
+    }
+    Ok(())
+}
+
+/// The brightness of each light, once the commands in `input` are followed as `setup` says.
+fn brightness_map<Light>(input: &str, setup: Setup) -> Result<Map<u32>, Error>
+where
+    Instruction: ManipulateLight<Light>,
+    Light: Copy + Default + Ord + Into<u64> + Into<u32>,
+{
+    let mut map = Map::new(setup.size.width, setup.size.height);
+    for (low, high, light) in Lights::<Light>::follow(input, setup)?.rects() {
+        for y in low.y..=high.y {
+            for x in low.x..=high.x {
+                map[Point::new(x, y)] = light.into();
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// Render the brightness grid of part 2, once every command is followed as `setup` says, to
+/// `output`.
+///
+/// The format is chosen by the extension of `output`: `.png` or `.svg`. See [`animate`] for
+/// `.gif`.
+pub fn render(input: &InputSource, output: &Path, setup: Setup) -> Result<(), Error> {
+    check_size(setup.size)?;
+    let input = input.read_to_string()?;
+    let map = match setup.brightness {
+        Brightness::U8 => brightness_map::<u8>(&input, setup)?,
+        Brightness::U16 => brightness_map::<u16>(&input, setup)?,
+        Brightness::U32 => brightness_map::<u32>(&input, setup)?,
+    };
+    let shade = shade(max_brightness(&map));
+    match Format::from_path(output)? {
+        Format::Png => Canvas::from_map(&map, &STYLE, shade).write_png(output)?,
+        Format::Svg => std::fs::write(output, viz::map_svg(&map, &STYLE, shade))?,
+        format => return Err(viz::Error::Unsupported(format).into()),
+    }
+    Ok(())
+}
+
+/// Animate the brightness grid of part 2 to the `.gif` at `output`, one frame per command.
+///
+/// Every command is shown, so the lights are held one by one whichever backend `setup` names.
+pub fn animate(input: &InputSource, output: &Path, setup: Setup) -> Result<(), Error> {
+    let format = Format::from_path(output)?;
+    if format != Format::Gif {
+        return Err(viz::Error::Unsupported(format).into());
+    }
+    check_size(setup.size)?;
+    let input = input.read_to_string()?;
+    match setup.brightness {
+        Brightness::U8 => animate_with::<u8>(&input, output, setup),
+        Brightness::U16 => animate_with::<u16>(&input, output, setup),
+        Brightness::U32 => animate_with::<u32>(&input, output, setup),
+    }
+}
+
+/// [`animate`], with brightness of type `Light`.
+fn animate_with<Light>(input: &str, output: &Path, setup: Setup) -> Result<(), Error>
+where
+    Instruction: ManipulateLight<Light>,
+    Light: Copy + Default + Into<u32>,
+{
+    let size = setup.size;
+    let commands = commands(input, size)?;
+    let (width, height) = STYLE.image_size(size.width, size.height);
+    let mut animation = Animation::create(output, width, height, FRAME_DURATION)?;
+    let mut map: Map<Light> = Map::new(size.width, size.height);
+    // the scale only ever grows, so that lights don't dim when others brighten
+    let mut max = 1;
+    for (idx, command) in commands.iter().enumerate() {
+        command
+            .apply(&mut map, setup.overflow)
+            .map_err(|point| TooBright::error(idx + 1, point))?;
+        max = max.max(max_brightness(&map));
+        animation.push(&Canvas::from_map(&map, &STYLE, shade(max)))?;
+    }
+    animation.hold(&Canvas::from_map(&map, &STYLE, shade(max)), 20)?;
+    Ok(())
+}
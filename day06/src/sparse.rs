@@ -0,0 +1,235 @@
+//! Lights kept as disjoint rectangles covering the grid, each of lights in the same state.
+//!
+//! The grid starts as a single dark rectangle, and following a command splits each rectangle it
+//! partly covers, so the work depends on how the commands overlap, not on how large the grid is: a
+//! grid a billion lights across is no harder than a small one. Totals are sums of areas.
+
+use crate::{Command, GridSize, Instruction, ManipulateLight, Overflow};
+use aoclib::geometry::Point;
+
+/// A rectangle of lights, from the first corner up to but not including the second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x0: i64,
+    y0: i64,
+    x1: i64,
+    y1: i64,
+}
+
+impl Rect {
+    /// The lights `command` covers in a grid of `size`.
+    fn covered_by(command: &Command, size: GridSize) -> Rect {
+        let (min, max) = command.corners(size);
+        Rect {
+            x0: min.x.into(),
+            y0: min.y.into(),
+            x1: i64::from(max.x) + 1,
+            y1: i64::from(max.y) + 1,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.x0 >= self.x1 || self.y0 >= self.y1
+    }
+
+    fn area(&self) -> u128 {
+        (self.x1 - self.x0) as u128 * (self.y1 - self.y0) as u128
+    }
+
+    fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect {
+            x0: self.x0.max(other.x0),
+            y0: self.y0.max(other.y0),
+            x1: self.x1.min(other.x1),
+            y1: self.y1.min(other.y1),
+        };
+        if rect.is_empty() {
+            None
+        } else {
+            Some(rect)
+        }
+    }
+
+    /// The parts of this rectangle outside `other`: at most four rectangles.
+    fn minus(&self, other: &Rect) -> Vec<Rect> {
+        let inside = match self.intersection(other) {
+            Some(inside) => inside,
+            None => return vec![*self],
+        };
+        let parts = [
+            // full-height strips to either side
+            Rect {
+                x1: inside.x0,
+                ..*self
+            },
+            Rect {
+                x0: inside.x1,
+                ..*self
+            },
+            // and what's left above and below
+            Rect {
+                x0: inside.x0,
+                x1: inside.x1,
+                y1: inside.y0,
+                ..*self
+            },
+            Rect {
+                x0: inside.x0,
+                x1: inside.x1,
+                y0: inside.y1,
+                ..*self
+            },
+        ];
+        parts
+            .iter()
+            .copied()
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
+}
+
+/// A grid of lights, kept as disjoint rectangles which cover it.
+pub(crate) struct Sparse<Light> {
+    size: GridSize,
+    rects: Vec<(Rect, Light)>,
+}
+
+impl<Light> Sparse<Light>
+where
+    Light: Default + Clone,
+{
+    /// A grid of `size` dark lights.
+    pub(crate) fn new(size: GridSize) -> Self {
+        let grid = Rect {
+            x0: 0,
+            y0: 0,
+            x1: size.width as i64,
+            y1: size.height as i64,
+        };
+        let rects = if grid.is_empty() {
+            Vec::new()
+        } else {
+            vec![(grid, Light::default())]
+        };
+        Sparse { size, rects }
+    }
+
+    /// Follow `command`, handling overflow as `overflow` says, or return the first light in
+    /// reading order which overflows.
+    pub(crate) fn apply(&mut self, command: &Command, overflow: Overflow) -> Result<(), Point>
+    where
+        Instruction: ManipulateLight<Light>,
+    {
+        let covered = Rect::covered_by(command, self.size);
+        let mut rects = Vec::with_capacity(self.rects.len());
+        // the rectangles aren't in any order, so the first to overflow is only known at the end
+        let mut overflowed: Option<Point> = None;
+        for (rect, light) in self.rects.drain(..) {
+            let inside = match rect.intersection(&covered) {
+                Some(inside) => inside,
+                None => {
+                    rects.push((rect, light));
+                    continue;
+                }
+            };
+            rects.extend(
+                rect.minus(&inside)
+                    .into_iter()
+                    .map(|part| (part, light.clone())),
+            );
+            let mut light = light;
+            if command
+                .instruction
+                .manipulate(&mut light, overflow)
+                .is_err()
+            {
+                let point = Point::new(inside.x0 as i32, inside.y0 as i32);
+                let is_first = match overflowed {
+                    Some(first) => (point.y, point.x) < (first.y, first.x),
+                    None => true,
+                };
+                if is_first {
+                    overflowed = Some(point);
+                }
+            }
+            rects.push((inside, light));
+        }
+        self.rects = rects;
+        match overflowed {
+            Some(point) => Err(point),
+            None => Ok(()),
+        }
+    }
+
+    /// The total of every light: how many are lit, or their total brightness.
+    pub(crate) fn total(&self) -> u128
+    where
+        Light: Copy + Into<u64>,
+    {
+        self.rects
+            .iter()
+            .map(|(rect, light)| rect.area() * u128::from((*light).into()))
+            .sum()
+    }
+
+    /// Each rectangle, as its least and greatest corners, inclusive, and the state of its lights.
+    pub(crate) fn rects(&self) -> impl '_ + Iterator<Item = (Point, Point, Light)>
+    where
+        Light: Copy,
+    {
+        self.rects.iter().map(|&(rect, light)| {
+            (
+                Point::new(rect.x0 as i32, rect.y0 as i32),
+                Point::new(rect.x1 as i32 - 1, rect.y1 as i32 - 1),
+                light,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: i64, y0: i64, x1: i64, y1: i64) -> Rect {
+        Rect { x0, y0, x1, y1 }
+    }
+
+    #[test]
+    fn test_minus() {
+        let outer = rect(0, 0, 4, 4);
+        let parts = outer.minus(&rect(1, 1, 3, 3));
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts.iter().map(Rect::area).sum::<u128>(), 12);
+        assert_eq!(outer.minus(&rect(0, 0, 2, 4)), vec![rect(2, 0, 4, 4)]);
+        assert_eq!(outer.minus(&rect(5, 5, 6, 6)), vec![outer]);
+        assert!(outer.minus(&rect(-1, -1, 5, 5)).is_empty());
+    }
+
+    #[test]
+    fn test_huge_grid() {
+        let size: GridSize = "1000000000x1000000000".parse().unwrap();
+        let commands: Vec<Command> = [
+            "turn on 0,0 through 999999999,999999999",
+            "toggle 0,0 through 999999999,0",
+            "turn off 499999999,499999999 through 500000000,500000000",
+        ]
+        .iter()
+        .map(|command| command.parse().unwrap())
+        .collect();
+        let mut lights: Sparse<bool> = Sparse::new(size);
+        for command in &commands {
+            lights.apply(command, Overflow::Error).unwrap();
+        }
+        assert_eq!(lights.total(), 1_000_000_000 * 999_999_999 - 4);
+
+        let mut lights: Sparse<u32> = Sparse::new(size);
+        for command in &commands {
+            lights.apply(command, Overflow::Error).unwrap();
+        }
+        assert_eq!(
+            lights.total(),
+            1_000_000_000_000_000_000 + 2_000_000_000 - 4
+        );
+    }
+}
@@ -24,11 +24,12 @@
 //!   reason, you'd like to emulate the circuit instead, almost all programming languages (for
 //!   example, C, JavaScript, or Python) provide operators for these gates.
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use lalrpop_util::lalrpop_mod;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::{path::Path, str::FromStr};
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
 lalrpop_mod!(
     #[allow(clippy::all)]
@@ -50,6 +51,14 @@ impl Signal {
             Self::Reference(r) => signals.get(r).copied(),
         }
     }
+
+    /// The wire this signal comes from, unless it's a literal.
+    fn wire(&self) -> Option<&str> {
+        match self {
+            Self::Literal(_) => None,
+            Self::Reference(r) => Some(r),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
@@ -73,6 +82,33 @@ impl Instruction {
             Self::Not(x) => Some(!x.value(signals)?),
         }
     }
+
+    /// The signals this instruction reads.
+    fn operands(&self) -> Vec<&Signal> {
+        match self {
+            Self::Copy(x) | Self::Not(x) => vec![x],
+            Self::And(x, y) | Self::Or(x, y) | Self::LShift(x, y) | Self::RShift(x, y) => {
+                vec![x, y]
+            }
+        }
+    }
+
+    /// The wires this instruction reads from.
+    fn inputs(&self) -> impl Iterator<Item = &str> {
+        self.operands().into_iter().filter_map(Signal::wire)
+    }
+
+    /// The gate this instruction needs, unless it copies a signal straight to the wire.
+    fn gate(&self) -> Option<&'static str> {
+        match self {
+            Self::Copy(_) => None,
+            Self::And(..) => Some("AND"),
+            Self::Or(..) => Some("OR"),
+            Self::LShift(..) => Some("LSHIFT"),
+            Self::RShift(..) => Some("RSHIFT"),
+            Self::Not(_) => Some("NOT"),
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
@@ -81,24 +117,6 @@ pub struct Wire {
     pub(crate) destination: String,
 }
 
-impl Wire {
-    /// Try to apply this wire's value to the signal table.
-    ///
-    /// Return `true` when the application was successful.
-    fn try_apply(&self, signals: &mut Signals) -> bool {
-        if signals.contains_key(&self.destination) {
-            return true;
-        }
-        match self.instruction.value(signals) {
-            Some(value) => {
-                signals.insert(self.destination.clone(), value);
-                true
-            }
-            None => false,
-        }
-    }
-}
-
 impl FromStr for Wire {
     type Err = lalrpop_util::ParseError<usize, String, &'static str>;
 
@@ -110,50 +128,397 @@ impl FromStr for Wire {
     }
 }
 
-pub fn compute_all_signals(mut wires: HashSet<Wire>, mut signals: Signals) -> Signals {
-    let mut pending_wires = HashSet::with_capacity(wires.len());
-    let mut prev_wires_len = 0;
+/// Compute the signal on every wire, given `signals` already known, in dependency order.
+///
+/// Each wire waits on the distinct wires it reads from which have no signal yet, and is evaluated
+/// once the last of those has one. So every wire is evaluated exactly once, in time linear in the
+/// number of wires and connections between them.
+///
+/// Wires left waiting either read from each other in a cycle, reported as
+/// [`Error::CircularDependency`], or from a wire with no source.
+pub fn compute_all_signals(wires: HashSet<Wire>, mut signals: Signals) -> Result<Signals, Error> {
+    // wires already given a signal keep it
+    let wires: Vec<Wire> = wires
+        .into_iter()
+        .filter(|wire| !signals.contains_key(&wire.destination))
+        .collect();
 
-    while wires.len() != prev_wires_len && !wires.is_empty() {
-        prev_wires_len = wires.len();
+    // for each wire without a signal, the indices of the wires which read from it
+    let mut readers: HashMap<&str, Vec<usize>> = HashMap::new();
+    // for each wire, how many of its inputs still have no signal
+    let mut waiting = Vec::with_capacity(wires.len());
+    let mut ready = Vec::new();
+    for (idx, wire) in wires.iter().enumerate() {
+        let mut inputs: Vec<&str> = wire
+            .instruction
+            .inputs()
+            .filter(|input| !signals.contains_key(*input))
+            .collect();
+        inputs.sort_unstable();
+        inputs.dedup();
+        for &input in &inputs {
+            readers.entry(input).or_default().push(idx);
+        }
+        if inputs.is_empty() {
+            ready.push(idx);
+        }
+        waiting.push(inputs.len());
+    }
 
-        for wire in wires.drain() {
-            if !wire.try_apply(&mut signals) {
-                pending_wires.insert(wire);
+    let mut evaluated = 0;
+    while let Some(idx) = ready.pop() {
+        evaluated += 1;
+        let wire = &wires[idx];
+        // each wire should have only one source; if not, the first evaluated wins
+        if signals.contains_key(&wire.destination) {
+            continue;
+        }
+        let value = wire
+            .instruction
+            .value(&signals)
+            .expect("every input of a ready wire has a signal");
+        signals.insert(wire.destination.clone(), value);
+        for &reader in readers.get(wire.destination.as_str()).into_iter().flatten() {
+            waiting[reader] -= 1;
+            if waiting[reader] == 0 {
+                ready.push(reader);
             }
         }
+    }
+    tracing::debug!(
+        evaluated,
+        unresolved = wires.len() - evaluated,
+        "evaluated signals in dependency order"
+    );
 
-        std::mem::swap(&mut wires, &mut pending_wires);
+    if evaluated < wires.len() {
+        return Err(unresolved(&wires, &waiting, &signals));
     }
+    Ok(signals)
+}
 
-    assert_eq!(wires.len(), 0, "failed to compute a signal for every wire");
-    signals
+/// Why some of `wires`, those still `waiting` on inputs, have no signal.
+fn unresolved(wires: &[Wire], waiting: &[usize], signals: &Signals) -> Error {
+    // for each wire left waiting, the wires it reads from which have no signal
+    let mut inputs: HashMap<&str, Vec<&str>> = wires
+        .iter()
+        .zip(waiting)
+        .filter(|(_, &waiting)| waiting > 0)
+        .map(|(wire, _)| (wire.destination.as_str(), Vec::new()))
+        .collect();
+    let mut missing = Vec::new();
+    for (wire, _) in wires
+        .iter()
+        .zip(waiting)
+        .filter(|(_, &waiting)| waiting > 0)
+    {
+        for input in wire.instruction.inputs() {
+            if inputs.contains_key(input) {
+                inputs
+                    .get_mut(wire.destination.as_str())
+                    .expect("every wire left waiting is in the table")
+                    .push(input);
+            } else if !signals.contains_key(input) {
+                missing.push(input);
+            }
+        }
+    }
+    match find_cycle(&inputs) {
+        Some(cycle) => Error::CircularDependency(cycle.into_iter().map(String::from).collect()),
+        None => Error::no_solution(format!(
+            "no signal on wire \"{}\"",
+            missing
+                .into_iter()
+                .min()
+                .expect("wires wait on a cycle or a missing wire")
+        )),
+    }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let wires: HashSet<Wire> = parse(input)?.collect();
-    let signals = Signals::with_capacity(wires.len());
-    let signals = compute_all_signals(wires, signals);
-    println!("value of 'a' wire (pt. 1): {:?}", signals.get("a"));
+/// Find a cycle among wires, given the wires each reads from, as the wires in it in order: each
+/// reading from the next, and the last from the first.
+fn find_cycle<'a>(inputs: &HashMap<&'a str, Vec<&'a str>>) -> Option<Vec<&'a str>> {
+    #[derive(PartialEq, Eq)]
+    enum Visit {
+        OnPath,
+        Done,
+    }
+    let mut visits: HashMap<&str, Visit> = HashMap::new();
+    let mut starts: Vec<&str> = inputs.keys().copied().collect();
+    starts.sort_unstable();
+    for start in starts {
+        if visits.contains_key(start) {
+            continue;
+        }
+        // a depth-first path of wires, each with how many of its inputs have been followed
+        let mut path = vec![(start, 0)];
+        visits.insert(start, Visit::OnPath);
+        while let Some(&(wire, followed)) = path.last() {
+            match inputs[wire].get(followed) {
+                Some(&input) => {
+                    path.last_mut().expect("the path isn't empty").1 += 1;
+                    match visits.get(input) {
+                        Some(Visit::OnPath) => {
+                            let from = path
+                                .iter()
+                                .position(|&(wire, _)| wire == input)
+                                .expect("the input is on the path");
+                            return Some(path[from..].iter().map(|&(wire, _)| wire).collect());
+                        }
+                        Some(Visit::Done) => {}
+                        None => {
+                            visits.insert(input, Visit::OnPath);
+                            path.push((input, 0));
+                        }
+                    }
+                }
+                None => {
+                    visits.insert(wire, Visit::Done);
+                    path.pop();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Describe the circuit `wires` make as a Graphviz DOT graph, with edges following the signals.
+///
+/// Wires are labeled by identifier, and gates by operation. Literal signals get nodes of their
+/// own, labeled by value.
+pub fn circuit_to_dot(wires: &HashSet<Wire>) -> String {
+    // sorted, so that the same circuit always gives the same graph
+    let mut wires: Vec<&Wire> = wires.iter().collect();
+    wires.sort_unstable_by(|a, b| a.destination.cmp(&b.destination));
+    let names: BTreeSet<&str> = wires
+        .iter()
+        .flat_map(|wire| {
+            wire.instruction
+                .inputs()
+                .chain(std::iter::once(wire.destination.as_str()))
+        })
+        .collect();
 
+    let mut dot = String::from("digraph circuit {\n");
+    for name in names {
+        dot.push_str(&format!("    \"{}\";\n", name));
+    }
+    let mut gates = 0;
+    let mut literals = 0;
+    for wire in wires {
+        let target = match wire.instruction.gate() {
+            Some(operation) => {
+                let gate = format!("gate{}", gates);
+                gates += 1;
+                dot.push_str(&format!(
+                    "    {} [label=\"{}\", shape=box];\n",
+                    gate, operation
+                ));
+                gate
+            }
+            None => format!("\"{}\"", wire.destination),
+        };
+        for operand in wire.instruction.operands() {
+            let source = match operand {
+                Signal::Literal(value) => {
+                    let literal = format!("signal{}", literals);
+                    literals += 1;
+                    dot.push_str(&format!(
+                        "    {} [label=\"{}\", shape=plaintext];\n",
+                        literal, value
+                    ));
+                    literal
+                }
+                Signal::Reference(name) => format!("\"{}\"", name),
+            };
+            dot.push_str(&format!("    {} -> {};\n", source, target));
+        }
+        if wire.instruction.gate().is_some() {
+            dot.push_str(&format!("    {} -> \"{}\";\n", target, wire.destination));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Write the circuit in `input` as a Graphviz DOT graph to `output`. See [`circuit_to_dot`].
+pub fn write_dot(input: &str, output: &Path) -> Result<(), Error> {
+    let wires: HashSet<Wire> = parse_str(input)?.collect();
+    std::fs::write(output, circuit_to_dot(&wires))?;
     Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let wires: HashSet<Wire> = parse(input)?.collect();
+fn no_signal() -> Error {
+    Error::no_solution("no signal on wire \"a\"")
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let wires: HashSet<Wire> = parse_str(input)?.collect();
+    let signals = Signals::with_capacity(wires.len());
+    let signals = compute_all_signals(wires, signals)?;
+    let a_value = signals.get("a").ok_or_else(no_signal)?;
+    Ok((*a_value).into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let wires: HashSet<Wire> = parse_str(input)?.collect();
     let signals = Signals::with_capacity(wires.len());
-    let signals = compute_all_signals(wires.clone(), signals);
-    let a_value = signals["a"];
+    let signals = compute_all_signals(wires.clone(), signals)?;
+    let a_value = *signals.get("a").ok_or_else(no_signal)?;
     let mut signals = Signals::with_capacity(wires.len());
     signals.insert("b".to_string(), a_value);
-    let signals = compute_all_signals(wires, signals);
-    println!("value of 'a' wire (pt. 2): {:?}", signals.get("a"));
+    let signals = compute_all_signals(wires, signals)?;
+    let a_value = signals.get("a").ok_or_else(no_signal)?;
+    Ok((*a_value).into())
+}
 
-    Ok(())
+pub const EXAMPLES: &[Example] = &[Example {
+    part: 1,
+    input: "\
+123 -> x
+456 -> y
+x AND y -> d
+x OR y -> e
+x LSHIFT 2 -> f
+y RSHIFT 2 -> g
+NOT x -> h
+NOT y -> i
+",
+    expected: "d: 72, e: 507, f: 492, g: 114, h: 65412, i: 65079, x: 123, y: 456",
+    solver: part1_example,
+}];
+
+/// The example circuit has no wire `a`, so report the signal on every wire instead.
+fn part1_example(input: &str) -> Result<Answer, Error> {
+    let wires: HashSet<Wire> = parse_str(input)?.collect();
+    let signals = compute_all_signals(wires, Signals::new())?;
+    let mut signals: Vec<_> = signals.into_iter().collect();
+    signals.sort_unstable();
+    let signals: Vec<_> = signals
+        .iter()
+        .map(|(wire, signal)| format!("{}: {}", wire, signal))
+        .collect();
+    Ok(signals.join(", ").into())
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(input: &str, known: Signals) -> Signals {
+        compute_all_signals(parse_str(input).unwrap().collect(), known).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(
+            part1_example(EXAMPLES[0].input).unwrap(),
+            EXAMPLES[0].expected.into()
+        );
+    }
+
+    /// A distinct wire identifier for each `idx`.
+    fn name(idx: usize) -> String {
+        let mut name = String::new();
+        let mut idx = idx + 1;
+        while idx > 0 {
+            idx -= 1;
+            name.push((b'a' + (idx % 26) as u8) as char);
+            idx /= 26;
+        }
+        name
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // each wire reads from the next, so the wires come in the reverse of dependency order
+        let mut input: String = (0..1000)
+            .map(|idx| format!("{1} AND {1} -> {0}\n", name(idx), name(idx + 1)))
+            .collect();
+        input.push_str(&format!("12345 -> {}\n", name(1000)));
+        let signals = signals(&input, Signals::new());
+        assert_eq!(signals.len(), 1001);
+        assert_eq!(signals["a"], 12345);
+    }
+
+    #[test]
+    fn test_unresolved() {
+        let err = |input: &str| {
+            compute_all_signals(parse_str(input).unwrap().collect(), Signals::new()).unwrap_err()
+        };
+        assert!(matches!(
+            err("y AND 1 -> x\nNOT x -> y\nx -> a\n5 -> c\n"),
+            Error::CircularDependency(cycle) if cycle == ["x", "y"]
+        ));
+        assert!(matches!(
+            err("a -> a\n"),
+            Error::CircularDependency(cycle) if cycle == ["a"]
+        ));
+        assert_eq!(
+            err("q OR 1 -> a\na -> b\n").to_string(),
+            "no signal on wire \"q\""
+        );
+        // a missing wire doesn't hide a cycle
+        assert!(matches!(
+            err("q OR z -> a\nNOT a -> z\n"),
+            Error::CircularDependency(cycle) if cycle == ["a", "z"]
+        ));
+    }
+
+    #[test]
+    fn test_dot() {
+        let wires: HashSet<Wire> =
+            parse_str("NOT x -> h\n123 -> x\nx AND y -> d\nx LSHIFT 2 -> f\n")
+                .unwrap()
+                .collect();
+        assert_eq!(
+            circuit_to_dot(&wires),
+            r#"digraph circuit {
+    "d";
+    "f";
+    "h";
+    "x";
+    "y";
+    gate0 [label="AND", shape=box];
+    "x" -> gate0;
+    "y" -> gate0;
+    gate0 -> "d";
+    gate1 [label="LSHIFT", shape=box];
+    "x" -> gate1;
+    signal0 [label="2", shape=plaintext];
+    signal0 -> gate1;
+    gate1 -> "f";
+    gate2 [label="NOT", shape=box];
+    "x" -> gate2;
+    gate2 -> "h";
+    signal1 [label="123", shape=plaintext];
+    signal1 -> "x";
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_known_signals() {
+        let mut known = Signals::new();
+        known.insert("b".to_string(), 7);
+        let signals = signals("3 -> b\nb LSHIFT 1 -> a\n", known);
+        assert_eq!(signals["a"], 14);
+    }
 }
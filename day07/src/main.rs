@@ -1,5 +1,4 @@
-use aoclib::{config::Config, website::get_input};
-use day07::{part1, part2};
+use day07::{part1_from_reader, part2_from_reader, write_dot};
 
 use color_eyre::eyre::Result;
 use std::path::PathBuf;
@@ -9,44 +8,31 @@ const DAY: u8 = 7;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
-
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
-
-    /// run part 2
-    #[structopt(long)]
-    part2: bool,
-}
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// write the circuit as a Graphviz DOT graph of its gates and wires to the specified path
+    #[structopt(long, parse(from_os_str))]
+    dot: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day07::EXAMPLES)?;
+        return Ok(());
     }
-    if args.part2 {
-        part2(&input_path)?;
+    // buffered, so that standard input can be read both for the graph and for solving
+    let input = args.common.source(DAY)?.buffered()?;
+    // before solving, so that circuits without a solution can still be drawn
+    if let Some(output_path) = &args.dot {
+        write_dot(&input.read_to_string()?, output_path)?;
     }
+    let answers = args
+        .common
+        .run(DAY, "", &input, part1_from_reader, part2_from_reader)?;
+    args.common.check(DAY, &answers)?;
     Ok(())
 }
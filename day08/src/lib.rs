@@ -1,7 +1,8 @@
-use aoclib::parse;
+use aoclib::input::parse_str;
 
 use std::{path::Path, unreachable};
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 enum State {
@@ -13,13 +14,16 @@ enum State {
 }
 
 fn unescape(s: &str) -> Result<String, Error> {
+    let malformed = |reason| Error::parse(format!("{:?}", s), reason);
     let mut state = State::ExpectInitalQuote;
     let mut out = String::with_capacity(s.len());
 
     for ch in s.chars() {
         match (&mut state, ch) {
             (State::ExpectInitalQuote, '"') => state = State::Normal,
-            (State::ExpectInitalQuote, _) => return Err(Error::NoLeadingQuote),
+            (State::ExpectInitalQuote, _) => {
+                return Err(malformed("string did not begin with a quote char"))
+            }
             (State::Normal, '"') => state = State::OutsideQuotes,
             (State::Normal, '\\') => state = State::Escape,
             (State::Normal, _) => out.push(ch),
@@ -29,25 +33,27 @@ fn unescape(s: &str) -> Result<String, Error> {
                     state = State::Normal;
                 }
                 'x' => state = State::CollectHex(String::with_capacity(2)),
-                _ => return Err(Error::UnexpectedEscapedChar(ch)),
+                _ => return Err(malformed(&format!("unexpected escaped character '{}'", ch))),
             },
             (State::CollectHex(ref mut hex), _) => match hex.len() {
                 0 => hex.push(ch),
                 1 => {
                     hex.push(ch);
                     let n = u8::from_str_radix(hex, 16)
-                        .map_err(|err| Error::BadHexEscape(err, hex.clone()))?;
+                        .map_err(|err| Error::parse(format!("hex escape \"{}\"", hex), err))?;
                     out.push(n.into());
                     state = State::Normal;
                 }
                 _ => unreachable!(),
             },
-            (State::OutsideQuotes, _) => return Err(Error::CharsAfterTrailingQuote),
+            (State::OutsideQuotes, _) => {
+                return Err(malformed("string contains chars past the final quote char"))
+            }
         }
     }
 
     if state != State::OutsideQuotes {
-        return Err(Error::NoTrailingQuote);
+        return Err(malformed("string did not end with a quote char"));
     }
 
     Ok(out)
@@ -69,27 +75,63 @@ fn escape(s: &str) -> String {
     out
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
     let mut total_unescaped_len = 0;
-    for s in parse::<String>(input)? {
+    for s in parse_str::<String>(input)? {
         total_unescaped_len += s.chars().count() - unescape(&s)?.chars().count();
     }
-    println!("total unescaped len: {}", total_unescaped_len);
-    Ok(())
+    Ok(total_unescaped_len.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
     let mut total_escaped_len = 0;
-    for s in parse::<String>(input)? {
+    for s in parse_str::<String>(input)? {
         total_escaped_len += escape(&s).chars().count() - s.chars().count();
     }
-    println!("total escaped len: {}", total_escaped_len);
-    Ok(())
+    Ok(total_escaped_len.into())
 }
 
-pub fn roundtrip_input(input: &Path) -> Result<(), Error> {
+const EXAMPLE_INPUT: &str = r#"""
+"abc"
+"aaa\"aaa"
+"\x27"
+"#;
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE_INPUT,
+        expected: "12",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE_INPUT,
+        expected: "19",
+        solver: part2_str,
+    },
+];
+
+pub fn roundtrip_input(input: &InputSource) -> Result<(), Error> {
     let mut output = false;
-    for input in parse::<String>(input)? {
+    let text = input.read_to_string()?;
+    for input in parse_str::<String>(&text)? {
         let escaped = escape(&input);
         let unescaped = match unescape(&escaped) {
             Ok(unescaped) => unescaped,
@@ -122,22 +164,6 @@ pub fn roundtrip_input(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("string did not begin with a quote char")]
-    NoLeadingQuote,
-    #[error("string did not end with a quote char")]
-    NoTrailingQuote,
-    #[error("string contains chars past the final quote char")]
-    CharsAfterTrailingQuote,
-    #[error("unexpected escaped character '{0}'")]
-    UnexpectedEscapedChar(char),
-    #[error("failed to parse \"{1}\" as integer.")]
-    BadHexEscape(#[source] std::num::ParseIntError, String),
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,59 +1,36 @@
-use aoclib::{config::Config, website::get_input};
-use day08::{part1, part2};
+use day08::{part1_from_reader, part2_from_reader};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
 
 const DAY: u8 = 8;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
-
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
-
-    /// run part 2
-    #[structopt(long)]
-    part2: bool,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
     /// run a roundtrip test on the input
     #[structopt(long)]
     roundtrip: bool,
 }
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
-}
-
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
-    }
-    if args.part2 {
-        part2(&input_path)?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day08::EXAMPLES)?;
+        return Ok(());
     }
+    let input = args.common.source(DAY)?.buffered()?;
+
+    let answers = args
+        .common
+        .run(DAY, "", &input, part1_from_reader, part2_from_reader)?;
+    args.common.check(DAY, &answers)?;
     if args.roundtrip {
-        day08::roundtrip_input(&input_path)?;
+        day08::roundtrip_input(&input)?;
     }
     Ok(())
 }
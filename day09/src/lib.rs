@@ -7,12 +7,24 @@
 //! locations he wants, but he must visit each location exactly once. What is the shortest distance
 //! he can travel to achieve this?
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use permutohedron::heap_recursive;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use thiserror::Error;
+pub use util::Error;
+use util::{
+    algo::{self, Backend},
+    example::Example,
+    lint::Diagnostic,
+    Answer, InputSource,
+};
+
+/// Trying every ordering suits inputs with at most this many places.
+const MAX_PERMUTED_PLACES: usize = 9;
+
+/// Held-Karp keeps a table of `2^n n` distances, so it can't cope with many more places than this.
+const MAX_HELD_KARP_PLACES: usize = 20;
 
 #[derive(Debug)]
 pub struct Route {
@@ -55,15 +67,19 @@ impl std::iter::FromIterator<Edge> for Routes {
     }
 }
 
+impl std::str::FromStr for Routes {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(parse_str(input)?.collect())
+    }
+}
+
 impl Routes {
-    fn find_extreme(&self, order: Ordering, default_dist: u32) -> Route {
+    /// Visit every ordering of the places, along with its total distance.
+    fn for_each_route(&self, mut visit: impl FnMut(&[&String], u32)) {
         let mut places: Vec<_> = self.places.iter().collect();
 
-        let mut route = Route {
-            stops: Vec::new(),
-            dist: default_dist,
-        };
-
         heap_recursive(&mut places, |ordering| {
             let this_dist: u32 = ordering
                 .windows(2)
@@ -74,10 +90,18 @@ impl Routes {
                 })
                 .sum();
 
-            if this_dist.cmp(&route.dist) == order {
-                route.stops = ordering.iter().map(|&s| s.clone()).collect::<Vec<_>>();
-                route.dist = this_dist;
-            }
+            visit(ordering, this_dist);
+        });
+    }
+
+    fn find_extreme(&self, order: Ordering, default_dist: u32) -> Route {
+        let mut route = Route {
+            stops: Vec::new(),
+            dist: default_dist,
+        };
+
+        self.for_each_route(|ordering, this_dist| {
+            route.consider(ordering, this_dist, order);
         });
 
         route
@@ -90,26 +114,254 @@ impl Routes {
     pub fn find_longest(&self) -> Route {
         self.find_extreme(Ordering::Greater, 0)
     }
+
+    /// Find both the shortest and the longest routes, in that order, in a single traversal.
+    pub fn find_extremes(&self) -> (Route, Route) {
+        let mut shortest = Route {
+            stops: Vec::new(),
+            dist: !0,
+        };
+        let mut longest = Route {
+            stops: Vec::new(),
+            dist: 0,
+        };
+
+        self.for_each_route(|ordering, this_dist| {
+            shortest.consider(ordering, this_dist, Ordering::Less);
+            longest.consider(ordering, this_dist, Ordering::Greater);
+        });
+
+        (shortest, longest)
+    }
+
+    /// Find the distance of the shortest or longest route by dynamic programming over subsets of
+    /// the places (the Held-Karp algorithm).
+    ///
+    /// This takes `O(2^n n^2)` time rather than `O(n!)`, so it copes with many more places than
+    /// trying every ordering, but it finds only the distance. `None` if no route visits every
+    /// place.
+    ///
+    /// Fails with more than 20 places, as the table of partial routes would be too large.
+    pub fn held_karp(&self, order: Ordering) -> Result<Option<u32>, Error> {
+        let places: Vec<_> = self.places.iter().collect();
+        let n = places.len();
+        if n > MAX_HELD_KARP_PLACES {
+            return Err(Error::TooLarge {
+                algorithm: HeldKarp.name(),
+                max: MAX_HELD_KARP_PLACES,
+                what: "places",
+            });
+        }
+        let dists: Vec<Vec<Option<u32>>> = places
+            .iter()
+            .map(|&from| {
+                places
+                    .iter()
+                    .map(|&to| self.dist_map.get(&(from.clone(), to.clone())).copied())
+                    .collect()
+            })
+            .collect();
+
+        // the extreme distance of a route visiting the places in `mask`, ending at `last`, is at
+        // `best[mask * n + last]`
+        let mut best = vec![None; (1 << n) * n];
+        for start in 0..n {
+            best[(1 << start) * n + start] = Some(0);
+        }
+        for mask in 1..1_usize << n {
+            for last in (0..n).filter(|last| mask & (1 << last) != 0) {
+                let dist = match best[mask * n + last] {
+                    Some(dist) => dist,
+                    None => continue,
+                };
+                for next in (0..n).filter(|next| mask & (1 << next) == 0) {
+                    if let Some(step) = dists[last][next] {
+                        let slot = &mut best[(mask | 1 << next) * n + next];
+                        improve(slot, dist + step, order);
+                    }
+                }
+            }
+        }
+
+        let full = (1 << n) - 1;
+        let mut extreme = None;
+        for dist in best[full * n..].iter().flatten() {
+            improve(&mut extreme, *dist, order);
+        }
+        Ok(extreme)
+    }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let routes: Routes = parse(input)?.collect();
-    let shortest = routes.find_shortest();
-    println!("shortest route length: {}", shortest.dist);
-    Ok(())
+/// Replace the distance in `slot` with `candidate` if there is none, or `candidate` compares
+/// as `order`.
+fn improve(slot: &mut Option<u32>, candidate: u32, order: Ordering) {
+    let better = match *slot {
+        Some(dist) => candidate.cmp(&dist) == order,
+        None => true,
+    };
+    if better {
+        *slot = Some(candidate);
+    }
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let routes: Routes = parse(input)?.collect();
-    let longest = routes.find_longest();
-    println!("longest route length: {}", longest.dist);
-    Ok(())
+impl Route {
+    /// Replace this route with the given ordering if its distance compares as `order`.
+    fn consider(&mut self, ordering: &[&String], dist: u32, order: Ordering) {
+        if dist.cmp(&self.dist) == order {
+            self.stops = ordering.iter().map(|&s| s.clone()).collect::<Vec<_>>();
+            self.dist = dist;
+        }
+    }
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+/// Tries every ordering of the places: quickest when there are few of them.
+pub struct Permutations;
+
+impl Backend<Routes> for Permutations {
+    fn name(&self) -> &'static str {
+        "permutations"
+    }
+
+    fn suits(&self, routes: &Routes) -> bool {
+        routes.places.len() <= MAX_PERMUTED_PLACES
+    }
+
+    fn part1(&self, routes: &Routes) -> Result<Answer, Error> {
+        Ok(routes.find_shortest().dist.into())
+    }
+
+    fn part2(&self, routes: &Routes) -> Result<Answer, Error> {
+        Ok(routes.find_longest().dist.into())
+    }
+
+    fn both(&self, routes: &Routes) -> Result<(Answer, Answer), Error> {
+        let (shortest, longest) = routes.find_extremes();
+        Ok((shortest.dist.into(), longest.dist.into()))
+    }
+}
+
+/// Dynamic programming over subsets of the places: copes with many more of them.
+pub struct HeldKarp;
+
+impl Backend<Routes> for HeldKarp {
+    fn name(&self) -> &'static str {
+        "held-karp"
+    }
+
+    fn suits(&self, routes: &Routes) -> bool {
+        // smaller inputs are quicker to permute, and larger ones don't fit in memory
+        routes.places.len() > MAX_PERMUTED_PLACES && routes.places.len() <= MAX_HELD_KARP_PLACES
+    }
+
+    fn part1(&self, routes: &Routes) -> Result<Answer, Error> {
+        let dist = routes.held_karp(Ordering::Less)?.ok_or_else(no_route)?;
+        Ok(dist.into())
+    }
+
+    fn part2(&self, routes: &Routes) -> Result<Answer, Error> {
+        let dist = routes.held_karp(Ordering::Greater)?.ok_or_else(no_route)?;
+        Ok(dist.into())
+    }
+}
+
+/// The algorithms which can find routes, in order of preference.
+///
+/// Permutations come last: with too many places for Held-Karp, they're slow, but they run.
+pub const BACKENDS: &[&dyn Backend<Routes>] = &[&HeldKarp, &Permutations];
+
+fn no_route() -> Error {
+    Error::no_solution("no route visits every place")
+}
+
+/// Parse the routes in `input`, and select the backend to find routes with.
+fn prepare(
+    input: impl std::io::Read,
+    algo: Option<&str>,
+) -> Result<(Routes, &'static dyn Backend<Routes>), Error> {
+    let routes: Routes = util::input::read_all(input)?.parse()?;
+    let backend = algo::select(BACKENDS, &routes, algo)?;
+    Ok((routes, backend))
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    part1_with_algo(input.as_bytes(), None)
+}
+
+/// As [`part1_from_reader`], solving with the backend named `algo` if given.
+pub fn part1_with_algo(input: impl std::io::Read, algo: Option<&str>) -> Result<Answer, Error> {
+    let (routes, backend) = prepare(input, algo)?;
+    backend.part1(&routes)
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    part2_with_algo(input.as_bytes(), None)
+}
+
+/// As [`part2_from_reader`], solving with the backend named `algo` if given.
+pub fn part2_with_algo(input: impl std::io::Read, algo: Option<&str>) -> Result<Answer, Error> {
+    let (routes, backend) = prepare(input, algo)?;
+    backend.part2(&routes)
+}
+
+const EXAMPLE_INPUT: &str = "\
+London to Dublin = 464
+London to Belfast = 518
+Dublin to Belfast = 141
+";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE_INPUT,
+        expected: "605",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE_INPUT,
+        expected: "982",
+        solver: part2_str,
+    },
+];
+
+/// Solve both parts, with a single traversal of the routes when trying every ordering.
+pub fn both(input: &Path) -> Result<(Answer, Answer), Error> {
+    both_from_reader(InputSource::from(input).reader()?)
+}
+
+/// As [`both`], reading the input from `input`.
+pub fn both_from_reader(input: impl std::io::Read) -> Result<(Answer, Answer), Error> {
+    both_with_algo(input, None)
+}
+
+/// As [`both_from_reader`], solving with the backend named `algo` if given.
+pub fn both_with_algo(
+    input: impl std::io::Read,
+    algo: Option<&str>,
+) -> Result<(Answer, Answer), Error> {
+    let (routes, backend) = prepare(input, algo)?;
+    backend.both(&routes)
+}
+
+/// Report every malformed line of `input`.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    util::lint::lines::<Edge>(input, "London to Dublin = 464")
 }
 
 #[cfg(test)]
@@ -187,4 +439,75 @@ mod test {
 
         assert!(fwd || rev);
     }
+
+    #[test]
+    fn test_example_extremes() {
+        let lines = "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141";
+        let routes: Routes = lines
+            .split('\n')
+            .map(|line| line.parse::<Edge>().unwrap())
+            .collect();
+
+        let (shortest, longest) = routes.find_extremes();
+        assert_eq!(shortest.dist, routes.find_shortest().dist);
+        assert_eq!(longest.dist, routes.find_longest().dist);
+        assert_eq!(605, shortest.dist);
+        assert_eq!(982, longest.dist);
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        // a complete graph of seven places, with scrambled distances
+        let routes: Routes = (0..7)
+            .flat_map(|from| (from + 1..7).map(move |to| (from, to)))
+            .map(|(from, to)| Edge {
+                from: format!("place{}", from),
+                to: format!("place{}", to),
+                distance: (from * 37 + to * 101) % 97 + 1,
+            })
+            .collect();
+        assert!(Permutations.suits(&routes));
+        assert_eq!(
+            Permutations.both(&routes).unwrap(),
+            HeldKarp.both(&routes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_select_by_size() {
+        let routes = |n: u32| -> Routes {
+            (1..n)
+                .map(|to| Edge {
+                    from: "hub".into(),
+                    to: to.to_string(),
+                    distance: to,
+                })
+                .collect()
+        };
+        let name = |routes: Routes| algo::select(BACKENDS, &routes, None).unwrap().name();
+        assert_eq!(name(routes(4)), "permutations");
+        assert_eq!(name(routes(12)), "held-karp");
+        assert_eq!(name(routes(40)), "permutations");
+    }
+
+    #[test]
+    fn test_held_karp_too_large() {
+        let routes: Routes = (1..40)
+            .map(|to| Edge {
+                from: "hub".into(),
+                to: to.to_string(),
+                distance: to,
+            })
+            .collect();
+        assert!(!HeldKarp.suits(&routes));
+        let result = HeldKarp.part1(&routes);
+        assert!(matches!(result, Err(Error::TooLarge { max: 20, .. })));
+    }
+
+    #[test]
+    fn test_lint() {
+        let input = "London to Dublin = 464\nLondon to Belfast 518\n\nDublin to Belfast = far\n";
+        let lines: Vec<_> = lint(input).iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![2, 4]);
+    }
 }
@@ -1,52 +1,52 @@
-use aoclib::{config::Config, website::get_input};
-use day09::{part1, part2};
+use day09::{both_with_algo, part1_with_algo, part2_with_algo};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
+use util::{cli::RunArgs, output::OutputFormat, timing::report_line, Timed};
 
 const DAY: u8 = 9;
 
-#[derive(StructOpt, Debug)]
-struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
-
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
-
-    /// run part 2
-    #[structopt(long)]
-    part2: bool,
-}
-
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
-}
-
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
-    }
-    if args.part2 {
-        part2(&input_path)?;
+    args.init_tracing();
+    if args.example {
+        args.run_examples(day09::EXAMPLES)?;
+        return Ok(());
     }
+    let input = args.source(DAY)?;
+    let algo = args.force_algo();
+
+    let answers = if !args.no_part1 && args.part2 {
+        // both parts may come from a single traversal, so they're timed together
+        let timed = Timed::measure(|| both_with_algo(input.reader()?, algo)).transpose()?;
+        let (shortest, longest) = timed.value;
+        if args.output == OutputFormat::Json {
+            // each record carries the duration of the shared traversal
+            for (part, answer) in [(1, &shortest), (2, &longest)].iter() {
+                let answer = Timed {
+                    value: (*answer).clone(),
+                    duration: timed.duration,
+                };
+                args.emit(DAY, *part, &answer);
+            }
+        } else {
+            println!("part 1: {}", shortest);
+            println!("part 2: {}", longest);
+            if args.time {
+                println!("{}", report_line("parts 1 and 2", timed.duration));
+            }
+        }
+        vec![(1, shortest), (2, longest)]
+    } else {
+        args.run(
+            DAY,
+            "",
+            &input,
+            |input| part1_with_algo(input, algo),
+            |input| part2_with_algo(input, algo),
+        )?
+    };
+    args.check(DAY, &answers)?;
     Ok(())
 }
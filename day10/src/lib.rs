@@ -8,9 +8,10 @@
 //! next step. For each step, take the previous value, and replace each run of digits (like `111`)
 //! with the number of digits (`3`) followed by the digit itself (`1`).
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use std::path::Path;
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
 pub fn look_and_say(sequence: &str) -> String {
     if sequence.is_empty() {
@@ -46,31 +47,85 @@ pub fn look_and_say_n(sequence: &str, n: usize) -> String {
     sequence
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for (idx, line) in parse::<String>(input)?.enumerate() {
-        let l_s = look_and_say_n(&line, 40);
-        println!("part 1 line {}: {}", idx, l_s.len());
+/// Look-and-say over a sequence of digit values (not ASCII), avoiding string formatting.
+///
+/// This is an alternative to [`look_and_say`] which exists mainly to be benchmarked against it.
+/// Each count must fit in a single digit, which holds for any input without a run of ten or more.
+pub fn look_and_say_digits(sequence: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(sequence.len() * 2);
+    let mut digits = sequence.iter().peekable();
+    while let Some(&digit) = digits.next() {
+        let mut count = 1;
+        while digits.next_if_eq(&&digit).is_some() {
+            count += 1;
+        }
+        output.push(count);
+        output.push(digit);
     }
-    Ok(())
+    output
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for (idx, line) in parse::<String>(input)?.enumerate() {
-        let l_s = look_and_say_n(&line, 50);
-        println!("part 2 line {}: {}", idx, l_s.len());
+/// As [`look_and_say_n`], operating on digit values via [`look_and_say_digits`].
+pub fn look_and_say_digits_n(sequence: &[u8], n: usize) -> Vec<u8> {
+    let mut sequence = sequence.to_vec();
+    for _ in 0..n {
+        sequence = look_and_say_digits(&sequence);
     }
-    Ok(())
+    sequence
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let line = parse_str::<String>(input)?.next().ok_or(Error::NoInput)?;
+    Ok(look_and_say_n(&line, 40).len().into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let line = parse_str::<String>(input)?.next().ok_or(Error::NoInput)?;
+    Ok(look_and_say_n(&line, 50).len().into())
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    part: 1,
+    input: "1",
+    expected: "312211",
+    solver: part1_example,
+}];
+
+/// The example only looks and says five times, and reports the sequence rather than its length.
+fn part1_example(input: &str) -> Result<Answer, Error> {
+    let line = parse_str::<String>(input)?.next().ok_or(Error::NoInput)?;
+    Ok(look_and_say_n(&line, 5).into())
+}
+
+/// Print each step of the worked example, as the puzzle text does.
+pub fn print_example_steps() {
+    let mut sequence = EXAMPLES[0].input.to_string();
+    for _ in 0..5 {
+        let next = look_and_say(&sequence);
+        println!("{} becomes {}", sequence, next);
+        sequence = next;
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::look_and_say;
+    use super::{look_and_say, look_and_say_digits_n, look_and_say_n};
 
     /// - `1` becomes `11` (1 copy of digit 1).
     /// - `11` becomes `21` (2 copies of digit 1).
@@ -85,4 +140,12 @@ mod tests {
         assert_eq!(look_and_say("1211"), "111221".to_string());
         assert_eq!(look_and_say("111221"), "312211".to_string());
     }
+
+    #[test]
+    fn test_digits_agree() {
+        let expect = look_and_say_n("1113222113", 20);
+        let digits = look_and_say_digits_n(&[1, 1, 1, 3, 2, 2, 2, 1, 1, 3], 20);
+        let digits: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+        assert_eq!(digits, expect);
+    }
 }
@@ -21,9 +21,10 @@
 //! - Passwords must contain at least two different, non-overlapping pairs of letters, like `aa`,
 //!   `bb`, or `zz`.
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use std::{fmt, path::Path};
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
 // low order bytes are stored in low order indices
 #[derive(Clone, Debug)]
@@ -132,29 +133,50 @@ impl Password {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for (idx, mut password) in parse::<Password>(input)?.enumerate() {
-        password.increment_checked();
-        println!("part 1 line {}: {}", idx, password);
-    }
-    Ok(())
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for (idx, mut password) in parse::<Password>(input)?.enumerate() {
-        password.increment_checked();
-        password.increment_checked();
-        println!("part 2 line {}: {}", idx, password);
-    }
-    Ok(())
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let mut password = parse_str::<Password>(input)?.next().ok_or(Error::NoInput)?;
+    password.increment_checked();
+    Ok(password.to_string().into())
 }
 
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let mut password = parse_str::<Password>(input)?.next().ok_or(Error::NoInput)?;
+    password.increment_checked();
+    password.increment_checked();
+    Ok(password.to_string().into())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "abcdefgh",
+        expected: "abcdffaa",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: "ghijklmn",
+        expected: "ghjaabcc",
+        solver: part1_str,
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
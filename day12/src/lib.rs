@@ -1,53 +1,208 @@
-use aoclib::parse;
-use std::path::Path;
-use thiserror::Error;
+use aoclib::input::parse_str;
+use std::{collections::BTreeMap, path::Path};
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let numbers_in: i64 = parse::<serde_json::Value>(input)?
+type Object = serde_json::Map<String, serde_json::Value>;
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let numbers_in: i64 = parse_str::<serde_json::Value>(input)?
         .map(|value| sum_of_numbers_in(&value, &|_| true))
         .sum();
-    println!("numbers in the input: {}", numbers_in);
-    Ok(())
+    Ok(numbers_in.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let numbers_in: i64 = parse::<serde_json::Value>(input)?
-        .map(|value| {
-            sum_of_numbers_in(&value, &|obj| {
-                !obj.values()
-                    .any(|value| value.as_str().map(|s| s == "red").unwrap_or_default())
-            })
-        })
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let numbers_in: i64 = parse_str::<serde_json::Value>(input)?
+        .map(|value| sum_of_numbers_in(&value, &not_red))
         .sum();
-    println!("non-red numbers in the input: {}", numbers_in);
+    Ok(numbers_in.into())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "[1,2,3]",
+        expected: "6",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: r#"{"a":{"b":4},"c":-1}"#,
+        expected: "3",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: r#"[-1,{"a":1}]"#,
+        expected: "0",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: r#"[1,{"c":"red","b":2},3]"#,
+        expected: "4",
+        solver: part2_str,
+    },
+    Example {
+        part: 2,
+        input: r#"{"d":"red","e":[1,2,3,4],"f":5}"#,
+        expected: "0",
+        solver: part2_str,
+    },
+    Example {
+        part: 2,
+        input: r#"[1,"red",5]"#,
+        expected: "6",
+        solver: part2_str,
+    },
+];
+
+/// Print the number-sum of each document, broken down by top-level key and by depth.
+///
+/// When `ignore_red` is set, objects with a `"red"` value are skipped as in part 2.
+pub fn report(input: &InputSource, ignore_red: bool) -> Result<(), Error> {
+    for value in parse_str::<serde_json::Value>(&input.read_to_string()?)? {
+        let report = if ignore_red {
+            SumReport::of(&value, &not_red)
+        } else {
+            SumReport::of(&value, &|_| true)
+        };
+
+        println!("total: {}", report.total);
+        if !report.by_key.is_empty() {
+            println!("by top-level key:");
+            for (key, sum) in &report.by_key {
+                println!("  {:>12}: {}", key, sum);
+            }
+        }
+        println!("by depth:");
+        for (depth, sum) in &report.by_depth {
+            println!("  {:>12}: {}", depth, sum);
+        }
+    }
     Ok(())
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+fn not_red(obj: &Object) -> bool {
+    !obj.values()
+        .any(|value| value.as_str().map(|s| s == "red").unwrap_or_default())
+}
+
+/// Where the numbers in a json document come from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SumReport {
+    /// sum of all numbers in the document
+    pub total: i64,
+    /// sum of the numbers under each key of the top-level object
+    ///
+    /// This is empty unless the document is an object.
+    pub by_key: BTreeMap<String, i64>,
+    /// sum of the numbers at each nesting depth, where the top-level value is at depth 0
+    pub by_depth: BTreeMap<usize, i64>,
+}
+
+impl SumReport {
+    /// Compute the report for `value`, skipping objects for which `filter_objects` is false.
+    pub fn of(value: &serde_json::Value, filter_objects: &dyn Fn(&Object) -> bool) -> SumReport {
+        let mut report = SumReport::default();
+        visit_numbers_in(value, filter_objects, None, 0, &mut |key, depth, n| {
+            report.total += n;
+            if let Some(key) = key {
+                *report.by_key.entry(key.to_string()).or_default() += n;
+            }
+            *report.by_depth.entry(depth).or_default() += n;
+        });
+        report
+    }
 }
 
 /// sum up integers in a json value
 ///
 /// we can represent these as integers, as there are no decimal points in the input
-fn sum_of_numbers_in(
-    value: &serde_json::Value,
-    filter_objects: &dyn Fn(&serde_json::Map<String, serde_json::Value>) -> bool,
-) -> i64 {
-    let sum_inner = |value: &serde_json::Value| sum_of_numbers_in(value, filter_objects);
+fn sum_of_numbers_in(value: &serde_json::Value, filter_objects: &dyn Fn(&Object) -> bool) -> i64 {
+    let mut sum = 0;
+    visit_numbers_in(value, filter_objects, None, 0, &mut |_, _, n| sum += n);
+    sum
+}
 
+/// visit each integer in a json value, along with the top-level key it's under and its depth
+fn visit_numbers_in<'a>(
+    value: &'a serde_json::Value,
+    filter_objects: &dyn Fn(&Object) -> bool,
+    key: Option<&'a str>,
+    depth: usize,
+    visit: &mut dyn FnMut(Option<&'a str>, usize, i64),
+) {
     match value {
-        serde_json::Value::Number(n) => n.as_i64().unwrap_or_default(),
-        serde_json::Value::Array(values) => values.iter().map(sum_inner).sum(),
-        serde_json::Value::Object(object) => {
-            if filter_objects(object) {
-                object.values().map(sum_inner).sum()
-            } else {
-                0
+        serde_json::Value::Number(n) => visit(key, depth, n.as_i64().unwrap_or_default()),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                visit_numbers_in(value, filter_objects, key, depth + 1, visit);
+            }
+        }
+        serde_json::Value::Object(object) if filter_objects(object) => {
+            for (inner_key, value) in object {
+                let key = if depth == 0 {
+                    Some(inner_key.as_str())
+                } else {
+                    key
+                };
+                visit_numbers_in(value, filter_objects, key, depth + 1, visit);
             }
         }
-        _ => 0,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let value: serde_json::Value =
+            serde_json::from_str(r#"{"a":[1,2,{"b":3}],"c":{"d":"red","e":4},"f":5}"#).unwrap();
+
+        let report = SumReport::of(&value, &|_| true);
+        assert_eq!(report.total, 15);
+        assert_eq!(report.total, sum_of_numbers_in(&value, &|_| true));
+        assert_eq!(report.by_key["a"], 6);
+        assert_eq!(report.by_key["c"], 4);
+        assert_eq!(report.by_key["f"], 5);
+        assert_eq!(
+            report.by_depth.into_iter().collect::<Vec<_>>(),
+            vec![(1, 5), (2, 7), (3, 3)]
+        );
+
+        let report = SumReport::of(&value, &not_red);
+        assert_eq!(report.total, 11);
+        assert_eq!(report.by_key.get("c"), None);
+    }
+
+    #[test]
+    fn test_report_array() {
+        let value: serde_json::Value =
+            serde_json::from_str("[1,{\"c\":\"red\",\"b\":2},3]").unwrap();
+        let report = SumReport::of(&value, &not_red);
+        assert_eq!(report.total, 4);
+        assert!(report.by_key.is_empty());
+        assert_eq!(sum_of_numbers_in(&value, &not_red), 4);
     }
 }
@@ -1,52 +1,39 @@
-use aoclib::{config::Config, website::get_input};
-use day12::{part1, part2};
+use day12::{part1_from_reader, part2_from_reader, report};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
 
 const DAY: u8 = 12;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
-
-    /// run part 2
+    /// report number sums by top-level key and by depth instead of solving;
+    /// with --part2, ignore red objects
     #[structopt(long)]
-    part2: bool,
-}
-
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    report: bool,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day12::EXAMPLES)?;
+        return Ok(());
     }
-    if args.part2 {
-        part2(&input_path)?;
+    let input = args.common.source(DAY)?.buffered()?;
+
+    if args.report {
+        report(&input, args.common.part2)?;
+        return Ok(());
     }
+
+    let answers = args
+        .common
+        .run(DAY, "", &input, part1_from_reader, part2_from_reader)?;
+    args.common.check(DAY, &answers)?;
     Ok(())
 }
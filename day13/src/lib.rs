@@ -9,14 +9,26 @@
 //! have a circular table that will be just big enough to fit everyone comfortably, and so each
 //! person will have exactly two neighbors.
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use permutohedron::heap_recursive;
 use std::{
     collections::{HashMap, HashSet},
     iter::FromIterator,
     path::Path,
 };
-use thiserror::Error;
+pub use util::Error;
+use util::{
+    algo::{self, Backend},
+    example::Example,
+    lint::Diagnostic,
+    Answer, InputSource,
+};
+
+/// Trying every seating suits tables of at most this many people, counting yourself.
+const MAX_PERMUTED_PEOPLE: usize = 9;
+
+/// Held-Karp keeps a table of `2^n n` seatings, so it can't cope with many more people than this.
+const MAX_HELD_KARP_PEOPLE: usize = 20;
 
 #[derive(Clone, Copy, Debug, parse_display::FromStr, parse_display::Display)]
 #[display(style = "snake_case")]
@@ -46,7 +58,8 @@ struct Edge {
 pub type Person = usize;
 pub type Relationships = HashMap<(Person, Person), i32>;
 
-struct Graph {
+/// How each guest feels about sitting next to each other guest.
+pub struct Graph {
     relationships: Relationships,
     index: Vec<String>,
 }
@@ -138,35 +151,238 @@ pub fn find_best_ordering(n_people: usize, relationships: &Relationships) -> Vec
     best_ordering
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let Graph {
-        relationships,
-        index,
-    } = parse(input)?.collect();
+/// Find the happiness of the best seating by dynamic programming over subsets of the people
+/// (the Held-Karp algorithm).
+///
+/// This takes `O(2^n n^2)` time rather than `O(n!)`, so it copes with many more people than
+/// trying every ordering, but it finds only the happiness.
+///
+/// Fails with more than 20 people, as the table of partial seatings would be too large.
+pub fn best_happiness_held_karp(
+    n_people: usize,
+    relationships: &Relationships,
+) -> Result<i32, Error> {
+    if n_people > MAX_HELD_KARP_PEOPLE {
+        return Err(Error::TooLarge {
+            algorithm: HeldKarp.name(),
+            max: MAX_HELD_KARP_PEOPLE,
+            what: "people",
+        });
+    }
+    if n_people < 2 {
+        let ordering: Vec<_> = (0..n_people).collect();
+        return Ok(evaluate_ordering(&ordering, relationships));
+    }
+
+    let feeling = |who, other| {
+        relationships
+            .get(&(who, other))
+            .copied()
+            .unwrap_or_default()
+    };
+    let pair: Vec<Vec<i32>> = (0..n_people)
+        .map(|a| {
+            (0..n_people)
+                .map(|b| feeling(a, b) + feeling(b, a))
+                .collect()
+        })
+        .collect();
+
+    // the table is circular, so everyone may as well be seated starting from person 0.
+    // the best happiness of seating the people in `mask`, ending with `last`, is at
+    // `best[mask * n_people + last]`
+    let n = n_people;
+    let mut best: Vec<Option<i32>> = vec![None; (1 << n) * n];
+    best[n] = Some(0);
+    for mask in (1..1_usize << n).step_by(2) {
+        for last in (0..n).filter(|last| mask & (1 << last) != 0) {
+            let happiness = match best[mask * n + last] {
+                Some(happiness) => happiness,
+                None => continue,
+            };
+            for next in (0..n).filter(|next| mask & (1 << next) == 0) {
+                let slot = &mut best[(mask | 1 << next) * n + next];
+                let candidate = happiness + pair[last][next];
+                match slot {
+                    Some(existing) if *existing >= candidate => {}
+                    _ => *slot = Some(candidate),
+                }
+            }
+        }
+    }
 
-    let n_people = index.len();
-    let best_ordering = find_best_ordering(n_people, &relationships);
-    let happiness = evaluate_ordering(&best_ordering, &relationships);
-    println!("Best happiness: {}", happiness);
-    Ok(())
+    let full = (1 << n) - 1;
+    Ok((1..n)
+        .filter_map(|last| Some(best[full * n + last]? + pair[last][0]))
+        .max()
+        .expect("every seating of at least two people is considered"))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let Graph {
-        relationships,
-        index,
-    } = parse(input)?.collect();
+/// Tries every seating: quickest for small tables.
+pub struct Permutations;
 
-    let n_people = index.len() + 1;
-    let best_ordering = find_best_ordering(n_people, &relationships);
-    let happiness = evaluate_ordering(&best_ordering, &relationships);
+impl Backend<Graph> for Permutations {
+    fn name(&self) -> &'static str {
+        "permutations"
+    }
+
+    fn suits(&self, graph: &Graph) -> bool {
+        // part 2 seats one more
+        graph.index.len() < MAX_PERMUTED_PEOPLE
+    }
 
-    println!("Best happiness (+1 guest): {}", happiness);
-    Ok(())
+    fn part1(&self, graph: &Graph) -> Result<Answer, Error> {
+        Ok(best_permuted(graph.index.len(), &graph.relationships).into())
+    }
+
+    fn part2(&self, graph: &Graph) -> Result<Answer, Error> {
+        Ok(best_permuted(graph.index.len() + 1, &graph.relationships).into())
+    }
+}
+
+fn best_permuted(n_people: usize, relationships: &Relationships) -> i32 {
+    let best_ordering = find_best_ordering(n_people, relationships);
+    evaluate_ordering(&best_ordering, relationships)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+/// Dynamic programming over subsets of the guests: copes with much larger tables.
+pub struct HeldKarp;
+
+impl Backend<Graph> for HeldKarp {
+    fn name(&self) -> &'static str {
+        "held-karp"
+    }
+
+    fn suits(&self, graph: &Graph) -> bool {
+        // smaller tables are quicker to permute, and larger ones don't fit in memory; part 2
+        // seats one more
+        graph.index.len() >= MAX_PERMUTED_PEOPLE && graph.index.len() < MAX_HELD_KARP_PEOPLE
+    }
+
+    fn part1(&self, graph: &Graph) -> Result<Answer, Error> {
+        Ok(best_happiness_held_karp(graph.index.len(), &graph.relationships)?.into())
+    }
+
+    fn part2(&self, graph: &Graph) -> Result<Answer, Error> {
+        Ok(best_happiness_held_karp(graph.index.len() + 1, &graph.relationships)?.into())
+    }
+}
+
+/// The algorithms which can seat the guests, in order of preference.
+///
+/// Permutations come last: with too many guests for Held-Karp, they're slow, but they run.
+pub const BACKENDS: &[&dyn Backend<Graph>] = &[&HeldKarp, &Permutations];
+
+/// Parse the guests in `input`, and select the backend to seat them with.
+fn prepare(
+    input: impl std::io::Read,
+    algo: Option<&str>,
+) -> Result<(Graph, &'static dyn Backend<Graph>), Error> {
+    let graph: Graph = parse_str(&util::input::read_all(input)?)?.collect();
+    let backend = algo::select(BACKENDS, &graph, algo)?;
+    Ok((graph, backend))
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    part1_with_algo(input.as_bytes(), None)
+}
+
+/// As [`part1_from_reader`], solving with the backend named `algo` if given.
+pub fn part1_with_algo(input: impl std::io::Read, algo: Option<&str>) -> Result<Answer, Error> {
+    let (graph, backend) = prepare(input, algo)?;
+    backend.part1(&graph)
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    part2_with_algo(input.as_bytes(), None)
+}
+
+/// As [`part2_from_reader`], solving with the backend named `algo` if given.
+pub fn part2_with_algo(input: impl std::io::Read, algo: Option<&str>) -> Result<Answer, Error> {
+    let (graph, backend) = prepare(input, algo)?;
+    backend.part2(&graph)
+}
+
+const EXAMPLE_INPUT: &str = "\
+Alice would gain 54 happiness units by sitting next to Bob.
+Alice would lose 79 happiness units by sitting next to Carol.
+Alice would lose 2 happiness units by sitting next to David.
+Bob would gain 83 happiness units by sitting next to Alice.
+Bob would lose 7 happiness units by sitting next to Carol.
+Bob would lose 63 happiness units by sitting next to David.
+Carol would lose 62 happiness units by sitting next to Alice.
+Carol would gain 60 happiness units by sitting next to Bob.
+Carol would gain 55 happiness units by sitting next to David.
+David would gain 46 happiness units by sitting next to Alice.
+David would lose 7 happiness units by sitting next to Bob.
+David would gain 41 happiness units by sitting next to Carol.
+";
+
+pub const EXAMPLES: &[Example] = &[Example {
+    part: 1,
+    input: EXAMPLE_INPUT,
+    expected: "330",
+    solver: part1_str,
+}];
+
+/// Report every malformed line of `input`.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    util::lint::lines::<Edge>(
+        input,
+        "Alice would gain 54 happiness units by sitting next to Bob.",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backends_agree() {
+        let graph: Graph = parse_str(EXAMPLE_INPUT).unwrap().collect();
+        for backend in BACKENDS {
+            assert_eq!(backend.part1(&graph).unwrap(), Answer::Int(330));
+        }
+        assert_eq!(
+            Permutations.part2(&graph).unwrap(),
+            HeldKarp.part2(&graph).unwrap()
+        );
+
+        // scrambled feelings among seven guests, some of them missing
+        let mut relationships = Relationships::new();
+        for who in 0..7 {
+            for other in (0..7).filter(|&other| other != who && (who + other) % 5 != 0) {
+                relationships.insert(
+                    (who, other),
+                    (who as i32 * 31 + other as i32 * 17) % 41 - 20,
+                );
+            }
+        }
+        assert_eq!(
+            best_permuted(7, &relationships),
+            best_happiness_held_karp(7, &relationships).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_held_karp_too_large() {
+        let result = best_happiness_held_karp(40, &Relationships::new());
+        assert!(matches!(result, Err(Error::TooLarge { max: 20, .. })));
+    }
 }
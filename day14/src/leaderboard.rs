@@ -0,0 +1,166 @@
+use crate::Race;
+use std::io::{self, Write};
+
+/// The standings of every reindeer at every second of a race.
+///
+/// Standings are stored as flat row-major tables: one row per second, one column per reindeer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    names: Vec<String>,
+    distances: Vec<u32>,
+    points: Vec<u32>,
+}
+
+impl Leaderboard {
+    /// Run `race` until `finish_time`, recording the standings after each second.
+    pub fn record(race: &mut Race, finish_time: u32) -> Leaderboard {
+        let names: Vec<_> = race.reindeer.iter().map(|r| r.name.clone()).collect();
+        let rows = finish_time.saturating_sub(race.timer) as usize;
+        let mut distances = Vec::with_capacity(rows * names.len());
+        let mut points = Vec::with_capacity(rows * names.len());
+
+        while race.timer < finish_time {
+            race.tick();
+            distances.extend(race.reindeer.iter().map(|r| r.distance));
+            points.extend(race.reindeer.iter().map(|r| r.points));
+        }
+
+        Leaderboard {
+            names,
+            distances,
+            points,
+        }
+    }
+
+    /// Names of the reindeer, in column order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Number of seconds recorded.
+    pub fn seconds(&self) -> usize {
+        if self.names.is_empty() {
+            0
+        } else {
+            self.distances.len() / self.names.len()
+        }
+    }
+
+    fn row<'a>(&self, table: &'a [u32], second: usize) -> &'a [u32] {
+        let width = self.names.len();
+        &table[(second - 1) * width..second * width]
+    }
+
+    /// Distance of each reindeer after `second` seconds.
+    ///
+    /// Seconds are 1-based: `distances(1)` is the state after the first tick.
+    ///
+    /// Panics if `second` is 0 or greater than `self.seconds()`.
+    pub fn distances(&self, second: usize) -> &[u32] {
+        self.row(&self.distances, second)
+    }
+
+    /// Points of each reindeer after `second` seconds.
+    ///
+    /// Panics if `second` is 0 or greater than `self.seconds()`.
+    pub fn points(&self, second: usize) -> &[u32] {
+        self.row(&self.points, second)
+    }
+
+    /// Indices of the reindeer sharing the lead by distance after `second` seconds.
+    pub fn leaders(&self, second: usize) -> Vec<usize> {
+        let distances = self.distances(second);
+        let best = distances.iter().copied().max();
+        distances
+            .iter()
+            .enumerate()
+            .filter(|(_, &distance)| Some(distance) == best)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Number of seconds after which more than one reindeer shared the lead.
+    pub fn ties(&self) -> usize {
+        (1..=self.seconds())
+            .filter(|&second| self.leaders(second).len() > 1)
+            .count()
+    }
+
+    /// Write the leaderboard as CSV.
+    ///
+    /// The header is `second`, then a `distance` and a `points` column for each reindeer.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "second")?;
+        for name in &self.names {
+            write!(writer, ",{} distance,{} points", name, name)?;
+        }
+        writeln!(writer)?;
+
+        for second in 1..=self.seconds() {
+            write!(writer, "{}", second)?;
+            for (distance, points) in self.distances(second).iter().zip(self.points(second)) {
+                write!(writer, ",{},{}", distance, points)?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Reindeer;
+
+    fn example_race() -> Race {
+        vec![
+            Reindeer::new("Comet".to_string(), 14, 10, 127),
+            Reindeer::new("Dancer".to_string(), 16, 11, 162),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_matches_simulation() {
+        let mut race = example_race();
+        let leaderboard = Leaderboard::record(&mut race, 1000);
+
+        assert_eq!(leaderboard.seconds(), 1000);
+        assert_eq!(leaderboard.distances(1), &[14, 16]);
+        assert_eq!(leaderboard.distances(11), &[140, 176]);
+        assert_eq!(leaderboard.distances(1000), &[1120, 1056]);
+        assert_eq!(leaderboard.points(1000), &[312, 689]);
+        assert_eq!(leaderboard.leaders(1), vec![1]);
+        assert_eq!(leaderboard.leaders(1000), vec![0]);
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut race = example_race();
+        let leaderboard = Leaderboard::record(&mut race, 2);
+
+        let mut csv = Vec::new();
+        leaderboard.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "second,Comet distance,Comet points,Dancer distance,Dancer points\n\
+             1,14,0,16,1\n\
+             2,28,0,32,2\n"
+        );
+    }
+
+    #[test]
+    fn test_ties() {
+        let mut race: Race = vec![
+            Reindeer::new("A".to_string(), 2, 1, 1),
+            Reindeer::new("B".to_string(), 1, 10, 1),
+        ]
+        .into_iter()
+        .collect();
+        let leaderboard = Leaderboard::record(&mut race, 4);
+        // A: 2 2 4 4; B: 1 2 3 4
+        assert_eq!(leaderboard.ties(), 2);
+    }
+}
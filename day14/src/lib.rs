@@ -25,10 +25,15 @@
 //! Given the descriptions of each reindeer (in your puzzle input), after exactly 2503 seconds,
 //! what distance has the winning reindeer traveled?
 
-use aoclib::parse;
+mod leaderboard;
+
+pub use leaderboard::Leaderboard;
+
+use aoclib::input::parse_str;
 use std::iter::FromIterator;
 use std::path::Path;
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, lint::Diagnostic, Answer, InputSource};
 
 const RACE_DURATION: u32 = 2503;
 
@@ -192,34 +197,107 @@ impl Race {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let mut race: Race = parse(input)?.collect();
-    race.run_to_time(RACE_DURATION);
+fn no_winner() -> Error {
+    Error::no_solution("no reindeer won :(")
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    winning_distance(input, RACE_DURATION)
+}
+
+/// The distance the leading reindeer has flown after `seconds`.
+fn winning_distance(input: &str, seconds: u32) -> Result<Answer, Error> {
+    let mut race: Race = parse_str(input)?.collect();
+    race.run_to_time(seconds);
     let winner = race
         .in_lead(race.by_distance())
         .next()
-        .ok_or(Error::NoWinner)?;
-    println!("winner: {:>8} @ {} km", winner.name, winner.distance);
-    Ok(())
+        .ok_or_else(no_winner)?;
+    Ok(winner.distance.into())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut race: Race = parse(input)?.collect();
-    race.run_to_time(RACE_DURATION);
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    winning_points(input, RACE_DURATION)
+}
+
+/// The points the leading reindeer has scored after `seconds`.
+fn winning_points(input: &str, seconds: u32) -> Result<Answer, Error> {
+    let mut race: Race = parse_str(input)?.collect();
+    race.run_to_time(seconds);
     let winner = race
         .in_lead(race.by_points())
         .next()
-        .ok_or(Error::NoWinner)?;
-    println!("winner: {:>8} @ {} points", winner.name, winner.points);
+        .ok_or_else(no_winner)?;
+    Ok(winner.points.into())
+}
+
+const EXAMPLE_INPUT: &str = "\
+Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
+Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds.
+";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE_INPUT,
+        expected: "1120",
+        solver: part1_example,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE_INPUT,
+        expected: "689",
+        solver: part2_example,
+    },
+];
+
+/// The example race is shorter than the real one.
+const EXAMPLE_DURATION: u32 = 1000;
+
+fn part1_example(input: &str) -> Result<Answer, Error> {
+    winning_distance(input, EXAMPLE_DURATION)
+}
+
+fn part2_example(input: &str) -> Result<Answer, Error> {
+    winning_points(input, EXAMPLE_DURATION)
+}
+
+/// Record the standings at every second of the race and write them as CSV to `output`.
+pub fn leaderboard(input: &InputSource, output: &Path) -> Result<(), Error> {
+    let mut race: Race = parse_str(&input.read_to_string()?)?.collect();
+    let leaderboard = Leaderboard::record(&mut race, RACE_DURATION);
+    let writer = std::io::BufWriter::new(std::fs::File::create(output)?);
+    leaderboard.write_csv(writer)?;
+    println!(
+        "recorded {} seconds; {} with a tied lead",
+        leaderboard.seconds(),
+        leaderboard.ties()
+    );
     Ok(())
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("no reindeer won :(")]
-    NoWinner,
+/// Report every malformed line of `input`.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    util::lint::lines::<Reindeer>(
+        input,
+        "Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.",
+    )
 }
 
 #[cfg(test)]
@@ -301,7 +379,7 @@ mod tests {
         let winner = race
             .in_lead(race.by_points())
             .next()
-            .ok_or(Error::NoWinner)
+            .ok_or_else(no_winner)
             .unwrap();
         assert_eq!(winner.name, "Dancer");
         assert_eq!(winner.points, 689);
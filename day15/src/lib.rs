@@ -18,14 +18,31 @@
 //! found by adding up each of the properties (negative totals become 0) and then multiplying
 //! together everything except calories.
 //!
-//! This program hill-climbs to a local maximum and hopes for the best.
+//! With few ingredients, this program simply tries every recipe. With more, it hill-climbs to a
+//! local maximum from several starting points and hopes for the best. The starting points are
+//! random, but seeded, so a run can always be repeated.
 
 pub mod recipe;
 use recipe::Recipe;
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use std::path::Path;
-use thiserror::Error;
+pub use util::Error;
+use util::{
+    algo::{self, Backend},
+    example::Example,
+    Answer, InputSource,
+};
+
+/// How many random recipes part 1 climbs from, besides the basic recipe.
+const RESTARTS: usize = 16;
+
+/// Trying every recipe suits inputs with at most this many ingredients: with four, there are
+/// under 200 000 recipes.
+const MAX_EXHAUSTED_INGREDIENTS: usize = 4;
+
+/// The calories a recipe must have in part 2.
+const CONSTRAINT: i32 = 500;
 
 /// A model of a recipe ingredient
 #[derive(PartialEq, Eq, Clone, Debug, parse_display::Display, parse_display::FromStr)]
@@ -39,31 +56,149 @@ pub struct Ingredient {
     pub calories: i32,
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let basic_recipe: Recipe = parse(input)?.collect();
-    let best_recipe = basic_recipe.climb_goodness();
-    println!("best recipe goodness: {}", best_recipe.goodness());
-    Ok(())
+/// The ingredients, and the seed for any random search among recipes of them.
+pub struct Kitchen {
+    pub recipe: Recipe,
+    pub seed: u64,
+}
+
+/// Tries every recipe: exact, and quick enough for a few ingredients.
+pub struct Exhaustive;
+
+impl Backend<Kitchen> for Exhaustive {
+    fn name(&self) -> &'static str {
+        "exhaustive"
+    }
+
+    fn suits(&self, kitchen: &Kitchen) -> bool {
+        kitchen.recipe.ingredients().len() <= MAX_EXHAUSTED_INGREDIENTS
+    }
+
+    fn part1(&self, kitchen: &Kitchen) -> Result<Answer, Error> {
+        Ok(kitchen.recipe.exhaust_goodness().goodness().into())
+    }
+
+    fn part2(&self, kitchen: &Kitchen) -> Result<Answer, Error> {
+        let best_recipe = kitchen
+            .recipe
+            .exhaust_goodness_constrained(CONSTRAINT)
+            .ok_or_else(unconstrained)?;
+        Ok(best_recipe.goodness().into())
+    }
+}
+
+/// Hill-climbs with random restarts for part 1, and prunes dominated partial recipes for part 2:
+/// copes with many more ingredients.
+pub struct Scalable;
+
+impl Backend<Kitchen> for Scalable {
+    fn name(&self) -> &'static str {
+        "scalable"
+    }
+
+    fn suits(&self, _kitchen: &Kitchen) -> bool {
+        true
+    }
+
+    fn part1(&self, kitchen: &Kitchen) -> Result<Answer, Error> {
+        let best_recipe = kitchen
+            .recipe
+            .climb_goodness_with_restarts(RESTARTS, &mut util::rng::seeded(kitchen.seed));
+        Ok(best_recipe.goodness().into())
+    }
+
+    fn part2(&self, kitchen: &Kitchen) -> Result<Answer, Error> {
+        let best_recipe = kitchen
+            .recipe
+            .dp_goodness_constrained(CONSTRAINT)
+            .ok_or_else(unconstrained)?;
+        Ok(best_recipe.goodness().into())
+    }
+}
+
+/// The algorithms which can find recipes, in order of preference.
+pub const BACKENDS: &[&dyn Backend<Kitchen>] = &[&Exhaustive, &Scalable];
+
+fn unconstrained() -> Error {
+    Error::no_solution(format!(
+        "no recipe found which matches constraint: {} calories",
+        CONSTRAINT
+    ))
+}
+
+/// Parse the ingredients in `input`, and select the backend to find recipes with.
+fn prepare(
+    input: impl std::io::Read,
+    seed: u64,
+    algo: Option<&str>,
+) -> Result<(Kitchen, &'static dyn Backend<Kitchen>), Error> {
+    let recipe = parse_str(&util::input::read_all(input)?)?.collect();
+    let kitchen = Kitchen { recipe, seed };
+    let backend = algo::select(BACKENDS, &kitchen, algo)?;
+    Ok((kitchen, backend))
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    part1_with_seed(input.as_bytes(), util::rng::DEFAULT_SEED)
+}
+
+/// As [`part1_from_reader`], seeding the random restarts of the hill climb with `seed`.
+pub fn part1_with_seed(input: impl std::io::Read, seed: u64) -> Result<Answer, Error> {
+    part1_with_algo(input, seed, None)
+}
+
+/// As [`part1_with_seed`], solving with the backend named `algo` if given.
+pub fn part1_with_algo(
+    input: impl std::io::Read,
+    seed: u64,
+    algo: Option<&str>,
+) -> Result<Answer, Error> {
+    let (kitchen, backend) = prepare(input, seed, algo)?;
+    backend.part1(&kitchen)
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    const CONSTRAINT: i32 = 500;
-    let basic_recipe: Recipe = parse(input)?.collect();
-    let best_recipe = basic_recipe
-        .exhaust_goodness_constrained(CONSTRAINT)
-        .ok_or(Error::NoSuchRecipe(CONSTRAINT))?;
-    println!(
-        "best recipe goodness (constrained to {} calories): {}",
-        CONSTRAINT,
-        best_recipe.goodness()
-    );
-    Ok(())
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("no recipe found which matches constraint: {0} calories")]
-    NoSuchRecipe(i32),
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    part2_with_algo(input.as_bytes(), None)
 }
+
+/// As [`part2_from_reader`], solving with the backend named `algo` if given.
+pub fn part2_with_algo(input: impl std::io::Read, algo: Option<&str>) -> Result<Answer, Error> {
+    let (kitchen, backend) = prepare(input, util::rng::DEFAULT_SEED, algo)?;
+    backend.part2(&kitchen)
+}
+
+const EXAMPLE_INPUT: &str = "\
+Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
+Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3
+";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE_INPUT,
+        expected: "62842880",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE_INPUT,
+        expected: "57600000",
+        solver: part2_str,
+    },
+];
@@ -1,52 +1,29 @@
-use aoclib::{config::Config, website::get_input};
-use day15::{part1, part2};
+use day15::{part1_with_algo, part2_with_algo};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
+use util::cli::RunArgs;
 
 const DAY: u8 = 15;
 
-#[derive(StructOpt, Debug)]
-struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
-
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
-
-    /// run part 2
-    #[structopt(long)]
-    part2: bool,
-}
-
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
-}
-
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
-    }
-    if args.part2 {
-        part2(&input_path)?;
+    args.init_tracing();
+    if args.example {
+        args.run_examples(day15::EXAMPLES)?;
+        return Ok(());
     }
+    let input = args.source(DAY)?;
+    let seed = args.seed();
+    let algo = args.force_algo();
+    let answers = args.run(
+        DAY,
+        "",
+        &input,
+        |input| part1_with_algo(input, seed, algo),
+        |input| part2_with_algo(input, algo),
+    )?;
+    args.check(DAY, &answers)?;
     Ok(())
 }
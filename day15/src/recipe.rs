@@ -1,8 +1,9 @@
 use itertools::Itertools;
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     iter::FromIterator,
 };
+use util::rng::Rng;
 
 use crate::Ingredient;
 
@@ -53,6 +54,10 @@ impl Recipe {
         }
     }
 
+    pub fn ingredients(&self) -> &[Ingredient] {
+        &self.ingredients
+    }
+
     pub fn quantity_of(&self, name: &str) -> Option<u8> {
         self.ingredients
             .binary_search_by_key(&name, |ingredient| &ingredient.name)
@@ -65,37 +70,48 @@ impl Recipe {
     }
 
     fn goodness_with(&self, quantities: &[u8]) -> i32 {
-        let mut capacity: i32 = 0;
-        let mut durability: i32 = 0;
-        let mut flavor: i32 = 0;
-        let mut texture: i32 = 0;
-
+        let mut properties = Properties::default();
         for (ingredient, qty) in self.ingredients.iter().zip(quantities) {
-            capacity += *qty as i32 * ingredient.capacity;
-            durability += *qty as i32 * ingredient.durability;
-            flavor += *qty as i32 * ingredient.flavor;
-            texture += *qty as i32 * ingredient.texture;
+            properties = properties.add(ingredient, *qty);
         }
+        properties.goodness()
+    }
 
-        for quality in &[capacity, durability, flavor, texture] {
-            if *quality < 0 {
-                return 0;
+    pub fn climb_goodness(&self) -> Recipe {
+        self.with_quantities(self.climb_from(self.quantities.clone()))
+    }
+
+    /// Hill-climb from the basic recipe and from `restarts` random recipes, returning the best
+    /// local maximum found.
+    ///
+    /// Hill climbing can get stuck on a local maximum; starting elsewhere may find a better one.
+    /// The result is never worse than [`Recipe::climb_goodness`].
+    pub fn climb_goodness_with_restarts(&self, restarts: usize, rng: &mut impl Rng) -> Recipe {
+        let mut best = self.climb_from(self.quantities.clone());
+        if self.ingredients.len() < 2 {
+            // every recipe is the same recipe
+            return self.with_quantities(best);
+        }
+        for _ in 0..restarts {
+            let climbed = self.climb_from(random_quantities(self.ingredients.len(), rng));
+            if self.goodness_with(&climbed) > self.goodness_with(&best) {
+                best = climbed;
             }
         }
-
-        capacity * durability * flavor * texture
+        self.with_quantities(best)
     }
 
-    pub fn climb_goodness(&self) -> Recipe {
+    /// Hill-climb from the given quantities to a local maximum of goodness.
+    fn climb_from(&self, quantities: Vec<u8>) -> Vec<u8> {
         if self.ingredients.len() < 2 {
             // no neighbors can exist
-            return self.clone();
+            return quantities;
         }
         // for 2 or more ingredients, at least one neighbor must exist
 
-        let mut best_recipe = self.quantities.clone();
+        let mut best_recipe = quantities;
         let mut prev_best_goodness = -1; // goodness function never returns below 0
-        let mut best_goodness = self.goodness();
+        let mut best_goodness = self.goodness_with(&best_recipe);
 
         // Hill climb. If the best goodness stops increasing, then we've found
         // at least a local maximum, and we can stop.
@@ -113,7 +129,7 @@ impl Recipe {
             }
         }
 
-        self.with_quantities(best_recipe)
+        best_recipe
     }
 
     pub fn calories(&self) -> i32 {
@@ -128,9 +144,21 @@ impl Recipe {
             .sum()
     }
 
+    /// Exhaustively check all possible recipes, returning the best (by goodness).
+    pub fn exhaust_goodness(&self) -> Recipe {
+        self.exhaust_best(|_| true)
+            .expect("every recipe is acceptable, including the basic one")
+    }
+
     /// Exhaustively check all possible recipes, returning the best of those (by goodness)
     /// which meets the calories constraint.
     pub fn exhaust_goodness_constrained(&self, calories: i32) -> Option<Recipe> {
+        self.exhaust_best(|quantities| self.calories_with(quantities) == calories)
+    }
+
+    /// Exhaustively check all possible recipes, returning the best of those (by goodness)
+    /// which are `acceptable`.
+    fn exhaust_best(&self, acceptable: impl Fn(&[u8]) -> bool) -> Option<Recipe> {
         let mut best_recipe = None;
         let mut visited = HashSet::new();
         let mut queue = VecDeque::new();
@@ -146,7 +174,7 @@ impl Recipe {
             queue.extend(neighbors_of(&quantities).filter(|quantity| !visited.contains(quantity)));
 
             // check this recipe
-            if self.calories_with(&quantities) == calories {
+            if acceptable(&quantities) {
                 best_recipe = match best_recipe {
                     None => Some(quantities),
                     Some(cur_best_recipe) => Some(
@@ -162,6 +190,122 @@ impl Recipe {
 
         best_recipe.map(|quantities| self.with_quantities(quantities))
     }
+
+    /// Find the best recipe (by goodness) which meets the calories constraint by dynamic
+    /// programming.
+    ///
+    /// Ingredients are considered one at a time. For each combination of teaspoons used and
+    /// calories so far, only the partial recipes whose property totals are not dominated by
+    /// another's are kept: as goodness never decreases when a property increases, a dominated
+    /// partial recipe can never complete to a better recipe than the one dominating it.
+    pub fn dp_goodness_constrained(&self, calories: i32) -> Option<Recipe> {
+        // when no ingredient has negative calories, exceeding the target is a dead end
+        let calories_only_increase = self
+            .ingredients
+            .iter()
+            .all(|ingredient| ingredient.calories >= 0);
+
+        let mut layer: HashMap<(u8, i32), Vec<Partial>> = HashMap::new();
+        layer.insert((0, 0), vec![Partial::default()]);
+
+        for (idx, ingredient) in self.ingredients.iter().enumerate() {
+            let is_last = idx == self.ingredients.len() - 1;
+            let mut next_layer: HashMap<(u8, i32), Vec<Partial>> = HashMap::new();
+
+            for ((used, calories_so_far), partials) in layer {
+                let remaining = TOTAL_INGREDIENTS - used;
+                let min_qty = if is_last { remaining } else { 0 };
+                for qty in min_qty..=remaining {
+                    let next_calories = calories_so_far + ingredient.calories * qty as i32;
+                    if calories_only_increase && next_calories > calories {
+                        break;
+                    }
+                    let front = next_layer.entry((used + qty, next_calories)).or_default();
+                    for partial in &partials {
+                        insert_non_dominated(front, partial.add(ingredient, qty));
+                    }
+                }
+            }
+
+            layer = next_layer;
+        }
+
+        layer
+            .remove(&(TOTAL_INGREDIENTS, calories))?
+            .into_iter()
+            .max_by_key(|partial| partial.properties.goodness())
+            .map(|partial| self.with_quantities(partial.quantities))
+    }
+}
+
+/// Property totals of a (partial) recipe, excluding calories.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+struct Properties {
+    capacity: i32,
+    durability: i32,
+    flavor: i32,
+    texture: i32,
+}
+
+impl Properties {
+    fn add(self, ingredient: &Ingredient, qty: u8) -> Properties {
+        let qty = qty as i32;
+        Properties {
+            capacity: self.capacity + qty * ingredient.capacity,
+            durability: self.durability + qty * ingredient.durability,
+            flavor: self.flavor + qty * ingredient.flavor,
+            texture: self.texture + qty * ingredient.texture,
+        }
+    }
+
+    fn as_array(&self) -> [i32; 4] {
+        [self.capacity, self.durability, self.flavor, self.texture]
+    }
+
+    fn goodness(&self) -> i32 {
+        let qualities = self.as_array();
+        if qualities.iter().any(|&quality| quality < 0) {
+            return 0;
+        }
+        qualities.iter().product()
+    }
+
+    /// `true` if no property of `self` is less than the corresponding property of `other`.
+    fn dominates(&self, other: &Properties) -> bool {
+        self.as_array()
+            .iter()
+            .zip(other.as_array().iter())
+            .all(|(mine, theirs)| mine >= theirs)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+struct Partial {
+    properties: Properties,
+    quantities: Vec<u8>,
+}
+
+impl Partial {
+    fn add(&self, ingredient: &Ingredient, qty: u8) -> Partial {
+        let mut quantities = self.quantities.clone();
+        quantities.push(qty);
+        Partial {
+            properties: self.properties.add(ingredient, qty),
+            quantities,
+        }
+    }
+}
+
+/// Add `partial` to the Pareto front `front`, unless something already there dominates it.
+fn insert_non_dominated(front: &mut Vec<Partial>, partial: Partial) {
+    if front
+        .iter()
+        .any(|existing| existing.properties.dominates(&partial.properties))
+    {
+        return;
+    }
+    front.retain(|existing| !partial.properties.dominates(&existing.properties));
+    front.push(partial);
 }
 
 /// Return an iterator of neighbors of the given quantity set.
@@ -180,6 +324,24 @@ fn neighbors_of(quantities: &[u8]) -> impl '_ + Iterator<Item = Vec<u8>> {
         })
 }
 
+/// Uniformly random quantities of `n` ingredients, totalling [`TOTAL_INGREDIENTS`].
+fn random_quantities(n: usize, rng: &mut impl Rng) -> Vec<u8> {
+    // cut the teaspoons into `n` runs at `n - 1` random points
+    let mut cuts: Vec<u8> = (1..n)
+        .map(|_| rng.gen_range(0..=TOTAL_INGREDIENTS))
+        .collect();
+    cuts.push(TOTAL_INGREDIENTS);
+    cuts.sort_unstable();
+    let mut prev = 0;
+    cuts.into_iter()
+        .map(|cut| {
+            let qty = cut - prev;
+            prev = cut;
+            qty
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{recipe::Recipe, Ingredient};
@@ -208,6 +370,31 @@ Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3
         assert_eq!(recipe.quantity_of("Cinnamon").unwrap(), 56);
     }
 
+    #[test]
+    fn test_hill_climb_with_restarts_example() {
+        let recipe = example()
+            .collect::<Recipe>()
+            .climb_goodness_with_restarts(10, &mut util::rng::seeded(1));
+        assert_eq!(recipe.goodness(), 62842880);
+    }
+
+    #[test]
+    fn test_random_quantities() {
+        let mut rng = util::rng::seeded(2);
+        for n in 1..5 {
+            let quantities = super::random_quantities(n, &mut rng);
+            assert_eq!(quantities.len(), n);
+            assert_eq!(quantities.iter().sum::<u8>(), super::TOTAL_INGREDIENTS);
+        }
+    }
+
+    #[test]
+    fn test_exhaust_example() {
+        let recipe = example().collect::<Recipe>().exhaust_goodness();
+        assert_eq!(recipe.goodness(), 62842880);
+        assert_eq!(recipe.quantity_of("Butterscotch").unwrap(), 44);
+    }
+
     #[test]
     fn test_exhaust_example_constrained() {
         let recipe = example()
@@ -220,4 +407,44 @@ Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3
         assert_eq!(recipe.quantity_of("Butterscotch").unwrap(), 40);
         assert_eq!(recipe.quantity_of("Cinnamon").unwrap(), 60);
     }
+
+    #[test]
+    fn test_dp_example_constrained() {
+        let recipe = example()
+            .collect::<Recipe>()
+            .dp_goodness_constrained(500)
+            .unwrap();
+
+        assert_eq!(recipe.goodness(), 57600000);
+        assert_eq!(recipe.calories(), 500);
+        assert_eq!(recipe.quantity_of("Butterscotch").unwrap(), 40);
+        assert_eq!(recipe.quantity_of("Cinnamon").unwrap(), 60);
+    }
+
+    #[test]
+    fn test_dp_matches_exhaustive() {
+        let recipe: Recipe = "
+Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
+Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3
+Sugar: capacity 0, durability 0, flavor -2, texture 2, calories 1
+"
+        .trim()
+        .split('\n')
+        .map(|line| line.parse::<Ingredient>().unwrap())
+        .collect();
+
+        for &calories in &[100, 300, 400, 450, 500, 555, 801] {
+            let exhaustive = recipe.exhaust_goodness_constrained(calories);
+            let dp = recipe.dp_goodness_constrained(calories);
+            assert_eq!(
+                exhaustive.as_ref().map(Recipe::goodness),
+                dp.as_ref().map(Recipe::goodness),
+                "calories: {}",
+                calories
+            );
+            if let Some(dp) = dp {
+                assert_eq!(dp.calories(), calories);
+            }
+        }
+    }
 }
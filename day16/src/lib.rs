@@ -43,10 +43,11 @@
 //!
 //! What is the number of the Sue that got you the gift?
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use std::path::Path;
 use std::str::FromStr;
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, lint::Diagnostic, Answer, InputSource};
 
 const RESULT: MfcsamQtys = MfcsamQtys {
     children: Some(3),
@@ -84,11 +85,16 @@ impl FromStr for MfcsamQtys {
         for item in s.split(',') {
             let item = item.trim();
 
-            let err = || Error::MalformedItem(item.to_string());
+            let err = || Error::parse(format!("\"{}\"", item), "malformed item");
 
             let mut kvs = item.split(':');
             let name = kvs.next().ok_or_else(err)?;
-            let qty = kvs.next().ok_or_else(err)?.trim().parse::<u32>()?;
+            let qty = kvs
+                .next()
+                .ok_or_else(err)?
+                .trim()
+                .parse::<u32>()
+                .map_err(|source| Error::parse(format!("\"{}\"", item), source))?;
             if kvs.next().is_some() {
                 return Err(err());
             }
@@ -203,40 +209,44 @@ impl Sue {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let mut found_sue = false;
-    for sue in parse::<Sue>(input)? {
-        if sue.can_be(&RESULT) {
-            println!("matching sue: {}", sue.num);
-            found_sue = true;
-        }
-    }
-    if !found_sue {
-        println!("no matching sue found");
-    }
-    Ok(())
+fn no_sue() -> Error {
+    Error::no_solution("no matching sue found")
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut found_sue = false;
-    for sue in parse::<Sue>(input)? {
-        if sue.can_be_retro(&RESULT) {
-            println!("matching sue (retro): {}", sue.num);
-            found_sue = true;
-        }
-    }
-    if !found_sue {
-        println!("no matching sue (retro) found");
-    }
-    Ok(())
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("malformed item: \"{0}\"")]
-    MalformedItem(String),
-    #[error(transparent)]
-    ParseInt(#[from] std::num::ParseIntError),
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let sue = parse_str::<Sue>(input)?
+        .find(|sue| sue.can_be(&RESULT))
+        .ok_or_else(no_sue)?;
+    Ok(sue.num.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let sue = parse_str::<Sue>(input)?
+        .find(|sue| sue.can_be_retro(&RESULT))
+        .ok_or_else(no_sue)?;
+    Ok(sue.num.into())
+}
+
+/// The puzzle text works through no example to an answer.
+pub const EXAMPLES: &[Example] = &[];
+
+/// Report every malformed line of `input`.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    util::lint::lines::<Sue>(input, "Sue 1: cars: 9, akitas: 3, goldfish: 0")
 }
@@ -15,9 +15,14 @@
 //! Filling all containers entirely, how many different combinations of containers can exactly fit
 //! all `150` liters of eggnog?
 
-use aoclib::parse;
+mod weighted;
+
+pub use weighted::{Combination, FillTable, WeightedContainer};
+
+use aoclib::input::parse_str;
 use std::path::Path;
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
 pub type Container = u8;
 
@@ -128,31 +133,120 @@ impl Iterator for EggnogFiller {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let containers: Vec<Container> = parse(input)?.collect();
-    let filler = EggnogFiller::new(EGGNOG_QTY, containers);
+fn capacities(input: &str) -> Result<Vec<Container>, Error> {
+    Ok(parse_str::<WeightedContainer>(input)?
+        .map(|container| container.capacity)
+        .collect())
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    combinations(input, EGGNOG_QTY)
+}
+
+/// How many combinations of containers hold exactly `liters`.
+fn combinations(input: &str, liters: u8) -> Result<Answer, Error> {
+    let containers = capacities(input)?;
+    let filler = EggnogFiller::new(liters, containers);
     let combo_count = filler.count();
-    println!("Possible combinations: {}", combo_count);
-    Ok(())
+    Ok(combo_count.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let containers: Vec<Container> = parse(input)?.collect();
-    let filler = EggnogFiller::new(EGGNOG_QTY, containers);
-    let min_ctrs = filler.clone().map(|c| c.len()).min().unwrap();
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    minimal_combinations(input, EGGNOG_QTY)
+}
+
+/// How many combinations of the fewest containers hold exactly `liters`.
+fn minimal_combinations(input: &str, liters: u8) -> Result<Answer, Error> {
+    let containers = capacities(input)?;
+    let filler = EggnogFiller::new(liters, containers);
+    let min_ctrs = filler
+        .clone()
+        .map(|c| c.len())
+        .min()
+        .ok_or_else(|| Error::no_solution("no combination of containers holds the eggnog"))?;
     let ways_min = filler.filter(|c| c.len() == min_ctrs).count();
+    Ok(ways_min.into())
+}
 
-    println!(
-        "..with {} ways to use only {} containers.",
-        ways_min, min_ctrs
-    );
-    Ok(())
+const EXAMPLE_INPUT: &str = "\
+20
+15
+10
+5
+5
+";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE_INPUT,
+        expected: "4",
+        solver: part1_example,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE_INPUT,
+        expected: "3",
+        solver: part2_example,
+    },
+];
+
+/// The example stores less eggnog than the real puzzle.
+const EXAMPLE_QTY: u8 = 25;
+
+fn part1_example(input: &str) -> Result<Answer, Error> {
+    combinations(input, EXAMPLE_QTY)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+fn part2_example(input: &str) -> Result<Answer, Error> {
+    minimal_combinations(input, EXAMPLE_QTY)
+}
+
+fn print_combination(label: &str, combination: Option<Combination>) {
+    match combination {
+        Some(combination) => {
+            let containers: Vec<_> = combination
+                .containers
+                .iter()
+                .map(|container| container.capacity.to_string())
+                .collect();
+            println!(
+                "{}: cost {} using [{}]",
+                label,
+                combination.cost,
+                containers.join(", ")
+            );
+        }
+        None => println!("{}: none", label),
+    }
+}
+
+/// Find the cheapest combinations of containers, given a cost for each.
+pub fn cheapest(input: &InputSource) -> Result<(), Error> {
+    let containers: Vec<WeightedContainer> = parse_str(&input.read_to_string()?)?.collect();
+    let table = FillTable::new(EGGNOG_QTY, containers);
+    print_combination("cheapest", table.cheapest());
+    print_combination(
+        "cheapest with fewest containers",
+        table.cheapest_with_min_count(),
+    );
+    Ok(())
 }
 
 #[cfg(test)]
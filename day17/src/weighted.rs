@@ -0,0 +1,216 @@
+use crate::Container;
+use std::{fmt, str::FromStr};
+
+/// A container which may carry a cost.
+///
+/// Its textual form is the capacity, optionally followed by `@` and the cost: `20` or `20 @ 3`.
+/// Containers without an explicit cost are free.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WeightedContainer {
+    pub capacity: Container,
+    pub cost: u32,
+}
+
+impl FromStr for WeightedContainer {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (capacity, cost) = match s.find('@') {
+            Some(idx) => (&s[..idx], s[idx + 1..].trim().parse()?),
+            None => (s, 0),
+        };
+        Ok(WeightedContainer {
+            capacity: capacity.trim().parse()?,
+            cost,
+        })
+    }
+}
+
+impl fmt::Display for WeightedContainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} @ {}", self.capacity, self.cost)
+    }
+}
+
+/// A particular combination of containers which exactly holds the eggnog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combination {
+    pub containers: Vec<WeightedContainer>,
+    pub cost: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Cell {
+    /// number of combinations reaching this cell
+    ways: u64,
+    /// cost and container indices of the cheapest combination reaching this cell
+    cheapest: Option<(u32, Vec<usize>)>,
+}
+
+/// Counting DP over the containers, which also tracks the cheapest combination.
+///
+/// Cell `[volume][count]` describes the combinations of exactly `count` containers whose
+/// capacities sum to `volume`.
+#[derive(Debug, Clone)]
+pub struct FillTable {
+    containers: Vec<WeightedContainer>,
+    target: usize,
+    cells: Vec<Vec<Cell>>,
+}
+
+impl FillTable {
+    pub fn new(target: Container, containers: Vec<WeightedContainer>) -> FillTable {
+        let target = target as usize;
+        let mut cells = vec![vec![Cell::default(); containers.len() + 1]; target + 1];
+        cells[0][0] = Cell {
+            ways: 1,
+            cheapest: Some((0, Vec::new())),
+        };
+
+        for (idx, container) in containers.iter().enumerate() {
+            let capacity = container.capacity as usize;
+            if capacity == 0 || capacity > target {
+                continue;
+            }
+            // iterate downwards so each container is used at most once
+            for volume in (capacity..=target).rev() {
+                for count in (1..=idx + 1).rev() {
+                    let (ways, cheapest) = {
+                        let from = &cells[volume - capacity][count - 1];
+                        let cheapest = from.cheapest.as_ref().map(|(cost, indices)| {
+                            let mut indices = indices.clone();
+                            indices.push(idx);
+                            (cost + container.cost, indices)
+                        });
+                        (from.ways, cheapest)
+                    };
+                    let cell = &mut cells[volume][count];
+                    cell.ways += ways;
+                    if let Some((cost, indices)) = cheapest {
+                        let improves = match cell.cheapest {
+                            Some((best, _)) => cost < best,
+                            None => true,
+                        };
+                        if improves {
+                            cell.cheapest = Some((cost, indices));
+                        }
+                    }
+                }
+            }
+        }
+
+        FillTable {
+            containers,
+            target,
+            cells,
+        }
+    }
+
+    fn combination(&self, cell: &Cell) -> Option<Combination> {
+        cell.cheapest.as_ref().map(|(cost, indices)| Combination {
+            containers: indices.iter().map(|&idx| self.containers[idx]).collect(),
+            cost: *cost,
+        })
+    }
+
+    /// Number of combinations which exactly hold the target volume.
+    pub fn ways(&self) -> u64 {
+        self.cells[self.target].iter().map(|cell| cell.ways).sum()
+    }
+
+    /// Number of combinations of exactly `count` containers which hold the target volume.
+    pub fn ways_with(&self, count: usize) -> u64 {
+        self.cells[self.target]
+            .get(count)
+            .map(|cell| cell.ways)
+            .unwrap_or_default()
+    }
+
+    /// Fewest containers which can exactly hold the target volume.
+    pub fn min_count(&self) -> Option<usize> {
+        self.cells[self.target]
+            .iter()
+            .position(|cell| cell.ways > 0)
+    }
+
+    /// Cheapest combination which exactly holds the target volume.
+    pub fn cheapest(&self) -> Option<Combination> {
+        self.cells[self.target]
+            .iter()
+            .filter_map(|cell| self.combination(cell))
+            .min_by_key(|combination| combination.cost)
+    }
+
+    /// Cheapest combination among those using the fewest containers.
+    pub fn cheapest_with_min_count(&self) -> Option<Combination> {
+        self.combination(&self.cells[self.target][self.min_count()?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EggnogFiller;
+
+    fn example() -> Vec<WeightedContainer> {
+        ["20 @ 9", "15 @ 2", "10 @ 3", "5 @ 1", "5"]
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "20".parse::<WeightedContainer>().unwrap(),
+            WeightedContainer {
+                capacity: 20,
+                cost: 0
+            }
+        );
+        assert_eq!(
+            "20 @ 3".parse::<WeightedContainer>().unwrap(),
+            WeightedContainer {
+                capacity: 20,
+                cost: 3
+            }
+        );
+        assert!("20 @".parse::<WeightedContainer>().is_err());
+    }
+
+    #[test]
+    fn test_counts_match_filler() {
+        let table = FillTable::new(25, example());
+        let filler = EggnogFiller::new(25, vec![20, 15, 10, 5, 5]);
+        assert_eq!(table.ways(), filler.clone().count() as u64);
+        assert_eq!(table.min_count(), Some(2));
+        assert_eq!(table.ways_with(2), 3);
+        assert_eq!(table.ways_with(3), 1);
+    }
+
+    #[test]
+    fn test_cheapest() {
+        let table = FillTable::new(25, example());
+
+        // 15 + 5 + 5 costs 3
+        let cheapest = table.cheapest().unwrap();
+        assert_eq!(cheapest.cost, 3);
+        let mut capacities: Vec<_> = cheapest.containers.iter().map(|c| c.capacity).collect();
+        capacities.sort_unstable();
+        assert_eq!(capacities, vec![5, 5, 15]);
+
+        // among the two-container options, 15 + 10 costs 5, and 20 + 5 costs 9
+        let cheapest = table.cheapest_with_min_count().unwrap();
+        assert_eq!(cheapest.cost, 5);
+        assert_eq!(cheapest.containers.len(), 2);
+    }
+
+    #[test]
+    fn test_impossible() {
+        let table = FillTable::new(7, example());
+        assert_eq!(table.ways(), 0);
+        assert_eq!(table.min_count(), None);
+        assert_eq!(table.cheapest(), None);
+        assert_eq!(table.cheapest_with_min_count(), None);
+    }
+}
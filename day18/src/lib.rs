@@ -78,7 +78,8 @@
 
 use aoclib::geometry::{tile::DisplayWidth, Map};
 use std::path::Path;
-use thiserror::Error;
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
 #[cfg(feature = "animate")]
 pub mod animate;
@@ -157,42 +158,101 @@ pub fn count_on(grid: &Grid) -> usize {
     grid.iter().filter(|light| light.is_on()).count()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let file = std::fs::File::open(input)?;
-    let buffer = std::io::BufReader::new(file);
-    let mut grid = Grid::try_from(buffer)?;
-    for _ in 0..ITERATIONS {
-        grid = next_state(&grid);
-    }
-    let on = count_on(&grid);
-    println!("{:5} lights on after {} iterations", on, ITERATIONS);
-    Ok(())
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let file = std::fs::File::open(input)?;
-    let buffer = std::io::BufReader::new(file);
-    let mut grid = Grid::try_from(buffer)?;
-    for _ in 0..ITERATIONS {
-        grid = next_state_stuck(&grid);
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    lights_on_after(input, ITERATIONS, next_state)
+}
+
+/// Count the lights which are on after animating the grid `steps` times.
+fn lights_on_after(input: &str, steps: u8, step: fn(&Grid) -> Grid) -> Result<Answer, Error> {
+    let mut grid = Grid::try_from(input)?;
+    for _ in 0..steps {
+        grid = step(&grid);
     }
     let on = count_on(&grid);
-    println!(
-        "{:5} lights on after {} iterations (part 2)",
-        on, ITERATIONS
-    );
-    Ok(())
+    Ok(on.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    lights_on_after(input, ITERATIONS, next_state_stuck)
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "\
+.#.#.#
+...##.
+#....#
+..#...
+#.#..#
+####..
+",
+        expected: "4",
+        solver: part1_example,
+    },
+    Example {
+        part: 2,
+        input: "\
+##.#.#
+...##.
+#....#
+..#...
+#.#..#
+####.#
+",
+        expected: "17",
+        solver: part2_example,
+    },
+];
+
+/// The example animates fewer steps than the real puzzle.
+/// The examples animate far fewer steps than the real puzzle.
+const EXAMPLE_STEPS: u8 = 4;
+const EXAMPLE_STEPS_STUCK: u8 = 5;
+
+fn part1_example(input: &str) -> Result<Answer, Error> {
+    lights_on_after(input, EXAMPLE_STEPS, next_state)
+}
+
+fn part2_example(input: &str) -> Result<Answer, Error> {
+    lights_on_after(input, EXAMPLE_STEPS_STUCK, next_state_stuck)
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[cfg(feature = "animate")]
-    #[error("encoding gif")]
-    Gif(#[from] gif::EncodingError),
-    #[error("could not read map")]
-    MapConversion(#[from] aoclib::geometry::map::MapConversionErr),
+/// Print the grid of the given part's worked example after each step, as the puzzle text does.
+pub fn print_example_steps(part: u8) -> Result<(), Error> {
+    let (steps, step): (u8, fn(&Grid) -> Grid) = match part {
+        1 => (EXAMPLE_STEPS, next_state),
+        _ => (EXAMPLE_STEPS_STUCK, next_state_stuck),
+    };
+    let example = EXAMPLES
+        .iter()
+        .find(|example| example.part == part)
+        .ok_or(Error::NoInput)?;
+
+    let mut grid = Grid::try_from(example.input)?;
+    println!("Initial state:\n{}", grid);
+    for n in 1..=steps {
+        grid = step(&grid);
+        let plural = if n == 1 { "" } else { "s" };
+        println!("After {} step{}:\n{}", n, plural, grid);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
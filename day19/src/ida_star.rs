@@ -0,0 +1,261 @@
+//! Exact minimal-steps search by iterative-deepening A*.
+//!
+//! Searches backwards from the medicine molecule towards `e`, undoing one replacement per step.
+//! Each replacement grows a molecule by at most some fixed number of element tokens, so the
+//! remaining token surplus divided by that maximum shrinkage is a lower bound on the number of
+//! steps still required. Only the current path is ever held in memory.
+
+use crate::{Input, Replacement};
+use std::collections::HashSet;
+
+const ELECTRON: &str = "e";
+
+/// How an IDA* search ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOutcome {
+    /// The medicine can be fabricated from `e` in this many steps, and no fewer.
+    Found(usize),
+    /// No sequence of replacements fabricates the medicine.
+    Unreachable,
+    /// The node budget was spent before the search concluded.
+    BudgetExhausted,
+}
+
+/// Result of a single depth-bounded pass.
+enum Pass {
+    Found(usize),
+    /// The smallest estimated total which exceeded the threshold, if any did.
+    Exceeded(Option<usize>),
+    OutOfBudget,
+}
+
+/// Number of element tokens in a molecule.
+///
+/// Elements begin with an uppercase letter; `e` is a token of its own.
+fn tokens(molecule: &str) -> usize {
+    if molecule == ELECTRON {
+        return 1;
+    }
+    molecule.chars().filter(char::is_ascii_uppercase).count()
+}
+
+struct Search<'a> {
+    /// replacements ordered by decreasing shrinkage, to find short paths early
+    rules: Vec<&'a Replacement>,
+    max_shrinkage: usize,
+    budget: Option<usize>,
+    nodes: usize,
+    /// the molecules on the path from the medicine to the one being expanded
+    path: HashSet<String>,
+}
+
+impl<'a> Search<'a> {
+    fn new(input: &'a Input, budget: Option<usize>) -> Search<'a> {
+        let shrinkage = |rule: &Replacement| tokens(&rule.to).saturating_sub(tokens(&rule.from));
+
+        let mut rules: Vec<_> = input.replacements.iter().collect();
+        rules.sort_by_key(|rule| std::cmp::Reverse(shrinkage(rule)));
+        let max_shrinkage = rules
+            .first()
+            .map(|rule| shrinkage(rule))
+            .unwrap_or_default();
+
+        Search {
+            rules,
+            max_shrinkage,
+            budget,
+            nodes: 0,
+            path: HashSet::new(),
+        }
+    }
+
+    /// Admissible lower bound on the steps from `molecule` back to `e`.
+    ///
+    /// `None` if `e` is provably unreachable.
+    fn heuristic(&self, molecule: &str) -> Option<usize> {
+        if molecule == ELECTRON {
+            return Some(0);
+        }
+        let surplus = tokens(molecule).saturating_sub(1);
+        if surplus == 0 {
+            // even a single-token molecule needs at least one step
+            return Some(1);
+        }
+        match self.max_shrinkage {
+            0 => None,
+            shrinkage => Some((surplus - 1) / shrinkage + 1),
+        }
+    }
+
+    /// Every molecule from which one replacement produces `molecule`.
+    fn predecessors<'b>(&'b self, molecule: &'b str) -> impl 'b + Iterator<Item = String> {
+        self.rules.iter().flat_map(move |rule| {
+            let matches: Vec<usize> = if rule.from == ELECTRON {
+                // `e` only ever appears alone
+                if molecule == rule.to {
+                    vec![0]
+                } else {
+                    Vec::new()
+                }
+            } else {
+                molecule
+                    .match_indices(rule.to.as_str())
+                    .map(|(idx, _)| idx)
+                    .collect()
+            };
+            matches.into_iter().map(move |idx| {
+                format!(
+                    "{}{}{}",
+                    &molecule[..idx],
+                    rule.from,
+                    &molecule[idx + rule.to.len()..]
+                )
+            })
+        })
+    }
+
+    fn pass(&mut self, molecule: &str, steps: usize, threshold: usize) -> Pass {
+        self.nodes += 1;
+        if matches!(self.budget, Some(budget) if self.nodes > budget) {
+            return Pass::OutOfBudget;
+        }
+
+        let estimate = match self.heuristic(molecule) {
+            Some(h) => steps + h,
+            None => return Pass::Exceeded(None),
+        };
+        if estimate > threshold {
+            return Pass::Exceeded(Some(estimate));
+        }
+        if molecule == ELECTRON {
+            return Pass::Found(steps);
+        }
+
+        let mut outcome = Pass::Exceeded(None);
+        let predecessors: Vec<String> = self.predecessors(molecule).collect();
+        self.path.insert(molecule.to_owned());
+        for predecessor in predecessors {
+            // returning to a molecule already on the path can only make it longer; without
+            // pruning, rules which undo each other would raise the threshold forever
+            if self.path.contains(&predecessor) {
+                continue;
+            }
+            match (self.pass(&predecessor, steps + 1, threshold), &outcome) {
+                (Pass::Exceeded(Some(t)), Pass::Exceeded(Some(next))) => {
+                    outcome = Pass::Exceeded(Some(t.min(*next)))
+                }
+                (Pass::Exceeded(Some(t)), _) => outcome = Pass::Exceeded(Some(t)),
+                (Pass::Exceeded(None), _) => {}
+                (done, _) => {
+                    outcome = done;
+                    break;
+                }
+            }
+        }
+        self.path.remove(molecule);
+        outcome
+    }
+}
+
+impl Input {
+    /// Find the minimal number of steps to fabricate the medicine from `e` by IDA*.
+    ///
+    /// This is exact but may take exponential time; `budget` limits the total number of nodes
+    /// expanded across all iterations.
+    pub(crate) fn ida_star(&self, budget: Option<usize>) -> SearchOutcome {
+        let mut search = Search::new(self, budget);
+        let mut threshold = match search.heuristic(&self.medicine) {
+            Some(h) => h,
+            None => return SearchOutcome::Unreachable,
+        };
+
+        loop {
+            match search.pass(&self.medicine, 0, threshold) {
+                Pass::Found(steps) => return SearchOutcome::Found(steps),
+                Pass::Exceeded(Some(next)) => threshold = next,
+                Pass::Exceeded(None) => return SearchOutcome::Unreachable,
+                Pass::OutOfBudget => return SearchOutcome::BudgetExhausted,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = "
+e => H
+e => O
+H => HO
+H => OH
+O => HH
+";
+
+    fn input(medicine: &str) -> Input {
+        format!("{}{}", RULES, medicine).trim().parse().unwrap()
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(input("HOH").ida_star(None), SearchOutcome::Found(3));
+        assert_eq!(input("HOHOHO").ida_star(None), SearchOutcome::Found(6));
+    }
+
+    #[test]
+    fn test_trivial() {
+        assert_eq!(input("e").ida_star(None), SearchOutcome::Found(0));
+        assert_eq!(input("H").ida_star(None), SearchOutcome::Found(1));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        assert_eq!(input("HOX").ida_star(None), SearchOutcome::Unreachable);
+    }
+
+    #[test]
+    fn test_cyclic_unreachable() {
+        // `H` and `O` turn into each other without changing length, and nothing makes `HOH`
+        let input = |medicine: &str| -> Input {
+            format!("e => HO\nH => O\nO => H\n\n{}", medicine)
+                .parse()
+                .unwrap()
+        };
+        assert_eq!(input("H").ida_star(None), SearchOutcome::Unreachable);
+        assert_eq!(input("HOH").ida_star(None), SearchOutcome::Unreachable);
+        assert_eq!(input("OH").ida_star(None), SearchOutcome::Found(3));
+    }
+
+    #[test]
+    fn test_budget() {
+        assert_eq!(
+            input("HOHOHO").ida_star(Some(3)),
+            SearchOutcome::BudgetExhausted
+        );
+    }
+
+    #[test]
+    fn test_agrees_with_formula() {
+        // shaped like the real input: `Rn`, `Y`, and `Ar` are never replaced
+        let input: Input = "
+e => HF
+e => NAl
+H => HCa
+H => NRnFAr
+Ca => CaCa
+Ca => PRnFYFAr
+F => CaF
+N => CRnFYFAr
+Al => ThF
+Th => ThCa
+CRnFYFArThCaCaF
+"
+        .trim()
+        .parse()
+        .unwrap();
+        assert_eq!(
+            input.ida_star(None),
+            SearchOutcome::Found(input.count_fabrication_steps())
+        );
+    }
+}
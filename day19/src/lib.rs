@@ -42,8 +42,14 @@
 //! molecule for which you need to calibrate the machine. How many distinct molecules can be
 //! created after all the different ways you can do one replacement on the medicine molecule?
 
-use std::{collections::HashSet, convert::TryFrom, path::Path, str::FromStr};
-use thiserror::Error;
+mod ida_star;
+mod random_restart;
+
+pub use ida_star::SearchOutcome;
+
+use std::{collections::HashSet, path::Path, str::FromStr};
+pub use util::Error;
+use util::{example::Example, lint::Diagnostic, Answer, InputSource};
 
 #[derive(Debug, Clone, PartialEq, Eq, parse_display::FromStr, parse_display::Display)]
 #[display("{from} => {to}")]
@@ -73,7 +79,7 @@ impl FromStr for Input {
                 input.replacements.push(
                     line.trim()
                         .parse()
-                        .map_err(|err| Error::Parse(err, line.trim().to_string()))?,
+                        .map_err(|err| Error::parse(format!("\"{}\"", line.trim()), err))?,
                 );
             }
         }
@@ -82,15 +88,6 @@ impl FromStr for Input {
     }
 }
 
-impl TryFrom<&Path> for Input {
-    type Error = Error;
-
-    fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        let data = std::fs::read_to_string(path)?;
-        data.parse()
-    }
-}
-
 impl Input {
     fn replace<'a>(&'a self, initial: &'a str) -> impl 'a + Iterator<Item = String> {
         (0..initial.len())
@@ -136,26 +133,148 @@ impl Input {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let input = Input::try_from(input)?;
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let input: Input = input.parse()?;
     let ssr = input.single_step_replacements();
-    println!("single step replacements: {}", ssr);
-    Ok(())
+    Ok(ssr.into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let input = Input::try_from(input)?;
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let input: Input = input.parse()?;
     let fabrication_steps = input.count_fabrication_steps();
-    println!("fabrication steps: {:?}", fabrication_steps);
-    Ok(())
+    Ok(fabrication_steps.into())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: "\
+H => HO
+H => OH
+O => HH
+
+HOH
+",
+        expected: "4",
+        solver: part1_str,
+    },
+    Example {
+        part: 1,
+        input: "\
+H => HO
+H => OH
+O => HH
+
+HOHOHO
+",
+        expected: "7",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: "\
+e => H
+e => O
+H => HO
+H => OH
+O => HH
+
+HOH
+",
+        expected: "3",
+        solver: part2_example,
+    },
+    Example {
+        part: 2,
+        input: "\
+e => H
+e => O
+H => HO
+H => OH
+O => HH
+
+HOHOHO
+",
+        expected: "6",
+        solver: part2_example,
+    },
+];
+
+/// The formula in part 2 relies on the shape of the real input, which the examples don't share;
+/// solve them by exact search instead.
+fn part2_example(input: &str) -> Result<Answer, Error> {
+    let input: Input = input.parse()?;
+    match input.ida_star(None) {
+        SearchOutcome::Found(steps) => Ok(steps.into()),
+        _ => Err(Error::no_solution("medicine cannot be fabricated")),
+    }
+}
+
+/// Find the minimal fabrication steps by exact search, as a check on the formula in part 2: how
+/// the search ended, and the steps the formula counts.
+///
+/// `budget` limits the number of molecules examined.
+pub fn ida_star(
+    input: &InputSource,
+    budget: Option<usize>,
+) -> Result<(SearchOutcome, usize), Error> {
+    let input: Input = input.read_to_string()?.parse()?;
+    Ok((input.ida_star(budget), input.count_fabrication_steps()))
 }
 
-#[derive(Debug, Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("parsing \"{1}\": {0}")]
-    Parse(#[source] parse_display::ParseError, String),
+/// Find the fabrication steps by randomized greedy search, as another check on the formula in
+/// part 2: the steps found, unless every restart got stuck, and the steps the formula counts.
+///
+/// Up to `restarts` random orderings of the rules are tried; `seed` determines which.
+pub fn random_restart(
+    input: &InputSource,
+    restarts: usize,
+    seed: u64,
+) -> Result<(Option<usize>, usize), Error> {
+    let input: Input = input.read_to_string()?.parse()?;
+    let steps = input.random_restart(restarts, &mut util::rng::seeded(seed));
+    Ok((steps, input.count_fabrication_steps()))
+}
+
+/// Report every malformed line of `input`.
+///
+/// Every line but the last is a replacement; the last is the medicine molecule.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    let lines: Vec<_> = util::lint::numbered_lines(input).collect();
+    let (medicine, replacements) = match lines.split_last() {
+        Some(split) => split,
+        None => return Vec::new(),
+    };
+    let mut diagnostics: Vec<_> = replacements
+        .iter()
+        .filter_map(|(line, text)| util::lint::check::<Replacement>(*line, text, "H => HO"))
+        .collect();
+    let (line, text) = medicine;
+    if !text.trim().chars().all(|ch| ch.is_ascii_alphabetic()) {
+        diagnostics.push(Diagnostic {
+            line: *line,
+            text: text.to_string(),
+            message: "the final line must be the medicine molecule".into(),
+            expected: "HOHOHO".into(),
+        });
+    }
+    diagnostics
 }
 
 #[cfg(test)]
@@ -198,4 +317,14 @@ HOHOHO
             6,
         )
     }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint("H => HO\nO => HH\n\nHOH\n").is_empty());
+        let lines: Vec<_> = lint("H => HO\nO -> HH\n\nH => O\n")
+            .iter()
+            .map(|d| d.line)
+            .collect();
+        assert_eq!(lines, vec![2, 4]);
+    }
 }
@@ -1,52 +1,59 @@
-use aoclib::{config::Config, website::get_input};
-use day19::{part1, part2};
+use day19::{ida_star, part1_from_reader, part2_from_reader, random_restart, SearchOutcome};
 
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
 
 const DAY: u8 = 19;
 
 #[derive(StructOpt, Debug)]
 struct RunArgs {
-    /// input file
-    #[structopt(long, parse(from_os_str))]
-    input: Option<PathBuf>,
+    #[structopt(flatten)]
+    common: util::cli::RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// find the fabrication steps by exact IDA* search
+    #[structopt(long = "ida-star")]
+    ida_star: bool,
 
-    /// run part 2
-    #[structopt(long)]
-    part2: bool,
-}
+    /// maximum number of molecules for the IDA* search to examine
+    #[structopt(long = "ida-budget")]
+    ida_budget: Option<usize>,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                // this does nothing if the input file already exists, but
-                // simplifies the workflow after cloning the repo on a new computer
-                get_input(&config, 2015, DAY)?;
-                Ok(config.input_for(2015, DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// find the fabrication steps by randomized greedy search, restarting up to this many times
+    #[structopt(long = "random-restarts")]
+    random_restarts: Option<usize>,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
+    args.common.init_tracing();
+    if args.common.example {
+        args.common.run_examples(day19::EXAMPLES)?;
+        return Ok(());
     }
-    if args.part2 {
-        part2(&input_path)?;
+    let input = args.common.source(DAY)?.buffered()?;
+
+    let answers = args
+        .common
+        .run(DAY, "", &input, part1_from_reader, part2_from_reader)?;
+    args.common.check(DAY, &answers)?;
+    if args.ida_star {
+        match ida_star(&input, args.ida_budget)? {
+            (SearchOutcome::Found(steps), formula) => {
+                println!("IDA* fabrication steps: {} (formula: {})", steps, formula)
+            }
+            (SearchOutcome::Unreachable, _) => println!("IDA*: medicine cannot be fabricated"),
+            (SearchOutcome::BudgetExhausted, _) => println!("IDA*: node budget exhausted"),
+        }
+    }
+    if let Some(restarts) = args.random_restarts {
+        match random_restart(&input, restarts, args.common.seed())? {
+            (Some(steps), formula) => println!(
+                "random restart fabrication steps: {} (formula: {})",
+                steps, formula
+            ),
+            (None, _) => println!("random restart: stuck after {} restarts", restarts),
+        }
     }
     Ok(())
 }
@@ -0,0 +1,125 @@
+//! Randomized greedy search by reverse reduction.
+//!
+//! Searches backwards from the medicine molecule towards `e`, greedily undoing whichever
+//! replacement comes first in a random ordering of the rules. When no rule applies short of `e`,
+//! the search starts over with a fresh ordering. This is fast but not exact: it finds some way to
+//! fabricate the medicine, which for inputs shaped like the real one is also the shortest.
+
+use crate::{Input, Replacement};
+use util::rng::{Rng, SliceRandom};
+
+const ELECTRON: &str = "e";
+
+/// Undo the first applicable rule, at its first occurrence in `molecule`.
+fn reduce(rules: &[&Replacement], molecule: &str) -> Option<String> {
+    rules.iter().find_map(|rule| {
+        if rule.from == ELECTRON {
+            // `e` only ever appears alone
+            return Some(rule.from.clone()).filter(|_| molecule == rule.to);
+        }
+        molecule.find(rule.to.as_str()).map(|idx| {
+            format!(
+                "{}{}{}",
+                &molecule[..idx],
+                rule.from,
+                &molecule[idx + rule.to.len()..]
+            )
+        })
+    })
+}
+
+impl Input {
+    /// Find some number of steps which fabricates the medicine from `e`, trying up to `restarts`
+    /// random rule orderings.
+    ///
+    /// `None` if every ordering got stuck.
+    pub(crate) fn random_restart(&self, restarts: usize, rng: &mut impl Rng) -> Option<usize> {
+        // rules which don't shrink a molecule could undo each other forever
+        let max_steps = self.medicine.len() * self.medicine.len();
+        let mut rules: Vec<_> = self.replacements.iter().collect();
+
+        for _ in 0..restarts {
+            rules.shuffle(rng);
+            let mut molecule = self.medicine.clone();
+            let mut steps = 0;
+            while molecule != ELECTRON && steps <= max_steps {
+                match reduce(&rules, &molecule) {
+                    Some(reduced) => molecule = reduced,
+                    None => break,
+                }
+                steps += 1;
+            }
+            if molecule == ELECTRON {
+                return Some(steps);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::rng::seeded;
+
+    const RULES: &str = "
+e => H
+e => O
+H => HO
+H => OH
+O => HH
+";
+
+    fn input(medicine: &str) -> Input {
+        format!("{}{}", RULES, medicine).trim().parse().unwrap()
+    }
+
+    #[test]
+    fn test_examples() {
+        let mut rng = seeded(3);
+        assert_eq!(input("HOH").random_restart(100, &mut rng), Some(3));
+        assert_eq!(input("HOHOHO").random_restart(100, &mut rng), Some(6));
+        assert_eq!(input("e").random_restart(1, &mut rng), Some(0));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        assert_eq!(input("HOX").random_restart(10, &mut seeded(3)), None);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let input = input("HOHOHOHOH");
+        assert_eq!(
+            input.random_restart(5, &mut seeded(7)),
+            input.random_restart(5, &mut seeded(7))
+        );
+    }
+
+    #[test]
+    fn test_agrees_with_formula() {
+        // shaped like the real input: `Rn`, `Y`, and `Ar` are never replaced
+        let input: Input = "
+e => HF
+e => NAl
+H => HCa
+H => NRnFAr
+Ca => CaCa
+Ca => PRnFYFAr
+F => CaF
+N => CRnFYFAr
+Al => ThF
+Th => ThCa
+CRnFYFArThCaCaF
+"
+        .trim()
+        .parse()
+        .unwrap();
+        for seed in 0..10 {
+            assert_eq!(
+                input.random_restart(100, &mut seeded(seed)),
+                Some(input.count_fabrication_steps())
+            );
+        }
+    }
+}
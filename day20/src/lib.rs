@@ -33,10 +33,23 @@
 //! What is the lowest house number of the house to get at least as many presents as the number in
 //! your puzzle input?
 
-use aoclib::parse;
+use aoclib::input::parse_str;
 use permutohedron::heap_recursive;
 
 use std::{collections::HashSet, convert::TryInto, path::Path};
+pub use util::Error;
+use util::{
+    algo::{self, Backend},
+    example::Example,
+    progress::{Progress, Silent},
+    Answer, InputSource,
+};
+
+/// Elves are reported to the progress tracker in multiples of this.
+const PROGRESS_INTERVAL: usize = 1 << 12;
+
+/// Delivering to an array of houses suits inputs needing at most this many: 256 MiB of them.
+const MAX_SIEVED_HOUSES: u64 = 1 << 25;
 
 /// Usized floor of the square root of the input number
 pub fn usqrt(num: u64) -> u64 {
@@ -97,6 +110,10 @@ impl SieveOfErasthenes {
                         quot /= p;
                     }
                 }
+                // at most one prime factor exceeds the square root
+                if quot > 1 {
+                    ret.push(quot);
+                }
                 ret
             }
         }
@@ -144,6 +161,12 @@ impl SieveOfErasthenes {
                 self.primes.push(through);
             }
         }
+        tracing::debug!(
+            from = self.through,
+            through = num,
+            primes = self.primes.len(),
+            "extended sieve"
+        );
         self.through = num;
     }
 }
@@ -154,18 +177,98 @@ pub fn presents_at(sieve: &mut SieveOfErasthenes, house: u64) -> u64 {
     factors.iter().fold(0, |acc, item| acc + (10 * item))
 }
 
+/// Sum the divisors of a number from its prime factorization, as produced by
+/// [`SieveOfErasthenes::factorize_prime`].
+///
+/// Each distinct prime `p` appearing `k` times contributes a factor of `1 + p + ... + p^k`.
+fn divisor_sum(prime_factors: &[u64]) -> u64 {
+    // the factorization of 1 is reported as `[1]`, which contributes nothing
+    let mut primes = prime_factors.iter().filter(|&&p| p > 1).peekable();
+    let mut sum = 1;
+    while let Some(&prime) = primes.next() {
+        let mut power = prime;
+        let mut powers_sum = 1 + prime;
+        while primes.next_if_eq(&&prime).is_some() {
+            power *= prime;
+            powers_sum += power;
+        }
+        sum *= powers_sum;
+    }
+    sum
+}
+
+/// Find the first house with at least `n` presents by factorizing each house in turn.
+///
+/// This is an alternative to [`first_house_with_n_presents`] which needs no memory proportional
+/// to `n`, and exists mainly to be benchmarked against it.
+pub fn first_house_by_factoring(n: u64) -> u64 {
+    let mut sieve = SieveOfErasthenes::new();
+    (1..)
+        .find(|&house| 10 * divisor_sum(&sieve.factorize_prime(house)) >= n)
+        .expect("house n/10 always has at least n presents")
+}
+
+/// Every divisor of a number, from its prime factorization as produced by
+/// [`SieveOfErasthenes::factorize_prime`].
+fn divisors(prime_factors: &[u64]) -> Vec<u64> {
+    let mut primes = prime_factors.iter().filter(|&&p| p > 1).peekable();
+    let mut divisors = vec![1];
+    while let Some(&prime) = primes.next() {
+        let mut multiplicity = 1;
+        while primes.next_if_eq(&&prime).is_some() {
+            multiplicity += 1;
+        }
+        let known = divisors.len();
+        let mut power = 1;
+        for _ in 0..multiplicity {
+            power *= prime;
+            for idx in 0..known {
+                divisors.push(divisors[idx] * power);
+            }
+        }
+    }
+    divisors
+}
+
+/// As [`first_house_by_factoring`], where each elf stops after 50 houses but delivers 11
+/// presents to each.
+pub fn first_house_by_factoring_limited(n: u64) -> u64 {
+    let mut sieve = SieveOfErasthenes::new();
+    (1..)
+        .find(|&house| {
+            let presents: u64 = divisors(&sieve.factorize_prime(house))
+                .into_iter()
+                .filter(|&elf| house / elf <= 50)
+                .sum();
+            11 * presents >= n
+        })
+        .expect("house n/11 always has at least n presents")
+}
+
 pub fn first_house_with_n_presents(n: u64) -> Result<u64, Error> {
+    first_house_with_n_presents_progress(n, &Silent)
+}
+
+/// As [`first_house_with_n_presents`], reporting the elves dispatched so far to `progress`.
+pub fn first_house_with_n_presents_progress(n: u64, progress: &dyn Progress) -> Result<u64, Error> {
     // the brute force of memory way!
     let n = n.try_into()?;
     let stop = (n / 10) + 1;
     // we have an upper bound for the answer: even if nobody else stops there, elf `n/10` will
     // stop by and drop off that many right away
     let mut houses = vec![0; stop];
+    tracing::debug!(houses = stop, "allocated house sieve");
+    progress.set_total(stop as u64);
     for elf in 1..stop {
+        if elf % PROGRESS_INTERVAL == 0 {
+            tracing::trace!(elves = elf, "dispatched elves");
+            progress.advance(elf as u64);
+        }
         for j in (1..).map(|jj| jj * elf).take_while(|jj| jj < &stop) {
             houses[j] += elf * 10;
         }
     }
+    progress.finish();
     for (i, h) in houses.iter().enumerate() {
         if h >= &n {
             return Ok(i.try_into()?);
@@ -175,13 +278,28 @@ pub fn first_house_with_n_presents(n: u64) -> Result<u64, Error> {
 }
 
 pub fn first_house_with_n_presents_limited(n: u64) -> Result<u64, Error> {
+    first_house_with_n_presents_limited_progress(n, &Silent)
+}
+
+/// As [`first_house_with_n_presents_limited`], reporting the elves dispatched so far to
+/// `progress`.
+pub fn first_house_with_n_presents_limited_progress(
+    n: u64,
+    progress: &dyn Progress,
+) -> Result<u64, Error> {
     // the brute force of memory way!
     let n = n.try_into()?;
     let stop = (n / 10) + 1;
     // we have an upper bound for the answer: even if nobody else stops there, elf `n/10` will
     // stop by and drop off that many right away
     let mut houses = vec![0; stop];
+    tracing::debug!(houses = stop, "allocated house sieve");
+    progress.set_total(stop as u64);
     for elf in 1..stop {
+        if elf % PROGRESS_INTERVAL == 0 {
+            tracing::trace!(elves = elf, "dispatched elves");
+            progress.advance(elf as u64);
+        }
         for j in (1..)
             .map(|jj| jj * elf)
             .take_while(|jj| jj < &stop)
@@ -190,6 +308,7 @@ pub fn first_house_with_n_presents_limited(n: u64) -> Result<u64, Error> {
             houses[j] += elf * 11;
         }
     }
+    progress.finish();
     for (i, h) in houses.iter().enumerate() {
         if h >= &n {
             return Ok(i.try_into()?);
@@ -198,34 +317,134 @@ pub fn first_house_with_n_presents_limited(n: u64) -> Result<u64, Error> {
     Ok(0)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for presents in parse::<u64>(input)? {
-        println!(
-            "First house with {} presents: {}",
-            presents,
-            first_house_with_n_presents(presents)?,
-        );
+/// The presents sought, and where to report progress towards finding them.
+pub struct Street<'a> {
+    pub presents: u64,
+    pub progress: &'a dyn Progress,
+}
+
+/// Delivers presents to an array of houses: quickest, but needs memory for every house.
+pub struct Sieve;
+
+impl<'a> Backend<Street<'a>> for Sieve {
+    fn name(&self) -> &'static str {
+        "sieve"
+    }
+
+    fn suits(&self, street: &Street<'a>) -> bool {
+        street.presents / 10 <= MAX_SIEVED_HOUSES
+    }
+
+    fn part1(&self, street: &Street<'a>) -> Result<Answer, Error> {
+        Ok(first_house_with_n_presents_progress(street.presents, street.progress)?.into())
+    }
+
+    fn part2(&self, street: &Street<'a>) -> Result<Answer, Error> {
+        Ok(first_house_with_n_presents_limited_progress(street.presents, street.progress)?.into())
     }
-    Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for presents in parse::<u64>(input)? {
-        println!(
-            "First house with {} presents with lazy elves: {}",
-            presents,
-            first_house_with_n_presents_limited(presents)?,
-        );
+/// Factorizes each house in turn: slower, but needs no memory per house.
+pub struct Factoring;
+
+impl<'a> Backend<Street<'a>> for Factoring {
+    fn name(&self) -> &'static str {
+        "factoring"
+    }
+
+    fn suits(&self, _street: &Street<'a>) -> bool {
+        true
+    }
+
+    fn part1(&self, street: &Street<'a>) -> Result<Answer, Error> {
+        Ok(first_house_by_factoring(street.presents).into())
+    }
+
+    fn part2(&self, street: &Street<'a>) -> Result<Answer, Error> {
+        Ok(first_house_by_factoring_limited(street.presents).into())
+    }
+}
+
+/// The algorithms which can find houses, in order of preference.
+pub const BACKENDS: &[&dyn for<'a> Backend<Street<'a>>] = &[&Sieve, &Factoring];
+
+fn solve(
+    input: &str,
+    limited: bool,
+    progress: &dyn Progress,
+    algo: Option<&str>,
+) -> Result<Answer, Error> {
+    let presents = parse_str::<u64>(input)?.next().ok_or(Error::NoInput)?;
+    let street = Street { presents, progress };
+    let backend = algo::select(BACKENDS, &street, algo)?;
+    if limited {
+        backend.part2(&street)
+    } else {
+        backend.part1(&street)
     }
-    Ok(())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("value cannot fit into `usize` on this architecture")]
-    Conversion(#[from] std::num::TryFromIntError),
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_with_progress(InputSource::from(input).reader()?, &Silent)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_with_progress(input, &Silent)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    solve(input, false, &Silent, None)
+}
+
+pub fn part1_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    part1_with_algo(input, progress, None)
+}
+
+/// As [`part1_with_progress`], solving with the backend named `algo` if given.
+pub fn part1_with_algo(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+    algo: Option<&str>,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, false, progress, algo)
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_with_progress(InputSource::from(input).reader()?, &Silent)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_with_progress(input, &Silent)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    solve(input, true, &Silent, None)
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    part: 1,
+    input: "150",
+    expected: "8",
+    solver: part1_str,
+}];
+
+pub fn part2_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    part2_with_algo(input, progress, None)
+}
+
+/// As [`part2_with_progress`], solving with the backend named `algo` if given.
+pub fn part2_with_algo(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+    algo: Option<&str>,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, true, progress, algo)
 }
 
 #[cfg(test)]
@@ -264,6 +483,18 @@ mod tests {
             360,
         ];
         assert_eq!(sieve.factorize(360), expected);
+        assert_eq!(sieve.factorize(42), vec![1, 2, 3, 6, 7, 14, 21, 42]);
+    }
+
+    #[test]
+    fn test_divisor_sum() {
+        let mut sieve = SieveOfErasthenes::new();
+        for house in 1..200 {
+            assert_eq!(
+                10 * divisor_sum(&sieve.factorize_prime(house)),
+                presents_at(&mut sieve, house)
+            );
+        }
     }
 
     #[test]
@@ -272,4 +503,47 @@ mod tests {
             assert_eq!(first_house_with_n_presents(input).unwrap(), output);
         }
     }
+
+    #[test]
+    fn test_divisors() {
+        let mut sieve = SieveOfErasthenes::new();
+        for house in 1..200 {
+            let mut divisors = divisors(&sieve.factorize_prime(house));
+            divisors.sort_unstable();
+            assert_eq!(divisors, sieve.factorize(house));
+        }
+    }
+
+    #[test]
+    fn test_limited_factoring_agrees() {
+        for n in (10..20_000).step_by(370) {
+            assert_eq!(
+                first_house_by_factoring_limited(n),
+                first_house_with_n_presents_limited(n).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_by_size() {
+        let name = |presents| {
+            let street = Street {
+                presents,
+                progress: &Silent,
+            };
+            algo::select(BACKENDS, &street, None).unwrap().name()
+        };
+        assert_eq!(name(34_000_000), "sieve");
+        assert_eq!(name(u64::MAX), "factoring");
+    }
+
+    #[test]
+    fn test_factoring_agrees() {
+        for n in (10..20_000).step_by(370) {
+            assert_eq!(
+                first_house_by_factoring(n),
+                first_house_with_n_presents(n).unwrap()
+            );
+        }
+    }
 }
@@ -63,6 +63,8 @@
 //! amount of gold you can spend and still win the fight?
 
 use std::path::Path;
+pub use util::Error;
+use util::{example::Example, input::find_group, lint::Diagnostic, Answer, InputSource};
 
 mod character;
 mod items;
@@ -74,7 +76,18 @@ use items::{item_shop, Item};
 use loadout::Loadout;
 use loadout_generator::loadout_generator;
 
-pub fn combat(mut agent: Character, mut respondent: Character) -> Character {
+pub fn combat(agent: Character, respondent: Character) -> Character {
+    fight(agent, respondent, |_, _, _| {})
+}
+
+/// Fight until a character falls, returning the victor.
+///
+/// `on_hit` sees the attacker, the defender after the hit, and the damage dealt.
+fn fight(
+    mut agent: Character,
+    mut respondent: Character,
+    mut on_hit: impl FnMut(&Character, &Character, u32),
+) -> Character {
     loop {
         // calc damage
         let damage = if respondent.armor < agent.damage {
@@ -84,10 +97,10 @@ pub fn combat(mut agent: Character, mut respondent: Character) -> Character {
         };
 
         // apply
-        if damage >= respondent.hp {
+        respondent.hp = respondent.hp.saturating_sub(damage);
+        on_hit(&agent, &respondent, damage);
+        if respondent.hp == 0 {
             return agent;
-        } else {
-            respondent.hp -= damage;
         }
 
         // swap roles
@@ -113,31 +126,108 @@ pub fn priciest_losing_loadout(items: &[Item], boss: Character) -> Option<(Loado
         .max_by_key(|(loadout, _)| loadout.cost())
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for boss in aoclib::input::parse_newline_sep::<Character>(input)? {
-        if let Some((loadout, _)) = cheapest_winning_loadout(&item_shop(), boss) {
-            println!("cheapest winning loadout cost: {}", loadout.cost());
-        } else {
-            println!("no winning loadout found");
+fn no_loadout() -> Error {
+    Error::no_solution("no suitable loadout found")
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let boss = find_group::<Character>(input).ok_or(Error::NoInput)?;
+    let (loadout, _) = cheapest_winning_loadout(&item_shop(), boss).ok_or_else(no_loadout)?;
+    Ok(loadout.cost().into())
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let boss = find_group::<Character>(input).ok_or(Error::NoInput)?;
+    let (loadout, _) = priciest_losing_loadout(&item_shop(), boss).ok_or_else(no_loadout)?;
+    Ok(loadout.cost().into())
+}
+
+/// The puzzle text works through no example to an answer.
+pub const EXAMPLES: &[Example] = &[];
+
+/// Print the blow-by-blow of the fight the puzzle text plays out.
+pub fn print_example_fight() {
+    fn name(character: &Character) -> &'static str {
+        match character.ctype {
+            CharacterType::Player => "player",
+            CharacterType::Boss => "boss",
         }
     }
-    Ok(())
+
+    let player = Character {
+        ctype: CharacterType::Player,
+        hp: 8,
+        damage: 5,
+        armor: 5,
+    };
+    let boss = Character {
+        ctype: CharacterType::Boss,
+        hp: 12,
+        damage: 7,
+        armor: 2,
+    };
+    let winner = fight(player, boss, |attacker, defender, damage| {
+        println!(
+            "- The {} deals {}-{} = {} damage; the {} goes down to {} hit points.",
+            name(attacker),
+            attacker.damage,
+            defender.armor,
+            damage,
+            name(defender),
+            defender.hp,
+        )
+    });
+    println!("The {} wins!", name(&winner));
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for boss in aoclib::input::parse_newline_sep::<Character>(input)? {
-        if let Some((loadout, _)) = priciest_losing_loadout(&item_shop(), boss) {
-            println!("priciest losing loadout cost: {}", loadout.cost());
-        } else {
-            println!("no losing loadout found");
+/// Report every malformed line of `input`.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    const EXPECTED: &str = "Hit Points: 104";
+    let mut diagnostics = Vec::new();
+    for (line, text) in util::lint::numbered_lines(input) {
+        let mut parts = text.splitn(2, ':');
+        let key = parts.next().unwrap_or_default().trim();
+        let value = parts.next().map(str::trim);
+        let message = match (key, value) {
+            ("Hit Points", Some(value)) | ("Damage", Some(value)) | ("Armor", Some(value)) => {
+                value.parse::<u32>().err().map(|err| err.to_string())
+            }
+            _ => Some("expected one of \"Hit Points\", \"Damage\", or \"Armor\"".into()),
+        };
+        if let Some(message) = message {
+            diagnostics.push(Diagnostic {
+                line,
+                text: text.to_string(),
+                message,
+                expected: EXPECTED.into(),
+            });
         }
     }
-    Ok(())
-}
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+    if diagnostics.is_empty() && find_group::<Character>(input).is_none() {
+        diagnostics.push(Diagnostic {
+            line: 1,
+            text: input.lines().next().unwrap_or_default().to_string(),
+            message: "the boss needs hit points, damage, and armor, in that order".into(),
+            expected: EXPECTED.into(),
+        });
+    }
+    diagnostics
 }
 
 #[cfg(test)]
@@ -162,4 +252,15 @@ mod tests {
         assert_eq!(winner.ctype, CharacterType::Player);
         assert_eq!(winner.hp, 2);
     }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint("Hit Points: 104\nDamage: 8\nArmor: 1\n").is_empty());
+        let lines: Vec<_> = lint("Hit Points: lots\nDamage: 8\nArmour: 1\n")
+            .iter()
+            .map(|d| d.line)
+            .collect();
+        assert_eq!(lines, vec![1, 3]);
+        assert_eq!(lint("Damage: 8\nArmor: 1\n").len(), 1);
+    }
 }
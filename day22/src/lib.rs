@@ -141,7 +141,20 @@ use effects::recharge::Recharge;
 use effects::shield::Shield;
 use effects::{EffectImpl, Effects, Magic};
 
-use std::{collections::VecDeque, path::Path};
+use std::{
+    collections::{BinaryHeap, VecDeque},
+    path::Path,
+};
+pub use util::Error;
+use util::{
+    example::Example,
+    input::find_group,
+    progress::{Progress, Silent},
+    Answer, InputSource,
+};
+
+/// Explored arenas are reported to the progress tracker in multiples of this.
+const PROGRESS_INTERVAL: u64 = 1 << 12;
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum CharacterType {
@@ -415,12 +428,34 @@ pub fn breadth_first_victory_search(arena: Arena) -> Arena {
 }
 
 pub fn breadth_first_victory_search_with_difficulty(arena: Arena, hard: bool) -> Arena {
+    breadth_first_victory_search_with_progress(arena, hard, &Silent)
+}
+
+/// As [`breadth_first_victory_search_with_difficulty`], reporting the number of arenas explored
+/// so far to `progress`.
+pub fn breadth_first_victory_search_with_progress(
+    arena: Arena,
+    hard: bool,
+    progress: &dyn Progress,
+) -> Arena {
     let mut found_victory = false;
     let mut candidates = Vec::new();
     let mut buffer = VecDeque::new();
+    let mut explored: u64 = 0;
     buffer.push_back(arena);
     while !buffer.is_empty() {
+        explored += 1;
+        // the interval is a power of two
+        if explored & (PROGRESS_INTERVAL - 1) == 0 {
+            progress.advance(explored);
+        }
         let mut arena = buffer.pop_front().unwrap();
+        tracing::trace!(
+            explored,
+            queued = buffer.len(),
+            mana_spent = arena.mana_spent,
+            "expanding arena"
+        );
         match if hard {
             arena.hard_turn()
         } else {
@@ -433,12 +468,21 @@ pub fn breadth_first_victory_search_with_difficulty(arena: Arena, hard: bool) ->
             }
             Err(victor) => {
                 if victor == CharacterType::Player {
+                    if !found_victory {
+                        tracing::debug!(
+                            explored,
+                            mana_spent = arena.mana_spent,
+                            "found first victory; no longer expanding"
+                        );
+                    }
                     found_victory = true;
                     candidates.push(arena);
                 }
             }
         }
     }
+    progress.finish();
+    tracing::debug!(explored, victories = candidates.len(), "search complete");
     candidates
         .iter()
         .fold(None, |acc, c| match acc {
@@ -449,26 +493,149 @@ pub fn breadth_first_victory_search_with_difficulty(arena: Arena, hard: bool) ->
         .clone()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for boss in aoclib::input::parse_newline_sep::<Character>(input)? {
-        let min = breadth_first_victory_search(Arena::with_boss(boss));
-        println!("Min mana required for easy victory: {}", min.mana_spent);
+/// Orders arenas so that a max-heap yields the one with the least mana spent.
+struct CheapestFirst(Arena);
+
+impl PartialEq for CheapestFirst {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.mana_spent == other.0.mana_spent
+    }
+}
+
+impl Eq for CheapestFirst {}
+
+impl PartialOrd for CheapestFirst {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CheapestFirst {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.0.mana_spent.cmp(&self.0.mana_spent)
     }
-    Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    for boss in aoclib::input::parse_newline_sep::<Character>(input)? {
-        let min = breadth_first_victory_search_with_difficulty(Arena::with_boss(boss), true);
-        println!("Min mana required for hard victory: {}", min.mana_spent);
+/// Find the cheapest victory by always expanding the arena with the least mana spent.
+///
+/// Mana spent never decreases from one turn to the next, so the first victory found is the
+/// cheapest, and the search stops there. This is an alternative to
+/// [`breadth_first_victory_search_with_difficulty`] which exists mainly to be benchmarked
+/// against it.
+pub fn cheapest_first_victory_search(arena: Arena, hard: bool) -> Option<Arena> {
+    let mut heap = BinaryHeap::new();
+    heap.push(CheapestFirst(arena));
+    while let Some(CheapestFirst(mut arena)) = heap.pop() {
+        match if hard {
+            arena.hard_turn()
+        } else {
+            arena.turn()
+        } {
+            Ok(futures) => heap.extend(futures.into_iter().map(CheapestFirst)),
+            Err(CharacterType::Player) => return Some(arena),
+            Err(CharacterType::Boss) => {}
+        }
+    }
+    None
+}
+
+/// Play out a fight in which the player casts `spells` in order.
+///
+/// Returns the final arena and the victor, or `None` if a spell can't be cast when its turn
+/// comes up.
+pub fn scripted_fight(mut arena: Arena, spells: &[Effects]) -> Option<(Arena, CharacterType)> {
+    let mut spells = spells.iter();
+    loop {
+        let futures = match arena.turn() {
+            Ok(futures) => futures,
+            Err(victor) => return Some((arena, victor)),
+        };
+        arena = match arena.turn {
+            CharacterType::Player => {
+                let spell = spells.next()?;
+                futures
+                    .into_iter()
+                    .find(|future| future.last_spell.as_ref() == Some(spell))?
+            }
+            CharacterType::Boss => futures.into_iter().next()?,
+        };
+    }
+}
+
+fn solve(input: &str, hard: bool, progress: &dyn Progress) -> Result<Answer, Error> {
+    let boss = find_group::<Character>(input).ok_or(Error::NoInput)?;
+    let min = breadth_first_victory_search_with_progress(Arena::with_boss(boss), hard, progress);
+    Ok(min.mana_spent.into())
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_with_progress(InputSource::from(input).reader()?, &Silent)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_with_progress(input, &Silent)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    solve(input, false, &Silent)
+}
+
+pub fn part1_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, false, progress)
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_with_progress(InputSource::from(input).reader()?, &Silent)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_with_progress(input, &Silent)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    solve(input, true, &Silent)
+}
+
+/// The puzzle text works through no example to an answer.
+pub const EXAMPLES: &[Example] = &[];
+
+/// The fights the puzzle text plays out: the boss's hit points, and the player's spells in order.
+const EXAMPLE_FIGHTS: &[(u8, &[Effects])] = &[
+    (13, &[Effects::Poison, Effects::MagicMissile]),
+    (
+        14,
+        &[
+            Effects::Recharge,
+            Effects::Shield,
+            Effects::Drain,
+            Effects::Poison,
+            Effects::MagicMissile,
+        ],
+    ),
+];
+
+/// Print the combat log of each fight the puzzle text plays out.
+pub fn print_example_fights() -> Result<(), Error> {
+    for (boss_hp, spells) in EXAMPLE_FIGHTS {
+        let arena = Arena::new(
+            Character::makeplayer(10, 250),
+            Character::makeboss(*boss_hp, 8),
+        );
+        let (arena, _) = scripted_fight(arena, spells)
+            .ok_or_else(|| Error::no_solution("an example spell could not be cast"))?;
+        println!("{}", arena.log());
     }
     Ok(())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part2_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, true, progress)
 }
 
 #[cfg(test)]
@@ -593,4 +760,29 @@ mod tests {
         arena = expect_spell(arena, Effects::MagicMissile);
         expect_victor(arena, pt);
     }
+
+    #[test]
+    fn test_example_fights() {
+        for (boss_hp, spells) in EXAMPLE_FIGHTS {
+            let arena = Arena::new(
+                Character::makeplayer(10, 250),
+                Character::makeboss(*boss_hp, 8),
+            );
+            let (arena, victor) = scripted_fight(arena, spells).unwrap();
+            assert_eq!(victor, CharacterType::Player);
+            assert!(arena
+                .log()
+                .ends_with("This kills the boss, and the player wins.\n"));
+        }
+    }
+
+    #[test]
+    fn test_searches_agree() {
+        for &boss in &[Character::makeboss(13, 8), Character::makeboss(14, 8)] {
+            let arena = Arena::new(Character::makeplayer(10, 250), boss);
+            let breadth_first = breadth_first_victory_search(arena.clone());
+            let cheapest_first = cheapest_first_victory_search(arena, false).unwrap();
+            assert_eq!(breadth_first.mana_spent, cheapest_first.mana_spent);
+        }
+    }
 }
@@ -34,6 +34,8 @@
 //! ```
 
 use std::{ops::AddAssign, path::Path};
+pub use util::Error;
+use util::{example::Example, lint::Diagnostic, Answer, InputSource};
 
 type Pointer = i32;
 
@@ -181,27 +183,60 @@ impl Cpu {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let instructions = aoclib::parse(input)?.collect();
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let instructions = aoclib::input::parse_str(input)?.collect();
     let mut cpu = Cpu::from_instructions(instructions);
     cpu.run();
-    println!("Terminating with register B = '{}'", cpu.get(Register::B));
-    Ok(())
+    Ok(cpu.get(Register::B).into())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let instructions = aoclib::parse(input)?.collect();
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_str(&InputSource::from(input).read_to_string()?)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_str(&util::input::read_all(input)?)
+}
+
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    let instructions = aoclib::input::parse_str(input)?.collect();
     let mut cpu = Cpu::from_instructions(instructions);
     cpu.set(Register::A, 1);
     cpu.run();
-    println!("Terminating with register B = '{}'", cpu.get(Register::B));
-    Ok(())
+    Ok(cpu.get(Register::B).into())
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    part: 1,
+    input: "\
+inc a
+jio a, +2
+tpl a
+inc a
+",
+    expected: "2",
+    solver: part1_example,
+}];
+
+/// The example leaves its result in register `a` rather than `b`.
+fn part1_example(input: &str) -> Result<Answer, Error> {
+    let instructions = aoclib::input::parse_str(input)?.collect();
+    let mut cpu = Cpu::from_instructions(instructions);
+    cpu.run();
+    Ok(cpu.get(Register::A).into())
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+/// Report every malformed line of `input`.
+pub fn lint(input: &str) -> Vec<Diagnostic> {
+    util::lint::lines::<Instruction>(input, "jio a, +19")
 }
 
 #[cfg(test)]
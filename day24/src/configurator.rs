@@ -1,7 +1,9 @@
 use std::cmp::Reverse;
+use util::progress::Progress;
 
 use crate::{
-    bounded_permutation_generator::BoundedPermutationGenerator, Compartment, Package, PackingList,
+    bounded_permutation_generator::BoundedPermutationGenerator, partition::partition_remainder,
+    Compartment, Package, PackingList,
 };
 
 type Solution = Vec<Option<Compartment>>;
@@ -53,13 +55,21 @@ impl<'a> Configurator<'a> {
         }
     }
 
-    /// Generate partial solutions for which the trunk is not considered.
-    ///
-    /// In these solutions the footwell is assigned. The sides have not been fully assigned, but
-    /// it has been demonstrated that at least one assignment is possible for the sides.
+    /// Generate complete solutions, with or without the trunk as appropriate.
     ///
-    /// The trunk is not used.
-    fn generate_footwell_no_trunk(&self) -> impl '_ + Iterator<Item = Solution> {
+    /// Note that not all complete solutions are generated. The footwell's solutions are exhaustively
+    /// generated, but all other compartments only have demonstration solutions.
+    fn generate_footwell(&self) -> impl '_ + Iterator<Item = Solution> {
+        let others: &[Compartment] = if self.use_trunk {
+            &[
+                Compartment::LeftSaddle,
+                Compartment::RightSaddle,
+                Compartment::Trunk,
+            ]
+        } else {
+            &[Compartment::LeftSaddle, Compartment::RightSaddle]
+        };
+
         BoundedPermutationGenerator::new(&self.packages, self.side_weight)
             .expect("sort guaranteed by the constructor")
             .into_iter(Compartment::Footwell)
@@ -67,66 +77,10 @@ impl<'a> Configurator<'a> {
                 // we need to ensure that it is possible to generate at least one full solution from
                 // this partial solution, but we don't need to bother actually generating more than
                 // one.
-                BoundedPermutationGenerator::from_solution(
-                    &self.packages,
-                    self.side_weight,
-                    partial_solution,
-                )
-                .expect("sort guaranteed by the constructor")
-                .into_iter(Compartment::LeftSaddle)
-                .next()
-            })
-    }
-
-    /// Generate partial solutions for which the trunk is considered.
-    ///
-    /// In these solutions the footwell is assigned. The sides have not been fully assigned, but
-    /// it has been demonstrated that at least one assignment is possible for the sides.
-    ///
-    /// The trunk is used.
-    fn generate_footwell_with_trunk(&self) -> impl '_ + Iterator<Item = Solution> {
-        self.generate_footwell_no_trunk()
-            .filter_map(move |partial_solution| {
-                // we need to ensure that it is possible to generate at least one full solution from
-                // this partial solution, but we don't need to bother actually generating more than
-                // one.
-                BoundedPermutationGenerator::from_solution(
-                    &self.packages,
-                    self.side_weight,
-                    partial_solution,
-                )
-                .expect("sort guaranteed by the constructor")
-                .into_iter(Compartment::RightSaddle)
-                .next()
+                partition_remainder(self.packages, self.side_weight, partial_solution, others)
             })
     }
 
-    /// Generate complete solutions, with or without the trunk as appropriate.
-    ///
-    /// Note that not all complete solutions are generated. The footwell's solutions are exhaustively
-    /// generated, but all other compartments only have demonstration solutions.
-    fn generate_footwell(&self) -> Box<dyn '_ + Iterator<Item = Solution>> {
-        if self.use_trunk {
-            Box::new(self.generate_footwell_with_trunk().map(|mut partial| {
-                partial.iter_mut().for_each(|item| {
-                    if item.is_none() {
-                        *item = Some(Compartment::Trunk)
-                    }
-                });
-                partial
-            }))
-        } else {
-            Box::new(self.generate_footwell_no_trunk().map(|mut partial| {
-                partial.iter_mut().for_each(|item| {
-                    if item.is_none() {
-                        *item = Some(Compartment::RightSaddle)
-                    }
-                });
-                partial
-            }))
-        }
-    }
-
     /// Generate a sequence of packing lists satisfying the given balance constraints.
     pub fn packing_lists(&self) -> impl '_ + Iterator<Item = PackingList> {
         self.generate_footwell()
@@ -138,9 +92,14 @@ impl<'a> Configurator<'a> {
     /// The best sleigh configuration is the one for which `sleigh.foot.len()` is minimal. If
     /// multiple sleighs can be configured with equal numbers of items in the footwells, the best
     /// of those is the one for which `sleigh.foot_qe()` is minimal.
-    pub fn best(&self) -> Option<PackingList> {
-        self.packing_lists()
-            .map(|packing_list| {
+    ///
+    /// The number of packing lists considered so far is reported to `progress`.
+    pub fn best(&self, progress: &dyn Progress) -> Option<PackingList<'_>> {
+        let best = self
+            .packing_lists()
+            .enumerate()
+            .map(|(idx, packing_list)| {
+                progress.advance(idx as u64 + 1);
                 (
                     packing_list.packages_in(Compartment::Footwell).count(),
                     packing_list.qe(Compartment::Footwell),
@@ -148,6 +107,8 @@ impl<'a> Configurator<'a> {
                 )
             })
             .min()
-            .map(|(_, _, packing_list)| packing_list)
+            .map(|(_, _, packing_list)| packing_list);
+        progress.finish();
+        best
     }
 }
@@ -55,51 +55,104 @@ mod bounded_permutation_generator;
 mod compartment;
 mod configurator;
 mod packing_list;
+mod partition;
 
 use std::path::Path;
+pub use util::Error;
+use util::{
+    example::Example,
+    progress::{Progress, Silent},
+    Answer, InputSource,
+};
 
+pub use crate::partition::can_partition;
 pub(crate) use crate::{
     compartment::Compartment, configurator::Configurator, packing_list::PackingList,
 };
 
 pub type Package = u16;
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let mut packages: Vec<Package> = aoclib::parse(input)?.collect();
-    let trunk = false;
-    let configurator =
-        Configurator::new(&mut packages, trunk).ok_or(Error::NoAppropriateLoading(trunk))?;
-    let best = configurator
-        .best()
-        .ok_or(Error::NoAppropriateLoading(trunk))?;
-    println!(
-        "QE of best enganglement (no trunk):   {:12}",
-        best.qe(Compartment::Footwell)
-    );
-    Ok(())
+fn no_appropriate_loading(trunk: bool) -> Error {
+    Error::no_solution(format!(
+        "failed to find an appropriate loading (trunk: {})",
+        trunk
+    ))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut packages: Vec<Package> = aoclib::parse(input)?.collect();
-    let trunk = true;
+fn solve(input: &str, trunk: bool, progress: &dyn Progress) -> Result<Answer, Error> {
+    let mut packages: Vec<Package> = aoclib::input::parse_str(input)?.collect();
     let configurator =
-        Configurator::new(&mut packages, trunk).ok_or(Error::NoAppropriateLoading(trunk))?;
+        Configurator::new(&mut packages, trunk).ok_or_else(|| no_appropriate_loading(trunk))?;
     let best = configurator
-        .best()
-        .ok_or(Error::NoAppropriateLoading(trunk))?;
-    println!(
-        "QE of best enganglement (with trunk): {:12}",
-        best.qe(Compartment::Footwell)
-    );
-    Ok(())
+        .best(progress)
+        .ok_or_else(|| no_appropriate_loading(trunk))?;
+    Ok(best.qe(Compartment::Footwell).into())
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_with_progress(InputSource::from(input).reader()?, &Silent)
+}
+
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_with_progress(input, &Silent)
+}
+
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    solve(input, false, &Silent)
+}
+
+pub fn part1_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, false, progress)
+}
+
+pub fn part2(input: &Path) -> Result<Answer, Error> {
+    part2_with_progress(InputSource::from(input).reader()?, &Silent)
+}
+
+pub fn part2_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part2_with_progress(input, &Silent)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("failed to find an appropriate loading (trunk: {0})")]
-    NoAppropriateLoading(bool),
+pub fn part2_str(input: &str) -> Result<Answer, Error> {
+    solve(input, true, &Silent)
+}
+
+const EXAMPLE_INPUT: &str = "\
+1
+2
+3
+4
+5
+7
+8
+9
+10
+11
+";
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        part: 1,
+        input: EXAMPLE_INPUT,
+        expected: "99",
+        solver: part1_str,
+    },
+    Example {
+        part: 2,
+        input: EXAMPLE_INPUT,
+        expected: "44",
+        solver: part2_str,
+    },
+];
+
+pub fn part2_with_progress(
+    input: impl std::io::Read,
+    progress: &dyn Progress,
+) -> Result<Answer, Error> {
+    solve(&util::input::read_all(input)?, true, progress)
 }
 
 #[cfg(test)]
@@ -110,7 +163,7 @@ mod tests {
     fn test_example_no_trunk() {
         let mut items = vec![1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
         let configurator = Configurator::new(&mut items, false).unwrap();
-        let best = configurator.best().unwrap();
+        let best = configurator.best(&Silent).unwrap();
         println!("Best sleigh configuration: {:?}", best);
         assert_eq!(best.qe(Compartment::Footwell), 99);
     }
@@ -119,8 +172,39 @@ mod tests {
     fn test_example_with_trunk() {
         let mut items = vec![1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
         let configurator = Configurator::new(&mut items, true).unwrap();
-        let best = configurator.best().unwrap();
+        let best = configurator.best(&Silent).unwrap();
         println!("Best sleigh configuration: {:?}", best);
         assert_eq!(best.qe(Compartment::Footwell), 44);
     }
+
+    #[test]
+    fn test_progress_reported() {
+        use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+        #[derive(Default)]
+        struct Recorder {
+            done: AtomicU64,
+            finished: AtomicBool,
+        }
+
+        impl Progress for Recorder {
+            fn advance(&self, done: u64) {
+                self.done.fetch_max(done, Ordering::SeqCst);
+            }
+
+            fn finish(&self) {
+                self.finished.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let mut items = vec![1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+        let configurator = Configurator::new(&mut items, false).unwrap();
+        let recorder = Recorder::default();
+        configurator.best(&recorder).unwrap();
+        assert_eq!(
+            recorder.done.load(Ordering::SeqCst),
+            configurator.packing_lists().count() as u64
+        );
+        assert!(recorder.finished.load(Ordering::SeqCst));
+    }
 }
@@ -0,0 +1,148 @@
+use std::cmp::Reverse;
+
+use crate::{bounded_permutation_generator::BoundedPermutationGenerator, Package};
+
+type Solution<Subset> = Vec<Option<Subset>>;
+
+/// Determine whether `packages` can be split into `groups` groups, each weighing exactly
+/// `group_weight`.
+///
+/// Returns a witness grouping if one exists. Every package appears in exactly one group, and the
+/// packages within each group are listed heaviest first.
+///
+/// ```
+/// # use day24::can_partition;
+/// let groups = can_partition(&[1, 2, 3, 4, 5, 7, 8, 9, 10, 11], 3, 20).unwrap();
+/// assert_eq!(groups.len(), 3);
+/// assert!(groups.iter().all(|group| group.iter().sum::<u16>() == 20));
+///
+/// assert!(can_partition(&[1, 2, 3, 4, 5, 7, 8, 9, 10, 11], 4, 15).is_some());
+/// assert!(can_partition(&[1, 2, 3, 4, 5, 7, 8, 9, 10, 11], 2, 30).is_some());
+/// assert!(can_partition(&[10, 9, 1], 2, 10).is_some());
+/// assert!(can_partition(&[11, 8, 1], 2, 10).is_none());
+/// ```
+pub fn can_partition(
+    packages: &[Package],
+    groups: usize,
+    group_weight: Package,
+) -> Option<Vec<Vec<Package>>> {
+    let mut items = packages.to_vec();
+    items.sort_unstable_by_key(|package| Reverse(*package));
+
+    let total: u64 = items.iter().map(|&package| package as u64).sum();
+    if total != groups as u64 * group_weight as u64 {
+        return None;
+    }
+
+    let subsets: Vec<usize> = (0..groups).collect();
+    let solution = partition_remainder(&items, group_weight, vec![None; items.len()], &subsets)?;
+
+    let mut grouping = vec![Vec::new(); groups];
+    for (package, group) in items.into_iter().zip(solution) {
+        grouping[group.expect("every package is assigned by a complete solution")].push(package);
+    }
+    Some(grouping)
+}
+
+/// Extend a partial solution by assigning every unassigned item to one of `subsets`, such that
+/// each of those subsets sums to exactly `target_sum`.
+///
+/// Backtracks over the choices for each subset in turn, so this is exhaustive: it returns `None`
+/// only when no such assignment exists.
+///
+/// # Preconditions
+///
+/// - `items` must be reverse-sorted.
+/// - `solution.len()` must equal `items.len()`.
+pub(crate) fn partition_remainder<Subset>(
+    items: &[Package],
+    target_sum: Package,
+    solution: Solution<Subset>,
+    subsets: &[Subset],
+) -> Option<Solution<Subset>>
+where
+    Subset: Copy + Eq + Unpin,
+{
+    match subsets.split_first() {
+        None => {
+            if solution.iter().all(Option::is_some) {
+                Some(solution)
+            } else {
+                None
+            }
+        }
+        Some((&last, [])) => {
+            // the final subset has no choices to make: it gets everything which is left over
+            let remaining: u64 = items
+                .iter()
+                .zip(solution.iter())
+                .filter(|(_, assignment)| assignment.is_none())
+                .map(|(&item, _)| item as u64)
+                .sum();
+            (remaining == target_sum as u64).then(|| {
+                solution
+                    .into_iter()
+                    .map(|assignment| assignment.or(Some(last)))
+                    .collect()
+            })
+        }
+        Some((&subset, rest)) => {
+            BoundedPermutationGenerator::from_solution(items, target_sum, solution)
+                .expect("preconditions guaranteed by the caller")
+                .into_iter(subset)
+                .find_map(|partial| partition_remainder(items, target_sum, partial, rest))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid(packages: &[Package], groups: usize, group_weight: Package) {
+        let grouping = can_partition(packages, groups, group_weight).unwrap();
+        assert_eq!(grouping.len(), groups);
+        for group in &grouping {
+            assert_eq!(group.iter().sum::<Package>(), group_weight);
+        }
+
+        let mut used: Vec<Package> = grouping.into_iter().flatten().collect();
+        let mut packages = packages.to_vec();
+        used.sort_unstable();
+        packages.sort_unstable();
+        assert_eq!(used, packages);
+    }
+
+    #[test]
+    fn test_example_groupings() {
+        let packages = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+        assert_valid(&packages, 3, 20);
+        assert_valid(&packages, 4, 15);
+    }
+
+    #[test]
+    fn test_remainder_after_footwell() {
+        // `11 9` is the best footwell in the example; the rest must still balance
+        assert_valid(&[1, 2, 3, 4, 5, 7, 8, 10], 2, 20);
+    }
+
+    #[test]
+    fn test_requires_backtracking() {
+        // the first candidate for the first group is `38 30 9`, which leaves a remainder that
+        // can't be balanced
+        assert_valid(&[38, 37, 33, 30, 21, 20, 18, 15, 10, 9], 3, 77);
+    }
+
+    #[test]
+    fn test_impossible() {
+        assert!(can_partition(&[11, 8, 1], 2, 10).is_none());
+        assert!(can_partition(&[5, 5, 5], 2, 5).is_none());
+        assert!(can_partition(&[7, 3], 3, 5).is_none());
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(can_partition(&[], 0, 10), Some(Vec::new()));
+        assert_eq!(can_partition(&[4, 3], 1, 7), Some(vec![vec![4, 3]]));
+    }
+}
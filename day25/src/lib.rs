@@ -66,6 +66,8 @@
 //! to let your-- oh, it's time for lunch! Bye!" The call disconnects.
 
 use std::path::Path;
+pub use util::Error;
+use util::{example::Example, Answer, InputSource};
 
 #[derive(Debug, parse_display::Display, parse_display::FromStr)]
 #[display("To continue, please consult the code grid in the manual.  Enter the code at row {row}, column {column}.")]
@@ -74,77 +76,163 @@ struct Input {
     column: usize,
 }
 
-/// Generate the correct list index of the requested row and column.
+/// The first code on the sheet, at row 1, column 1.
+pub const FIRST_CODE: u64 = 20151125;
+/// Each code is the previous code times this value...
+pub const MULTIPLIER: u64 = 252533;
+/// ...modulo this value.
+pub const MODULUS: u64 = 33554393;
+
+/// Convert a `(row, column)` coordinate into its 1-based position in the diagonal fill order.
 ///
-/// Note that row and column indices start at 1. If given 0 indices, the function returns 0.
-fn rc_to_index(r: usize, c: usize) -> usize {
-    if r == 0 || c == 0 {
-        return 0;
+/// Row and column indices start at 1. Returns `None` if either is 0, or if the position would not
+/// fit in a `usize`.
+///
+/// ```
+/// # use day25::diagonal_index;
+/// assert_eq!(diagonal_index(1, 1), Some(1));
+/// assert_eq!(diagonal_index(4, 2), Some(12));
+/// assert_eq!(diagonal_index(1, 5), Some(15));
+/// assert_eq!(diagonal_index(0, 5), None);
+/// ```
+pub fn diagonal_index(row: usize, column: usize) -> Option<usize> {
+    if row == 0 || column == 0 {
+        return None;
     }
-    // add the last diagonal immmediately. This is the only incomplete digonal.
-    let mut ret = c - 1;
+    // every diagonal before this one is complete; diagonal `n` contains `n` codes
+    let tier = row.checked_add(column - 1)?;
+    let preceding = triangular(tier - 1)?;
 
-    let tier = r + c - 1;
+    // the last diagonal is the only incomplete one; it's filled from the bottom left
+    preceding.checked_add(column)
+}
 
-    for diag in 1..tier {
-        ret += diag;
+/// Convert a 1-based position in the diagonal fill order into its `(row, column)` coordinate.
+///
+/// This is the inverse of [`diagonal_index`]. Returns `None` for index 0.
+///
+/// ```
+/// # use day25::diagonal_coordinate;
+/// assert_eq!(diagonal_coordinate(1), Some((1, 1)));
+/// assert_eq!(diagonal_coordinate(12), Some((4, 2)));
+/// assert_eq!(diagonal_coordinate(15), Some((1, 5)));
+/// assert_eq!(diagonal_coordinate(0), None);
+/// ```
+pub fn diagonal_coordinate(index: usize) -> Option<(usize, usize)> {
+    if index == 0 {
+        return None;
+    }
+    // find the diagonal `tier` such that `T(tier - 1) < index <= T(tier)`
+    let mut tier = (((8.0 * index as f64 + 1.0).sqrt() - 1.0) / 2.0) as usize;
+    while matches!(triangular(tier), Some(t) if t < index) {
+        tier += 1;
+    }
+    while tier > 1 && !matches!(triangular(tier - 1), Some(t) if t < index) {
+        tier -= 1;
     }
 
-    ret
+    let column = index - triangular(tier - 1)?;
+    let row = tier + 1 - column;
+    Some((row, column))
 }
 
-pub struct CodeGen {
-    values: Vec<usize>,
+/// The `n`th triangular number, `1 + 2 + ... + n`.
+fn triangular(n: usize) -> Option<usize> {
+    // one of `n` and `n + 1` is even, so divide that one before multiplying
+    if n & 1 == 0 {
+        (n / 2).checked_mul(n.checked_add(1)?)
+    } else {
+        n.checked_mul(n / 2 + 1)
+    }
 }
 
-impl CodeGen {
-    pub fn new(magic: usize) -> CodeGen {
-        CodeGen {
-            values: vec![magic],
+/// Generate the correct list index of the requested row and column.
+///
+/// Note that row and column indices start at 1. If given 0 indices, the function returns 0.
+fn rc_to_index(r: usize, c: usize) -> usize {
+    diagonal_index(r, c).map_or(0, |index| index - 1)
+}
+
+/// Compute `base.pow(exp) % modulus` by repeated squaring.
+///
+/// This takes `O(log exp)` multiplications, where generating the codes in sequence would take
+/// `O(exp)`.
+fn mod_pow(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    base %= modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
         }
+        base = base * base % modulus;
+        exp >>= 1;
     }
+    result
+}
 
-    /// Find the next value in sequence.
-    fn gen_next(&mut self) {
-        let prev = self.values.last().unwrap().clone();
-        let current = (prev * 252533) % 33554393;
-        self.values.push(current);
+/// Code generator seeded with an arbitrary first code.
+pub struct CodeGen {
+    magic: u64,
+}
+
+impl CodeGen {
+    pub fn new(magic: u64) -> CodeGen {
+        CodeGen { magic }
     }
 
-    /// Get the value at the appropriate row and column. Generate as required.
-    pub fn get(&mut self, r: usize, c: usize) -> usize {
-        let index = rc_to_index(r, c);
-        while self.values.len() <= index {
-            self.gen_next();
-        }
-        self.values[index]
+    /// Get the value at the appropriate row and column.
+    ///
+    /// The code at index `n` is `magic * MULTIPLIER^n % MODULUS`, so it can be computed directly
+    /// without generating any of the codes before it.
+    pub fn get(&self, r: usize, c: usize) -> u64 {
+        let index = rc_to_index(r, c) as u64;
+        self.magic % MODULUS * mod_pow(MULTIPLIER, index, MODULUS) % MODULUS
     }
 }
 
 impl Default for CodeGen {
     /// Generate a CodeGen with the magic number given in the puzzle.
     fn default() -> CodeGen {
-        CodeGen::new(20151125)
+        CodeGen::new(FIRST_CODE)
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    for Input { row, column } in aoclib::parse(input)? {
-        let mut cg = CodeGen::default();
-        println!("Code at ({}, {}): {}", row, column, cg.get(row, column));
-    }
-    Ok(())
+/// Compute the code written at the given row and column of the manual.
+///
+/// ```
+/// # use day25::code_at;
+/// assert_eq!(code_at(1, 1), 20151125);
+/// assert_eq!(code_at(2, 1), 31916031);
+/// assert_eq!(code_at(6, 6), 27995004);
+/// ```
+pub fn code_at(row: usize, column: usize) -> u64 {
+    CodeGen::default().get(row, column)
+}
+
+pub fn part1(input: &Path) -> Result<Answer, Error> {
+    part1_str(&InputSource::from(input).read_to_string()?)
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+pub fn part1_from_reader(input: impl std::io::Read) -> Result<Answer, Error> {
+    part1_str(&util::input::read_all(input)?)
 }
 
+pub fn part1_str(input: &str) -> Result<Answer, Error> {
+    let Input { row, column } = aoclib::input::parse_str(input)?
+        .next()
+        .ok_or(Error::NoInput)?;
+    Ok(code_at(row, column).into())
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example { part: 1, input: "To continue, please consult the code grid in the manual.  Enter the code at row 2, column 1.", expected: "31916031", solver: part1_str },
+    Example { part: 1, input: "To continue, please consult the code grid in the manual.  Enter the code at row 6, column 6.", expected: "27995004", solver: part1_str },
+];
+
 #[cfg(test)]
 mod tests {
-    use super::{rc_to_index, CodeGen};
+    use super::*;
+    use proptest::prelude::*;
 
     /// Test generation of sequence numbers based on row and column
     ///
@@ -170,9 +258,9 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let row1 = vec![20151125, 18749137, 17289845, 30943339, 10071777, 33511524];
-        let row6 = vec![33071741, 6796745, 25397450, 24659492, 1534922, 27995004];
-        let mut cg = CodeGen::default();
+        let row1 = [20151125, 18749137, 17289845, 30943339, 10071777, 33511524];
+        let row6 = [33071741, 6796745, 25397450, 24659492, 1534922, 27995004];
+        let cg = CodeGen::default();
 
         for (col, expect) in row1.iter().enumerate() {
             assert_eq!(&cg.get(1, col + 1), expect);
@@ -181,4 +269,62 @@ mod tests {
             assert_eq!(&cg.get(6, col + 1), expect);
         }
     }
+
+    #[test]
+    fn test_mod_pow_matches_iteration() {
+        let mut code = FIRST_CODE;
+        for index in 0..10_000 {
+            assert_eq!(
+                FIRST_CODE * mod_pow(MULTIPLIER, index, MODULUS) % MODULUS,
+                code
+            );
+            code = code * MULTIPLIER % MODULUS;
+        }
+    }
+
+    #[test]
+    fn test_diagonal_coordinates_in_fill_order() {
+        let expect = [(1, 1), (2, 1), (1, 2), (3, 1), (2, 2), (1, 3), (4, 1)];
+        for (idx, &coordinate) in expect.iter().enumerate() {
+            assert_eq!(diagonal_coordinate(idx + 1), Some(coordinate));
+        }
+    }
+
+    #[test]
+    fn test_diagonal_overflow() {
+        assert_eq!(diagonal_index(usize::MAX, 1), None);
+        assert_eq!(diagonal_index(1, usize::MAX), None);
+        let (row, column) = diagonal_coordinate(usize::MAX).unwrap();
+        assert_eq!(diagonal_index(row, column), Some(usize::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_index_round_trip(index in 1..=usize::MAX) {
+            let (row, column) = diagonal_coordinate(index).unwrap();
+            prop_assert!(row >= 1 && column >= 1);
+            prop_assert_eq!(diagonal_index(row, column), Some(index));
+        }
+
+        #[test]
+        fn prop_coordinate_round_trip(row in 1..=1_000_000_usize, column in 1..=1_000_000_usize) {
+            let index = diagonal_index(row, column).unwrap();
+            prop_assert_eq!(diagonal_coordinate(index), Some((row, column)));
+        }
+
+        #[test]
+        fn prop_next_index_moves_up_and_right(row in 2..=1_000_000_usize, column in 1..=1_000_000_usize) {
+            let index = diagonal_index(row, column).unwrap();
+            prop_assert_eq!(diagonal_index(row - 1, column + 1), Some(index + 1));
+        }
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let input: Input = "To continue, please consult the code grid in the manual.  Enter the code at row 2947, column 3029."
+            .parse()
+            .unwrap();
+        assert_eq!(input.row, 2947);
+        assert_eq!(input.column, 3029);
+    }
 }
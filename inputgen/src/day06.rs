@@ -0,0 +1,21 @@
+use rand::Rng;
+
+const GRID_SIZE: usize = 1000;
+
+/// `size` light-switching instructions.
+pub fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let action = ["turn on", "turn off", "toggle"][rng.gen_range(0..3)];
+        let (x0, x1) = span(rng);
+        let (y0, y1) = span(rng);
+        out.push_str(&format!("{} {},{} through {},{}\n", action, x0, y0, x1, y1));
+    }
+    out
+}
+
+fn span(rng: &mut impl Rng) -> (usize, usize) {
+    let a = rng.gen_range(0..GRID_SIZE);
+    let b = rng.gen_range(0..GRID_SIZE);
+    (a.min(b), a.max(b))
+}
@@ -0,0 +1,62 @@
+use rand::{seq::SliceRandom, Rng};
+
+/// A circuit of `size` wires, including `a` and `b`, in shuffled order.
+///
+/// Each wire only depends on wires defined before it, so the circuit always settles. `b` is a
+/// constant, so that it can be overridden.
+pub fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let size = size.max(2);
+    let names: Vec<String> = std::iter::once("b".to_string())
+        .chain((0..size - 2).map(wire_name))
+        .chain(std::iter::once("a".to_string()))
+        .collect();
+
+    let mut lines = vec![format!("{} -> b", rng.gen::<u16>())];
+    for (idx, name) in names.iter().enumerate().skip(1) {
+        let mut earlier = || names[rng.gen_range(0..idx)].as_str();
+        let (x, y) = (earlier(), earlier());
+        let instruction = match rng.gen_range(0..8) {
+            0 => format!("{}", rng.gen::<u16>()),
+            1 => x.to_string(),
+            2 => format!("{} AND {}", x, y),
+            3 => format!("1 AND {}", x),
+            4 => format!("{} OR {}", x, y),
+            5 => format!("{} LSHIFT {}", x, rng.gen_range(1..16)),
+            6 => format!("{} RSHIFT {}", x, rng.gen_range(1..16)),
+            _ => format!("NOT {}", x),
+        };
+        lines.push(format!("{} -> {}", instruction, name));
+    }
+    lines.shuffle(rng);
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// A unique lowercase name of at least two letters, so as never to collide with `a` or `b`.
+fn wire_name(index: usize) -> String {
+    // bijective base 26, skipping past the 26 single-letter names
+    let mut n = index + 27;
+    let mut name = Vec::new();
+    while n > 0 {
+        n -= 1;
+        name.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_wire_names() {
+        let names: HashSet<_> = (0..2000).map(wire_name).collect();
+        assert_eq!(names.len(), 2000);
+        assert!(names.iter().all(|name| name.len() >= 2));
+    }
+}
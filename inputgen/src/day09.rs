@@ -0,0 +1,18 @@
+use rand::Rng;
+
+/// The distances between every pair of `size` locations.
+pub fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let size = size.max(2);
+    let mut out = String::new();
+    for from in 0..size {
+        for to in from + 1..size {
+            out.push_str(&format!(
+                "{} to {} = {}\n",
+                super::name(from),
+                super::name(to),
+                rng.gen_range(1..=1000)
+            ));
+        }
+    }
+    out
+}
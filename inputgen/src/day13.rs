@@ -0,0 +1,20 @@
+use rand::Rng;
+
+/// How each of `size` guests feels about sitting next to each other guest.
+pub fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let size = size.max(2);
+    let mut out = String::new();
+    for who in 0..size {
+        for other in (0..size).filter(|&other| other != who) {
+            let modify = if rng.gen() { "gain" } else { "lose" };
+            out.push_str(&format!(
+                "{} would {} {} happiness units by sitting next to {}.\n",
+                super::name(who),
+                modify,
+                rng.gen_range(0..100),
+                super::name(other)
+            ));
+        }
+    }
+    out
+}
@@ -0,0 +1,59 @@
+use rand::{seq::SliceRandom, Rng};
+
+const COMPOUNDS: [&str; 10] = [
+    "children",
+    "cats",
+    "samoyeds",
+    "pomeranians",
+    "akitas",
+    "vizslas",
+    "goldfish",
+    "trees",
+    "cars",
+    "perfumes",
+];
+
+/// `size` aunts, each with three known compounds.
+///
+/// One aunt exactly matches the MFCSAM readout, and another matches it once the readings for
+/// cats, trees, pomeranians, and goldfish are understood as ranges, so both parts have an answer.
+pub fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let size = size.max(2);
+    let exact = rng.gen_range(0..size);
+    let ranged = (exact + rng.gen_range(1..size)) % size;
+
+    let mut out = String::new();
+    for idx in 0..size {
+        let possessions = if idx == exact {
+            format!("cats: 7, children: 3, {}", exact_filler(rng))
+        } else if idx == ranged {
+            format!(
+                "cats: {}, pomeranians: {}, {}",
+                rng.gen_range(8..=10),
+                rng.gen_range(0..3),
+                exact_filler(rng)
+            )
+        } else {
+            COMPOUNDS
+                .choose_multiple(rng, 3)
+                .map(|compound| format!("{}: {}", compound, rng.gen_range(0..=10)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        out.push_str(&format!("Sue {}: {}\n", idx + 1, possessions));
+    }
+    out
+}
+
+/// A compound whose reading is exact in both parts, with its reading.
+fn exact_filler(rng: &mut impl Rng) -> &'static str {
+    [
+        "akitas: 0",
+        "vizslas: 0",
+        "cars: 2",
+        "perfumes: 1",
+        "samoyeds: 2",
+    ]
+    .choose(rng)
+    .unwrap()
+}
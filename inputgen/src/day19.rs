@@ -0,0 +1,68 @@
+use rand::{seq::SliceRandom, Rng};
+
+const ELEMENTS: [&str; 12] = [
+    "Al", "B", "Ca", "F", "H", "Mg", "N", "O", "P", "Si", "Th", "Ti",
+];
+
+/// A set of replacements, and a medicine molecule fabricated from `e` in `size` steps.
+///
+/// At least one step is always taken.
+///
+/// The replacements have the same shape as real inputs: each element becomes two elements, or
+/// else an element followed by a parenthesized list, written with `Rn`, `Y`, and `Ar`.
+pub fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let mut replacements: Vec<(&str, Vec<&str>)> = Vec::new();
+    for _ in 0..3 {
+        replacements.push(("e", vec![element(rng), element(rng)]));
+    }
+    for &from in ELEMENTS.iter() {
+        for _ in 0..rng.gen_range(1..=4) {
+            let mut to = vec![element(rng)];
+            match rng.gen_range(0..4) {
+                0 => to.push(element(rng)),
+                list_len => {
+                    to.push("Rn");
+                    to.push(element(rng));
+                    for _ in 1..list_len {
+                        to.push("Y");
+                        to.push(element(rng));
+                    }
+                    to.push("Ar");
+                }
+            }
+            replacements.push((from, to));
+        }
+    }
+
+    let mut molecule = vec!["e"];
+    for _ in 0..size.max(1) {
+        let expandable: Vec<usize> = molecule
+            .iter()
+            .enumerate()
+            .filter(|(_, &atom)| !matches!(atom, "Rn" | "Y" | "Ar"))
+            .map(|(idx, _)| idx)
+            .collect();
+        let idx = *expandable.choose(rng).unwrap();
+        let (_, to) = replacements
+            .iter()
+            .filter(|(from, _)| *from == molecule[idx])
+            .collect::<Vec<_>>()
+            .choose(rng)
+            .copied()
+            .unwrap();
+        molecule.splice(idx..=idx, to.iter().copied());
+    }
+
+    let mut out = String::new();
+    for (from, to) in &replacements {
+        out.push_str(&format!("{} => {}\n", from, to.concat()));
+    }
+    out.push('\n');
+    out.push_str(&molecule.concat());
+    out.push('\n');
+    out
+}
+
+fn element(rng: &mut impl Rng) -> &'static str {
+    ELEMENTS.choose(rng).unwrap()
+}
@@ -0,0 +1,46 @@
+use rand::Rng;
+
+/// Bounds how large register `a` can grow before the final loop.
+const MAX_TRIPLES: usize = 12;
+
+/// The Collatz loop with which real programs end; it terminates so long as `a` is at least 1.
+const COLLATZ: &str = "\
+jio a, +8
+inc b
+jie a, +4
+tpl a
+inc a
+jmp +2
+hlf a
+jmp -7
+";
+
+/// A program of `size` instructions which always halts.
+///
+/// All jumps before the final loop go forwards, and the final loop starts with `a` at least 1.
+pub fn generate(size: usize, rng: &mut impl Rng) -> String {
+    let body = size.saturating_sub(COLLATZ.lines().count() + 1);
+    let mut triples = 0;
+    let mut out = String::new();
+    for idx in 0..body {
+        // a forward jump can land anywhere up to the instruction following the body
+        let remaining = body - idx;
+        let register = if rng.gen() { "a" } else { "b" };
+        let instruction = match rng.gen_range(0..6) {
+            0 if triples < MAX_TRIPLES => {
+                triples += 1;
+                "tpl a".to_string()
+            }
+            1 => "hlf a".to_string(),
+            2 => format!("jmp +{}", rng.gen_range(1..=remaining)),
+            3 => format!("jie {}, +{}", register, rng.gen_range(1..=remaining)),
+            4 => format!("jio {}, +{}", register, rng.gen_range(1..=remaining)),
+            _ => format!("inc {}", register),
+        };
+        out.push_str(&instruction);
+        out.push('\n');
+    }
+    out.push_str("inc a\n");
+    out.push_str(COLLATZ);
+    out
+}
@@ -0,0 +1,135 @@
+//! Synthesize random, valid puzzle inputs.
+//!
+//! Generated inputs are useful for stress-testing the solvers and fuzzing their parsers. The same
+//! day, size, and seed always produce the same input.
+//!
+//! The meaning of the size depends on the day:
+//!
+//! | day | size                   |
+//! | --- | ---------------------- |
+//! | 6   | instructions           |
+//! | 7   | wires                  |
+//! | 9   | locations              |
+//! | 13  | guests                 |
+//! | 16  | aunts named Sue        |
+//! | 19  | fabrication steps      |
+//! | 23  | instructions           |
+
+mod day06;
+mod day07;
+mod day09;
+mod day13;
+mod day16;
+mod day19;
+mod day23;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Days for which inputs can be generated.
+pub const DAYS: [u8; 7] = [6, 7, 9, 13, 16, 19, 23];
+
+/// Generate an input of roughly `size` for `day` from `seed`.
+pub fn generate(day: u8, size: usize, seed: u64) -> Result<String, Error> {
+    generate_with(day, size, &mut ChaCha8Rng::seed_from_u64(seed))
+}
+
+/// Generate an input of roughly `size` for `day`, drawing randomness from `rng`.
+pub fn generate_with(day: u8, size: usize, rng: &mut impl Rng) -> Result<String, Error> {
+    Ok(match day {
+        6 => day06::generate(size, rng),
+        7 => day07::generate(size, rng),
+        9 => day09::generate(size, rng),
+        13 => day13::generate(size, rng),
+        16 => day16::generate(size, rng),
+        19 => day19::generate(size, rng),
+        23 => day23::generate(size, rng),
+        _ => return Err(Error::UnsupportedDay(day)),
+    })
+}
+
+/// A unique, capitalized, pronounceable name for each index.
+fn name(mut index: usize) -> String {
+    const CONSONANTS: &[u8] = b"bdfgklmnprstvz";
+    const VOWELS: &[u8] = b"aeiou";
+
+    let mut name = String::new();
+    loop {
+        name.push(CONSONANTS[index % CONSONANTS.len()] as char);
+        index /= CONSONANTS.len();
+        name.push(VOWELS[index % VOWELS.len()] as char);
+        index /= VOWELS.len();
+        if index == 0 {
+            break;
+        }
+        index -= 1;
+    }
+    let mut chars = name.chars();
+    let first = chars.next().unwrap().to_ascii_uppercase();
+    std::iter::once(first).chain(chars).collect()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("no input generator for day {0}; try one of {:?}", DAYS)]
+    UnsupportedDay(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_names_are_unique() {
+        let names: HashSet<_> = (0..5000).map(name).collect();
+        assert_eq!(names.len(), 5000);
+    }
+
+    #[test]
+    fn test_deterministic() {
+        for &day in DAYS.iter() {
+            assert_eq!(
+                generate(day, 10, 42).unwrap(),
+                generate(day, 10, 42).unwrap()
+            );
+        }
+        assert_ne!(generate(6, 10, 1).unwrap(), generate(6, 10, 2).unwrap());
+        assert!(generate(1, 10, 1).is_err());
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for seed in 0..3 {
+            let input = |day, size| generate(day, size, seed).unwrap();
+
+            let lights = input(6, 10);
+            ::day06::part1_str(&lights).unwrap();
+            ::day06::part2_str(&lights).unwrap();
+
+            let circuit = input(7, 200);
+            ::day07::part1_str(&circuit).unwrap();
+            ::day07::part2_str(&circuit).unwrap();
+
+            let routes = input(9, 6);
+            ::day09::part1_str(&routes).unwrap();
+            ::day09::part2_str(&routes).unwrap();
+
+            let guests = input(13, 6);
+            ::day13::part1_str(&guests).unwrap();
+            ::day13::part2_str(&guests).unwrap();
+
+            let sues = input(16, 100);
+            ::day16::part1_str(&sues).unwrap();
+            ::day16::part2_str(&sues).unwrap();
+
+            let molecule = input(19, 30);
+            ::day19::part1_str(&molecule).unwrap();
+            assert_eq!(::day19::part2_str(&molecule).unwrap(), 30.into());
+
+            let program = input(23, 40);
+            ::day23::part1_str(&program).unwrap();
+            ::day23::part2_str(&program).unwrap();
+        }
+    }
+}
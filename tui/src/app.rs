@@ -0,0 +1,262 @@
+//! The state of the dashboard, independent of how it is drawn.
+
+use aoc2015_all::{parts_of, solve_with_progress, Part, DAYS};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    time::Instant,
+};
+use util::{progress::Progress, Answer, InputSource, Timed};
+
+/// Progress of a running part, shared between its solver thread and the dashboard.
+#[derive(Debug)]
+pub struct Tracker {
+    started: Instant,
+    done: AtomicU64,
+    // 0 until the solver announces a total
+    total: AtomicU64,
+}
+
+impl Default for Tracker {
+    fn default() -> Self {
+        Tracker {
+            started: Instant::now(),
+            done: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Tracker {
+    /// Describe how far along the part is.
+    pub fn status_line(&self) -> String {
+        let total = match self.total.load(Ordering::Relaxed) {
+            0 => None,
+            total => Some(total),
+        };
+        util::progress::status_line(
+            "running",
+            self.done.load(Ordering::Relaxed),
+            total,
+            self.started.elapsed(),
+        )
+    }
+}
+
+impl Progress for Tracker {
+    fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    fn advance(&self, done: u64) {
+        self.done.fetch_max(done, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+pub enum Status {
+    Idle,
+    Running(Arc<Tracker>),
+    Solved(Timed<Answer>),
+    Failed(String),
+}
+
+/// One part of one day.
+#[derive(Debug)]
+pub struct Row {
+    pub day: u8,
+    pub part: Part,
+    pub status: Status,
+}
+
+/// A part which has finished running, as reported by its solver thread.
+#[derive(Debug)]
+pub struct Finished {
+    pub day: u8,
+    pub part: Part,
+    pub result: Result<Timed<Answer>, String>,
+}
+
+pub struct App {
+    pub rows: Vec<Row>,
+    pub selected: usize,
+    pub quit: bool,
+    finished_tx: Sender<Finished>,
+    finished_rx: Receiver<Finished>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    pub fn new() -> App {
+        let rows = DAYS
+            .flat_map(|day| {
+                parts_of(day).iter().map(move |&part| Row {
+                    day,
+                    part,
+                    status: Status::Idle,
+                })
+            })
+            .collect();
+        let (finished_tx, finished_rx) = channel();
+        App {
+            rows,
+            selected: 0,
+            quit: false,
+            finished_tx,
+            finished_rx,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.rows.len() - 1);
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Run the selected part.
+    pub fn run_selected(&mut self) {
+        self.run(self.selected);
+    }
+
+    /// Run every part of the selected day.
+    pub fn run_selected_day(&mut self) {
+        let day = self.rows[self.selected].day;
+        for idx in 0..self.rows.len() {
+            if self.rows[idx].day == day {
+                self.run(idx);
+            }
+        }
+    }
+
+    /// Run every part of every day.
+    pub fn run_all(&mut self) {
+        for idx in 0..self.rows.len() {
+            self.run(idx);
+        }
+    }
+
+    /// How many parts are still running.
+    pub fn running(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| matches!(row.status, Status::Running(_)))
+            .count()
+    }
+
+    /// Start solving the part at `idx` on its own thread, unless it is already running.
+    fn run(&mut self, idx: usize) {
+        let row = &mut self.rows[idx];
+        if let Status::Running(_) = row.status {
+            return;
+        }
+
+        let tracker = Arc::new(Tracker::default());
+        row.status = Status::Running(tracker.clone());
+
+        let (day, part) = (row.day, row.part);
+        let finished_tx = self.finished_tx.clone();
+        std::thread::spawn(move || {
+            let result = solve(day, part, tracker.as_ref());
+            // the dashboard may have quit already; there's nobody left to tell
+            let _ = finished_tx.send(Finished { day, part, result });
+        });
+    }
+
+    /// Record the outcome of every part which has finished since the last call.
+    pub fn collect(&mut self) {
+        while let Ok(Finished { day, part, result }) = self.finished_rx.try_recv() {
+            if let Some(row) = self
+                .rows
+                .iter_mut()
+                .find(|row| row.day == day && row.part == part)
+            {
+                row.status = match result {
+                    Ok(timed) => Status::Solved(timed),
+                    Err(err) => Status::Failed(err),
+                };
+            }
+        }
+    }
+}
+
+/// Solve a part against its configured input, turning every kind of failure into a message.
+fn solve(day: u8, part: Part, progress: &dyn Progress) -> Result<Timed<Answer>, String> {
+    let input = util::config::input_for(day)
+        .and_then(|path| Ok(InputSource::File(path).read_to_string()?))
+        .map_err(|err| format!("locating puzzle input: {}", err))?;
+    match catch_unwind(AssertUnwindSafe(|| {
+        Timed::measure(|| solve_with_progress(day, part, &input, progress)).transpose()
+    })) {
+        Ok(Ok(timed)) => Ok(timed),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(_) => Err("panicked".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rows() {
+        let app = App::new();
+        assert_eq!(app.rows.len(), 49);
+        assert_eq!((app.rows[0].day, app.rows[0].part), (1, Part::One));
+        let last = app.rows.last().unwrap();
+        assert_eq!((last.day, last.part), (25, Part::One));
+    }
+
+    #[test]
+    fn test_selection_stays_in_bounds() {
+        let mut app = App::new();
+        app.select_previous();
+        assert_eq!(app.selected, 0);
+        for _ in 0..100 {
+            app.select_next();
+        }
+        assert_eq!(app.selected, app.rows.len() - 1);
+    }
+
+    #[test]
+    fn test_collect() {
+        let mut app = App::new();
+        app.rows[2].status = Status::Running(Arc::default());
+        assert_eq!(app.running(), 1);
+
+        let timed = Timed {
+            value: Answer::Int(58),
+            duration: Duration::from_millis(1),
+        };
+        app.finished_tx
+            .send(Finished {
+                day: 2,
+                part: Part::One,
+                result: Ok(timed),
+            })
+            .unwrap();
+        app.collect();
+        assert_eq!(app.running(), 0);
+        assert!(matches!(app.rows[2].status, Status::Solved(_)));
+    }
+
+    #[test]
+    fn test_tracker() {
+        let tracker = Tracker::default();
+        tracker.set_total(10);
+        tracker.advance(5);
+        tracker.advance(3);
+        assert!(tracker.status_line().contains("50% (5 of 10)"));
+    }
+}
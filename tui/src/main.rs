@@ -0,0 +1,67 @@
+//! Interactive dashboard for running the whole year.
+//!
+//! Lists every part of every day. Parts run on background threads, reporting their progress as
+//! they go, so the dashboard stays responsive while the slow days grind.
+
+mod app;
+mod ui;
+
+use app::App;
+use color_eyre::eyre::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::{
+    io::{self, Stdout},
+    time::Duration,
+};
+
+/// How often the dashboard redraws while waiting for input.
+const TICK: Duration = Duration::from_millis(100);
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+
+    // restore the terminal even if the dashboard fails
+    let result = run(&mut terminal, &mut App::new());
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> Result<()> {
+    while !app.quit {
+        app.collect();
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    handle_key(app, key.code);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Char('q') | KeyCode::Esc => app.quit = true,
+        KeyCode::Up | KeyCode::Char('k') => app.select_previous(),
+        KeyCode::Down | KeyCode::Char('j') => app.select_next(),
+        KeyCode::Enter | KeyCode::Char(' ') => app.run_selected(),
+        KeyCode::Char('d') => app.run_selected_day(),
+        KeyCode::Char('a') => app.run_all(),
+        _ => {}
+    }
+}
@@ -0,0 +1,102 @@
+//! Drawing the dashboard.
+
+use crate::app::{App, Status};
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use util::timing::format_duration;
+
+const HELP: &str = "↑/↓ select  enter run part  d run day  a run all  q quit";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let areas = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(frame.size());
+
+    let rows = app.rows.iter().map(|row| {
+        let (status, style, duration, answer) = match &row.status {
+            Status::Idle => (
+                "-".to_string(),
+                Style::default().fg(Color::DarkGray),
+                String::new(),
+                String::new(),
+            ),
+            Status::Running(tracker) => (
+                tracker.status_line(),
+                Style::default().fg(Color::Yellow),
+                String::new(),
+                String::new(),
+            ),
+            Status::Solved(timed) => (
+                "solved".to_string(),
+                Style::default().fg(Color::Green),
+                format_duration(timed.duration),
+                timed.value.to_string(),
+            ),
+            Status::Failed(err) => (
+                err.clone(),
+                Style::default().fg(Color::Red),
+                String::new(),
+                String::new(),
+            ),
+        };
+        Row::new(vec![
+            Cell::from(row.day.to_string()),
+            Cell::from(row.part.to_string()),
+            Cell::from(status).style(style),
+            Cell::from(duration),
+            Cell::from(answer),
+        ])
+    });
+
+    let title = match app.running() {
+        0 => "Advent of Code 2015".to_string(),
+        running => format!("Advent of Code 2015 ({} running)", running),
+    };
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Min(30),
+            Constraint::Length(10),
+            Constraint::Min(20),
+        ],
+    )
+    .header(
+        Row::new(vec!["day", "part", "status", "duration", "answer"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(title))
+    .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    let mut state = TableState::default();
+    state.select(Some(app.selected));
+    frame.render_stateful_widget(table, areas[0], &mut state);
+    frame.render_widget(Paragraph::new(HELP), areas[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_draw() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+        let mut app = App::new();
+        app.rows[0].status = Status::Failed("no input".into());
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol())
+                .collect::<String>()
+        };
+        assert!(line(1).contains("status"));
+        assert!(line(2).contains("no input"));
+        assert!(line(9).starts_with("↑/↓ select"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Choosing between several algorithms which solve the same puzzle.
+//!
+//! Some days have a brute-force algorithm which is quickest for inputs the size of the real puzzle,
+//! and a scalable one which copes with much larger inputs. Each such day lists its backends in
+//! order of preference, and the first which suits the input solves it, unless `--force-algo`
+//! names another.
+
+use crate::{Answer, Error};
+
+/// One of several interchangeable algorithms for a day's puzzle.
+pub trait Backend<Puzzle> {
+    /// The name by which `--force-algo` selects this backend.
+    fn name(&self) -> &'static str;
+
+    /// Whether this backend is a good choice for `puzzle`.
+    ///
+    /// Backends are tried in order of preference. The last is the fallback when none suits, so it
+    /// should at least be able to solve every puzzle, however slowly.
+    fn suits(&self, puzzle: &Puzzle) -> bool;
+
+    fn part1(&self, puzzle: &Puzzle) -> Result<Answer, Error>;
+
+    fn part2(&self, puzzle: &Puzzle) -> Result<Answer, Error>;
+
+    /// Solve both parts. Backends which can share work between the parts override this.
+    fn both(&self, puzzle: &Puzzle) -> Result<(Answer, Answer), Error> {
+        Ok((self.part1(puzzle)?, self.part2(puzzle)?))
+    }
+}
+
+/// Choose the backend to solve `puzzle` with.
+///
+/// That's the backend named `forced`, if given; otherwise the first which suits the puzzle, or
+/// failing that, the last.
+pub fn select<'a, Puzzle>(
+    backends: &[&'a dyn Backend<Puzzle>],
+    puzzle: &Puzzle,
+    forced: Option<&str>,
+) -> Result<&'a dyn Backend<Puzzle>, Error> {
+    let backend = match forced {
+        Some(name) => backends
+            .iter()
+            .find(|backend| backend.name() == name)
+            .ok_or_else(|| Error::UnknownAlgorithm {
+                name: name.to_string(),
+                known: names(backends),
+            })?,
+        None => backends
+            .iter()
+            .find(|backend| backend.suits(puzzle))
+            .or_else(|| backends.last())
+            .ok_or_else(|| Error::no_solution("no algorithm to solve with"))?,
+    };
+    tracing::debug!(
+        algorithm = backend.name(),
+        forced = forced.is_some(),
+        "selected algorithm"
+    );
+    Ok(*backend)
+}
+
+/// The names of `backends`, separated by commas.
+fn names<Puzzle>(backends: &[&dyn Backend<Puzzle>]) -> String {
+    backends
+        .iter()
+        .map(|backend| backend.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums the numbers, suiting lists no longer than its limit.
+    struct Summer(&'static str, usize);
+
+    impl Backend<Vec<u32>> for Summer {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn suits(&self, puzzle: &Vec<u32>) -> bool {
+            puzzle.len() <= self.1
+        }
+
+        fn part1(&self, puzzle: &Vec<u32>) -> Result<Answer, Error> {
+            Ok(puzzle.iter().sum::<u32>().into())
+        }
+
+        fn part2(&self, puzzle: &Vec<u32>) -> Result<Answer, Error> {
+            Ok(puzzle.len().into())
+        }
+    }
+
+    const BACKENDS: &[&dyn Backend<Vec<u32>>] = &[&Summer("small", 2), &Summer("medium", 4)];
+
+    #[test]
+    fn test_select() {
+        let select_name = |puzzle: Vec<u32>, forced| {
+            select(BACKENDS, &puzzle, forced)
+                .map(|backend| backend.name())
+                .map_err(|err| err.to_string())
+        };
+        assert_eq!(select_name(vec![1, 2], None), Ok("small"));
+        assert_eq!(select_name(vec![1, 2, 3], None), Ok("medium"));
+        // nothing suits, so the last backend is the fallback
+        assert_eq!(select_name(vec![0; 10], None), Ok("medium"));
+        assert_eq!(select_name(vec![1, 2, 3], Some("small")), Ok("small"));
+        assert_eq!(
+            select_name(vec![1], Some("huge")),
+            Err("no algorithm named 'huge'; choose one of small, medium".to_string())
+        );
+    }
+
+    #[test]
+    fn test_both() {
+        let answers = Summer("small", 2).both(&vec![1, 2]).unwrap();
+        assert_eq!(answers, (Answer::Int(3), Answer::Int(2)));
+    }
+}
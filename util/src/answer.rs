@@ -0,0 +1,91 @@
+//! The value produced by solving one part of a puzzle.
+
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt};
+
+/// The answer to one part of a puzzle.
+///
+/// Puzzle answers are either integers or short strings. Unsigned values too large for an `i64`
+/// are kept as their decimal representation.
+///
+/// Answers serialize as a bare number or string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Int(i64),
+    Str(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! from_lossless {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(n.into())
+                }
+            }
+        )*
+    };
+}
+
+from_lossless!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! from_wide {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    match i64::try_from(n) {
+                        Ok(n) => Answer::Int(n),
+                        Err(_) => Answer::Str(n.to_string()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_wide!(isize, usize, u64, i128, u128);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Answer::from(-3_i32), Answer::Int(-3));
+        assert_eq!(Answer::from(280_usize), Answer::Int(280));
+        assert_eq!(
+            Answer::from(u64::MAX),
+            Answer::Str("18446744073709551615".into())
+        );
+        assert_eq!(Answer::from("cqjxxyzz"), Answer::Str("cqjxxyzz".into()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::Int(1783).to_string(), "1783");
+        assert_eq!(Answer::Str("abc".into()).to_string(), "abc");
+    }
+}
@@ -0,0 +1,227 @@
+//! Remembering answers on disk, so that slow days are instant on a second run.
+//!
+//! Answers are keyed by day, part, and a SHA-256 hash of the [`Variant`] and the input text. Each
+//! one is stored as a small JSON file in the cache directory. Changing a solver doesn't invalidate
+//! its cached answers; clear the cache after doing so.
+
+use crate::Answer;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Where answers are cached unless told otherwise.
+pub const DEFAULT_DIR: &str = ".aoc2015-cache";
+
+/// A directory of cached answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Cache {
+        Cache { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file holding the answer for this day, part, and key.
+    fn path(&self, day: u8, part: u8, key: &Key) -> PathBuf {
+        self.dir
+            .join(format!("day{:02}-part{}-{}.json", day, part, key.0))
+    }
+
+    /// The cached answer for this day, part, and key, if there is one.
+    ///
+    /// Unreadable or corrupt entries are treated as missing.
+    pub fn get(&self, day: u8, part: u8, key: &Key) -> Option<Answer> {
+        let json = std::fs::read_to_string(self.path(day, part, key)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Remember the answer for this day, part, and key.
+    pub fn put(&self, day: u8, part: u8, key: &Key, answer: &Answer) -> Result<(), Error> {
+        std::fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_string(answer)?;
+        std::fs::write(self.path(day, part, key), json)?;
+        Ok(())
+    }
+
+    /// Forget every cached answer, returning how many there were.
+    ///
+    /// Files in the cache directory which don't look like cached answers are left alone.
+    pub fn clear(&self) -> Result<usize, Error> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            let is_answer = matches!(
+                path.file_name().and_then(|name| name.to_str()),
+                Some(name) if name.starts_with("day") && name.ends_with(".json")
+            );
+            if is_answer {
+                std::fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// What an answer is cached by besides its day and part: a hash of the [`Variant`] and the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key(String);
+
+impl Key {
+    pub fn new(variant: &str, input: &str) -> Key {
+        Key::read(variant, input.as_bytes()).expect("reading from memory can't fail")
+    }
+
+    /// The key for `variant` and the text read from `input`, which is hashed as it's read rather
+    /// than held in memory.
+    pub fn read(variant: &str, mut input: impl Read) -> io::Result<Key> {
+        let mut hasher = Sha256::new().chain(variant.as_bytes()).chain(b"\0");
+        io::copy(&mut input, &mut hasher)?;
+        Ok(Key(format!("{:x}", hasher.finalize())))
+    }
+}
+
+/// How a run departs from the puzzle as set, so that its answers are cached apart.
+///
+/// Displays as each parameter which isn't at its default, as `name=value`, separated by commas.
+/// The puzzle as set is the empty variant.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Variant(Vec<String>);
+
+impl Variant {
+    pub fn new() -> Variant {
+        Variant::default()
+    }
+
+    /// Note the `value` of parameter `name`, unless it's the default.
+    pub fn param<T>(self, name: &str, value: &T) -> Variant
+    where
+        T: Default + PartialEq + fmt::Display,
+    {
+        if *value == T::default() {
+            self
+        } else {
+            self.with(name, value)
+        }
+    }
+
+    /// Note the `value` of parameter `name`, whatever it is.
+    pub fn with(mut self, name: &str, value: impl fmt::Display) -> Variant {
+        self.0.push(format!("{}={}", name, value));
+        self
+    }
+
+    /// Whether this is the puzzle as set, whose answers can be checked against the real ones.
+    pub fn is_usual(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(","))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("serializing answer")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> Cache {
+        let dir = std::env::temp_dir().join(format!("aoc2015-cache-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        Cache::new(dir)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cache = scratch("round-trip");
+        assert_eq!(cache.get(4, 1, &Key::new("", "abcdef")), None);
+
+        cache
+            .put(4, 1, &Key::new("", "abcdef"), &Answer::Int(609043))
+            .unwrap();
+        cache
+            .put(
+                11,
+                2,
+                &Key::new("", "hepxcrrq"),
+                &Answer::Str("heqaabcc".into()),
+            )
+            .unwrap();
+        cache
+            .put(4, 1, &Key::new("zeros=6", "abcdef"), &Answer::Int(5))
+            .unwrap();
+        assert_eq!(
+            cache.get(4, 1, &Key::new("", "abcdef")),
+            Some(Answer::Int(609043))
+        );
+        assert_eq!(
+            cache.get(11, 2, &Key::new("", "hepxcrrq")),
+            Some(Answer::Str("heqaabcc".into()))
+        );
+        assert_eq!(
+            cache.get(4, 1, &Key::new("zeros=6", "abcdef")),
+            Some(Answer::Int(5))
+        );
+
+        // the key covers the day, part, variant, and input
+        assert_eq!(cache.get(4, 2, &Key::new("", "abcdef")), None);
+        assert_eq!(cache.get(5, 1, &Key::new("", "abcdef")), None);
+        assert_eq!(cache.get(4, 1, &Key::new("zeros=7", "abcdef")), None);
+        assert_eq!(cache.get(4, 1, &Key::new("", "abcdeg")), None);
+
+        assert_eq!(cache.clear().unwrap(), 3);
+        assert_eq!(cache.get(4, 1, &Key::new("", "abcdef")), None);
+        std::fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_key() {
+        let key = Key::new("zeros=6", "abcdef");
+        assert_eq!(Key::read("zeros=6", "abcdef".as_bytes()).unwrap(), key);
+        assert_ne!(Key::new("zeros=6", "abcdeg"), key);
+        assert_ne!(Key::new("", "abcdef"), key);
+        // the variant and input are kept apart
+        assert_ne!(Key::new("a", "bc"), Key::new("ab", "c"));
+    }
+
+    #[test]
+    fn test_variant() {
+        assert!(Variant::new().param("zeros", &0_u32).is_usual());
+        let variant = Variant::new()
+            .param("zeros", &6_u32)
+            .param("part", &0_u8)
+            .with("seed", 1);
+        assert!(!variant.is_usual());
+        assert_eq!(variant.to_string(), "zeros=6,seed=1");
+    }
+
+    #[test]
+    fn test_clear_missing_dir() {
+        assert_eq!(scratch("missing").clear().unwrap(), 0);
+    }
+}
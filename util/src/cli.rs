@@ -0,0 +1,610 @@
+//! Command-line arguments shared by every day's binary.
+
+use crate::{
+    cache::{self, Cache, Key},
+    color::Painter,
+    example::Example,
+    input::InputSource,
+    mem,
+    output::{OutputFormat, Record},
+    progress::{Progress, Silent, StatusLines},
+    rng,
+    timing::Timed,
+    verify::{self, ExpectedAnswers, Verdict},
+    Answer,
+};
+use std::{
+    io::{self, BufRead},
+    path::PathBuf,
+    sync::Once,
+};
+use structopt::StructOpt;
+
+/// Flags common to every day.
+///
+/// Days with flags of their own can include these with `#[structopt(flatten)]`.
+#[derive(StructOpt, Debug, Clone, Default)]
+pub struct RunArgs {
+    /// input file, or `-` to read standard input
+    #[structopt(long, parse(from_os_str))]
+    pub input: Option<PathBuf>,
+
+    /// skip part 1
+    #[structopt(long = "no-part1")]
+    pub no_part1: bool,
+
+    /// run part 2
+    #[structopt(long)]
+    pub part2: bool,
+
+    /// solve the puzzle's worked examples instead of the real input
+    #[structopt(long)]
+    pub example: bool,
+
+    /// report how long each part takes
+    #[structopt(long)]
+    pub time: bool,
+
+    /// report the peak heap usage of each part; requires building with `--features util/mem`
+    #[structopt(long)]
+    pub mem: bool,
+
+    /// report progress of slow solvers on stderr
+    #[structopt(long)]
+    pub progress: bool,
+
+    /// log what the solvers are doing to stderr; repeat for more detail
+    #[structopt(short, long, parse(from_occurrences))]
+    pub verbose: u8,
+
+    /// seed for solvers which search randomly; the same seed always gives the same run
+    #[structopt(long)]
+    pub seed: Option<u64>,
+
+    /// solve with the named algorithm, rather than the one which best suits the input
+    #[structopt(long)]
+    pub force_algo: Option<String>,
+
+    /// output format: `text`, or `json` for one record per part
+    #[structopt(long, default_value = "text")]
+    pub output: OutputFormat,
+
+    #[structopt(flatten)]
+    pub check: CheckArgs,
+
+    #[structopt(flatten)]
+    pub cache: CacheArgs,
+}
+
+/// Flags controlling verification of answers.
+#[derive(StructOpt, Debug, Clone, Default)]
+pub struct CheckArgs {
+    /// compare answers against the expected answers, failing on any mismatch
+    #[structopt(long)]
+    pub check: bool,
+
+    /// file of expected answers
+    #[structopt(long, parse(from_os_str), default_value = "answers.toml")]
+    pub answers: PathBuf,
+}
+
+/// Flags controlling the cache of answers.
+#[derive(StructOpt, Debug, Clone)]
+pub struct CacheArgs {
+    /// always solve, neither using nor updating the cache of answers
+    #[structopt(long = "no-cache")]
+    pub no_cache: bool,
+
+    /// directory in which answers are cached
+    #[structopt(long, parse(from_os_str), default_value = cache::DEFAULT_DIR)]
+    pub cache_dir: PathBuf,
+}
+
+impl Default for CacheArgs {
+    fn default() -> Self {
+        CacheArgs {
+            no_cache: false,
+            cache_dir: cache::DEFAULT_DIR.into(),
+        }
+    }
+}
+
+impl CacheArgs {
+    /// The cache of answers, unless caching was disabled.
+    pub fn cache(&self) -> Option<Cache> {
+        if self.no_cache {
+            None
+        } else {
+            Some(Cache::new(&self.cache_dir))
+        }
+    }
+}
+
+impl CheckArgs {
+    /// Load the expected answers, if checking was requested.
+    pub fn expected(&self) -> Result<Option<ExpectedAnswers>, Error> {
+        if !self.check {
+            return Ok(None);
+        }
+        ExpectedAnswers::load(&self.answers)
+            .map(Some)
+            .map_err(|source| Error::Answers {
+                path: self.answers.clone(),
+                source,
+            })
+    }
+}
+
+impl RunArgs {
+    /// The input: as given on the command line, where `-` means standard input, or else the
+    /// configured input file for `day`.
+    pub fn source(&self, day: u8) -> Result<InputSource, Error> {
+        match self.input {
+            None => crate::config::input_for(day)
+                .map(InputSource::File)
+                .map_err(Error::Input),
+            Some(ref path) => Ok(path.clone().into()),
+        }
+    }
+
+    /// Show the solvers' tracing events on stderr, as detailed as `--verbose` requests.
+    pub fn init_tracing(&self) {
+        crate::trace::init(self.verbose);
+    }
+
+    /// The seed for randomized solvers: as given with `--seed`, or else the default.
+    pub fn seed(&self) -> u64 {
+        self.seed.unwrap_or(rng::DEFAULT_SEED)
+    }
+
+    /// The algorithm named with `--force-algo`, if any.
+    ///
+    /// Only days with several algorithms take any notice of it.
+    pub fn force_algo(&self) -> Option<&str> {
+        self.force_algo.as_deref()
+    }
+
+    /// The progress reporter for the given part: status lines with `--progress`, otherwise silent.
+    pub fn progress(&self, part: u8) -> Box<dyn Progress> {
+        if self.progress {
+            Box::new(StatusLines::new(format!("part {}", part)))
+        } else {
+            Box::new(Silent)
+        }
+    }
+
+    /// Print the answer to one part in the requested output format.
+    ///
+    /// In text mode, the duration is only printed with `--time`. JSON records always include it.
+    pub fn emit(&self, day: u8, part: u8, timed: &Timed<Answer>) {
+        self.emit_with_peak(day, part, timed, None);
+    }
+
+    /// As [`RunArgs::emit`], also reporting the peak heap usage of the part if it was measured.
+    pub fn emit_with_peak(&self, day: u8, part: u8, timed: &Timed<Answer>, peak: Option<usize>) {
+        match self.output {
+            OutputFormat::Json => println!(
+                "{}",
+                Record::solved(day, part, timed)
+                    .with_peak_heap(peak)
+                    .to_json()
+            ),
+            OutputFormat::Text => {
+                let mut notes = Vec::new();
+                if self.time {
+                    notes.push(crate::timing::format_duration(timed.duration));
+                }
+                if let Some(peak) = peak {
+                    notes.push(format!("peak heap {}", mem::format_bytes(peak)));
+                }
+                if notes.is_empty() {
+                    println!("part {}: {}", part, timed.value);
+                } else {
+                    println!("part {}: {} ({})", part, timed.value, notes.join(", "));
+                }
+            }
+        }
+    }
+
+    /// Print an answer which was found in the cache rather than solved.
+    fn emit_cached(&self, day: u8, part: u8, answer: &Answer) {
+        match self.output {
+            OutputFormat::Json => println!("{}", Record::cached(day, part, answer).to_json()),
+            OutputFormat::Text => println!("part {}: {} (cached)", part, answer),
+        }
+    }
+
+    /// The cache of answers, unless it was disabled with `--no-cache`, or by asking for
+    /// something only a solver can do: `--time`, `--mem`, `--progress`, or `--force-algo`.
+    pub fn answer_cache(&self) -> Option<Cache> {
+        if self.time || self.mem || self.progress || self.force_algo.is_some() {
+            None
+        } else {
+            self.cache.cache()
+        }
+    }
+
+    /// The key to cache the answers for `variant` under, covering the `--seed` and
+    /// `--force-algo` which solvers are given too.
+    fn cache_variant(&self, variant: &str) -> String {
+        let mut params = Vec::new();
+        if !variant.is_empty() {
+            params.push(variant.to_owned());
+        }
+        if let Some(seed) = self.seed {
+            params.push(format!("seed={}", seed));
+        }
+        if let Some(algo) = &self.force_algo {
+            params.push(format!("algo={}", algo));
+        }
+        params.join(",")
+    }
+
+    /// Solve a single part, printing its answer.
+    ///
+    /// Answers are looked up in and saved to the [`answer_cache`](RunArgs::answer_cache). They're
+    /// cached apart from those for other `variant`s of the puzzle; see
+    /// [`Variant`](cache::Variant). With `--mem`, the peak heap usage of the part is measured and
+    /// reported too.
+    pub fn solve<E>(
+        &self,
+        day: u8,
+        part: u8,
+        variant: &str,
+        input: &InputSource,
+        solver: impl FnOnce(Box<dyn BufRead>) -> Result<Answer, E>,
+    ) -> Result<Answer, E>
+    where
+        E: From<io::Error>,
+    {
+        let cache = self.answer_cache();
+        // the cache key is hashed from the input, so standard input must be kept to solve from
+        let input = match cache {
+            Some(_) => input.clone().buffered()?,
+            None => input.clone(),
+        };
+        let key = match cache {
+            Some(_) => Some(Key::read(&self.cache_variant(variant), input.reader()?)?),
+            None => None,
+        };
+
+        if let (Some(cache), Some(key)) = (&cache, &key) {
+            if let Some(answer) = cache.get(day, part, key) {
+                self.emit_cached(day, part, &answer);
+                return Ok(answer);
+            }
+        }
+
+        let solve = || Timed::measure(|| solver(input.reader()?));
+        let (timed, peak) = if self.mem {
+            warn_unless_counting();
+            mem::measure(solve)
+        } else {
+            (solve(), None)
+        };
+        let timed = timed.transpose()?;
+        self.emit_with_peak(day, part, &timed, peak);
+
+        if let (Some(cache), Some(key)) = (&cache, &key) {
+            if let Err(err) = cache.put(day, part, key, &timed.value) {
+                tracing::warn!(dir = %cache.dir().display(), "failed to cache answer: {}", err);
+            }
+        }
+        Ok(timed.value)
+    }
+
+    /// Solve each part requested on the command line, returning the answers by part number.
+    ///
+    /// Standard input is read only once; both parts see the same text. Answers are cached as for
+    /// [`solve`](RunArgs::solve).
+    pub fn run<E>(
+        &self,
+        day: u8,
+        variant: &str,
+        input: &InputSource,
+        part1: impl FnOnce(Box<dyn BufRead>) -> Result<Answer, E>,
+        part2: impl FnOnce(Box<dyn BufRead>) -> Result<Answer, E>,
+    ) -> Result<Vec<(u8, Answer)>, E>
+    where
+        E: From<io::Error>,
+    {
+        let input = input.clone().buffered()?;
+        let mut answers = Vec::with_capacity(2);
+        if self.wants_part(1) {
+            answers.push((1, self.solve(day, 1, variant, &input, part1)?));
+        }
+        if self.wants_part(2) {
+            answers.push((2, self.solve(day, 2, variant, &input, part2)?));
+        }
+        Ok(answers)
+    }
+
+    /// Whether the given part was requested on the command line.
+    pub fn wants_part(&self, part: u8) -> bool {
+        match part {
+            1 => !self.no_part1,
+            2 => self.part2,
+            _ => false,
+        }
+    }
+
+    /// Solve the worked examples of each requested part, printing each answer.
+    ///
+    /// Fails if any answer differs from the one the puzzle text gives.
+    pub fn run_examples(&self, examples: &[Example]) -> Result<(), Error> {
+        let painter = Painter::stdout();
+        let mut incorrect = 0;
+        for part in (1..=2).filter(|&part| self.wants_part(part)) {
+            let of_part = examples.iter().filter(|example| example.part == part);
+            for (idx, example) in of_part.enumerate() {
+                let label = format!("part {} example {}", part, idx + 1);
+                match example.solve() {
+                    Ok(answer) if example.is_expected(&answer) => {
+                        println!("{}", painter.success(format!("{}: {}", label, answer)))
+                    }
+                    Ok(answer) => {
+                        incorrect += 1;
+                        println!(
+                            "{}",
+                            painter.failure(format!(
+                                "{}: expected {}, got {}",
+                                label, example.expected, answer
+                            ))
+                        );
+                    }
+                    Err(err) => {
+                        incorrect += 1;
+                        println!("{}", painter.failure(format!("{}: {}", label, err)));
+                    }
+                }
+            }
+        }
+
+        if incorrect > 0 {
+            Err(Error::Incorrect(incorrect))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// With `--check`, compare `answers` against the expected answers for `day`.
+    ///
+    /// Reports the verdict for each part, and fails if any answer is incorrect. Parts with no
+    /// recorded answer only produce a warning. Verdicts go to stderr when stdout carries JSON.
+    pub fn check(&self, day: u8, answers: &[(u8, Answer)]) -> Result<(), Error> {
+        let expected = match self.check.expected()? {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+
+        let json = self.output == OutputFormat::Json;
+        let painter = if json {
+            Painter::stderr()
+        } else {
+            Painter::stdout()
+        };
+        let report = |line: String| {
+            if json {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        };
+        let mut incorrect = 0;
+        for (part, answer) in answers {
+            match expected.check(day, *part, answer) {
+                Verdict::Correct => report(painter.success(format!("part {}", part))),
+                Verdict::Incorrect { expected } => {
+                    incorrect += 1;
+                    report(painter.failure(format!(
+                        "part {}: expected {}, got {}",
+                        part, expected, answer
+                    )));
+                }
+                Verdict::Unknown => {
+                    report(painter.warning(format!("part {}: no expected answer", part)))
+                }
+            }
+        }
+
+        if incorrect > 0 {
+            Err(Error::Incorrect(incorrect))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Warn, once, that `--mem` can't report anything without the `mem` feature.
+fn warn_unless_counting() {
+    static WARNED: Once = Once::new();
+    if !mem::ENABLED {
+        WARNED.call_once(|| {
+            tracing::warn!("peak heap usage is only measured when built with `--features util/mem`")
+        });
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("locating puzzle input")]
+    Input(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("loading expected answers from {}", .path.display())]
+    Answers {
+        path: PathBuf,
+        #[source]
+        source: verify::Error,
+    },
+    #[error("{0} incorrect answer(s)")]
+    Incorrect(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags() {
+        let args = RunArgs::from_iter(&["day", "--input", "in.txt", "--part2", "--time"]);
+        assert_eq!(
+            args.source(1).unwrap(),
+            InputSource::File(PathBuf::from("in.txt"))
+        );
+        assert!(!args.no_part1);
+        assert!(args.part2);
+        assert!(args.time);
+        assert_eq!(args.output, OutputFormat::Text);
+
+        let args = RunArgs::from_iter(&["day", "--output", "json"]);
+        assert_eq!(args.output, OutputFormat::Json);
+
+        let args = RunArgs::from_iter(&["day", "--input", "-"]);
+        assert_eq!(args.source(1).unwrap(), InputSource::Stdin);
+    }
+
+    fn no_cache() -> CacheArgs {
+        CacheArgs {
+            no_cache: true,
+            ..CacheArgs::default()
+        }
+    }
+
+    #[test]
+    fn test_run_selects_parts() {
+        let args = RunArgs {
+            no_part1: true,
+            part2: true,
+            cache: no_cache(),
+            ..RunArgs::default()
+        };
+        let mut ran = Vec::new();
+        args.run::<io::Error>(
+            1,
+            "",
+            &InputSource::Literal("".into()),
+            |_| {
+                ran.push(1);
+                Ok(Answer::Int(1))
+            },
+            |_| Ok(Answer::Int(2)),
+        )
+        .unwrap();
+        assert!(ran.is_empty());
+    }
+
+    #[test]
+    fn test_run_shares_input() {
+        let args = RunArgs {
+            part2: true,
+            cache: no_cache(),
+            ..RunArgs::default()
+        };
+        let input = InputSource::Literal("abc".into());
+        let read = |reader: Box<dyn BufRead>| crate::input::read_all(reader).map(Answer::from);
+        let answers = args.run(1, "", &input, read, read).unwrap();
+        assert_eq!(
+            answers,
+            vec![(1, Answer::from("abc")), (2, Answer::from("abc"))]
+        );
+    }
+
+    #[test]
+    fn test_solve_uses_cache() {
+        let cache_dir = std::env::temp_dir().join("aoc2015-cli-test-cache");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let args = RunArgs {
+            cache: CacheArgs {
+                no_cache: false,
+                cache_dir: cache_dir.clone(),
+            },
+            ..RunArgs::default()
+        };
+        let input = InputSource::Literal("abc".into());
+
+        let solved = args.solve::<io::Error>(1, 1, "", &input, |_| Ok(Answer::Int(3)));
+        assert_eq!(solved.unwrap(), Answer::Int(3));
+        let cached =
+            args.solve::<io::Error>(1, 1, "", &input, |_| panic!("answer should be cached"));
+        assert_eq!(cached.unwrap(), Answer::Int(3));
+        let variant = args.solve::<io::Error>(1, 1, "size=2", &input, |_| Ok(Answer::Int(5)));
+        assert_eq!(variant.unwrap(), Answer::Int(5));
+
+        let args = RunArgs {
+            cache: CacheArgs {
+                no_cache: true,
+                cache_dir: cache_dir.clone(),
+            },
+            ..RunArgs::default()
+        };
+        let uncached = args.solve::<io::Error>(1, 1, "", &input, |_| Ok(Answer::Int(4)));
+        assert_eq!(uncached.unwrap(), Answer::Int(4));
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_solver_flags_skip_cache() {
+        let cache_dir = std::env::temp_dir().join("aoc2015-cli-test-cache-flags");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let cached = RunArgs {
+            cache: CacheArgs {
+                no_cache: false,
+                cache_dir: cache_dir.clone(),
+            },
+            ..RunArgs::default()
+        };
+        let input = InputSource::Literal("abc".into());
+        cached
+            .solve::<io::Error>(1, 1, "", &input, |_| Ok(Answer::Int(3)))
+            .unwrap();
+
+        let timed = RunArgs {
+            time: true,
+            ..cached.clone()
+        };
+        let forced = RunArgs {
+            force_algo: Some("nonesuch".into()),
+            ..cached.clone()
+        };
+        for args in &[timed, forced] {
+            assert!(args.answer_cache().is_none());
+            let solved = args.solve::<io::Error>(1, 1, "", &input, |_| Ok(Answer::Int(4)));
+            assert_eq!(solved.unwrap(), Answer::Int(4));
+        }
+
+        // a different seed is a different run
+        let seeded = RunArgs {
+            seed: Some(7),
+            ..cached.clone()
+        };
+        let solved = seeded.solve::<io::Error>(1, 1, "", &input, |_| Ok(Answer::Int(5)));
+        assert_eq!(solved.unwrap(), Answer::Int(5));
+        assert_eq!(seeded.cache_variant("size=2"), "size=2,seed=7");
+        assert_eq!(seeded.cache_variant(""), "seed=7");
+        assert_eq!(cached.cache_variant("size=2"), "size=2");
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_examples() {
+        fn length(input: &str) -> Result<Answer, crate::Error> {
+            Ok(input.len().into())
+        }
+        let example = |part, input, expected| Example {
+            part,
+            input,
+            expected,
+            solver: length,
+        };
+
+        let args = RunArgs::default();
+        assert!(args.run_examples(&[example(1, "abc", "3")]).is_ok());
+        // part 2 wasn't requested, so its wrong answer doesn't count
+        assert!(args
+            .run_examples(&[example(1, "abc", "3"), example(2, "ab", "3")])
+            .is_ok());
+        assert!(matches!(
+            args.run_examples(&[example(1, "ab", "3")]),
+            Err(Error::Incorrect(1))
+        ));
+    }
+}
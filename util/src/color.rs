@@ -0,0 +1,133 @@
+//! Colorized status output for terminals.
+//!
+//! Color is only emitted when the output stream is a terminal and the `NO_COLOR` environment
+//! variable is unset or empty, per <https://no-color.org>.
+
+use std::fmt;
+
+/// ANSI colors used for status output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Cyan,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Red => "31",
+            Self::Green => "32",
+            Self::Yellow => "33",
+            Self::Cyan => "36",
+        }
+    }
+}
+
+/// An output stream whose color support can be detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// `true` when the `NO_COLOR` convention asks us not to emit color.
+fn no_color_requested() -> bool {
+    matches!(std::env::var_os("NO_COLOR"), Some(value) if !value.is_empty())
+}
+
+/// `true` when colored output should be written to the given stream.
+pub fn enabled(stream: Stream) -> bool {
+    let is_tty = match stream {
+        Stream::Stdout => atty::is(atty::Stream::Stdout),
+        Stream::Stderr => atty::is(atty::Stream::Stderr),
+    };
+    is_tty && !no_color_requested()
+}
+
+/// Formats status lines, with or without color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Painter {
+    pub enabled: bool,
+}
+
+impl Painter {
+    /// A painter for lines written to stdout.
+    pub fn stdout() -> Self {
+        Painter {
+            enabled: enabled(Stream::Stdout),
+        }
+    }
+
+    /// A painter for lines written to stderr.
+    pub fn stderr() -> Self {
+        Painter {
+            enabled: enabled(Stream::Stderr),
+        }
+    }
+
+    /// Wrap `text` in the escape codes for `color`, if color is enabled.
+    pub fn paint(&self, text: impl fmt::Display, color: Color) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Wrap `text` in the escape codes for bold text, if color is enabled.
+    pub fn bold(&self, text: impl fmt::Display) -> String {
+        if self.enabled {
+            format!("\x1b[1m{}\x1b[0m", text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// A line reporting that something succeeded.
+    pub fn success(&self, message: impl fmt::Display) -> String {
+        format!("{} {}", self.paint("ok", Color::Green), message)
+    }
+
+    /// A line reporting that something failed.
+    pub fn failure(&self, message: impl fmt::Display) -> String {
+        format!("{} {}", self.paint("FAIL", Color::Red), message)
+    }
+
+    /// A line reporting a warning.
+    pub fn warning(&self, message: impl fmt::Display) -> String {
+        format!("{} {}", self.paint("warn", Color::Yellow), message)
+    }
+
+    /// A line reporting a computed answer, with the answer highlighted.
+    pub fn answer(&self, label: impl fmt::Display, answer: impl fmt::Display) -> String {
+        format!("{}: {}", label, self.bold(self.paint(answer, Color::Cyan)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN: Painter = Painter { enabled: false };
+    const COLOR: Painter = Painter { enabled: true };
+
+    #[test]
+    fn test_plain_output() {
+        assert_eq!(PLAIN.success("day 1"), "ok day 1");
+        assert_eq!(PLAIN.failure("day 2"), "FAIL day 2");
+        assert_eq!(PLAIN.warning("day 3"), "warn day 3");
+        assert_eq!(PLAIN.answer("part 1", 232), "part 1: 232");
+    }
+
+    #[test]
+    fn test_colored_output() {
+        assert_eq!(COLOR.success("day 1"), "\x1b[32mok\x1b[0m day 1");
+        assert_eq!(COLOR.failure("day 2"), "\x1b[31mFAIL\x1b[0m day 2");
+        assert_eq!(
+            COLOR.answer("part 1", 232),
+            "part 1: \x1b[1m\x1b[36m232\x1b[0m\x1b[0m"
+        );
+    }
+}
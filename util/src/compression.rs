@@ -0,0 +1,90 @@
+//! Transparent decompression of archived puzzle inputs.
+//!
+//! Compressed files are recognized by their extension, so `input.txt.gz` reads exactly as
+//! `input.txt` would.
+
+use std::{
+    io::{self, BufRead, Read},
+    path::Path,
+};
+
+/// A compression format which input files may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// The compression format of the file at `path`, judging by its extension.
+    pub fn of(path: &Path) -> Option<Compression> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Compression::Gzip),
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Wrap `reader` so that reading produces the decompressed data.
+    pub fn decoder<'a>(self, reader: impl 'a + BufRead) -> io::Result<Box<dyn 'a + Read>> {
+        Ok(match self {
+            // concatenated gzip members decompress to the concatenation of their contents
+            Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+            Compression::Zstd => zstd_decoder(reader)?,
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn zstd_decoder<'a>(reader: impl 'a + BufRead) -> io::Result<Box<dyn 'a + Read>> {
+    Ok(Box::new(zstd::stream::read::Decoder::with_buffer(reader)?))
+}
+
+// zstd is a C library, which doesn't build for the browser
+#[cfg(target_arch = "wasm32")]
+fn zstd_decoder<'a>(_reader: impl 'a + BufRead) -> io::Result<Box<dyn 'a + Read>> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "zstd input is not supported in the browser",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const TEXT: &str = "1\n2\n3\n";
+
+    fn decompress(compression: Compression, data: &[u8]) -> String {
+        crate::input::read_all(compression.decoder(data).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_of() {
+        assert_eq!(
+            Compression::of(Path::new("input.txt.gz")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::of(Path::new("day07.zst")),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::of(Path::new("input.txt")), None);
+        assert_eq!(Compression::of(Path::new("gz")), None);
+    }
+
+    #[test]
+    fn test_gzip() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(TEXT.as_bytes()).unwrap();
+        let data = encoder.finish().unwrap();
+        assert_eq!(decompress(Compression::Gzip, &data), TEXT);
+    }
+
+    #[test]
+    fn test_zstd() {
+        let data = zstd::stream::encode_all(TEXT.as_bytes(), 0).unwrap();
+        assert_eq!(decompress(Compression::Zstd, &data), TEXT);
+    }
+}
@@ -0,0 +1,19 @@
+//! Locating puzzle input via the shared AoC configuration.
+//!
+//! The session token and input directory are managed by `aoclib`; this just wraps the dance which
+//! every binary performs to find its input.
+
+use std::path::PathBuf;
+
+pub use aoclib::{config::Config, website::get_input};
+
+/// Find the input file for the given day of 2015.
+///
+/// This loads the configured session token and input directory, and downloads the input if it is
+/// not yet present. That does nothing if the input file already exists, but simplifies the
+/// workflow after cloning the repo on a new computer.
+pub fn input_for(day: u8) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    let config = Config::load()?;
+    get_input(&config, 2015, day)?;
+    Ok(config.input_for(2015, day))
+}
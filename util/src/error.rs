@@ -0,0 +1,104 @@
+//! The error type shared by every day's solutions.
+
+use std::num::TryFromIntError;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Something went wrong while solving a puzzle.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("no input found")]
+    NoInput,
+    /// Part of the input couldn't be understood.
+    #[error("parsing {what}: {source}")]
+    Parse {
+        what: String,
+        #[source]
+        source: BoxError,
+    },
+    /// The input was understood, but has no solution. The message says what was sought.
+    #[error("{0}")]
+    NoSolution(String),
+    /// Values depend on each other in a cycle, so none of them can be worked out. Lists the
+    /// names of the values in the cycle, each depending on the next, and the last on the first.
+    #[error("circular dependency among {}", .0.join(", "))]
+    CircularDependency(Vec<String>),
+    /// A value didn't fit in the type it needed to be converted into.
+    #[error("value out of range")]
+    Conversion(#[source] BoxError),
+    /// `--force-algo` named an algorithm which the day doesn't have.
+    #[error("no algorithm named '{name}'; choose one of {known}")]
+    UnknownAlgorithm { name: String, known: String },
+    /// The input is too large for the algorithm chosen to solve it.
+    #[error("{algorithm} can handle at most {max} {what}")]
+    TooLarge {
+        algorithm: &'static str,
+        max: usize,
+        what: &'static str,
+    },
+    /// Drawing a picture of the solution failed.
+    #[error("rendering output")]
+    Render(#[source] BoxError),
+}
+
+impl Error {
+    /// `what` couldn't be parsed, for the reason given by `source`.
+    pub fn parse(what: impl Into<String>, source: impl Into<BoxError>) -> Self {
+        Error::Parse {
+            what: what.into(),
+            source: source.into(),
+        }
+    }
+
+    /// No solution exists; `message` describes what was sought.
+    pub fn no_solution(message: impl Into<String>) -> Self {
+        Error::NoSolution(message.into())
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(err: TryFromIntError) -> Self {
+        Error::Conversion(Box::new(err))
+    }
+}
+
+impl From<aoclib::geometry::map::MapConversionErr> for Error {
+    fn from(err: aoclib::geometry::map::MapConversionErr) -> Self {
+        Error::parse("map", err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_messages() {
+        assert_eq!(
+            Error::parse("'x'", "not a direction").to_string(),
+            "parsing 'x': not a direction"
+        );
+        assert_eq!(
+            Error::no_solution("no matching sue found").to_string(),
+            "no matching sue found"
+        );
+        assert_eq!(
+            Error::CircularDependency(vec!["x".into(), "y".into()]).to_string(),
+            "circular dependency among x, y"
+        );
+        assert_eq!(
+            Error::TooLarge {
+                algorithm: "held-karp",
+                max: 20,
+                what: "places"
+            }
+            .to_string(),
+            "held-karp can handle at most 20 places"
+        );
+        let err: Error = u8::try_from(300_u32).unwrap_err().into();
+        assert!(matches!(err, Error::Conversion(_)));
+    }
+}
@@ -0,0 +1,62 @@
+//! Worked examples from the puzzle text.
+//!
+//! Each day lists its examples in a constant `EXAMPLES`, alongside the answers the puzzle text
+//! gives for them, so that every day can be checked against them at once.
+
+use crate::{Answer, Error};
+
+/// A worked example, and the answer the puzzle text gives for it.
+#[derive(Clone, Copy)]
+pub struct Example {
+    pub part: u8,
+    pub input: &'static str,
+    pub expected: &'static str,
+    /// Usually the part's `partN_str`. Some examples are smaller than the real puzzle: fewer
+    /// steps, a shorter race. Those need a solver of their own with the example's parameters.
+    pub solver: fn(&str) -> Result<Answer, Error>,
+}
+
+impl std::fmt::Debug for Example {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Example")
+            .field("part", &self.part)
+            .field("input", &self.input)
+            .field("expected", &self.expected)
+            .finish()
+    }
+}
+
+impl Example {
+    pub fn solve(&self) -> Result<Answer, Error> {
+        (self.solver)(self.input)
+    }
+
+    /// Whether `answer` is the one the puzzle text gives.
+    ///
+    /// Answers are compared by their textual form.
+    pub fn is_expected(&self, answer: &Answer) -> bool {
+        answer.to_string() == self.expected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_lines(input: &str) -> Result<Answer, Error> {
+        Ok(input.lines().count().into())
+    }
+
+    #[test]
+    fn test_example() {
+        let example = Example {
+            part: 1,
+            input: "a\nb\n",
+            expected: "2",
+            solver: count_lines,
+        };
+        let answer = example.solve().unwrap();
+        assert!(example.is_expected(&answer));
+        assert!(!example.is_expected(&Answer::Str("two".into())));
+    }
+}
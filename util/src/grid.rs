@@ -0,0 +1,346 @@
+//! Dense storage keyed by points, for hot paths where a `HashMap<Point, _>` is too slow.
+//!
+//! The points a puzzle visits tend to cluster, so storing values in a grid covering their bounding
+//! box avoids hashing on every lookup. The grid grows to fit new points in any direction, so
+//! coordinates may be negative and the extent need not be known in advance.
+
+use aoclib::geometry::Point;
+use std::iter::FromIterator;
+
+/// A map from points to values, stored densely.
+///
+/// Lookups are an offset and an index. Growing doubles the grid in each direction that needs it,
+/// so inserting points along a path of any shape is amortized constant time.
+#[derive(Clone, Debug)]
+pub struct DenseMap<T> {
+    /// The point stored at index 0: the minimum corner of the grid.
+    origin: Point,
+    width: usize,
+    height: usize,
+    cells: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> Default for DenseMap<T> {
+    fn default() -> Self {
+        DenseMap {
+            origin: Point::new(0, 0),
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> DenseMap<T> {
+    pub fn new() -> DenseMap<T> {
+        Self::default()
+    }
+
+    /// An empty map whose grid already covers every point from `min` to `max` inclusive.
+    ///
+    /// When the extent is known in advance, this avoids copying the grid as it grows.
+    pub fn with_bounds(min: Point, max: Point) -> DenseMap<T> {
+        if min.x > max.x || min.y > max.y {
+            return Self::default();
+        }
+        let width = (max.x as i64 - min.x as i64 + 1) as usize;
+        let height = (max.y as i64 - min.y as i64 + 1) as usize;
+        DenseMap {
+            origin: min,
+            width,
+            height,
+            cells: std::iter::repeat_with(|| None)
+                .take(width * height)
+                .collect(),
+            len: 0,
+        }
+    }
+
+    /// The number of points which have a value.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The index of `point` within `cells`, if the grid covers it.
+    fn index(&self, point: Point) -> Option<usize> {
+        let x = (point.x as i64 - self.origin.x as i64) as usize;
+        let y = (point.y as i64 - self.origin.y as i64) as usize;
+        // negative offsets wrap around to huge values, so a single comparison suffices
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    fn point_of(&self, index: usize) -> Point {
+        Point::new(
+            self.origin.x + (index % self.width) as i32,
+            self.origin.y + (index / self.width) as i32,
+        )
+    }
+
+    /// Grow the grid until it covers `point`, returning its index.
+    fn reserve(&mut self, point: Point) -> usize {
+        if let Some(index) = self.index(point) {
+            return index;
+        }
+        if self.cells.is_empty() {
+            self.origin = point;
+            self.width = 1;
+            self.height = 1;
+            self.cells.push(None);
+            return 0;
+        }
+
+        // extend each side which falls short by at least the current size, to amortize copying
+        let max_x = self.origin.x + self.width as i32 - 1;
+        let max_y = self.origin.y + self.height as i32 - 1;
+        let grow = |short: i32, size: usize| {
+            if short > 0 {
+                (short as usize).max(size)
+            } else {
+                0
+            }
+        };
+        let left = grow(self.origin.x - point.x, self.width);
+        let right = grow(point.x - max_x, self.width);
+        let top = grow(self.origin.y - point.y, self.height);
+        let bottom = grow(point.y - max_y, self.height);
+
+        let width = self.width + left + right;
+        let height = self.height + top + bottom;
+        let mut cells: Vec<Option<T>> = std::iter::repeat_with(|| None)
+            .take(width * height)
+            .collect();
+        for (index, cell) in self.cells.drain(..).enumerate() {
+            let (x, y) = (index % self.width, index / self.width);
+            cells[(y + top) * width + x + left] = cell;
+        }
+
+        self.origin = Point::new(self.origin.x - left as i32, self.origin.y - top as i32);
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.index(point)
+            .expect("grid was grown to cover the point")
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index(point)
+            .and_then(|index| self.cells[index].as_ref())
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        match self.index(point) {
+            Some(index) => self.cells[index].as_mut(),
+            None => None,
+        }
+    }
+
+    pub fn contains_key(&self, point: Point) -> bool {
+        self.get(point).is_some()
+    }
+
+    /// Set the value at `point`, returning the previous value if there was one.
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        let index = self.reserve(point);
+        let previous = self.cells[index].replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// The value at `point`, inserting the result of `default` first if there is none.
+    pub fn get_or_insert_with(&mut self, point: Point, default: impl FnOnce() -> T) -> &mut T {
+        let index = self.reserve(point);
+        let cell = &mut self.cells[index];
+        if cell.is_none() {
+            self.len += 1;
+        }
+        cell.get_or_insert_with(default)
+    }
+
+    /// The value at `point`, inserting the default value first if there is none.
+    pub fn get_or_default(&mut self, point: Point) -> &mut T
+    where
+        T: Default,
+    {
+        self.get_or_insert_with(point, T::default)
+    }
+
+    /// Every point with a value, and its value, in reading order.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, cell)| {
+                cell.as_ref().map(|value| (self.point_of(index), value))
+            })
+    }
+
+    /// Every point with a value, in reading order.
+    pub fn keys(&self) -> impl '_ + Iterator<Item = Point> {
+        self.iter().map(|(point, _)| point)
+    }
+
+    /// Every value, in reading order of their points.
+    pub fn values(&self) -> impl '_ + Iterator<Item = &T> {
+        self.cells.iter().filter_map(Option::as_ref)
+    }
+}
+
+impl<T> Extend<(Point, T)> for DenseMap<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.insert(point, value);
+        }
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for DenseMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut map = DenseMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+/// A set of points, stored densely.
+#[derive(Clone, Debug, Default)]
+pub struct DenseSet {
+    map: DenseMap<()>,
+}
+
+impl DenseSet {
+    pub fn new() -> DenseSet {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.map.contains_key(point)
+    }
+
+    /// Add `point` to the set, returning `true` if it wasn't already present.
+    pub fn insert(&mut self, point: Point) -> bool {
+        self.map.insert(point, ()).is_none()
+    }
+
+    /// Every point in the set, in reading order.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = Point> {
+        self.map.keys()
+    }
+}
+
+impl Extend<Point> for DenseSet {
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, iter: I) {
+        for point in iter {
+            self.insert(point);
+        }
+    }
+}
+
+impl FromIterator<Point> for DenseSet {
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        let mut set = DenseSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_grows_in_every_direction() {
+        let mut map = DenseMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.get(Point::new(0, 0)), None);
+
+        let points = [(0, 0), (3, -2), (-5, 7), (-1, -1), (100, 0), (0, -100)];
+        for (value, &(x, y)) in points.iter().enumerate() {
+            assert_eq!(map.insert(Point::new(x, y), value), None);
+        }
+        assert_eq!(map.len(), points.len());
+        for (value, &(x, y)) in points.iter().enumerate() {
+            assert_eq!(map.get(Point::new(x, y)), Some(&value));
+        }
+        assert_eq!(map.get(Point::new(1, 1)), None);
+        assert_eq!(map.get(Point::new(i32::MAX, i32::MIN)), None);
+
+        assert_eq!(map.insert(Point::new(3, -2), 10), Some(1));
+        assert_eq!(map.len(), points.len());
+    }
+
+    #[test]
+    fn test_agrees_with_hash_map() {
+        let mut dense = DenseMap::new();
+        let mut hashed: HashMap<Point, u32> = HashMap::new();
+        let mut point = Point::new(0, 0);
+        for step in 0..1000_i32 {
+            // a wandering spiral, revisiting some points
+            point = Point::new(point.x + (step % 7) - 3, point.y + (step % 5) - 2);
+            *dense.get_or_default(point) += 1;
+            *hashed.entry(point).or_default() += 1;
+        }
+
+        assert_eq!(dense.len(), hashed.len());
+        let mut pairs: Vec<_> = dense.iter().map(|(point, &n)| (point, n)).collect();
+        let mut expect: Vec<_> = hashed.into_iter().collect();
+        pairs.sort_unstable();
+        expect.sort_unstable();
+        assert_eq!(pairs, expect);
+    }
+
+    #[test]
+    fn test_with_bounds() {
+        let mut map = DenseMap::with_bounds(Point::new(-2, -1), Point::new(3, 4));
+        assert!(map.is_empty());
+        assert_eq!(map.cells.len(), 36);
+        map.insert(Point::new(-2, 4), 'a');
+        map.insert(Point::new(3, -1), 'b');
+        assert_eq!(map.cells.len(), 36);
+        assert_eq!(map.get(Point::new(-2, 4)), Some(&'a'));
+
+        // points outside the bounds still fit; the grid just grows
+        map.insert(Point::new(10, 10), 'c');
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(Point::new(3, -1)), Some(&'b'));
+
+        let empty: DenseMap<()> = DenseMap::with_bounds(Point::new(1, 0), Point::new(0, 0));
+        assert!(empty.cells.is_empty());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set: DenseSet = vec![Point::new(0, 0), Point::new(-1, 2)]
+            .into_iter()
+            .collect();
+        assert!(!set.insert(Point::new(0, 0)));
+        assert!(set.insert(Point::new(2, -1)));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(Point::new(-1, 2)));
+        assert!(!set.contains(Point::new(-1, -1)));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![Point::new(2, -1), Point::new(0, 0), Point::new(-1, 2)]
+        );
+    }
+}
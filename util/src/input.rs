@@ -0,0 +1,364 @@
+//! Uniform access to puzzle input, wherever it comes from.
+//!
+//! Input files compressed with gzip or zstd are decompressed on the fly; see
+//! [`Compression`](crate::compression::Compression).
+
+use crate::compression::Compression;
+use std::{
+    ffi::OsStr,
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// Where a puzzle's input comes from.
+///
+/// On the command line, a path of `-` means standard input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    /// Text already in memory. It's shared, so cloning the source or reading it copies nothing.
+    Literal(Arc<str>),
+}
+
+impl InputSource {
+    /// Open a buffered reader over this input.
+    pub fn reader(&self) -> io::Result<Box<dyn BufRead>> {
+        Ok(match self {
+            Self::File(path) => {
+                let file = BufReader::new(File::open(path)?);
+                match Compression::of(path) {
+                    Some(compression) => Box::new(BufReader::new(compression.decoder(file)?)),
+                    None => Box::new(file),
+                }
+            }
+            Self::Stdin => Box::new(BufReader::new(io::stdin())),
+            Self::Literal(text) => Box::new(Cursor::new(SharedText(text.clone()))),
+        })
+    }
+
+    /// Read the entire input into a string.
+    pub fn read_to_string(&self) -> io::Result<String> {
+        match self {
+            Self::File(path) if Compression::of(path).is_some() => read_all(self.reader()?),
+            Self::File(path) => std::fs::read_to_string(path),
+            Self::Stdin => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer)?;
+                Ok(buffer)
+            }
+            Self::Literal(text) => Ok(text.to_string()),
+        }
+    }
+
+    /// Iterate over the lines of this input.
+    ///
+    /// Line terminators are stripped, as with [`BufRead::lines`].
+    pub fn lines(&self) -> io::Result<impl Iterator<Item = io::Result<String>>> {
+        Ok(self.reader()?.lines())
+    }
+
+    /// Read standard input into memory, so that this input can be read more than once.
+    ///
+    /// Other sources are returned unchanged.
+    pub fn buffered(self) -> io::Result<Self> {
+        match self {
+            Self::Stdin => Ok(Self::Literal(self.read_to_string()?.into())),
+            _ => Ok(self),
+        }
+    }
+
+    /// The path of this input, if it is a file.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::File(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// The bytes of the text of an [`InputSource::Literal`], for a reader to share.
+struct SharedText(Arc<str>);
+
+impl AsRef<[u8]> for SharedText {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl From<PathBuf> for InputSource {
+    fn from(path: PathBuf) -> Self {
+        if path.as_os_str() == "-" {
+            Self::Stdin
+        } else {
+            Self::File(path)
+        }
+    }
+}
+
+impl From<&Path> for InputSource {
+    fn from(path: &Path) -> Self {
+        path.to_path_buf().into()
+    }
+}
+
+impl From<&OsStr> for InputSource {
+    fn from(path: &OsStr) -> Self {
+        PathBuf::from(path).into()
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "<stdin>"),
+            Self::Literal(_) => write!(f, "<literal>"),
+        }
+    }
+}
+
+/// Read everything remaining in `reader` into a string.
+pub fn read_all(mut reader: impl Read) -> io::Result<String> {
+    let mut buffer = String::new();
+    reader.read_to_string(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Split `text` into sections separated by one or more blank lines.
+///
+/// Lines containing only whitespace count as blank. Sections never include their terminating
+/// newline, and blank lines at the start or end of `text` produce no sections.
+///
+/// Sections needn't all have the same format: a caller can parse each one into a different type.
+///
+/// ```
+/// # use util::input::groups;
+/// let mut sections = groups("H => HO\nO => HH\n\nHOH\n");
+/// assert_eq!(sections.next(), Some("H => HO\nO => HH"));
+/// assert_eq!(sections.next(), Some("HOH"));
+/// assert_eq!(sections.next(), None);
+/// ```
+pub fn groups(text: &str) -> Groups<'_> {
+    Groups { remaining: text }
+}
+
+/// Parse each blank-line-separated section of `text` as a `T`.
+///
+/// See [`groups`] for the definition of a section.
+pub fn parse_groups<T>(text: &str) -> impl '_ + Iterator<Item = Result<T, ParseGroupError<T::Err>>>
+where
+    T: FromStr,
+{
+    groups(text).enumerate().map(|(index, section)| {
+        section
+            .parse()
+            .map_err(|source| ParseGroupError { index, source })
+    })
+}
+
+/// The first blank-line-separated section of `text` which parses as a `T`, for formats whose
+/// sections each end with a newline.
+///
+/// See [`groups`] for the definition of a section; sections don't include their terminating
+/// newline, so it's restored before parsing.
+pub fn find_group<T: FromStr>(text: &str) -> Option<T> {
+    groups(text).find_map(|section| format!("{}\n", section).parse().ok())
+}
+
+/// Iterator over the blank-line-separated sections of some text.
+///
+/// Constructed by [`groups`].
+#[derive(Debug, Clone)]
+pub struct Groups<'a> {
+    remaining: &'a str,
+}
+
+/// Split off the first line of `text`, returning the line without its terminator and the rest.
+fn split_line(text: &str) -> (&str, &str) {
+    let (line, rest) = match text.find('\n') {
+        Some(idx) => (&text[..idx], &text[idx + 1..]),
+        None => (text, ""),
+    };
+    (line.strip_suffix('\r').unwrap_or(line), rest)
+}
+
+impl<'a> Iterator for Groups<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // skip any blank lines preceding the section
+        loop {
+            if self.remaining.is_empty() {
+                return None;
+            }
+            let (line, rest) = split_line(self.remaining);
+            if !line.trim().is_empty() {
+                break;
+            }
+            self.remaining = rest;
+        }
+
+        let section = self.remaining;
+        let mut section_len = 0;
+        while !self.remaining.is_empty() {
+            let (line, rest) = split_line(self.remaining);
+            if line.trim().is_empty() {
+                break;
+            }
+            section_len = section.len() - self.remaining.len() + line.len();
+            self.remaining = rest;
+        }
+
+        Some(&section[..section_len])
+    }
+}
+
+/// A section of grouped input failed to parse.
+#[derive(Debug)]
+pub struct ParseGroupError<E> {
+    /// 0-based index of the section which failed to parse.
+    pub index: usize,
+    pub source: E,
+}
+
+impl<E: fmt::Display> fmt::Display for ParseGroupError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parsing section {}: {}", self.index, self.source)
+    }
+}
+
+impl<E> std::error::Error for ParseGroupError<E>
+where
+    E: 'static + std::error::Error,
+{
+    fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dash_means_stdin() {
+        assert_eq!(InputSource::from(OsStr::new("-")), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from(OsStr::new("input.txt")),
+            InputSource::File("input.txt".into())
+        );
+    }
+
+    #[test]
+    fn test_literal() {
+        let source = InputSource::Literal("abc\ndef\n".into());
+        assert_eq!(source.read_to_string().unwrap(), "abc\ndef\n");
+        let lines = source
+            .lines()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, vec!["abc", "def"]);
+    }
+
+    #[test]
+    fn test_file() {
+        let path = std::env::temp_dir().join(format!("util-input-{}.txt", std::process::id()));
+        std::fs::write(&path, "123\n456").unwrap();
+
+        let source = InputSource::from(path.as_path());
+        assert_eq!(source.path(), Some(path.as_path()));
+        assert_eq!(source.read_to_string().unwrap(), "123\n456");
+        let lines = source
+            .lines()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, vec!["123", "456"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compressed_file() {
+        let path = std::env::temp_dir().join(format!("util-input-{}.txt.zst", std::process::id()));
+        std::fs::write(
+            &path,
+            zstd::stream::encode_all(&b"123\n456"[..], 0).unwrap(),
+        )
+        .unwrap();
+
+        let source = InputSource::from(path.as_path());
+        assert_eq!(source.read_to_string().unwrap(), "123\n456");
+        let lines = source
+            .lines()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, vec!["123", "456"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_groups() {
+        let text = "\n\na\nb\n\n\n  \nc\r\n\r\nd\ne\n";
+        assert_eq!(groups(text).collect::<Vec<_>>(), vec!["a\nb", "c", "d\ne"]);
+        assert_eq!(groups("").count(), 0);
+        assert_eq!(groups("\n \n").count(), 0);
+        assert_eq!(groups("single").collect::<Vec<_>>(), vec!["single"]);
+    }
+
+    #[test]
+    fn test_find_group() {
+        /// Only parses with its final newline.
+        #[derive(Debug, PartialEq)]
+        struct HitPoints(u32);
+
+        impl FromStr for HitPoints {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let s = s.strip_prefix("Hit Points: ").ok_or(())?;
+                let s = s.strip_suffix('\n').ok_or(())?;
+                s.parse().map(HitPoints).map_err(|_| ())
+            }
+        }
+
+        let text = "Damage: 8\n\nHit Points: 104\n\nHit Points: 12\n";
+        assert_eq!(find_group(text), Some(HitPoints(104)));
+        assert_eq!(find_group::<HitPoints>("Damage: 8\n"), None);
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        #[derive(Debug, PartialEq)]
+        struct Sum(u32);
+
+        impl FromStr for Sum {
+            type Err = std::num::ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.lines()
+                    .map(|line| line.parse::<u32>())
+                    .sum::<Result<u32, _>>()
+                    .map(Sum)
+            }
+        }
+
+        let sums = parse_groups::<Sum>("1\n2\n\n3\n\n4\n5\n6")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(sums, vec![Sum(3), Sum(3), Sum(15)]);
+
+        let err = parse_groups::<Sum>("1\n\nx")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(err.index, 1);
+    }
+}
@@ -0,0 +1,28 @@
+//! Helpers shared by the day crates which aren't specific to any one puzzle.
+
+pub mod algo;
+pub mod answer;
+pub mod cache;
+pub mod cli;
+pub mod color;
+pub mod compression;
+pub mod config;
+pub mod error;
+pub mod example;
+pub mod grid;
+pub mod input;
+pub mod lint;
+pub mod mem;
+pub mod output;
+pub mod progress;
+pub mod rng;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod submit;
+pub mod timing;
+pub mod trace;
+pub mod verify;
+
+pub use answer::Answer;
+pub use error::Error;
+pub use input::{groups, parse_groups, InputSource};
+pub use timing::{time_fn, Timed};
@@ -0,0 +1,81 @@
+//! Report every malformed line of a puzzle input, rather than stopping at the first.
+
+use std::{fmt, str::FromStr};
+
+/// A problem with a single line of input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line number.
+    pub line: usize,
+    /// The offending line.
+    pub text: String,
+    /// What went wrong.
+    pub message: String,
+    /// What a well-formed line looks like.
+    pub expected: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "line {}: {}", self.line, self.message)?;
+        writeln!(f, "  | {}", self.text)?;
+        write!(f, "  = expected a line like `{}`", self.expected)
+    }
+}
+
+/// The non-blank lines of `input`, with their 1-based line numbers.
+pub fn numbered_lines(input: &str) -> impl '_ + Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Check `line` by parsing it as a `T`.
+pub fn check<T>(line: usize, text: &str, expected: &str) -> Option<Diagnostic>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.parse::<T>().err().map(|err| Diagnostic {
+        line,
+        text: text.to_string(),
+        message: err.to_string(),
+        expected: expected.to_string(),
+    })
+}
+
+/// Parse each non-blank line of `input` as a `T`, reporting every line which fails.
+///
+/// `expected` is an example of a well-formed line.
+pub fn lines<T>(input: &str, expected: &str) -> Vec<Diagnostic>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    numbered_lines(input)
+        .filter_map(|(line, text)| check::<T>(line, text, expected))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_every_bad_line() {
+        let diagnostics = lines::<u32>("1\nx\n\n3\n-4\n", "123");
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.line, diagnostic.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(2, "x"), (5, "-4")]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "line 2: invalid digit found in string\n  | x\n  = expected a line like `123`"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Measuring peak heap usage.
+//!
+//! With the `mem` feature, every allocation in the program goes through a counting allocator
+//! which tracks the bytes currently live and their high-water mark. Enable it for a day's binary
+//! with `--features util/mem`. Without the feature, nothing is counted and [`measure`] reports
+//! no peak.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Whether allocations are being counted.
+pub const ENABLED: bool = cfg!(feature = "mem");
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "mem")]
+mod counting {
+    use super::{LIVE, PEAK};
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::Ordering,
+    };
+
+    /// The system allocator, keeping count of live bytes.
+    struct Counting;
+
+    fn grow(bytes: usize) {
+        let live = LIVE.fetch_add(bytes, Ordering::SeqCst) + bytes;
+        PEAK.fetch_max(live, Ordering::SeqCst);
+    }
+
+    fn shrink(bytes: usize) {
+        LIVE.fetch_sub(bytes, Ordering::SeqCst);
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                grow(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                grow(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            shrink(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                if new_size > layout.size() {
+                    grow(new_size - layout.size());
+                } else {
+                    shrink(layout.size() - new_size);
+                }
+            }
+            new_ptr
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+}
+
+/// Run `f`, returning its result and the peak number of heap bytes it had live at once, beyond
+/// those which were already live when it started.
+///
+/// The peak is `None` unless the `mem` feature is enabled. Measurements are global, so they
+/// include allocations made by other threads, and must not be nested.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<usize>) {
+    let baseline = LIVE.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let value = f();
+    let peak = PEAK.load(Ordering::SeqCst).saturating_sub(baseline);
+    (value, if ENABLED { Some(peak) } else { None })
+}
+
+/// Format a number of bytes compactly, in the largest binary unit which keeps the value at
+/// least 1.
+///
+/// ```
+/// # use util::mem::format_bytes;
+/// assert_eq!(format_bytes(512), "512B");
+/// assert_eq!(format_bytes(1536), "1.50KiB");
+/// assert_eq!(format_bytes(40 << 20), "40.00MiB");
+/// assert_eq!(format_bytes(3 << 30), "3.00GiB");
+/// ```
+pub fn format_bytes(bytes: usize) -> String {
+    const KIB: usize = 1 << 10;
+    const MIB: usize = 1 << 20;
+    const GIB: usize = 1 << 30;
+    if bytes < KIB {
+        format!("{}B", bytes)
+    } else if bytes < MIB {
+        format!("{:.2}KiB", bytes as f64 / KIB as f64)
+    } else if bytes < GIB {
+        format!("{:.2}MiB", bytes as f64 / MIB as f64)
+    } else {
+        format!("{:.2}GiB", bytes as f64 / GIB as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let (len, peak) = measure(|| vec![0_u8; 1 << 20].len());
+        assert_eq!(len, 1 << 20);
+        if ENABLED {
+            assert!(peak.unwrap() >= 1 << 20);
+        } else {
+            assert_eq!(peak, None);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Machine-readable reporting of results.
+
+use crate::{Answer, Timed};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+
+/// How results are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON [`Record`] per line.
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown output format \"{0}\"; expected \"text\" or \"json\"")]
+pub struct UnknownFormat(String);
+
+/// The result of solving one part of one day.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use util::{output::Record, Answer, Timed};
+/// let timed = Timed { value: Answer::Int(2640), duration: Duration::from_millis(3) };
+/// assert_eq!(
+///     Record::solved(14, 1, &timed).to_json(),
+///     r#"{"day":14,"part":1,"answer":2640,"duration_ms":3}"#,
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_heap_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+}
+
+impl Record {
+    pub fn solved(day: u8, part: u8, timed: &Timed<Answer>) -> Record {
+        Record {
+            day,
+            part,
+            input: None,
+            answer: Some(timed.value.clone()),
+            duration_ms: Some(timed.duration.as_millis() as u64),
+            peak_heap_bytes: None,
+            error: None,
+            cached: false,
+        }
+    }
+
+    pub fn failed(day: u8, part: u8, error: impl ToString) -> Record {
+        Record {
+            day,
+            part,
+            input: None,
+            answer: None,
+            duration_ms: None,
+            peak_heap_bytes: None,
+            error: Some(error.to_string()),
+            cached: false,
+        }
+    }
+
+    /// An answer which was found in the cache rather than solved, so has no duration.
+    pub fn cached(day: u8, part: u8, answer: &Answer) -> Record {
+        Record {
+            day,
+            part,
+            input: None,
+            answer: Some(answer.clone()),
+            duration_ms: None,
+            peak_heap_bytes: None,
+            error: None,
+            cached: true,
+        }
+    }
+
+    /// Include the peak heap usage, if it was measured.
+    pub fn with_peak_heap(mut self, bytes: Option<usize>) -> Record {
+        self.peak_heap_bytes = bytes.map(|bytes| bytes as u64);
+        self
+    }
+
+    /// Note which input produced this record, when there may be several.
+    pub fn with_input(mut self, input: impl ToString) -> Record {
+        self.input = Some(input.to_string());
+        self
+    }
+
+    /// Serialize this record as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("records always serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_string_answer() {
+        let timed = Timed {
+            value: Answer::Str("hepxxyzz".into()),
+            duration: Duration::from_micros(1500),
+        };
+        assert_eq!(
+            Record::solved(11, 2, &timed).to_json(),
+            r#"{"day":11,"part":2,"answer":"hepxxyzz","duration_ms":1}"#
+        );
+    }
+
+    #[test]
+    fn test_peak_heap() {
+        let timed = Timed {
+            value: Answer::Int(831600),
+            duration: Duration::from_millis(40),
+        };
+        assert_eq!(
+            Record::solved(20, 1, &timed)
+                .with_peak_heap(Some(2_910_000))
+                .to_json(),
+            r#"{"day":20,"part":1,"answer":831600,"duration_ms":40,"peak_heap_bytes":2910000}"#
+        );
+    }
+
+    #[test]
+    fn test_failed() {
+        assert_eq!(
+            Record::failed(4, 2, "no coin found").to_json(),
+            r#"{"day":4,"part":2,"error":"no coin found"}"#
+        );
+    }
+
+    #[test]
+    fn test_cached() {
+        assert_eq!(
+            Record::cached(4, 1, &Answer::Int(609043)).to_json(),
+            r#"{"day":4,"part":1,"answer":609043,"cached":true}"#
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("json".parse(), Ok(OutputFormat::Json));
+        assert_eq!("text".parse(), Ok(OutputFormat::Text));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Progress reporting for long-running solvers.
+//!
+//! Slow solvers accept a `&dyn Progress` and report the work they've done as they go, so that
+//! binaries can show that something is happening instead of appearing hung. Solvers which don't
+//! care pass [`Silent`].
+
+use crate::timing::format_duration;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Receiver of progress reports.
+///
+/// Reports may come from several threads at once, and need not arrive in order; implementations
+/// should treat `done` as a high-water mark.
+pub trait Progress: Sync {
+    /// Announce the total amount of work, when it is known in advance.
+    fn set_total(&self, _total: u64) {}
+
+    /// Announce how much work is expected, when the total isn't known in advance but can be
+    /// estimated, as for a random search.
+    fn set_expected(&self, _expected: u64) {}
+
+    /// Report that `done` units of work have been completed so far.
+    fn advance(&self, done: u64);
+
+    /// Report that the work is complete.
+    fn finish(&self) {}
+}
+
+/// Discards all progress reports.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Silent;
+
+impl Progress for Silent {
+    fn advance(&self, _done: u64) {}
+}
+
+#[derive(Debug)]
+struct State {
+    total: Option<u64>,
+    expected: Option<u64>,
+    done: u64,
+    last_report: Instant,
+    reported: bool,
+}
+
+/// Writes a status line to stderr at most once per interval.
+#[derive(Debug)]
+pub struct StatusLines {
+    label: String,
+    interval: Duration,
+    started: Instant,
+    state: Mutex<State>,
+}
+
+impl StatusLines {
+    pub fn new(label: impl Into<String>) -> StatusLines {
+        StatusLines::with_interval(label, Duration::from_secs(1))
+    }
+
+    pub fn with_interval(label: impl Into<String>, interval: Duration) -> StatusLines {
+        let now = Instant::now();
+        StatusLines {
+            label: label.into(),
+            interval,
+            started: now,
+            state: Mutex::new(State {
+                total: None,
+                expected: None,
+                done: 0,
+                last_report: now,
+                reported: false,
+            }),
+        }
+    }
+}
+
+impl Progress for StatusLines {
+    fn set_total(&self, total: u64) {
+        self.state.lock().unwrap().total = Some(total);
+    }
+
+    fn set_expected(&self, expected: u64) {
+        self.state.lock().unwrap().expected = Some(expected);
+    }
+
+    fn advance(&self, done: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.done = state.done.max(done);
+        let now = Instant::now();
+        if now.duration_since(state.last_report) >= self.interval {
+            state.last_report = now;
+            state.reported = true;
+            let elapsed = now - self.started;
+            let line = match (state.total, state.expected) {
+                (None, Some(expected)) => estimate_line(&self.label, state.done, expected, elapsed),
+                (total, _) => status_line(&self.label, state.done, total, elapsed),
+            };
+            eprintln!("{}", line);
+        }
+    }
+
+    fn finish(&self) {
+        let state = self.state.lock().unwrap();
+        // quick solvers finish silently
+        if state.reported {
+            eprintln!(
+                "{}: done in {}",
+                self.label,
+                format_duration(self.started.elapsed())
+            );
+        }
+    }
+}
+
+/// Format a single line of status.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use util::progress::status_line;
+/// assert_eq!(
+///     status_line("part 1", 250, Some(1000), Duration::from_secs(2)),
+///     "part 1: [#####...............]  25% (250 of 1000) after 2.00s",
+/// );
+/// assert_eq!(
+///     status_line("part 2", 1234, None, Duration::from_secs(2)),
+///     "part 2: 1234 so far after 2.00s",
+/// );
+/// ```
+pub fn status_line(label: &str, done: u64, total: Option<u64>, elapsed: Duration) -> String {
+    const WIDTH: u64 = 20;
+
+    match total {
+        Some(total) if total > 0 => {
+            let done = done.min(total);
+            let filled = (done * WIDTH / total) as usize;
+            format!(
+                "{}: [{}{}] {:>3}% ({} of {}) after {}",
+                label,
+                "#".repeat(filled),
+                ".".repeat(WIDTH as usize - filled),
+                done * 100 / total,
+                done,
+                total,
+                format_duration(elapsed)
+            )
+        }
+        _ => format!(
+            "{}: {} so far after {}",
+            label,
+            done,
+            format_duration(elapsed)
+        ),
+    }
+}
+
+/// Format a single line of status for work whose total is only expected, with its rate and an
+/// estimate of the time remaining.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use util::progress::estimate_line;
+/// assert_eq!(
+///     estimate_line("part 2", 2_000_000, 16_000_000, Duration::from_secs(2)),
+///     "part 2: 2000000 so far at 1000000/s after 2.00s; about 14.00s more expected",
+/// );
+/// assert_eq!(
+///     estimate_line("part 2", 20_000_000, 16_000_000, Duration::from_secs(2)),
+///     "part 2: 20000000 so far at 10000000/s after 2.00s; more than the 16000000 expected",
+/// );
+/// ```
+pub fn estimate_line(label: &str, done: u64, expected: u64, elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs_f64();
+    let rate = if seconds > 0.0 {
+        done as f64 / seconds
+    } else {
+        0.0
+    };
+    let outlook = if done >= expected {
+        format!("more than the {} expected", expected)
+    } else if rate > 0.0 {
+        let remaining = Duration::from_secs_f64((expected - done) as f64 / rate);
+        format!("about {} more expected", format_duration(remaining))
+    } else {
+        format!("{} expected", expected)
+    };
+    format!(
+        "{}: {} so far at {:.0}/s after {}; {}",
+        label,
+        done,
+        rate,
+        format_duration(elapsed),
+        outlook
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line_bounds() {
+        let elapsed = Duration::from_millis(1500);
+        assert_eq!(
+            status_line("x", 0, Some(10), elapsed),
+            "x: [....................]   0% (0 of 10) after 1.50s"
+        );
+        assert_eq!(
+            status_line("x", 12, Some(10), elapsed),
+            "x: [####################] 100% (10 of 10) after 1.50s"
+        );
+        assert_eq!(
+            status_line("x", 3, Some(0), elapsed),
+            "x: 3 so far after 1.50s"
+        );
+    }
+
+    #[test]
+    fn test_estimate_line_before_any_time() {
+        assert_eq!(
+            estimate_line("x", 0, 256, Duration::from_secs(0)),
+            "x: 0 so far at 0/s after 0ns; 256 expected"
+        );
+    }
+
+    #[test]
+    fn test_high_water_mark() {
+        let progress = StatusLines::with_interval("x", Duration::from_secs(3600));
+        progress.advance(10);
+        progress.advance(5);
+        assert_eq!(progress.state.lock().unwrap().done, 10);
+    }
+}
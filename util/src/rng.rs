@@ -0,0 +1,34 @@
+//! Reproducible randomness for solvers which search randomly.
+//!
+//! Solvers never seed themselves: they accept an [`Rng`] or a seed from their caller, so that
+//! any run can be repeated exactly by passing the same `--seed`.
+
+use rand::SeedableRng;
+pub use rand::{seq::SliceRandom, Rng};
+use rand_chacha::ChaCha8Rng;
+
+/// The generator handed to randomized solvers.
+pub type SolverRng = ChaCha8Rng;
+
+/// The seed used when none is given.
+pub const DEFAULT_SEED: u64 = 2015;
+
+/// A generator which produces the same sequence every time for a given seed.
+pub fn seeded(seed: u64) -> SolverRng {
+    SolverRng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_is_reproducible() {
+        let draw = |seed| {
+            let mut rng = seeded(seed);
+            (0..8).map(|_| rng.gen::<u32>()).collect::<Vec<_>>()
+        };
+        assert_eq!(draw(DEFAULT_SEED), draw(DEFAULT_SEED));
+        assert_ne!(draw(DEFAULT_SEED), draw(DEFAULT_SEED + 1));
+    }
+}
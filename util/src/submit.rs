@@ -0,0 +1,332 @@
+//! Submitting answers to adventofcode.com.
+//!
+//! This uses the same session token which `aoclib` uses to download the input.
+
+use crate::{config::Config, Answer};
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf};
+
+const YEAR: u32 = 2015;
+
+/// Identifies this tool to adventofcode.com, as its maintainer asks of automated requests.
+const USER_AGENT: &str = "github.com/coriolinus/adventofcode-2015 by coriolinus@gmail.com";
+
+/// What the site had to say about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, with no hint in which direction.
+    Incorrect,
+    /// An answer was submitted too recently. The site reports how long to wait, i.e. `"37s"`.
+    RateLimited {
+        wait: Option<String>,
+    },
+    /// This part has already been solved, or its prerequisite has not.
+    WrongLevel,
+    /// The response didn't match any of the expected messages. Contains the response text.
+    Unrecognized(String),
+}
+
+impl Response {
+    /// Interpret the HTML body returned after submitting an answer.
+    pub fn interpret(body: &str) -> Response {
+        let text = main_text(body);
+        if text.contains("That's the right answer") {
+            Response::Correct
+        } else if text.contains("That's not the right answer") {
+            if text.contains("too high") {
+                Response::TooHigh
+            } else if text.contains("too low") {
+                Response::TooLow
+            } else {
+                Response::Incorrect
+            }
+        } else if text.contains("You gave an answer too recently") {
+            let wait = text
+                .split("You have ")
+                .nth(1)
+                .and_then(|rest| rest.split(" left to wait").next())
+                .map(|wait| wait.trim().to_string());
+            Response::RateLimited { wait }
+        } else if text.contains("solving the right level") {
+            Response::WrongLevel
+        } else {
+            Response::Unrecognized(text)
+        }
+    }
+
+    pub fn is_correct(&self) -> bool {
+        *self == Response::Correct
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Correct => write!(f, "that's the right answer"),
+            Response::TooHigh => write!(f, "incorrect: too high"),
+            Response::TooLow => write!(f, "incorrect: too low"),
+            Response::Incorrect => write!(f, "incorrect"),
+            Response::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited: wait {} before trying again", wait)
+            }
+            Response::RateLimited { wait: None } => write!(f, "rate limited"),
+            Response::WrongLevel => write!(f, "wrong level: is this part already solved?"),
+            Response::Unrecognized(text) => write!(f, "unrecognized response: {}", text),
+        }
+    }
+}
+
+/// Extract the text of the `<article>` element, or the whole body if there is none, with all
+/// tags removed and whitespace collapsed.
+fn main_text(body: &str) -> String {
+    let article = match (body.find("<article"), body.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &body[start..end],
+        _ => body,
+    };
+
+    let mut text = String::with_capacity(article.len());
+    let mut in_tag = false;
+    for ch in article.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Submit `answer` as the solution to the given part of the given day.
+pub fn submit(config: &Config, day: u8, part: u8, answer: &Answer) -> Result<Response, Error> {
+    let url = format!("https://adventofcode.com/{}/day/{}/answer", YEAR, day);
+    let response = ureq::post(&url)
+        .set("User-Agent", USER_AGENT)
+        .set("Cookie", &format!("session={}", config.session))
+        .send_form(&[
+            ("level", &part.to_string()),
+            ("answer", &answer.to_string()),
+        ])
+        .map_err(Box::new)?;
+    let body = response.into_string()?;
+    Ok(Response::interpret(&body))
+}
+
+/// Which way a rejected answer was off, when the site said.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+/// An answer the site turned down.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rejection {
+    pub answer: Answer,
+    pub hint: Option<Hint>,
+}
+
+impl Rejection {
+    /// The rejection carried by `response`, if it turned `answer` down.
+    pub fn from_response(answer: &Answer, response: &Response) -> Option<Rejection> {
+        let hint = match response {
+            Response::TooHigh => Some(Hint::TooHigh),
+            Response::TooLow => Some(Hint::TooLow),
+            Response::Incorrect => None,
+            _ => return None,
+        };
+        Some(Rejection {
+            answer: answer.clone(),
+            hint,
+        })
+    }
+
+    /// Whether this rejection shows `answer` to be wrong too: because it's the same answer, or
+    /// because it's an integer at least as far off in the hinted direction.
+    pub fn rules_out(&self, answer: &Answer) -> bool {
+        match (self.hint, &self.answer, answer) {
+            (Some(Hint::TooHigh), Answer::Int(rejected), Answer::Int(n)) => n >= rejected,
+            (Some(Hint::TooLow), Answer::Int(rejected), Answer::Int(n)) => n <= rejected,
+            _ => self.answer.to_string() == answer.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} was rejected", self.answer)?;
+        match self.hint {
+            Some(Hint::TooHigh) => write!(f, " as too high"),
+            Some(Hint::TooLow) => write!(f, " as too low"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The answers the site has turned down, so that none is submitted twice.
+///
+/// Each day and part keeps a JSON list of its rejections in the given directory; the cache
+/// directory is a good home, as clearing the cache leaves them alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejections {
+    dir: PathBuf,
+}
+
+impl Rejections {
+    pub fn new(dir: impl Into<PathBuf>) -> Rejections {
+        Rejections { dir: dir.into() }
+    }
+
+    fn path(&self, day: u8, part: u8) -> PathBuf {
+        self.dir
+            .join(format!("rejected-day{:02}-part{}.json", day, part))
+    }
+
+    /// Every rejection recorded for this day and part.
+    ///
+    /// A missing or corrupt list is treated as empty.
+    pub fn load(&self, day: u8, part: u8) -> Vec<Rejection> {
+        std::fs::read_to_string(self.path(day, part))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// The recorded rejection which rules out `answer`, if there is one.
+    pub fn ruling_out(&self, day: u8, part: u8, answer: &Answer) -> Option<Rejection> {
+        self.load(day, part)
+            .into_iter()
+            .find(|rejection| rejection.rules_out(answer))
+    }
+
+    /// Remember that the site turned down an answer for this day and part.
+    pub fn record(&self, day: u8, part: u8, rejection: Rejection) -> Result<(), Error> {
+        let mut rejections = self.load(day, part);
+        rejections.push(rejection);
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(day, part), serde_json::to_string(&rejections)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("recording rejected answers")]
+    Json(#[from] serde_json::Error),
+    #[error("submitting answer")]
+    Http(#[from] Box<ureq::Error>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    #[test]
+    fn test_correct() {
+        let body = page(
+            "That's the right answer!  You are <span class=\"day-success\">one gold star</span> \
+             closer to powering the weather machine. <a href=\"/2015/day/1#part2\">[Continue to Part Two]</a>",
+        );
+        assert_eq!(Response::interpret(&body), Response::Correct);
+    }
+
+    #[test]
+    fn test_incorrect() {
+        let too_high = page(
+            "That's not the right answer; your answer is too high.  If you're stuck, make sure \
+             you're using the full input data.",
+        );
+        assert_eq!(Response::interpret(&too_high), Response::TooHigh);
+
+        let too_low = page("That's not the right answer; your answer is too low.");
+        assert_eq!(Response::interpret(&too_low), Response::TooLow);
+
+        let wrong = page("That's not the right answer.  If you're stuck, try the subreddit.");
+        assert_eq!(Response::interpret(&wrong), Response::Incorrect);
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        let body = page(
+            "You gave an answer too recently; you have to wait after submitting an answer before \
+             trying again.  You have 37s left to wait. <a href=\"/2015/day/1\">[Return to Day 1]</a>",
+        );
+        assert_eq!(
+            Response::interpret(&body),
+            Response::RateLimited {
+                wait: Some("37s".into())
+            }
+        );
+    }
+
+    #[test]
+    fn test_wrong_level() {
+        let body = page(
+            "You don't seem to be solving the right level.  Did you already complete it? \
+             <a href=\"/2015/day/1\">[Return to Day 1]</a>",
+        );
+        assert_eq!(Response::interpret(&body), Response::WrongLevel);
+    }
+
+    #[test]
+    fn test_unrecognized() {
+        assert_eq!(
+            Response::interpret("<p>Please log in.</p>"),
+            Response::Unrecognized("Please log in.".into())
+        );
+    }
+
+    #[test]
+    fn test_rejections() {
+        let dir = std::env::temp_dir().join("aoc2015-submit-test-rejections");
+        let _ = std::fs::remove_dir_all(&dir);
+        let rejections = Rejections::new(&dir);
+        assert_eq!(rejections.ruling_out(1, 1, &Answer::Int(100)), None);
+
+        for (answer, response) in [
+            (Answer::Int(100), Response::TooHigh),
+            (Answer::Int(10), Response::TooLow),
+            (Answer::Int(50), Response::Incorrect),
+        ] {
+            let rejection = Rejection::from_response(&answer, &response).unwrap();
+            rejections.record(1, 1, rejection).unwrap();
+        }
+        assert_eq!(
+            Rejection::from_response(&Answer::Int(42), &Response::Correct),
+            None
+        );
+
+        let ruled_out = |n: i64| rejections.ruling_out(1, 1, &Answer::Int(n)).is_some();
+        assert!(ruled_out(100));
+        assert!(ruled_out(250));
+        assert!(ruled_out(10));
+        assert!(ruled_out(-3));
+        assert!(ruled_out(50));
+        assert!(!ruled_out(11));
+        assert!(!ruled_out(51));
+        assert!(!ruled_out(99));
+        assert_eq!(rejections.ruling_out(1, 2, &Answer::Int(100)), None);
+        assert_eq!(
+            rejections
+                .ruling_out(1, 1, &Answer::Int(120))
+                .unwrap()
+                .to_string(),
+            "100 was rejected as too high"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
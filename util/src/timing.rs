@@ -0,0 +1,115 @@
+//! Measuring and reporting how long things take.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// A value along with the wall-clock time it took to compute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timed<T> {
+    pub value: T,
+    pub duration: Duration,
+}
+
+impl<T> Timed<T> {
+    /// Compute a value, measuring how long that took.
+    pub fn measure(f: impl FnOnce() -> T) -> Self {
+        let (value, duration) = time_fn(f);
+        Timed { value, duration }
+    }
+
+    /// Transform the value, keeping the original duration.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Timed<U> {
+        Timed {
+            value: f(self.value),
+            duration: self.duration,
+        }
+    }
+
+    /// Discard the timing information.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, E> Timed<Result<T, E>> {
+    /// Convert a timed result into a result of a timed value.
+    pub fn transpose(self) -> Result<Timed<T>, E> {
+        let duration = self.duration;
+        self.value.map(|value| Timed { value, duration })
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Timed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.value, format_duration(self.duration))
+    }
+}
+
+/// Run `f`, returning its result and the wall-clock time it took.
+pub fn time_fn<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+/// Format a duration compactly, in the largest unit which keeps the value at least 1.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use util::timing::format_duration;
+/// assert_eq!(format_duration(Duration::from_nanos(15)), "15ns");
+/// assert_eq!(format_duration(Duration::from_nanos(12_345)), "12.35µs");
+/// assert_eq!(format_duration(Duration::from_micros(456_700)), "456.70ms");
+/// assert_eq!(format_duration(Duration::from_millis(1_234)), "1.23s");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+/// Format a labeled duration as a single aligned report line, e.g. `part 1:      1.23ms`.
+pub fn report_line(label: &str, duration: Duration) -> String {
+    format!("{}: {:>10}", label, format_duration(duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_fn_returns_value() {
+        let (value, duration) = time_fn(|| {
+            std::thread::sleep(Duration::from_millis(5));
+            42
+        });
+        assert_eq!(value, 42);
+        assert!(duration >= Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_timed_transpose() {
+        let ok: Timed<Result<u8, ()>> = Timed::measure(|| Ok(3));
+        assert_eq!(ok.transpose().unwrap().value, 3);
+
+        let err: Timed<Result<u8, &str>> = Timed::measure(|| Err("nope"));
+        assert_eq!(err.transpose().unwrap_err(), "nope");
+    }
+
+    #[test]
+    fn test_report_line() {
+        assert_eq!(
+            report_line("part 1", Duration::from_micros(1_230)),
+            "part 1:     1.23ms"
+        );
+    }
+}
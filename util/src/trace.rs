@@ -0,0 +1,37 @@
+//! Route the solvers' `tracing` events to stderr.
+
+use tracing::Level;
+
+/// The most detailed level shown at a given verbosity: `-v` shows debug events, `-vv` and beyond
+/// show trace events too. Without `-v`, only warnings get through.
+pub fn level(verbosity: u8) -> Level {
+    match verbosity {
+        0 => Level::WARN,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Install a subscriber printing events up to the level implied by `verbosity` on stderr.
+///
+/// Does nothing if a subscriber has already been installed.
+pub fn init(verbosity: u8) {
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level(verbosity))
+        .with_writer(std::io::stderr)
+        .with_ansi(atty::is(atty::Stream::Stderr))
+        .try_init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_levels() {
+        assert_eq!(level(0), Level::WARN);
+        assert_eq!(level(1), Level::DEBUG);
+        assert_eq!(level(2), Level::TRACE);
+        assert_eq!(level(5), Level::TRACE);
+    }
+}
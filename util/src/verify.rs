@@ -0,0 +1,157 @@
+//! Checking computed answers against known-good ones.
+//!
+//! Expected answers live in a TOML file with a table per day and a key per part:
+//!
+//! ```toml
+//! [day01]
+//! part1 = 280
+//! part2 = 1797
+//!
+//! [day11]
+//! part1 = "hepxxyzz"
+//! ```
+
+use crate::Answer;
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+
+/// Whether a computed answer matches the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect {
+        expected: Answer,
+    },
+    /// No answer is recorded for this day and part.
+    Unknown,
+}
+
+/// Known-good answers, by day and part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedAnswers {
+    answers: BTreeMap<(u8, u8), Answer>,
+}
+
+impl ExpectedAnswers {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    /// Compare an answer against the expected one.
+    ///
+    /// Answers are compared by their textual form, so `42` and `"42"` are equivalent.
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Unknown,
+            Some(expected) if expected.to_string() == answer.to_string() => Verdict::Correct,
+            Some(expected) => Verdict::Incorrect {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+/// Parse a key of the form `{prefix}NN`, such as `day07` or `part2`.
+fn parse_key(key: &str, prefix: &str) -> Result<u8, Error> {
+    key.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| Error::BadKey(key.to_string()))
+}
+
+impl FromStr for ExpectedAnswers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let document: toml::value::Table = toml::from_str(s)?;
+        let mut answers = BTreeMap::new();
+
+        for (day_key, parts) in document {
+            let day = parse_key(&day_key, "day")?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| Error::BadKey(day_key.clone()))?;
+            for (part_key, value) in parts {
+                let part = parse_key(part_key, "part")?;
+                let answer = match value {
+                    toml::Value::Integer(n) => Answer::Int(*n),
+                    toml::Value::String(s) => Answer::Str(s.clone()),
+                    _ => return Err(Error::BadValue(format!("{}.{}", day_key, part_key))),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+
+        Ok(ExpectedAnswers { answers })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error("expected a key like `day01` or `part1`, found `{0}`")]
+    BadKey(String),
+    #[error("answer for {0} must be an integer or a string")]
+    BadValue(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+[day01]
+part1 = 280
+part2 = 1797
+
+[day11]
+part1 = "hepxxyzz"
+
+[day24]
+part1 = "11266889531"
+"#;
+
+    #[test]
+    fn test_parse() {
+        let expected: ExpectedAnswers = EXAMPLE.parse().unwrap();
+        assert_eq!(expected.get(1, 1), Some(&Answer::Int(280)));
+        assert_eq!(expected.get(1, 2), Some(&Answer::Int(1797)));
+        assert_eq!(expected.get(11, 1), Some(&Answer::Str("hepxxyzz".into())));
+        assert_eq!(expected.get(11, 2), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let expected: ExpectedAnswers = EXAMPLE.parse().unwrap();
+        assert_eq!(expected.check(1, 1, &Answer::Int(280)), Verdict::Correct);
+        assert_eq!(
+            expected.check(1, 2, &Answer::Int(1796)),
+            Verdict::Incorrect {
+                expected: Answer::Int(1797)
+            }
+        );
+        assert_eq!(expected.check(2, 1, &Answer::Int(1)), Verdict::Unknown);
+        // numeric answers may be recorded as strings
+        assert_eq!(
+            expected.check(24, 1, &Answer::from(11266889531_u64)),
+            Verdict::Correct
+        );
+    }
+
+    #[test]
+    fn test_bad_keys() {
+        assert!(matches!(
+            "[dayone]\npart1 = 1".parse::<ExpectedAnswers>(),
+            Err(Error::BadKey(_))
+        ));
+        assert!(matches!(
+            "[day01]\npart1 = 1.5".parse::<ExpectedAnswers>(),
+            Err(Error::BadValue(_))
+        ));
+    }
+}
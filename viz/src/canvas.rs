@@ -0,0 +1,234 @@
+use crate::{Error, Rgb, Style};
+use aoclib::geometry::Map;
+use std::{
+    convert::TryInto,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// An RGB raster image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    subpixels: Vec<u8>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Canvas {
+        Canvas {
+            width,
+            height,
+            subpixels: background.repeat(width * height),
+        }
+    }
+
+    /// Paint each cell of `map` for which `paint` returns a color.
+    pub fn from_map<T>(map: &Map<T>, style: &Style, paint: impl Fn(&T) -> Option<Rgb>) -> Canvas {
+        let (width, height) = style.image_size(map.width(), map.height());
+        let mut canvas = Canvas::new(width, height, style.background);
+        map.for_each_point(|tile, point| {
+            if let Some(color) = paint(tile) {
+                canvas.fill_cell(style, point.x as usize, point.y as usize, color);
+            }
+        });
+        canvas
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The color of a single pixel.
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let idx = (y * self.width + x) * 3;
+        self.subpixels[idx..idx + 3].try_into().ok()
+    }
+
+    /// Set a single pixel. Pixels out of bounds are ignored.
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            let idx = (y * self.width + x) * 3;
+            self.subpixels[idx..idx + 3].copy_from_slice(&color);
+        }
+    }
+
+    /// Fill a rectangle, clipped to the canvas.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    /// Fill the cell at column `x` and row `y`, leaving the style's gap around it.
+    pub fn fill_cell(&mut self, style: &Style, x: usize, y: usize, color: Rgb) {
+        let (left, top) = style.cell_origin(x, y);
+        let size = style.cell.saturating_sub(2 * style.gap);
+        self.fill_rect(left + style.gap, top + style.gap, size, size, color);
+    }
+
+    fn dimensions(&self) -> Result<(u16, u16), Error> {
+        let too_large = || Error::TooLarge(self.width, self.height);
+        Ok((
+            self.width.try_into().map_err(|_| too_large())?,
+            self.height.try_into().map_err(|_| too_large())?,
+        ))
+    }
+
+    /// Encode this canvas as a PNG.
+    pub fn encode_png<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.subpixels)?;
+        Ok(())
+    }
+
+    /// Write this canvas to a PNG file.
+    pub fn write_png(&self, path: &Path) -> Result<(), Error> {
+        self.encode_png(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// An animated GIF, written one frame at a time.
+pub struct Animation<W: Write> {
+    encoder: gif::Encoder<W>,
+    width: u16,
+    height: u16,
+    /// in hundredths of a second
+    delay: u16,
+}
+
+impl Animation<BufWriter<File>> {
+    /// Create a looping animation file whose frames are `width` by `height` pixels.
+    pub fn create(
+        path: &Path,
+        width: usize,
+        height: usize,
+        frame_duration: Duration,
+    ) -> Result<Self, Error> {
+        let writer = BufWriter::new(File::create(path)?);
+        Animation::new(writer, width, height, frame_duration)
+    }
+}
+
+impl<W: Write> Animation<W> {
+    pub fn new(
+        writer: W,
+        width: usize,
+        height: usize,
+        frame_duration: Duration,
+    ) -> Result<Self, Error> {
+        let (width, height) = Canvas::new(width, height, crate::BLACK).dimensions()?;
+        let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        Ok(Animation {
+            encoder,
+            width,
+            height,
+            delay: (frame_duration.as_millis() / 10) as u16,
+        })
+    }
+
+    /// Append a frame, which must have the dimensions of the animation.
+    pub fn push(&mut self, canvas: &Canvas) -> Result<(), Error> {
+        if canvas.dimensions()? != (self.width, self.height) {
+            return Err(Error::FrameSize);
+        }
+        let mut frame = gif::Frame::from_rgb_speed(self.width, self.height, &canvas.subpixels, 10);
+        frame.delay = self.delay;
+        self.encoder.write_frame(&frame)?;
+        Ok(())
+    }
+
+    /// Append the same frame several times.
+    pub fn hold(&mut self, canvas: &Canvas, frames: usize) -> Result<(), Error> {
+        for _ in 0..frames {
+            self.push(canvas)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BLACK, WHITE};
+    use aoclib::geometry::Point;
+
+    fn checkerboard() -> Map<bool> {
+        let mut map = Map::new(2, 2);
+        map[Point::new(0, 0)] = true;
+        map[Point::new(1, 1)] = true;
+        map
+    }
+
+    #[test]
+    fn test_from_map() {
+        let style = Style {
+            cell: 2,
+            gap: 0,
+            border: 1,
+            background: BLACK,
+        };
+        let canvas = Canvas::from_map(&checkerboard(), &style, |&lit| Some(WHITE).filter(|_| lit));
+        assert_eq!((canvas.width(), canvas.height()), (6, 6));
+        // border
+        assert_eq!(canvas.get(0, 0), Some(BLACK));
+        // first cell
+        assert_eq!(canvas.get(1, 1), Some(WHITE));
+        assert_eq!(canvas.get(2, 2), Some(WHITE));
+        // second cell is unlit
+        assert_eq!(canvas.get(3, 1), Some(BLACK));
+        // last cell
+        assert_eq!(canvas.get(4, 4), Some(WHITE));
+        assert_eq!(canvas.get(6, 6), None);
+    }
+
+    #[test]
+    fn test_gap() {
+        let style = Style {
+            cell: 3,
+            gap: 1,
+            border: 0,
+            background: BLACK,
+        };
+        let canvas = Canvas::from_map(&checkerboard(), &style, |&lit| Some(WHITE).filter(|_| lit));
+        assert_eq!(canvas.get(0, 0), Some(BLACK));
+        assert_eq!(canvas.get(1, 1), Some(WHITE));
+        assert_eq!(canvas.get(2, 2), Some(BLACK));
+    }
+
+    #[test]
+    fn test_encode_png() {
+        let mut out = Vec::new();
+        Canvas::new(3, 2, WHITE).encode_png(&mut out).unwrap();
+        assert_eq!(&out[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn test_animation() {
+        let mut out = Vec::new();
+        {
+            let mut animation = Animation::new(&mut out, 3, 2, Duration::from_millis(100)).unwrap();
+            animation.hold(&Canvas::new(3, 2, WHITE), 2).unwrap();
+            assert!(matches!(
+                animation.push(&Canvas::new(2, 2, WHITE)),
+                Err(Error::FrameSize)
+            ));
+        }
+        assert_eq!(&out[..6], b"GIF89a");
+    }
+}
@@ -0,0 +1,132 @@
+//! Rendering of grids and point trails as PNG, animated GIF, and SVG.
+//!
+//! Raster output is built on a [`Canvas`]; every cell of the source occupies a square of
+//! [`Style::cell`] pixels. SVG output is generated directly as text.
+
+mod canvas;
+mod svg;
+mod trail;
+
+pub use canvas::{Animation, Canvas};
+pub use svg::map_svg;
+pub use trail::{trails_canvas, trails_svg, Trail};
+
+use std::path::Path;
+
+/// A color as red, green, and blue components.
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const WARM_WHITE: Rgb = [253, 244, 220];
+pub const RED: Rgb = [200, 40, 40];
+pub const GREEN: Rgb = [40, 160, 60];
+
+/// Linear interpolation between two colors; `t` is clamped to `0.0..=1.0`.
+///
+/// ```
+/// # use viz::{blend, BLACK, WHITE};
+/// assert_eq!(blend(BLACK, WHITE, 0.5), [128, 128, 128]);
+/// ```
+pub fn blend(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    let mut out = [0; 3];
+    for ((out, from), to) in out.iter_mut().zip(from.iter()).zip(to.iter()) {
+        *out = (*from as f64 + (*to as f64 - *from as f64) * t).round() as u8;
+    }
+    out
+}
+
+/// How cells are laid out in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    /// width and height of each cell, in pixels
+    pub cell: usize,
+    /// empty pixels around each painted cell, inside its square
+    pub gap: usize,
+    /// width of the empty border around the whole image, in pixels
+    pub border: usize,
+    pub background: Rgb,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            cell: 4,
+            gap: 0,
+            border: 2,
+            background: BLACK,
+        }
+    }
+}
+
+impl Style {
+    /// Pixel dimensions of an image containing `width` by `height` cells.
+    pub fn image_size(&self, width: usize, height: usize) -> (usize, usize) {
+        (
+            width * self.cell + 2 * self.border,
+            height * self.cell + 2 * self.border,
+        )
+    }
+
+    /// Pixel coordinates of the top left of the cell at column `x` and row `y`.
+    fn cell_origin(&self, x: usize, y: usize) -> (usize, usize) {
+        (self.border + x * self.cell, self.border + y * self.cell)
+    }
+}
+
+/// Output formats, chosen by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Gif,
+    Svg,
+}
+
+impl Format {
+    /// Determine the format from the extension of `path`.
+    ///
+    /// ```
+    /// # use viz::Format;
+    /// # use std::path::Path;
+    /// assert_eq!(Format::from_path(Path::new("trail.SVG")).unwrap(), Format::Svg);
+    /// assert!(Format::from_path(Path::new("trail.bmp")).is_err());
+    /// ```
+    pub fn from_path(path: &Path) -> Result<Format, Error> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Ok(Format::Png),
+            "gif" => Ok(Format::Gif),
+            "svg" => Ok(Format::Svg),
+            _ => Err(Error::UnknownFormat(path.to_path_buf())),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("encoding png")]
+    Png(#[from] png::EncodingError),
+    #[error("encoding gif")]
+    Gif(#[from] gif::EncodingError),
+    #[error("image of {0}x{1} pixels is too large")]
+    TooLarge(usize, usize),
+    #[error("frame size does not match the animation")]
+    FrameSize,
+    #[error("unknown image format for {0}; expected .png, .gif, or .svg")]
+    UnknownFormat(std::path::PathBuf),
+    #[error("{0:?} output is not supported for this image")]
+    Unsupported(Format),
+}
+
+impl From<Error> for util::Error {
+    fn from(err: Error) -> Self {
+        util::Error::Render(Box::new(err))
+    }
+}
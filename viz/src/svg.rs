@@ -0,0 +1,70 @@
+use crate::{Rgb, Style};
+use aoclib::geometry::Map;
+use std::fmt::Write;
+
+pub(crate) fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Open an SVG document of the given pixel size, filled with the background color.
+pub(crate) fn header(width: usize, height: usize, background: Rgb) -> String {
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" "#,
+            r#"viewBox="0 0 {w} {h}">"#,
+            "\n",
+            r#"<rect width="{w}" height="{h}" fill="{bg}"/>"#,
+            "\n"
+        ),
+        w = width,
+        h = height,
+        bg = hex(background)
+    )
+}
+
+pub(crate) const FOOTER: &str = "</svg>\n";
+
+/// Render `map` as an SVG document, with a square for each cell which `paint` colors.
+///
+/// Each painted cell becomes its own element, so this is best suited to sparse or small grids.
+pub fn map_svg<T>(map: &Map<T>, style: &Style, paint: impl Fn(&T) -> Option<Rgb>) -> String {
+    let (width, height) = style.image_size(map.width(), map.height());
+    let mut svg = header(width, height, style.background);
+    let size = style.cell.saturating_sub(2 * style.gap);
+    map.for_each_point(|tile, point| {
+        if let Some(color) = paint(tile) {
+            let (left, top) = style.cell_origin(point.x as usize, point.y as usize);
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                left + style.gap,
+                top + style.gap,
+                size,
+                size,
+                hex(color)
+            )
+            .expect("writing to a string cannot fail");
+        }
+    });
+    svg.push_str(FOOTER);
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WHITE;
+    use aoclib::geometry::Point;
+
+    #[test]
+    fn test_map_svg() {
+        let mut map: Map<bool> = Map::new(2, 1);
+        map[Point::new(1, 0)] = true;
+        let svg = map_svg(&map, &Style::default(), |&lit| Some(WHITE).filter(|_| lit));
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(r##"<rect x="6" y="2" width="4" height="4" fill="#ffffff"/>"##));
+        // background and one lit cell
+        assert_eq!(svg.matches("<rect").count(), 2);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    svg::{header, hex, FOOTER},
+    Canvas, Rgb, Style,
+};
+use aoclib::geometry::Point;
+use std::fmt::Write;
+
+/// A sequence of visited points, drawn in a single color.
+///
+/// Trails use the conventions of `aoclib::geometry`: `Direction::Up` increases `y`, so larger `y`
+/// values are drawn nearer the top of the image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trail {
+    pub points: Vec<Point>,
+    pub color: Rgb,
+}
+
+/// The extent of a set of trails, in cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bounds {
+    min_x: i32,
+    max_y: i32,
+    width: usize,
+    height: usize,
+}
+
+impl Bounds {
+    fn of(trails: &[Trail]) -> Bounds {
+        let mut points = trails.iter().flat_map(|trail| trail.points.iter());
+        let first = match points.next() {
+            Some(first) => *first,
+            None => {
+                return Bounds {
+                    min_x: 0,
+                    max_y: 0,
+                    width: 0,
+                    height: 0,
+                }
+            }
+        };
+        let (mut min, mut max) = (first, first);
+        for point in points {
+            min.x = min.x.min(point.x);
+            min.y = min.y.min(point.y);
+            max.x = max.x.max(point.x);
+            max.y = max.y.max(point.y);
+        }
+        Bounds {
+            min_x: min.x,
+            max_y: max.y,
+            width: (max.x - min.x + 1) as usize,
+            height: (max.y - min.y + 1) as usize,
+        }
+    }
+
+    /// Column and row of the cell containing `point`.
+    fn cell(&self, point: Point) -> (usize, usize) {
+        (
+            (point.x - self.min_x) as usize,
+            (self.max_y - point.y) as usize,
+        )
+    }
+}
+
+/// Rasterize `trails`, painting every visited cell. Later trails are drawn over earlier ones.
+pub fn trails_canvas(trails: &[Trail], style: &Style) -> Canvas {
+    let bounds = Bounds::of(trails);
+    let (width, height) = style.image_size(bounds.width, bounds.height);
+    let mut canvas = Canvas::new(width, height, style.background);
+    for trail in trails {
+        for point in &trail.points {
+            let (x, y) = bounds.cell(*point);
+            canvas.fill_cell(style, x, y, trail.color);
+        }
+    }
+    canvas
+}
+
+/// Render `trails` as an SVG document, with one polyline through the cell centers per trail.
+pub fn trails_svg(trails: &[Trail], style: &Style) -> String {
+    let bounds = Bounds::of(trails);
+    let (width, height) = style.image_size(bounds.width, bounds.height);
+    let mut svg = header(width, height, style.background);
+    let stroke = style.cell.saturating_sub(2 * style.gap).max(1);
+    for trail in trails {
+        let points = trail
+            .points
+            .iter()
+            .map(|point| {
+                let (x, y) = bounds.cell(*point);
+                let (left, top) = style.cell_origin(x, y);
+                let center = style.cell as f64 / 2.0;
+                format!("{},{}", left as f64 + center, top as f64 + center)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+            points,
+            hex(trail.color),
+            stroke
+        )
+        .expect("writing to a string cannot fail");
+    }
+    svg.push_str(FOOTER);
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BLACK, GREEN, RED};
+
+    fn example() -> Vec<Trail> {
+        vec![
+            Trail {
+                points: vec![Point::new(0, 0), Point::new(0, 1), Point::new(1, 1)],
+                color: RED,
+            },
+            Trail {
+                points: vec![Point::new(0, 0), Point::new(-1, 0)],
+                color: GREEN,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::of(&example());
+        assert_eq!((bounds.width, bounds.height), (3, 2));
+        // up is toward the top of the image
+        assert_eq!(bounds.cell(Point::new(-1, 1)), (0, 0));
+        assert_eq!(bounds.cell(Point::new(1, 0)), (2, 1));
+    }
+
+    #[test]
+    fn test_trails_canvas() {
+        let style = Style {
+            cell: 1,
+            gap: 0,
+            border: 0,
+            background: BLACK,
+        };
+        let canvas = trails_canvas(&example(), &style);
+        assert_eq!((canvas.width(), canvas.height()), (3, 2));
+        assert_eq!(canvas.get(1, 0), Some(RED));
+        assert_eq!(canvas.get(2, 0), Some(RED));
+        assert_eq!(canvas.get(0, 1), Some(GREEN));
+        // the origin was visited by both; the later trail wins
+        assert_eq!(canvas.get(1, 1), Some(GREEN));
+        assert_eq!(canvas.get(2, 1), Some(BLACK));
+    }
+
+    #[test]
+    fn test_trails_svg() {
+        let svg = trails_svg(&example(), &Style::default());
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(r#"points="8,8 8,4 12,4""#));
+    }
+
+    #[test]
+    fn test_empty() {
+        let canvas = trails_canvas(&[], &Style::default());
+        assert_eq!((canvas.width(), canvas.height()), (4, 4));
+    }
+}
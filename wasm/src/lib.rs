@@ -0,0 +1,106 @@
+//! WebAssembly bindings, so that the solutions can run in a browser.
+//!
+//! Build with `wasm-pack build wasm --target web`, then from JavaScript:
+//!
+//! ```js
+//! import init, { solve } from "./pkg/wasm.js";
+//! await init();
+//! console.log(solve(1, 1, "(()(()("));
+//! ```
+
+use util::Answer;
+use wasm_bindgen::prelude::*;
+
+pub type Error = Box<dyn std::error::Error>;
+
+macro_rules! dispatch {
+    ($day:expr, $part:expr, $input:expr; $($n:literal => $krate:ident),* $(,)?) => {
+        match ($day, $part) {
+            $(
+                ($n, 1) => Ok($krate::part1_str($input)?),
+                ($n, 2) => Ok($krate::part2_str($input)?),
+            )*
+            (day, 1) | (day, 2) => Err(format!("day {} is not implemented", day).into()),
+            (_, part) => Err(format!("part must be 1 or 2, not {}", part).into()),
+        }
+    };
+}
+
+/// Solve the given part of the given day's puzzle for `input`.
+pub fn try_solve(day: u8, part: u8, input: &str) -> Result<Answer, Error> {
+    match (day, part) {
+        (25, 1) => Ok(day25::part1_str(input)?),
+        // christmas day has only a single part
+        (25, 2) => Err("day 25 has no part 2".into()),
+        _ => dispatch!(
+            day, part, input;
+            1 => day01,
+            2 => day02,
+            3 => day03,
+            4 => day04,
+            5 => day05,
+            6 => day06,
+            7 => day07,
+            8 => day08,
+            9 => day09,
+            10 => day10,
+            11 => day11,
+            12 => day12,
+            13 => day13,
+            14 => day14,
+            15 => day15,
+            16 => day16,
+            17 => day17,
+            18 => day18,
+            19 => day19,
+            20 => day20,
+            21 => day21,
+            22 => day22,
+            23 => day23,
+            24 => day24,
+        ),
+    }
+}
+
+/// Solve the given part of the given day's puzzle for `input`.
+///
+/// Returns the answer, or a message beginning with `error: ` if it could not be solved.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> String {
+    match try_solve(day, part, input) {
+        Ok(answer) => answer.to_string(),
+        Err(err) => format!("error: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(solve(1, 1, "(()(()(\n"), "3");
+        assert_eq!(solve(1, 2, "()())"), "5");
+        assert_eq!(solve(2, 1, "2x3x4\n1x1x10\n"), "101");
+        assert_eq!(
+            solve(5, 2, "qjhvhtzxzqqjkmpb\nxxyxx\nieodomkazucvgmuy\n"),
+            "2"
+        );
+        assert_eq!(
+            solve(
+                9,
+                1,
+                "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141\n"
+            ),
+            "605"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(solve(25, 2, ""), "error: day 25 has no part 2");
+        assert_eq!(solve(26, 1, ""), "error: day 26 is not implemented");
+        assert_eq!(solve(1, 3, ""), "error: part must be 1 or 2, not 3");
+        assert!(solve(3, 1, "").starts_with("error: "));
+    }
+}